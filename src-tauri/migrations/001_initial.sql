@@ -1,9 +1,15 @@
+-- Schema meta table: スキーマバージョン（1行のみ）
+CREATE TABLE IF NOT EXISTS schema_meta (
+    version INTEGER NOT NULL
+);
+
 -- Folders table: タスクのフォルダ分類
 CREATE TABLE IF NOT EXISTS folders (
     id VARCHAR PRIMARY KEY,
     name VARCHAR NOT NULL,
     color VARCHAR(7) NOT NULL DEFAULT '#6b7280',
     icon VARCHAR(50),
+    description TEXT,
     sort_order INTEGER NOT NULL DEFAULT 0,
     created_at TIMESTAMPTZ NOT NULL,
     updated_at TIMESTAMPTZ NOT NULL
@@ -17,6 +23,8 @@ CREATE TABLE IF NOT EXISTS tasks (
     description TEXT,
     color VARCHAR(7) NOT NULL DEFAULT '#3b82f6',
     archived BOOLEAN NOT NULL DEFAULT FALSE,
+    hourly_rate DOUBLE,
+    currency VARCHAR(10),
     created_at TIMESTAMPTZ NOT NULL,
     updated_at TIMESTAMPTZ NOT NULL
 );
@@ -28,7 +36,8 @@ CREATE TABLE IF NOT EXISTS artifacts (
     artifact_type VARCHAR(50) NOT NULL,
     reference TEXT,
     metadata JSON,
-    created_at TIMESTAMPTZ NOT NULL
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
 -- Time entries table: 作業記録
@@ -37,6 +46,7 @@ CREATE TABLE IF NOT EXISTS time_entries (
     task_id VARCHAR,
     started_at TIMESTAMPTZ NOT NULL,
     ended_at TIMESTAMPTZ,
+    duration_seconds BIGINT,
     memo TEXT,
     created_at TIMESTAMPTZ NOT NULL,
     updated_at TIMESTAMPTZ NOT NULL
@@ -49,6 +59,46 @@ CREATE TABLE IF NOT EXISTS entry_artifacts (
     PRIMARY KEY (entry_id, artifact_id)
 );
 
+-- Heartbeats table: 計測中エントリの最終生存確認時刻
+CREATE TABLE IF NOT EXISTS heartbeats (
+    entry_id VARCHAR PRIMARY KEY,
+    last_heartbeat TIMESTAMPTZ NOT NULL
+);
+
+-- Entry notes table: 計測中の記録に時刻付きで残すメモ（メインのメモとは別）
+CREATE TABLE IF NOT EXISTS entry_notes (
+    id VARCHAR PRIMARY KEY,
+    entry_id VARCHAR NOT NULL,
+    text TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+-- Settings table: アプリ全体の設定（キー/値）
+CREATE TABLE IF NOT EXISTS settings (
+    key VARCHAR PRIMARY KEY,
+    value VARCHAR NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+-- Audit log table: タスク・時間記録の変更履歴
+CREATE TABLE IF NOT EXISTS audit_log (
+    id VARCHAR PRIMARY KEY,
+    entity_type VARCHAR NOT NULL,
+    entity_id VARCHAR NOT NULL,
+    action VARCHAR NOT NULL,
+    old_value TEXT,
+    new_value TEXT,
+    "at" TIMESTAMPTZ NOT NULL
+);
+
+-- Auto rules table: メモのキーワードからタスクを自動で割り当てるルール
+CREATE TABLE IF NOT EXISTS auto_rules (
+    id VARCHAR PRIMARY KEY,
+    pattern VARCHAR NOT NULL,
+    task_id VARCHAR NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
 -- Indexes for better query performance
 CREATE INDEX IF NOT EXISTS idx_time_entries_task_id ON time_entries(task_id);
 CREATE INDEX IF NOT EXISTS idx_time_entries_started_at ON time_entries(started_at);
@@ -56,3 +106,5 @@ CREATE INDEX IF NOT EXISTS idx_time_entries_ended_at ON time_entries(ended_at);
 CREATE INDEX IF NOT EXISTS idx_tasks_archived ON tasks(archived);
 CREATE INDEX IF NOT EXISTS idx_tasks_folder_id ON tasks(folder_id);
 CREATE INDEX IF NOT EXISTS idx_folders_sort_order ON folders(sort_order);
+CREATE INDEX IF NOT EXISTS idx_audit_log_entity_id ON audit_log(entity_id);
+CREATE INDEX IF NOT EXISTS idx_entry_notes_entry_id ON entry_notes(entry_id);
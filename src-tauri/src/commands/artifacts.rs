@@ -1,16 +1,31 @@
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
 use chrono::{DateTime, Utc};
 use duckdb::Connection;
+use regex::Regex;
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
-use crate::db::{Artifact, CreateArtifact};
+use crate::commands::settings::{
+    fetch_setting, store_setting, ARTIFACT_TYPES_ENFORCED_KEY, ARTIFACT_TYPES_KEY,
+    ARTIFACT_TYPES_NORMALIZE_KEY,
+};
+use crate::commands::{ensure_max_length, MAX_NAME_LENGTH};
+use crate::db::{Artifact, ArtifactDateRange, ArtifactTypePolicy, ArtifactWithUsage, CreateArtifact};
 use crate::error::{AppError, AppResult};
 use crate::AppState;
 
+/// 取り込んだファイルを保存するディレクトリ名（データディレクトリ直下）
+const MANAGED_ARTIFACTS_DIR: &str = "artifacts";
+
 /// 成果物をDBに保存する
-fn insert_artifact(conn: &Connection, artifact: &Artifact) -> AppResult<()> {
+pub(crate) fn insert_artifact(conn: &Connection, artifact: &Artifact) -> AppResult<()> {
     conn.execute(
-        "INSERT INTO artifacts (id, name, artifact_type, reference, metadata, created_at)
-         VALUES (?, ?, ?, ?, ?, ?)",
+        "INSERT INTO artifacts (id, name, artifact_type, reference, metadata, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
         duckdb::params![
             artifact.id.to_string(),
             &artifact.name,
@@ -18,38 +33,59 @@ fn insert_artifact(conn: &Connection, artifact: &Artifact) -> AppResult<()> {
             &artifact.reference,
             artifact.metadata.as_ref().map(|m| m.to_string()),
             artifact.created_at,
+            artifact.updated_at,
         ],
     )?;
     Ok(())
 }
 
-/// 成果物一覧を取得する
-fn fetch_artifacts(conn: &Connection, limit: Option<i64>) -> AppResult<Vec<Artifact>> {
+/// 成果物一覧を参照件数付きで取得する
+///
+/// `unlinked_only` が true の場合はどのエントリーにも紐付いていない成果物のみを返す。
+fn fetch_artifacts(
+    conn: &Connection,
+    limit: Option<i64>,
+    unlinked_only: bool,
+) -> AppResult<Vec<ArtifactWithUsage>> {
+    let filter = if unlinked_only {
+        "WHERE NOT EXISTS (SELECT 1 FROM entry_artifacts x WHERE x.artifact_id = a.id)"
+    } else {
+        ""
+    };
+    let base = format!(
+        "SELECT a.id, a.name, a.artifact_type, a.reference, a.metadata, a.created_at, a.updated_at,
+                COUNT(ea.entry_id) as usage_count
+         FROM artifacts a
+         LEFT JOIN entry_artifacts ea ON ea.artifact_id = a.id
+         {}
+         GROUP BY a.id, a.name, a.artifact_type, a.reference, a.metadata, a.created_at, a.updated_at
+         ORDER BY a.created_at DESC, a.id",
+        filter
+    );
     let sql = if let Some(lim) = limit {
-        format!(
-            "SELECT id, name, artifact_type, reference, metadata, created_at
-             FROM artifacts ORDER BY created_at DESC LIMIT {}",
-            lim
-        )
+        format!("{} LIMIT {}", base, lim)
     } else {
-        "SELECT id, name, artifact_type, reference, metadata, created_at
-         FROM artifacts ORDER BY created_at DESC"
-            .to_string()
+        base
     };
 
     let mut stmt = conn.prepare(&sql)?;
     let rows = stmt.query_map([], |row| {
         let id_str: String = row.get(0)?;
         let created_at: DateTime<Utc> = row.get(5)?;
+        let updated_at: DateTime<Utc> = row.get(6)?;
         let metadata_str: Option<String> = row.get(4)?;
 
-        Ok(Artifact {
-            id: Uuid::parse_str(&id_str).unwrap(),
-            name: row.get(1)?,
-            artifact_type: row.get(2)?,
-            reference: row.get(3)?,
-            metadata: metadata_str.and_then(|s| serde_json::from_str(&s).ok()),
-            created_at,
+        Ok(ArtifactWithUsage {
+            artifact: Artifact {
+                id: Uuid::parse_str(&id_str).unwrap(),
+                name: row.get(1)?,
+                artifact_type: row.get(2)?,
+                reference: row.get(3)?,
+                metadata: metadata_str.and_then(|s| serde_json::from_str(&s).ok()),
+                created_at,
+                updated_at,
+            },
+            usage_count: row.get(7)?,
         })
     })?;
 
@@ -60,10 +96,37 @@ fn fetch_artifacts(conn: &Connection, limit: Option<i64>) -> AppResult<Vec<Artif
     Ok(artifacts)
 }
 
+/// 成果物の作成日時の範囲と件数を1回のクエリで取得する（該当する成果物がなければNone）
+fn fetch_artifact_date_range(
+    conn: &Connection,
+    artifact_type: Option<&str>,
+) -> AppResult<Option<ArtifactDateRange>> {
+    let mut sql = String::from("SELECT MIN(created_at), MAX(created_at), COUNT(*) FROM artifacts");
+    let mut params: Vec<&str> = Vec::new();
+    if let Some(artifact_type) = artifact_type {
+        sql.push_str(" WHERE artifact_type = ?");
+        params.push(artifact_type);
+    }
+
+    let (earliest, latest, count): (Option<DateTime<Utc>>, Option<DateTime<Utc>>, i64) = conn
+        .query_row(&sql, duckdb::params_from_iter(params), |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })?;
+
+    Ok(match (earliest, latest) {
+        (Some(earliest), Some(latest)) if count > 0 => Some(ArtifactDateRange {
+            earliest,
+            latest,
+            count,
+        }),
+        _ => None,
+    })
+}
+
 /// IDで成果物を取得する
 fn fetch_artifact_by_id(conn: &Connection, id: &Uuid) -> AppResult<Artifact> {
     let mut stmt = conn.prepare(
-        "SELECT id, name, artifact_type, reference, metadata, created_at
+        "SELECT id, name, artifact_type, reference, metadata, created_at, updated_at
          FROM artifacts WHERE id = ?",
     )?;
 
@@ -71,6 +134,7 @@ fn fetch_artifact_by_id(conn: &Connection, id: &Uuid) -> AppResult<Artifact> {
         .query_row([id.to_string()], |row| {
             let id_str: String = row.get(0)?;
             let created_at: DateTime<Utc> = row.get(5)?;
+            let updated_at: DateTime<Utc> = row.get(6)?;
             let metadata_str: Option<String> = row.get(4)?;
 
             Ok(Artifact {
@@ -80,6 +144,7 @@ fn fetch_artifact_by_id(conn: &Connection, id: &Uuid) -> AppResult<Artifact> {
                 reference: row.get(3)?,
                 metadata: metadata_str.and_then(|s| serde_json::from_str(&s).ok()),
                 created_at,
+                updated_at,
             })
         })
         .map_err(|_| AppError::NotFound(format!("Artifact with id {} not found", id)))?;
@@ -87,8 +152,88 @@ fn fetch_artifact_by_id(conn: &Connection, id: &Uuid) -> AppResult<Artifact> {
     Ok(artifact)
 }
 
+/// メタデータの指定キーの値が一致する成果物を取得する（メタデータがNULLの成果物は一致しない）
+fn fetch_artifacts_by_metadata(
+    conn: &Connection,
+    key: &str,
+    value: &str,
+) -> AppResult<Vec<Artifact>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, artifact_type, reference, metadata, created_at, updated_at
+         FROM artifacts
+         WHERE metadata IS NOT NULL AND json_extract_string(metadata, ?) = ?
+         ORDER BY created_at DESC, id",
+    )?;
+
+    let rows = stmt.query_map([key, value], |row| {
+        let id_str: String = row.get(0)?;
+        let created_at: DateTime<Utc> = row.get(5)?;
+        let updated_at: DateTime<Utc> = row.get(6)?;
+        let metadata_str: Option<String> = row.get(4)?;
+
+        Ok(Artifact {
+            id: Uuid::parse_str(&id_str).unwrap(),
+            name: row.get(1)?,
+            artifact_type: row.get(2)?,
+            reference: row.get(3)?,
+            metadata: metadata_str.and_then(|s| serde_json::from_str(&s).ok()),
+            created_at,
+            updated_at,
+        })
+    })?;
+
+    let mut artifacts = Vec::new();
+    for row in rows {
+        artifacts.push(row?);
+    }
+    Ok(artifacts)
+}
+
+/// 参照先が完全一致する成果物を取得する（大文字小文字を区別する）
+fn fetch_artifact_by_reference(conn: &Connection, reference: &str) -> AppResult<Option<Artifact>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, artifact_type, reference, metadata, created_at, updated_at
+         FROM artifacts WHERE reference = ?
+         ORDER BY created_at ASC LIMIT 1",
+    )?;
+
+    let result = stmt.query_row([reference], |row| {
+        let id_str: String = row.get(0)?;
+        let created_at: DateTime<Utc> = row.get(5)?;
+        let updated_at: DateTime<Utc> = row.get(6)?;
+        let metadata_str: Option<String> = row.get(4)?;
+
+        Ok(Artifact {
+            id: Uuid::parse_str(&id_str).unwrap(),
+            name: row.get(1)?,
+            artifact_type: row.get(2)?,
+            reference: row.get(3)?,
+            metadata: metadata_str.and_then(|s| serde_json::from_str(&s).ok()),
+            created_at,
+            updated_at,
+        })
+    });
+
+    match result {
+        Ok(artifact) => Ok(Some(artifact)),
+        Err(duckdb::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(AppError::Database(e)),
+    }
+}
+
+/// 参照先が同じ既存の成果物があればそれを返し、なければ新規に保存する
+fn insert_or_reuse_artifact(conn: &Connection, artifact: Artifact) -> AppResult<(Artifact, bool)> {
+    if let Some(ref reference) = artifact.reference {
+        if let Some(existing) = fetch_artifact_by_reference(conn, reference)? {
+            return Ok((existing, true));
+        }
+    }
+    insert_artifact(conn, &artifact)?;
+    Ok((artifact, false))
+}
+
 /// エントリに成果物を紐付ける
-fn link_artifact_to_entry(conn: &Connection, entry_id: &Uuid, artifact_id: &Uuid) -> AppResult<()> {
+pub(crate) fn link_artifact_to_entry(conn: &Connection, entry_id: &Uuid, artifact_id: &Uuid) -> AppResult<()> {
     conn.execute(
         "INSERT INTO entry_artifacts (entry_id, artifact_id) VALUES (?, ?)",
         duckdb::params![entry_id.to_string(), artifact_id.to_string()],
@@ -96,6 +241,16 @@ fn link_artifact_to_entry(conn: &Connection, entry_id: &Uuid, artifact_id: &Uuid
     Ok(())
 }
 
+/// エントリと成果物が紐付いているか確認する
+fn is_linked(conn: &Connection, entry_id: &Uuid, artifact_id: &Uuid) -> AppResult<bool> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM entry_artifacts WHERE entry_id = ? AND artifact_id = ?",
+        duckdb::params![entry_id.to_string(), artifact_id.to_string()],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
+
 /// エントリから成果物の紐付けを解除する
 fn unlink_artifact_from_entry(
     conn: &Connection,
@@ -116,24 +271,160 @@ fn unlink_artifact_from_entry(
     Ok(())
 }
 
-/// 成果物を作成する
-#[tauri::command]
-pub fn create_artifact(
-    state: tauri::State<AppState>,
-    artifact: CreateArtifact,
-    entry_id: Option<String>,
-) -> AppResult<Artifact> {
+/// メモ中のhttp(s) URLを出現順に重複なく抽出する（末尾の句読点は含めない）
+fn extract_urls(text: &str) -> Vec<String> {
+    static URL_PATTERN: OnceLock<Regex> = OnceLock::new();
+    let pattern = URL_PATTERN.get_or_init(|| {
+        Regex::new(r#"https?://[^\s<>()\[\]{}"'`、。「」]+"#).expect("valid URL pattern")
+    });
+
+    let mut urls: Vec<String> = Vec::new();
+    for m in pattern.find_iter(text) {
+        let url = m
+            .as_str()
+            .trim_end_matches(['.', ',', ';', ':', '!', '?'])
+            .to_string();
+        if !urls.contains(&url) {
+            urls.push(url);
+        }
+    }
+    urls
+}
+
+/// エントリのメモからURLの成果物を作成（参照先で重複排除）し、エントリに紐付ける
+fn link_urls_from_memo(conn: &Connection, entry_id: &Uuid) -> AppResult<Vec<Artifact>> {
+    let tx = conn.unchecked_transaction()?;
+    let memo: Option<String> = match tx.query_row(
+        "SELECT memo FROM time_entries WHERE id = ?",
+        [entry_id.to_string()],
+        |row| row.get(0),
+    ) {
+        Ok(memo) => memo,
+        Err(duckdb::Error::QueryReturnedNoRows) => {
+            return Err(AppError::NotFound(format!(
+                "Entry with id {} not found",
+                entry_id
+            )))
+        }
+        Err(e) => return Err(AppError::Database(e)),
+    };
+
+    let mut artifacts = Vec::new();
+    for url in extract_urls(memo.as_deref().unwrap_or_default()) {
+        let candidate = Artifact::new(url.clone(), "url".to_string(), Some(url), None);
+        let (artifact, _) = insert_or_reuse_artifact(&tx, candidate)?;
+        if !is_linked(&tx, entry_id, &artifact.id)? {
+            link_artifact_to_entry(&tx, entry_id, &artifact.id)?;
+        }
+        artifacts.push(artifact);
+    }
+    tx.commit()?;
+
+    Ok(artifacts)
+}
+
+/// 成果物作成リクエストの内容を検証する
+pub(crate) fn validate_create_artifact(artifact: &CreateArtifact) -> AppResult<()> {
     if artifact.name.trim().is_empty() {
         return Err(AppError::InvalidInput(
             "Artifact name cannot be empty".to_string(),
         ));
     }
+    ensure_max_length(&artifact.name, "Artifact name", MAX_NAME_LENGTH)?;
 
     if artifact.artifact_type.trim().is_empty() {
         return Err(AppError::InvalidInput(
             "Artifact type cannot be empty".to_string(),
         ));
     }
+    Ok(())
+}
+
+/// 比較用に成果物の種別を揃える（正規化しない場合はそのまま）
+fn normalize_artifact_type(artifact_type: &str, normalize: bool) -> String {
+    if normalize {
+        artifact_type.trim().to_lowercase()
+    } else {
+        artifact_type.to_string()
+    }
+}
+
+/// 設定から成果物の種別の運用方針を取得する（未設定なら許可リストは空で強制しない）
+fn fetch_artifact_type_policy(conn: &Connection) -> AppResult<ArtifactTypePolicy> {
+    let types = match fetch_setting(conn, ARTIFACT_TYPES_KEY)? {
+        Some(json) => serde_json::from_str(&json)?,
+        None => Vec::new(),
+    };
+    Ok(ArtifactTypePolicy {
+        types,
+        enforce: fetch_setting(conn, ARTIFACT_TYPES_ENFORCED_KEY)?.as_deref() == Some("true"),
+        normalize: fetch_setting(conn, ARTIFACT_TYPES_NORMALIZE_KEY)?.as_deref() == Some("true"),
+    })
+}
+
+/// 成果物の種別の運用方針を保存する（正規化する場合は許可リストも正規化して重複を除く）
+fn store_artifact_type_policy(
+    conn: &Connection,
+    policy: ArtifactTypePolicy,
+) -> AppResult<ArtifactTypePolicy> {
+    let mut types: Vec<String> = Vec::new();
+    for artifact_type in &policy.types {
+        if artifact_type.trim().is_empty() {
+            return Err(AppError::InvalidInput(
+                "Artifact type cannot be empty".to_string(),
+            ));
+        }
+        let artifact_type = normalize_artifact_type(artifact_type, policy.normalize);
+        if !types.contains(&artifact_type) {
+            types.push(artifact_type);
+        }
+    }
+    if policy.enforce && types.is_empty() {
+        return Err(AppError::InvalidInput(
+            "At least one artifact type is required to enforce the list".to_string(),
+        ));
+    }
+
+    let policy = ArtifactTypePolicy { types, ..policy };
+    let tx = conn.unchecked_transaction()?;
+    store_setting(&tx, ARTIFACT_TYPES_KEY, Some(&serde_json::to_string(&policy.types)?))?;
+    store_setting(&tx, ARTIFACT_TYPES_ENFORCED_KEY, Some(&policy.enforce.to_string()))?;
+    store_setting(&tx, ARTIFACT_TYPES_NORMALIZE_KEY, Some(&policy.normalize.to_string()))?;
+    tx.commit()?;
+    Ok(policy)
+}
+
+/// 許可リストの強制が有効な場合、種別がリストに含まれているか検証する
+pub(crate) fn ensure_allowed_artifact_type(conn: &Connection, artifact_type: &str) -> AppResult<()> {
+    let policy = fetch_artifact_type_policy(conn)?;
+    if !policy.enforce {
+        return Ok(());
+    }
+
+    let artifact_type = normalize_artifact_type(artifact_type, policy.normalize);
+    let allowed = policy
+        .types
+        .iter()
+        .any(|allowed| normalize_artifact_type(allowed, policy.normalize) == artifact_type);
+    if !allowed {
+        return Err(AppError::InvalidInput(format!(
+            "Artifact type '{}' is not allowed. Allowed types: {}",
+            artifact_type,
+            policy.types.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+/// 成果物を作成する
+#[tauri::command]
+pub fn create_artifact(
+    state: tauri::State<AppState>,
+    artifact: CreateArtifact,
+    entry_id: Option<String>,
+    dedupe: Option<bool>,
+) -> AppResult<Artifact> {
+    validate_create_artifact(&artifact)?;
 
     let new_artifact = Artifact::new(
         artifact.name,
@@ -143,23 +434,237 @@ pub fn create_artifact(
     );
 
     state.db.with_connection(|conn| {
-        insert_artifact(conn, &new_artifact)?;
+        ensure_allowed_artifact_type(conn, &new_artifact.artifact_type)?;
+
+        let (artifact, reused) = if dedupe.unwrap_or(false) {
+            insert_or_reuse_artifact(conn, new_artifact)?
+        } else {
+            insert_artifact(conn, &new_artifact)?;
+            (new_artifact, false)
+        };
 
         // エントリIDが指定されていれば紐付ける
         if let Some(ref eid) = entry_id {
             let entry_uuid = Uuid::parse_str(eid)
                 .map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", eid)))?;
-            link_artifact_to_entry(conn, &entry_uuid, &new_artifact.id)?;
+            // 再利用した成果物が既に紐付いている場合は何もしない
+            let already_linked = reused && is_linked(conn, &entry_uuid, &artifact.id)?;
+            if !already_linked {
+                link_artifact_to_entry(conn, &entry_uuid, &artifact.id)?;
+            }
         }
 
-        Ok(new_artifact)
+        Ok(artifact)
+    })
+}
+
+/// 成果物の作成日時の範囲と件数を取得する（種別で絞り込み可能）
+#[tauri::command]
+pub fn get_artifact_date_range(
+    state: tauri::State<AppState>,
+    artifact_type: Option<String>,
+) -> AppResult<Option<ArtifactDateRange>> {
+    state
+        .db
+        .with_connection(|conn| fetch_artifact_date_range(conn, artifact_type.as_deref()))
+}
+
+/// 成果物の種別の許可リストと運用方針を取得する
+#[tauri::command]
+pub fn get_artifact_types(state: tauri::State<AppState>) -> AppResult<ArtifactTypePolicy> {
+    state.db.with_connection(fetch_artifact_type_policy)
+}
+
+/// 成果物の種別の許可リストを設定する（enforce・normalizeは省略時に無効）
+#[tauri::command]
+pub fn set_artifact_types(
+    state: tauri::State<AppState>,
+    types: Vec<String>,
+    enforce: Option<bool>,
+    normalize: Option<bool>,
+) -> AppResult<ArtifactTypePolicy> {
+    let policy = ArtifactTypePolicy {
+        types,
+        enforce: enforce.unwrap_or(false),
+        normalize: normalize.unwrap_or(false),
+    };
+    state
+        .db
+        .with_connection(|conn| store_artifact_type_policy(conn, policy))
+}
+
+/// 時間記録のメモに含まれるURLを成果物として紐付ける
+#[tauri::command]
+pub fn extract_links_from_memo(
+    state: tauri::State<AppState>,
+    entry_id: String,
+) -> AppResult<Vec<Artifact>> {
+    let entry_uuid = Uuid::parse_str(&entry_id)
+        .map_err(|_| AppError::InvalidInput(format!("Invalid entry UUID: {}", entry_id)))?;
+
+    state
+        .db
+        .with_connection(|conn| link_urls_from_memo(conn, &entry_uuid))
+}
+
+/// ファイルを管理ディレクトリにコピーする（同名のファイルがあれば `名前-1.拡張子` のように連番を付ける）
+fn copy_into_managed_dir(source: &Path, managed_dir: &Path) -> AppResult<PathBuf> {
+    let file_name = source
+        .file_name()
+        .ok_or_else(|| AppError::InvalidInput(format!("Invalid file path: {}", source.display())))?;
+    let stem = Path::new(file_name)
+        .file_stem()
+        .unwrap_or(file_name)
+        .to_string_lossy()
+        .to_string();
+    let extension = Path::new(file_name)
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+
+    fs::create_dir_all(managed_dir)?;
+    let mut input = File::open(source)?;
+    let mut candidate = managed_dir.join(file_name);
+    let mut suffix = 0;
+    loop {
+        // create_newで作成できた場合だけ使う（既存のファイルは上書きしない）
+        match OpenOptions::new().write(true).create_new(true).open(&candidate) {
+            Ok(mut output) => {
+                io::copy(&mut input, &mut output)?;
+                return Ok(candidate);
+            }
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                suffix += 1;
+                candidate = managed_dir.join(format!("{}-{}{}", stem, suffix, extension));
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+/// ファイル内容のSHA-256を16進文字列で返す
+fn file_sha256(path: &Path) -> AppResult<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// ファイルを管理ディレクトリに取り込み、成果物として作成してエントリに紐付ける
+fn import_file_into(
+    conn: &Connection,
+    managed_dir: &Path,
+    entry_id: &Uuid,
+    source: &Path,
+) -> AppResult<Artifact> {
+    if !source.is_file() {
+        return Err(AppError::NotFound(format!(
+            "File not found: {}",
+            source.display()
+        )));
+    }
+    // コピーしてから存在しないことが分かるとファイルが残るため先に確認する
+    let entry_count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM time_entries WHERE id = ?",
+        [entry_id.to_string()],
+        |row| row.get(0),
+    )?;
+    if entry_count == 0 {
+        return Err(AppError::NotFound(format!(
+            "Entry with id {} not found",
+            entry_id
+        )));
+    }
+
+    let copied = copy_into_managed_dir(source, managed_dir)?;
+    let result = insert_file_artifact(conn, entry_id, source, &copied);
+    if result.is_err() {
+        // DBに保存できなかったコピーは残さない
+        let _ = fs::remove_file(&copied);
+    }
+    result
+}
+
+/// 管理ディレクトリにコピーしたファイルの成果物を作成してエントリに紐付ける
+fn insert_file_artifact(
+    conn: &Connection,
+    entry_id: &Uuid,
+    source: &Path,
+    copied: &Path,
+) -> AppResult<Artifact> {
+    let metadata = serde_json::json!({
+        "size_bytes": fs::metadata(copied)?.len(),
+        "sha256": file_sha256(copied)?,
+        "original_path": source.to_string_lossy(),
+    });
+    let name = copied
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let artifact = Artifact::new(
+        name,
+        "file".to_string(),
+        Some(copied.to_string_lossy().to_string()),
+        Some(metadata),
+    );
+
+    let tx = conn.unchecked_transaction()?;
+    insert_artifact(&tx, &artifact)?;
+    link_artifact_to_entry(&tx, entry_id, &artifact.id)?;
+    tx.commit()?;
+    Ok(artifact)
+}
+
+/// ファイルをアプリのデータディレクトリにコピーし、成果物としてエントリに紐付ける
+#[tauri::command]
+pub fn import_file_artifact(
+    state: tauri::State<AppState>,
+    entry_id: String,
+    source_path: String,
+) -> AppResult<Artifact> {
+    let entry_uuid = Uuid::parse_str(&entry_id)
+        .map_err(|_| AppError::InvalidInput(format!("Invalid entry UUID: {}", entry_id)))?;
+    let managed_dir = state
+        .db
+        .path()
+        .and_then(|path| path.parent())
+        .map(|dir| dir.join(MANAGED_ARTIFACTS_DIR))
+        .ok_or_else(|| {
+            AppError::OperationFailed("Database has no data directory".to_string())
+        })?;
+
+    state.db.with_connection(|conn| {
+        import_file_into(conn, &managed_dir, &entry_uuid, Path::new(&source_path))
     })
 }
 
 /// 成果物一覧を取得する
 #[tauri::command]
-pub fn list_artifacts(state: tauri::State<AppState>, limit: Option<i64>) -> AppResult<Vec<Artifact>> {
-    state.db.with_connection(|conn| fetch_artifacts(conn, limit))
+pub fn list_artifacts(
+    state: tauri::State<AppState>,
+    limit: Option<i64>,
+    unlinked_only: Option<bool>,
+) -> AppResult<Vec<ArtifactWithUsage>> {
+    state
+        .db
+        .with_connection(|conn| fetch_artifacts(conn, limit, unlinked_only.unwrap_or(false)))
+}
+
+/// メタデータのキーと値で成果物を検索する
+#[tauri::command]
+pub fn find_artifacts_by_metadata(
+    state: tauri::State<AppState>,
+    key: String,
+    value: String,
+) -> AppResult<Vec<Artifact>> {
+    if key.trim().is_empty() {
+        return Err(AppError::InvalidInput(
+            "Metadata key cannot be empty".to_string(),
+        ));
+    }
+
+    state
+        .db
+        .with_connection(|conn| fetch_artifacts_by_metadata(conn, key.trim(), &value))
 }
 
 /// エントリに成果物を紐付ける
@@ -199,27 +704,91 @@ pub fn unlink_artifact(
         .with_connection(|conn| unlink_artifact_from_entry(conn, &entry_uuid, &artifact_uuid))
 }
 
-/// 成果物を削除する
+/// 指定された項目だけ成果物を更新する（紐付けはそのまま残る）
+///
+/// 参照先に空文字を指定すると参照先を消す。
+fn update_artifact_fields(
+    conn: &Connection,
+    id: &Uuid,
+    name: Option<String>,
+    reference: Option<String>,
+    metadata: Option<serde_json::Value>,
+) -> AppResult<Artifact> {
+    let mut artifact = fetch_artifact_by_id(conn, id)?;
+
+    if let Some(name) = name {
+        if name.trim().is_empty() {
+            return Err(AppError::InvalidInput(
+                "Artifact name cannot be empty".to_string(),
+            ));
+        }
+        ensure_max_length(&name, "Artifact name", MAX_NAME_LENGTH)?;
+        artifact.name = name;
+    }
+    if let Some(reference) = reference {
+        artifact.reference = Some(reference).filter(|r| !r.trim().is_empty());
+    }
+    if let Some(metadata) = metadata {
+        artifact.metadata = Some(metadata);
+    }
+    artifact.updated_at = Utc::now();
+
+    conn.execute(
+        "UPDATE artifacts SET name = ?, reference = ?, metadata = ?, updated_at = ? WHERE id = ?",
+        duckdb::params![
+            &artifact.name,
+            &artifact.reference,
+            artifact.metadata.as_ref().map(|m| m.to_string()),
+            artifact.updated_at,
+            artifact.id.to_string(),
+        ],
+    )?;
+    Ok(artifact)
+}
+
+/// 成果物の名前・参照先・メタデータを更新する
 #[tauri::command]
-pub fn delete_artifact(state: tauri::State<AppState>, id: String) -> AppResult<()> {
+pub fn update_artifact(
+    state: tauri::State<AppState>,
+    id: String,
+    name: Option<String>,
+    reference: Option<String>,
+    metadata: Option<serde_json::Value>,
+) -> AppResult<Artifact> {
     let artifact_id = Uuid::parse_str(&id)
         .map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", id)))?;
 
     state.db.with_connection(|conn| {
-        // 成果物が存在するか確認
-        let _ = fetch_artifact_by_id(conn, &artifact_id)?;
+        update_artifact_fields(conn, &artifact_id, name, reference, metadata)
+    })
+}
 
-        // 紐付けを削除（ON DELETE CASCADEがあるが明示的に）
-        conn.execute(
-            "DELETE FROM entry_artifacts WHERE artifact_id = ?",
-            [artifact_id.to_string()],
-        )?;
+/// 成果物と紐付けを削除し、削除した紐付けの件数を返す
+fn delete_artifact_by_id(conn: &Connection, artifact_id: &Uuid) -> AppResult<usize> {
+    // 成果物が存在するか確認
+    let _ = fetch_artifact_by_id(conn, artifact_id)?;
+
+    // 紐付けを削除（ON DELETE CASCADEがあるが明示的に）
+    let links_removed = conn.execute(
+        "DELETE FROM entry_artifacts WHERE artifact_id = ?",
+        [artifact_id.to_string()],
+    )?;
 
-        // 成果物を削除
-        conn.execute("DELETE FROM artifacts WHERE id = ?", [artifact_id.to_string()])?;
+    // 成果物を削除
+    conn.execute("DELETE FROM artifacts WHERE id = ?", [artifact_id.to_string()])?;
 
-        Ok(())
-    })
+    Ok(links_removed)
+}
+
+/// 成果物を削除する（削除した紐付けの件数を返す）
+#[tauri::command]
+pub fn delete_artifact(state: tauri::State<AppState>, id: String) -> AppResult<usize> {
+    let artifact_id = Uuid::parse_str(&id)
+        .map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", id)))?;
+
+    state
+        .db
+        .with_connection(|conn| delete_artifact_by_id(conn, &artifact_id))
 }
 
 #[cfg(test)]
@@ -231,6 +800,85 @@ mod tests {
         Database::new_in_memory().unwrap()
     }
 
+    fn insert_entry_row(conn: &Connection) -> AppResult<Uuid> {
+        let entry = TimeEntry::start(None, None);
+        conn.execute(
+            "INSERT INTO time_entries (id, started_at, ended_at, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?)",
+            duckdb::params![
+                entry.id.to_string(),
+                entry.started_at,
+                entry.started_at,
+                entry.created_at,
+                entry.updated_at,
+            ],
+        )?;
+        Ok(entry.id)
+    }
+
+    mod artifact_type_policy_tests {
+        use super::*;
+
+        #[test]
+        fn 強制しない場合はどの種別でも許可される() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                ensure_allowed_artifact_type(conn, "anything")?;
+
+                let policy = ArtifactTypePolicy {
+                    types: vec!["document".to_string()],
+                    enforce: false,
+                    normalize: false,
+                };
+                store_artifact_type_policy(conn, policy)?;
+                ensure_allowed_artifact_type(conn, "doc")
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 強制する場合は許可リストにない種別を拒否する() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let policy = ArtifactTypePolicy {
+                    types: vec!["document".to_string(), "url".to_string()],
+                    enforce: true,
+                    normalize: false,
+                };
+                store_artifact_type_policy(conn, policy.clone())?;
+                assert_eq!(fetch_artifact_type_policy(conn)?, policy);
+
+                ensure_allowed_artifact_type(conn, "document")?;
+                let result = ensure_allowed_artifact_type(conn, "Document");
+                assert!(matches!(result, Err(AppError::InvalidInput(_))));
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 正規化すると空白と大文字小文字の違いを無視して比較する() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let policy = ArtifactTypePolicy {
+                    types: vec![" Document".to_string(), "document".to_string()],
+                    enforce: true,
+                    normalize: true,
+                };
+                let stored = store_artifact_type_policy(conn, policy)?;
+                assert_eq!(stored.types, vec!["document".to_string()]);
+
+                ensure_allowed_artifact_type(conn, "  DOCUMENT ")?;
+                assert!(ensure_allowed_artifact_type(conn, "doc").is_err());
+                Ok(())
+            })
+            .unwrap();
+        }
+    }
+
     mod create_artifact_tests {
         use super::*;
 
@@ -308,12 +956,95 @@ mod tests {
     mod list_artifacts_tests {
         use super::*;
 
+        #[test]
+        fn 作成日時が同じ成果物は毎回同じ順序で返る() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let created_at = Utc::now();
+                let mut expected = Vec::new();
+                for i in 0..5 {
+                    let mut artifact =
+                        Artifact::new(format!("成果物{}", i), "document".to_string(), None, None);
+                    artifact.created_at = created_at;
+                    insert_artifact(conn, &artifact)?;
+                    expected.push(artifact.id.to_string());
+                }
+                expected.sort();
+
+                for _ in 0..3 {
+                    let ids: Vec<String> = fetch_artifacts(conn, None, false)?
+                        .iter()
+                        .map(|a| a.artifact.id.to_string())
+                        .collect();
+                    assert_eq!(ids, expected);
+                }
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 参照している時間記録の件数が取得できる() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let used = Artifact::new("使用中".to_string(), "document".to_string(), None, None);
+                let unused = Artifact::new("未使用".to_string(), "document".to_string(), None, None);
+                insert_artifact(conn, &used)?;
+                insert_artifact(conn, &unused)?;
+                for _ in 0..2 {
+                    let entry_id = insert_entry_row(conn)?;
+                    link_artifact_to_entry(conn, &entry_id, &used.id)?;
+                }
+
+                let artifacts = fetch_artifacts(conn, None, false)?;
+                let count_of = |id: &Uuid| {
+                    artifacts
+                        .iter()
+                        .find(|a| a.artifact.id == *id)
+                        .map(|a| a.usage_count)
+                };
+                assert_eq!(count_of(&used.id), Some(2));
+                assert_eq!(count_of(&unused.id), Some(0));
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 未紐付けのみを指定するとエントリーに紐付いていない成果物だけが返る() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let used = Artifact::new("使用中".to_string(), "document".to_string(), None, None);
+                insert_artifact(conn, &used)?;
+                let entry_id = insert_entry_row(conn)?;
+                link_artifact_to_entry(conn, &entry_id, &used.id)?;
+                for i in 0..3 {
+                    let unused =
+                        Artifact::new(format!("未使用{}", i), "document".to_string(), None, None);
+                    insert_artifact(conn, &unused)?;
+                }
+
+                let artifacts = fetch_artifacts(conn, None, true)?;
+                assert_eq!(artifacts.len(), 3);
+                assert!(artifacts.iter().all(|a| a.usage_count == 0));
+                assert!(artifacts.iter().all(|a| a.artifact.id != used.id));
+
+                let limited = fetch_artifacts(conn, Some(2), true)?;
+                assert_eq!(limited.len(), 2);
+                Ok(())
+            })
+            .unwrap();
+        }
+
         #[test]
         fn 空のデータベースから成果物一覧を取得すると空のベクターが返る() {
             let db = create_test_db();
 
             let artifacts = db
-                .with_connection(|conn| fetch_artifacts(conn, None))
+                .with_connection(|conn| fetch_artifacts(conn, None, false))
                 .unwrap();
 
             assert!(artifacts.is_empty());
@@ -327,10 +1058,10 @@ mod tests {
 
             db.with_connection(|conn| {
                 insert_artifact(conn, &artifact)?;
-                let artifacts = fetch_artifacts(conn, None)?;
+                let artifacts = fetch_artifacts(conn, None, false)?;
 
                 assert_eq!(artifacts.len(), 1);
-                assert_eq!(artifacts[0].name, "テスト");
+                assert_eq!(artifacts[0].artifact.name, "テスト");
                 Ok(())
             })
             .unwrap();
@@ -351,7 +1082,7 @@ mod tests {
                     insert_artifact(conn, &artifact)?;
                 }
 
-                let artifacts = fetch_artifacts(conn, Some(3))?;
+                let artifacts = fetch_artifacts(conn, Some(3), false)?;
                 assert_eq!(artifacts.len(), 3);
                 Ok(())
             })
@@ -373,10 +1104,142 @@ mod tests {
                     Artifact::new("成果物2".to_string(), "document".to_string(), None, None);
                 insert_artifact(conn, &artifact2)?;
 
-                let artifacts = fetch_artifacts(conn, None)?;
+                let artifacts = fetch_artifacts(conn, None, false)?;
                 assert_eq!(artifacts.len(), 2);
-                assert_eq!(artifacts[0].name, "成果物2"); // 新しい方が先
-                assert_eq!(artifacts[1].name, "成果物1");
+                assert_eq!(artifacts[0].artifact.name, "成果物2"); // 新しい方が先
+                assert_eq!(artifacts[1].artifact.name, "成果物1");
+                Ok(())
+            })
+            .unwrap();
+        }
+    }
+
+    mod artifact_date_range_tests {
+        use super::*;
+
+        #[test]
+        fn 種別で絞り込んだ作成日時の範囲と件数を取得できる() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                assert_eq!(fetch_artifact_date_range(conn, None)?, None);
+
+                conn.execute_batch(
+                    "INSERT INTO artifacts (id, name, artifact_type, created_at) VALUES
+                     ('a1', '資料1', 'document', '2024-11-01 09:00:00+00'),
+                     ('a2', '資料2', 'document', '2024-12-15 09:00:00+00'),
+                     ('a3', 'PR', 'pull_request', '2025-01-01 09:00:00+00');",
+                )?;
+
+                let range = fetch_artifact_date_range(conn, Some("document"))?.unwrap();
+                assert_eq!(range.count, 2);
+                assert_eq!(range.earliest.to_rfc3339(), "2024-11-01T09:00:00+00:00");
+                assert_eq!(range.latest.to_rfc3339(), "2024-12-15T09:00:00+00:00");
+
+                assert_eq!(fetch_artifact_date_range(conn, None)?.unwrap().count, 3);
+                assert_eq!(fetch_artifact_date_range(conn, Some("url"))?, None);
+                Ok(())
+            })
+            .unwrap();
+        }
+    }
+
+    mod dedupe_artifact_tests {
+        use super::*;
+
+        fn pull_request(reference: &str) -> Artifact {
+            Artifact::new(
+                "PR".to_string(),
+                "pull_request".to_string(),
+                Some(reference.to_string()),
+                None,
+            )
+        }
+
+        #[test]
+        fn 同じ参照先の成果物は再利用される() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let (first, reused) =
+                    insert_or_reuse_artifact(conn, pull_request("https://github.com/o/r/pull/1"))?;
+                assert!(!reused);
+
+                let (second, reused) =
+                    insert_or_reuse_artifact(conn, pull_request("https://github.com/o/r/pull/1"))?;
+                assert!(reused);
+                assert_eq!(second.id, first.id);
+                assert_eq!(fetch_artifacts(conn, None, false)?.len(), 1);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 参照先の比較は大文字小文字を区別する() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                insert_or_reuse_artifact(conn, pull_request("https://github.com/o/r/pull/1"))?;
+                let (_, reused) =
+                    insert_or_reuse_artifact(conn, pull_request("https://github.com/O/R/pull/1"))?;
+                assert!(!reused);
+                assert_eq!(fetch_artifacts(conn, None, false)?.len(), 2);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 参照先がない成果物は常に新規作成される() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let note = || Artifact::new("メモ".to_string(), "note".to_string(), None, None);
+                insert_or_reuse_artifact(conn, note())?;
+                let (_, reused) = insert_or_reuse_artifact(conn, note())?;
+                assert!(!reused);
+                Ok(())
+            })
+            .unwrap();
+        }
+    }
+
+    mod find_artifacts_by_metadata_tests {
+        use super::*;
+
+        #[test]
+        fn メタデータの値が一致する成果物だけが返る() {
+            let db = create_test_db();
+            let foo = Artifact::new(
+                "PR #42".to_string(),
+                "pull_request".to_string(),
+                None,
+                Some(serde_json::json!({ "repo": "foo", "pr": 42 })),
+            );
+            let bar = Artifact::new(
+                "PR #7".to_string(),
+                "pull_request".to_string(),
+                None,
+                Some(serde_json::json!({ "repo": "bar", "pr": 7 })),
+            );
+            let no_metadata = Artifact::new("メモ".to_string(), "note".to_string(), None, None);
+
+            db.with_connection(|conn| {
+                insert_artifact(conn, &foo)?;
+                insert_artifact(conn, &bar)?;
+                insert_artifact(conn, &no_metadata)?;
+
+                let by_repo = fetch_artifacts_by_metadata(conn, "repo", "foo")?;
+                assert_eq!(by_repo.len(), 1);
+                assert_eq!(by_repo[0].id, foo.id);
+
+                // 数値は文字列として比較される
+                let by_pr = fetch_artifacts_by_metadata(conn, "pr", "7")?;
+                assert_eq!(by_pr.len(), 1);
+                assert_eq!(by_pr[0].id, bar.id);
+
+                assert!(fetch_artifacts_by_metadata(conn, "missing", "foo")?.is_empty());
                 Ok(())
             })
             .unwrap();
@@ -513,6 +1376,157 @@ mod tests {
         }
     }
 
+    mod extract_links_tests {
+        use super::*;
+
+        fn insert_entry_with_memo(conn: &Connection, memo: &str) -> AppResult<Uuid> {
+            let entry = TimeEntry::start(None, Some(memo.to_string()));
+            conn.execute(
+                "INSERT INTO time_entries (id, started_at, ended_at, memo, created_at, updated_at)
+                 VALUES (?, ?, ?, ?, ?, ?)",
+                duckdb::params![
+                    entry.id.to_string(),
+                    entry.started_at,
+                    entry.started_at,
+                    &entry.memo,
+                    entry.created_at,
+                    entry.updated_at,
+                ],
+            )?;
+            Ok(entry.id)
+        }
+
+        #[test]
+        fn メモからURLを抽出できる() {
+            let urls = extract_urls(
+                "レビュー https://example.com/pr/1, 資料は[こちら](http://example.com/doc)。\nhttps://example.com/pr/1",
+            );
+
+            assert_eq!(
+                urls,
+                vec![
+                    "https://example.com/pr/1".to_string(),
+                    "http://example.com/doc".to_string()
+                ]
+            );
+        }
+
+        #[test]
+        fn URLごとに成果物が作成されエントリに紐付く() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let entry_id =
+                    insert_entry_with_memo(conn, "https://example.com/a と https://example.com/b")?;
+
+                let artifacts = link_urls_from_memo(conn, &entry_id)?;
+                assert_eq!(artifacts.len(), 2);
+                assert!(artifacts.iter().all(|a| a.artifact_type == "url"));
+                for artifact in &artifacts {
+                    assert!(is_linked(conn, &entry_id, &artifact.id)?);
+                }
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 既存の成果物は参照先で再利用され重複して紐付かない() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let existing = Artifact::new(
+                    "既存".to_string(),
+                    "url".to_string(),
+                    Some("https://example.com/a".to_string()),
+                    None,
+                );
+                insert_artifact(conn, &existing)?;
+                let entry_id = insert_entry_with_memo(conn, "https://example.com/a")?;
+
+                let first = link_urls_from_memo(conn, &entry_id)?;
+                let second = link_urls_from_memo(conn, &entry_id)?;
+                assert_eq!(first[0].id, existing.id);
+                assert_eq!(second[0].id, existing.id);
+
+                let links: i64 = conn.query_row(
+                    "SELECT COUNT(*) FROM entry_artifacts WHERE entry_id = ?",
+                    [entry_id.to_string()],
+                    |row| row.get(0),
+                )?;
+                assert_eq!(links, 1);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 存在しないエントリはNotFoundになる() {
+            let db = create_test_db();
+
+            let result = db.with_connection(|conn| link_urls_from_memo(conn, &Uuid::new_v4()));
+
+            assert!(matches!(result, Err(AppError::NotFound(_))));
+        }
+    }
+
+    mod update_artifact_tests {
+        use super::*;
+
+        #[test]
+        fn 指定した項目だけ更新され紐付けと更新日時が保たれる() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let mut artifact = Artifact::new(
+                    "設計書（誤字".to_string(),
+                    "document".to_string(),
+                    Some("https://example.com/doc".to_string()),
+                    None,
+                );
+                artifact.updated_at = Utc::now() - chrono::Duration::days(1);
+                insert_artifact(conn, &artifact)?;
+                let entry_id = insert_entry_row(conn)?;
+                link_artifact_to_entry(conn, &entry_id, &artifact.id)?;
+
+                let updated = update_artifact_fields(
+                    conn,
+                    &artifact.id,
+                    Some("設計書".to_string()),
+                    None,
+                    None,
+                )?;
+                assert_eq!(updated.name, "設計書");
+                assert_eq!(updated.reference.as_deref(), Some("https://example.com/doc"));
+                assert!(updated.updated_at > artifact.updated_at);
+
+                let stored = fetch_artifact_by_id(conn, &artifact.id)?;
+                assert_eq!(stored.name, "設計書");
+                assert!(is_linked(conn, &entry_id, &artifact.id)?);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 空の名前や存在しない成果物はエラーになる() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let artifact = Artifact::new("メモ".to_string(), "note".to_string(), None, None);
+                insert_artifact(conn, &artifact)?;
+
+                let result = update_artifact_fields(conn, &artifact.id, Some(" ".to_string()), None, None);
+                assert!(matches!(result, Err(AppError::InvalidInput(_))));
+
+                let result = update_artifact_fields(conn, &Uuid::new_v4(), None, None, None);
+                assert!(matches!(result, Err(AppError::NotFound(_))));
+                Ok(())
+            })
+            .unwrap();
+        }
+    }
+
     mod delete_artifact_tests {
         use super::*;
 
@@ -573,6 +1587,26 @@ mod tests {
             .unwrap();
         }
 
+        #[test]
+        fn 削除した紐付けの件数が返される() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let artifact =
+                    Artifact::new("テスト".to_string(), "document".to_string(), None, None);
+                insert_artifact(conn, &artifact)?;
+                for _ in 0..3 {
+                    let entry_id = insert_entry_row(conn)?;
+                    link_artifact_to_entry(conn, &entry_id, &artifact.id)?;
+                }
+
+                assert_eq!(delete_artifact_by_id(conn, &artifact.id)?, 3);
+                assert!(fetch_artifact_by_id(conn, &artifact.id).is_err());
+                Ok(())
+            })
+            .unwrap();
+        }
+
         #[test]
         fn 存在しない成果物を削除しようとするとエラーになる() {
             let db = create_test_db();
@@ -583,4 +1617,71 @@ mod tests {
             assert!(result.is_err());
         }
     }
+
+    mod import_file_artifact_tests {
+        use super::*;
+
+        #[test]
+        fn ファイルをコピーしてサイズとハッシュ付きの成果物を作成する() {
+            let db = create_test_db();
+            let temp_dir = tempfile::tempdir().unwrap();
+            let source = temp_dir.path().join("notes.txt");
+            fs::write(&source, "hello").unwrap();
+            let managed_dir = temp_dir.path().join(MANAGED_ARTIFACTS_DIR);
+
+            db.with_connection(|conn| {
+                let entry_id = insert_entry_row(conn)?;
+                let artifact = import_file_into(conn, &managed_dir, &entry_id, &source)?;
+
+                let reference = PathBuf::from(artifact.reference.clone().unwrap());
+                assert_eq!(reference, managed_dir.join("notes.txt"));
+                assert_eq!(fs::read_to_string(&reference)?, "hello");
+                let metadata = artifact.metadata.clone().unwrap();
+                assert_eq!(metadata["size_bytes"], 5);
+                assert_eq!(
+                    metadata["sha256"],
+                    "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+                );
+                assert!(is_linked(conn, &entry_id, &artifact.id)?);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 同名のファイルは連番を付けて保存される() {
+            let db = create_test_db();
+            let temp_dir = tempfile::tempdir().unwrap();
+            let source = temp_dir.path().join("notes.txt");
+            fs::write(&source, "hello").unwrap();
+            let managed_dir = temp_dir.path().join(MANAGED_ARTIFACTS_DIR);
+
+            db.with_connection(|conn| {
+                let entry_id = insert_entry_row(conn)?;
+                import_file_into(conn, &managed_dir, &entry_id, &source)?;
+                let second = import_file_into(conn, &managed_dir, &entry_id, &source)?;
+
+                assert_eq!(second.name, "notes-1.txt");
+                assert!(managed_dir.join("notes-1.txt").exists());
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 存在しないエントリにはファイルをコピーしない() {
+            let db = create_test_db();
+            let temp_dir = tempfile::tempdir().unwrap();
+            let source = temp_dir.path().join("notes.txt");
+            fs::write(&source, "hello").unwrap();
+            let managed_dir = temp_dir.path().join(MANAGED_ARTIFACTS_DIR);
+
+            let result = db.with_connection(|conn| {
+                import_file_into(conn, &managed_dir, &Uuid::new_v4(), &source)
+            });
+
+            assert!(matches!(result, Err(AppError::NotFound(_))));
+            assert!(!managed_dir.exists());
+        }
+    }
 }
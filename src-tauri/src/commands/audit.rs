@@ -0,0 +1,154 @@
+use chrono::{DateTime, Utc};
+use duckdb::Connection;
+use uuid::Uuid;
+
+use crate::db::AuditLogEntry;
+use crate::error::{AppError, AppResult};
+use crate::AppState;
+
+/// 監査ログの取得件数の既定値
+const DEFAULT_AUDIT_LOG_LIMIT: i64 = 100;
+
+/// 監査ログを記録する（呼び出し元の変更と同じトランザクション内で呼ぶ）
+pub(crate) fn record_audit(
+    conn: &Connection,
+    entity_type: &str,
+    entity_id: &Uuid,
+    action: &str,
+    old_value: Option<&str>,
+    new_value: Option<&str>,
+) -> AppResult<()> {
+    conn.execute(
+        "INSERT INTO audit_log (id, entity_type, entity_id, action, old_value, new_value, \"at\")
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+        duckdb::params![
+            Uuid::new_v4().to_string(),
+            entity_type,
+            entity_id.to_string(),
+            action,
+            old_value,
+            new_value,
+            Utc::now(),
+        ],
+    )?;
+    Ok(())
+}
+
+/// 監査ログを新しい順に取得する（entity_id未指定の場合はすべて）
+fn fetch_audit_log(
+    conn: &Connection,
+    entity_id: Option<&Uuid>,
+    limit: i64,
+) -> AppResult<Vec<AuditLogEntry>> {
+    let mut sql = String::from(
+        "SELECT id, entity_type, entity_id, action, old_value, new_value, \"at\" FROM audit_log",
+    );
+    let mut params: Vec<Box<dyn duckdb::ToSql>> = Vec::new();
+    if let Some(entity_id) = entity_id {
+        sql.push_str(" WHERE entity_id = ?");
+        params.push(Box::new(entity_id.to_string()));
+    }
+    sql.push_str(" ORDER BY \"at\" DESC, id LIMIT ?");
+    params.push(Box::new(limit));
+
+    let mut stmt = conn.prepare(&sql)?;
+    let param_refs: Vec<&dyn duckdb::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+    let rows = stmt.query_map(param_refs.as_slice(), |row| {
+        let id_str: String = row.get(0)?;
+        let entity_id_str: String = row.get(2)?;
+        let at: DateTime<Utc> = row.get(6)?;
+
+        Ok(AuditLogEntry {
+            id: Uuid::parse_str(&id_str).unwrap(),
+            entity_type: row.get(1)?,
+            entity_id: Uuid::parse_str(&entity_id_str).unwrap(),
+            action: row.get(3)?,
+            old_value: row.get(4)?,
+            new_value: row.get(5)?,
+            at,
+        })
+    })?;
+
+    let mut entries = Vec::new();
+    for row in rows {
+        entries.push(row?);
+    }
+    Ok(entries)
+}
+
+/// 監査ログを取得する
+#[tauri::command]
+pub fn get_audit_log(
+    state: tauri::State<AppState>,
+    entity_id: Option<String>,
+    limit: Option<i64>,
+) -> AppResult<Vec<AuditLogEntry>> {
+    let entity_uuid = entity_id
+        .map(|id| {
+            Uuid::parse_str(&id).map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", id)))
+        })
+        .transpose()?;
+
+    let limit = limit.unwrap_or(DEFAULT_AUDIT_LOG_LIMIT);
+    if limit <= 0 {
+        return Err(AppError::InvalidInput(
+            "Limit must be greater than 0".to_string(),
+        ));
+    }
+
+    state
+        .db
+        .with_connection(|conn| fetch_audit_log(conn, entity_uuid.as_ref(), limit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+
+    fn create_test_db() -> Database {
+        Database::new_in_memory().unwrap()
+    }
+
+    mod fetch_audit_log_tests {
+        use super::*;
+
+        #[test]
+        fn 指定したエンティティの監査ログのみ取得できる() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let target = Uuid::new_v4();
+                let other = Uuid::new_v4();
+                record_audit(conn, "task", &target, "rename", Some("旧"), Some("新"))?;
+                record_audit(conn, "task", &other, "archive", Some("false"), Some("true"))?;
+
+                let log = fetch_audit_log(conn, Some(&target), 10)?;
+                assert_eq!(log.len(), 1);
+                assert_eq!(log[0].action, "rename");
+                assert_eq!(log[0].old_value.as_deref(), Some("旧"));
+                assert_eq!(log[0].new_value.as_deref(), Some("新"));
+
+                assert_eq!(fetch_audit_log(conn, None, 10)?.len(), 2);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 取得件数を制限できる() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let target = Uuid::new_v4();
+                for _ in 0..3 {
+                    record_audit(conn, "entry", &target, "update", None, None)?;
+                }
+
+                assert_eq!(fetch_audit_log(conn, Some(&target), 2)?.len(), 2);
+                Ok(())
+            })
+            .unwrap();
+        }
+    }
+}
@@ -0,0 +1,310 @@
+use chrono::{DateTime, Utc};
+use duckdb::Connection;
+use uuid::Uuid;
+
+use crate::commands::entries::{fetch_entry_by_id, write_entry_update};
+use crate::commands::tasks::fetch_task_by_id;
+use crate::commands::{ensure_max_length, MAX_NAME_LENGTH};
+use crate::db::{AutoRule, AutoRuleResult, UpdateEntry};
+use crate::error::{AppError, AppResult};
+use crate::AppState;
+
+/// 自動割り当てルールを作成順に取得する
+fn fetch_auto_rules(conn: &Connection) -> AppResult<Vec<AutoRule>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, pattern, task_id, created_at FROM auto_rules ORDER BY created_at, id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        let id_str: String = row.get(0)?;
+        let task_id_str: String = row.get(2)?;
+        let created_at: DateTime<Utc> = row.get(3)?;
+
+        Ok(AutoRule {
+            id: Uuid::parse_str(&id_str).unwrap(),
+            pattern: row.get(1)?,
+            task_id: Uuid::parse_str(&task_id_str).unwrap(),
+            created_at,
+        })
+    })?;
+
+    let mut rules = Vec::new();
+    for row in rows {
+        rules.push(row?);
+    }
+    Ok(rules)
+}
+
+/// 自動割り当てルールを追加する
+fn insert_auto_rule(conn: &Connection, pattern: &str, task_id: &Uuid) -> AppResult<AutoRule> {
+    let pattern = pattern.trim();
+    if pattern.is_empty() {
+        return Err(AppError::InvalidInput("Pattern cannot be empty".to_string()));
+    }
+    ensure_max_length(pattern, "Pattern", MAX_NAME_LENGTH)?;
+    fetch_task_by_id(conn, task_id)?;
+
+    let rule = AutoRule {
+        id: Uuid::new_v4(),
+        pattern: pattern.to_string(),
+        task_id: *task_id,
+        created_at: Utc::now(),
+    };
+    conn.execute(
+        "INSERT INTO auto_rules (id, pattern, task_id, created_at) VALUES (?, ?, ?, ?)",
+        duckdb::params![
+            rule.id.to_string(),
+            &rule.pattern,
+            rule.task_id.to_string(),
+            rule.created_at
+        ],
+    )?;
+    Ok(rule)
+}
+
+/// タスク未設定の時間記録のメモをルールと照合し、最初に一致したルールのタスクを割り当てる
+///
+/// ルールは作成順に照合し、アーカイブ済みや削除済みのタスクを指すルールは無視する。
+/// 停止処理の途中でも使えるよう、トランザクションは呼び出し側で管理する。
+pub(crate) fn apply_auto_rules_to_entry(conn: &Connection, entry_id: &Uuid) -> AppResult<AutoRuleResult> {
+    let result = conn.query_row(
+        "SELECT task_id, memo FROM time_entries WHERE id = ?",
+        [entry_id.to_string()],
+        |row| {
+            let task_id: Option<String> = row.get(0)?;
+            let memo: Option<String> = row.get(1)?;
+            Ok((task_id, memo))
+        },
+    );
+    let (task_id, memo) = match result {
+        Ok(row) => row,
+        Err(duckdb::Error::QueryReturnedNoRows) => {
+            return Err(AppError::NotFound(format!(
+                "Entry with id {} not found",
+                entry_id
+            )))
+        }
+        Err(e) => return Err(AppError::Database(e)),
+    };
+
+    let not_matched = AutoRuleResult {
+        matched: false,
+        task_id: None,
+    };
+    let Some(memo) = memo.filter(|m| !m.trim().is_empty()) else {
+        return Ok(not_matched);
+    };
+    if task_id.is_some() {
+        return Ok(not_matched);
+    }
+
+    let matched = conn.query_row(
+        "SELECT r.task_id FROM auto_rules r
+         JOIN tasks t ON t.id = r.task_id AND NOT t.archived
+         WHERE strpos(lower(?), lower(r.pattern)) > 0
+         ORDER BY r.created_at, r.id
+         LIMIT 1",
+        [memo],
+        |row| row.get::<_, String>(0),
+    );
+    let task_id = match matched {
+        Ok(task_id) => Uuid::parse_str(&task_id).unwrap(),
+        Err(duckdb::Error::QueryReturnedNoRows) => return Ok(not_matched),
+        Err(e) => return Err(AppError::Database(e)),
+    };
+
+    write_entry_update(
+        conn,
+        entry_id,
+        UpdateEntry {
+            task_id: Some(Some(task_id)),
+            ..Default::default()
+        },
+    )?;
+
+    Ok(AutoRuleResult {
+        matched: true,
+        task_id: Some(task_id),
+    })
+}
+
+/// ルールでタスクを割り当て、割り当てた記録の月の月次レポートのキャッシュを破棄する
+pub(crate) fn assign_task_by_rules(state: &AppState, entry_id: &Uuid) -> AppResult<AutoRuleResult> {
+    let (result, started_at) = state.db.with_connection(|conn| {
+        let tx = conn.unchecked_transaction()?;
+        let result = apply_auto_rules_to_entry(&tx, entry_id)?;
+        let started_at = fetch_entry_by_id(&tx, entry_id)?.started_at;
+        tx.commit()?;
+        Ok((result, started_at))
+    })?;
+    if result.matched {
+        state.report_cache.invalidate(started_at);
+    }
+    Ok(result)
+}
+
+/// 自動割り当てルール一覧を取得する
+#[tauri::command]
+pub fn list_auto_rules(state: tauri::State<AppState>) -> AppResult<Vec<AutoRule>> {
+    state.db.with_connection(fetch_auto_rules)
+}
+
+/// 自動割り当てルールを作成する
+#[tauri::command]
+pub fn create_auto_rule(
+    state: tauri::State<AppState>,
+    pattern: String,
+    task_id: String,
+) -> AppResult<AutoRule> {
+    let task_uuid = Uuid::parse_str(&task_id)
+        .map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", task_id)))?;
+
+    state
+        .db
+        .with_connection(|conn| insert_auto_rule(conn, &pattern, &task_uuid))
+}
+
+/// 自動割り当てルールを削除する
+#[tauri::command]
+pub fn delete_auto_rule(state: tauri::State<AppState>, id: String) -> AppResult<()> {
+    let rule_id = Uuid::parse_str(&id)
+        .map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", id)))?;
+
+    state.db.with_connection(|conn| {
+        let deleted = conn.execute("DELETE FROM auto_rules WHERE id = ?", [rule_id.to_string()])?;
+        if deleted == 0 {
+            return Err(AppError::NotFound(format!("Auto rule with id {} not found", id)));
+        }
+        Ok(())
+    })
+}
+
+/// タスク未設定の時間記録にルールでタスクを割り当てる
+#[tauri::command]
+pub fn apply_auto_rules(state: tauri::State<AppState>, entry_id: String) -> AppResult<AutoRuleResult> {
+    let entry_uuid = Uuid::parse_str(&entry_id)
+        .map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", entry_id)))?;
+
+    assign_task_by_rules(&state, &entry_uuid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+
+    fn create_test_db() -> Database {
+        Database::new_in_memory().unwrap()
+    }
+
+    fn insert_task(conn: &Connection, name: &str, archived: bool) -> AppResult<Uuid> {
+        let id = Uuid::new_v4();
+        conn.execute(
+            "INSERT INTO tasks (id, name, color, archived, created_at, updated_at)
+             VALUES (?, ?, '#000000', ?, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+            duckdb::params![id.to_string(), name, archived],
+        )?;
+        Ok(id)
+    }
+
+    fn insert_entry(conn: &Connection, task_id: Option<&Uuid>, memo: &str) -> AppResult<Uuid> {
+        let id = Uuid::new_v4();
+        conn.execute(
+            "INSERT INTO time_entries (id, task_id, started_at, ended_at, memo, created_at, updated_at)
+             VALUES (?, ?, '2024-01-15 09:00:00+00', '2024-01-15 10:00:00+00', ?, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+            duckdb::params![id.to_string(), task_id.map(|t| t.to_string()), memo],
+        )?;
+        Ok(id)
+    }
+
+    fn assigned_task(conn: &Connection, entry_id: &Uuid) -> AppResult<Option<String>> {
+        Ok(conn.query_row(
+            "SELECT task_id FROM time_entries WHERE id = ?",
+            [entry_id.to_string()],
+            |row| row.get(0),
+        )?)
+    }
+
+    mod apply_auto_rules_tests {
+        use super::*;
+
+        #[test]
+        fn メモに一致した最初のルールのタスクが割り当てられる() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let review = insert_task(conn, "レビュー", false)?;
+                let meeting = insert_task(conn, "会議", false)?;
+                insert_auto_rule(conn, "review", &review)?;
+                insert_auto_rule(conn, "MTG", &meeting)?;
+                let entry = insert_entry(conn, None, "定例mtgの後にReview対応")?;
+
+                let result = apply_auto_rules_to_entry(conn, &entry)?;
+                assert_eq!(
+                    result,
+                    AutoRuleResult {
+                        matched: true,
+                        task_id: Some(review),
+                    }
+                );
+                assert_eq!(assigned_task(conn, &entry)?, Some(review.to_string()));
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn タスク設定済みや一致しない記録は変更されない() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let review = insert_task(conn, "レビュー", false)?;
+                let other = insert_task(conn, "その他", false)?;
+                insert_auto_rule(conn, "review", &review)?;
+                let tasked = insert_entry(conn, Some(&other), "review")?;
+                let unmatched = insert_entry(conn, None, "設計")?;
+
+                assert!(!apply_auto_rules_to_entry(conn, &tasked)?.matched);
+                assert_eq!(assigned_task(conn, &tasked)?, Some(other.to_string()));
+                assert!(!apply_auto_rules_to_entry(conn, &unmatched)?.matched);
+                assert_eq!(assigned_task(conn, &unmatched)?, None);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn アーカイブ済みタスクのルールは無視される() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let archived = insert_task(conn, "旧タスク", true)?;
+                insert_auto_rule(conn, "review", &archived)?;
+                let entry = insert_entry(conn, None, "review")?;
+
+                assert!(!apply_auto_rules_to_entry(conn, &entry)?.matched);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 空のパターンや存在しないタスクのルールは作成できない() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let task = insert_task(conn, "レビュー", false)?;
+                assert!(matches!(
+                    insert_auto_rule(conn, "  ", &task),
+                    Err(AppError::InvalidInput(_))
+                ));
+                assert!(matches!(
+                    insert_auto_rule(conn, "review", &Uuid::new_v4()),
+                    Err(AppError::NotFound(_))
+                ));
+                assert!(fetch_auto_rules(conn)?.is_empty());
+                Ok(())
+            })
+            .unwrap();
+        }
+    }
+}
@@ -0,0 +1,824 @@
+use std::path::Path;
+
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeDelta, Utc};
+use duckdb::types::Value;
+use duckdb::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::commands::settings::{fetch_setting, READONLY_QUERY_ENABLED_KEY};
+use crate::db::migrations;
+use crate::error::{AppError, AppResult};
+use crate::AppState;
+
+/// データベース最適化の結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactResult {
+    /// 最適化前のファイルサイズ（バイト、メモリ上のデータベースはNone）
+    pub size_before: Option<u64>,
+    pub size_after: Option<u64>,
+}
+
+/// 保存されているデータの概要
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DatabaseStats {
+    pub active_tasks: i64,
+    pub archived_tasks: i64,
+    /// 計測中のエントリ数
+    pub open_entries: i64,
+    /// 完了済みのエントリ数
+    pub closed_entries: i64,
+    pub artifacts: i64,
+    pub folders: i64,
+    /// 最も古いエントリの開始日時（エントリがなければNone）
+    pub earliest_started_at: Option<DateTime<Utc>>,
+    /// 最も新しいエントリの開始日時（エントリがなければNone）
+    pub latest_started_at: Option<DateTime<Utc>>,
+    /// 完了済みエントリの合計秒数
+    pub total_seconds: i64,
+}
+
+/// 参照先が存在しない紐付け（時間記録と成果物の組）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DanglingLink {
+    pub entry_id: String,
+    pub artifact_id: String,
+}
+
+/// 参照整合性の検査結果（関係ごとの参照先が存在しない行）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct IntegrityReport {
+    /// 存在しないタスクを参照している時間記録のID
+    pub entries_missing_task: Vec<String>,
+    /// 存在しないフォルダを参照しているタスクのID
+    pub tasks_missing_folder: Vec<String>,
+    /// 存在しない時間記録を参照している紐付け
+    pub links_missing_entry: Vec<DanglingLink>,
+    /// 存在しない成果物を参照している紐付け
+    pub links_missing_artifact: Vec<DanglingLink>,
+    /// 存在しない時間記録のハートビートのエントリID
+    pub heartbeats_missing_entry: Vec<String>,
+    /// 存在しない時間記録を参照しているノートのID
+    #[serde(default)]
+    pub notes_missing_entry: Vec<String>,
+}
+
+impl IntegrityReport {
+    pub fn is_clean(&self) -> bool {
+        self.entries_missing_task.is_empty()
+            && self.tasks_missing_folder.is_empty()
+            && self.links_missing_entry.is_empty()
+            && self.links_missing_artifact.is_empty()
+            && self.heartbeats_missing_entry.is_empty()
+            && self.notes_missing_entry.is_empty()
+    }
+}
+
+/// 参照先が存在しない行の修復方法
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RepairStrategy {
+    /// 参照をNULLにして行は残す（NULLにできない紐付け・ハートビート・ノートは削除する）
+    Nullify,
+    /// 参照先が存在しない行を、その記録の紐付け・ハートビート・ノートとともに削除する
+    /// （タスクのフォルダ参照はNULLにする）
+    Delete,
+}
+
+/// 参照整合性の修復結果（関係ごとに修復した件数）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct IntegrityRepairResult {
+    pub entries_fixed: usize,
+    pub tasks_fixed: usize,
+    pub links_removed: usize,
+    pub heartbeats_removed: usize,
+    #[serde(default)]
+    pub notes_removed: usize,
+}
+
+/// 時間記録から存在しないタスクへの参照の条件
+const ENTRIES_MISSING_TASK: &str = "task_id IS NOT NULL AND NOT EXISTS (SELECT 1 FROM tasks t WHERE t.id = time_entries.task_id)";
+/// タスクから存在しないフォルダへの参照の条件
+const TASKS_MISSING_FOLDER: &str = "folder_id IS NOT NULL AND NOT EXISTS (SELECT 1 FROM folders f WHERE f.id = tasks.folder_id)";
+/// 紐付けから存在しない時間記録・成果物への参照の条件
+const LINKS_MISSING_ENTRY: &str = "NOT EXISTS (SELECT 1 FROM time_entries e WHERE e.id = entry_artifacts.entry_id)";
+const LINKS_MISSING_ARTIFACT: &str = "NOT EXISTS (SELECT 1 FROM artifacts a WHERE a.id = entry_artifacts.artifact_id)";
+/// ハートビートから存在しない時間記録への参照の条件
+const HEARTBEATS_MISSING_ENTRY: &str = "NOT EXISTS (SELECT 1 FROM time_entries e WHERE e.id = heartbeats.entry_id)";
+/// ノートから存在しない時間記録への参照の条件
+const NOTES_MISSING_ENTRY: &str = "NOT EXISTS (SELECT 1 FROM time_entries e WHERE e.id = entry_notes.entry_id)";
+
+/// 条件に一致する行のIDを取得する
+fn fetch_dangling_ids(conn: &Connection, table: &str, id_column: &str, condition: &str) -> AppResult<Vec<String>> {
+    let sql = format!("SELECT {id_column} FROM {table} WHERE {condition} ORDER BY {id_column}");
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map([], |row| row.get(0))?;
+
+    let mut ids = Vec::new();
+    for row in rows {
+        ids.push(row?);
+    }
+    Ok(ids)
+}
+
+/// 条件に一致する紐付けを取得する
+fn fetch_dangling_links(conn: &Connection, condition: &str) -> AppResult<Vec<DanglingLink>> {
+    let sql = format!(
+        "SELECT entry_id, artifact_id FROM entry_artifacts WHERE {condition} ORDER BY entry_id, artifact_id"
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map([], |row| {
+        Ok(DanglingLink {
+            entry_id: row.get(0)?,
+            artifact_id: row.get(1)?,
+        })
+    })?;
+
+    let mut links = Vec::new();
+    for row in rows {
+        links.push(row?);
+    }
+    Ok(links)
+}
+
+/// 関係ごとに参照先が存在しない行を検査する
+fn inspect_integrity(conn: &Connection) -> AppResult<IntegrityReport> {
+    Ok(IntegrityReport {
+        entries_missing_task: fetch_dangling_ids(conn, "time_entries", "id", ENTRIES_MISSING_TASK)?,
+        tasks_missing_folder: fetch_dangling_ids(conn, "tasks", "id", TASKS_MISSING_FOLDER)?,
+        links_missing_entry: fetch_dangling_links(conn, LINKS_MISSING_ENTRY)?,
+        links_missing_artifact: fetch_dangling_links(conn, LINKS_MISSING_ARTIFACT)?,
+        heartbeats_missing_entry: fetch_dangling_ids(conn, "heartbeats", "entry_id", HEARTBEATS_MISSING_ENTRY)?,
+        notes_missing_entry: fetch_dangling_ids(conn, "entry_notes", "id", NOTES_MISSING_ENTRY)?,
+    })
+}
+
+/// 参照先が存在しない行を指定した方法で修復する
+///
+/// 時間記録を削除する場合は、その記録を参照する紐付け・ハートビート・ノートも同じトランザクションで
+/// 削除し、修復後に参照先を失った行が残らないようにする。
+/// フォルダはタスクの分類にすぎないため、どちらの方法でもタスクは削除せず参照をNULLにする。
+fn repair_dangling_references(conn: &Connection, strategy: RepairStrategy) -> AppResult<IntegrityRepairResult> {
+    let tx = conn.unchecked_transaction()?;
+    let mut links_removed = tx.execute(
+        &format!("DELETE FROM entry_artifacts WHERE {LINKS_MISSING_ENTRY} OR {LINKS_MISSING_ARTIFACT}"),
+        [],
+    )?;
+    let mut heartbeats_removed =
+        tx.execute(&format!("DELETE FROM heartbeats WHERE {HEARTBEATS_MISSING_ENTRY}"), [])?;
+    let mut notes_removed =
+        tx.execute(&format!("DELETE FROM entry_notes WHERE {NOTES_MISSING_ENTRY}"), [])?;
+    let tasks_fixed = tx.execute(
+        &format!("UPDATE tasks SET folder_id = NULL WHERE {TASKS_MISSING_FOLDER}"),
+        [],
+    )?;
+    let entries_fixed = match strategy {
+        RepairStrategy::Nullify => tx.execute(
+            &format!("UPDATE time_entries SET task_id = NULL WHERE {ENTRIES_MISSING_TASK}"),
+            [],
+        )?,
+        RepairStrategy::Delete => {
+            // 削除する時間記録を参照する行を先に削除する
+            let deleted_entries = format!("entry_id IN (SELECT id FROM time_entries WHERE {ENTRIES_MISSING_TASK})");
+            links_removed +=
+                tx.execute(&format!("DELETE FROM entry_artifacts WHERE {deleted_entries}"), [])?;
+            heartbeats_removed +=
+                tx.execute(&format!("DELETE FROM heartbeats WHERE {deleted_entries}"), [])?;
+            notes_removed +=
+                tx.execute(&format!("DELETE FROM entry_notes WHERE {deleted_entries}"), [])?;
+            tx.execute(&format!("DELETE FROM time_entries WHERE {ENTRIES_MISSING_TASK}"), [])?
+        }
+    };
+    let result = IntegrityRepairResult {
+        entries_fixed,
+        tasks_fixed,
+        links_removed,
+        heartbeats_removed,
+        notes_removed,
+    };
+
+    tx.commit()?;
+    Ok(result)
+}
+
+/// ファイルサイズを取得する（WALファイルも含める）
+fn database_file_size(path: Option<&Path>) -> Option<u64> {
+    let path = path?;
+    let main = std::fs::metadata(path).ok()?.len();
+    let mut wal_path = path.as_os_str().to_owned();
+    wal_path.push(".wal");
+    let wal = std::fs::metadata(Path::new(&wal_path))
+        .map(|m| m.len())
+        .unwrap_or(0);
+    Some(main + wal)
+}
+
+/// チェックポイントを実行してWALを本体に反映し、統計情報を更新する
+fn compact(conn: &Connection) -> AppResult<()> {
+    conn.execute_batch("FORCE CHECKPOINT; ANALYZE;")?;
+    Ok(())
+}
+
+/// テーブルごとの集計クエリでデータの概要を取得する
+fn fetch_database_stats(conn: &Connection) -> AppResult<DatabaseStats> {
+    let (active_tasks, archived_tasks) = conn.query_row(
+        "SELECT COUNT(*) FILTER (WHERE NOT archived), COUNT(*) FILTER (WHERE archived)
+         FROM tasks",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+
+    let (open_entries, closed_entries, earliest_started_at, latest_started_at, total_seconds) =
+        conn.query_row(
+            "SELECT COUNT(*) FILTER (WHERE ended_at IS NULL),
+                    COUNT(*) FILTER (WHERE ended_at IS NOT NULL),
+                    MIN(started_at),
+                    MAX(started_at),
+                    COALESCE(SUM(COALESCE(
+                        duration_seconds,
+                        (EPOCH(ended_at::TIMESTAMP) - EPOCH(started_at::TIMESTAMP))::BIGINT
+                    )) FILTER (WHERE ended_at IS NOT NULL), 0)::BIGINT
+             FROM time_entries",
+            [],
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                ))
+            },
+        )?;
+
+    let artifacts = conn.query_row("SELECT COUNT(*) FROM artifacts", [], |row| row.get(0))?;
+    let folders = conn.query_row("SELECT COUNT(*) FROM folders", [], |row| row.get(0))?;
+
+    Ok(DatabaseStats {
+        active_tasks,
+        archived_tasks,
+        open_entries,
+        closed_entries,
+        artifacts,
+        folders,
+        earliest_started_at,
+        latest_started_at,
+        total_seconds,
+    })
+}
+
+/// 単一のSELECT/WITH文であることを確認し、末尾のセミコロンを除いた文を返す
+fn validate_readonly_query(sql: &str) -> AppResult<&str> {
+    let trimmed = sql.trim();
+    let statement = trimmed.strip_suffix(';').unwrap_or(trimmed).trim_end();
+    if statement.is_empty() {
+        return Err(AppError::InvalidInput("Query cannot be empty".to_string()));
+    }
+    if statement.contains(';') {
+        return Err(AppError::InvalidInput(
+            "Multiple statements are not allowed".to_string(),
+        ));
+    }
+
+    let keyword: String = statement
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect::<String>()
+        .to_ascii_uppercase();
+    if keyword != "SELECT" && keyword != "WITH" {
+        return Err(AppError::InvalidInput(
+            "Only SELECT or WITH queries are allowed".to_string(),
+        ));
+    }
+    Ok(statement)
+}
+
+/// DuckDBの値をJSONの値に変換する（数値・真偽値以外は文字列として表現する）
+fn value_to_json(value: Value) -> serde_json::Value {
+    use serde_json::Value as Json;
+
+    match value {
+        Value::Null => Json::Null,
+        Value::Boolean(v) => Json::from(v),
+        Value::TinyInt(v) => Json::from(v),
+        Value::SmallInt(v) => Json::from(v),
+        Value::Int(v) => Json::from(v),
+        Value::BigInt(v) => Json::from(v),
+        Value::UTinyInt(v) => Json::from(v),
+        Value::USmallInt(v) => Json::from(v),
+        Value::UInt(v) => Json::from(v),
+        Value::UBigInt(v) => Json::from(v),
+        // i64に収まるHUGEINTは数値、収まらない値は文字列として返す
+        Value::HugeInt(v) => i64::try_from(v)
+            .map(Json::from)
+            .unwrap_or_else(|_| Json::from(v.to_string())),
+        Value::Float(v) => Json::from(v),
+        Value::Double(v) => Json::from(v),
+        Value::Decimal(v) => Json::from(v.to_string()),
+        Value::Text(v) | Value::Enum(v) => Json::from(v),
+        Value::Timestamp(unit, v) => DateTime::<Utc>::from_timestamp_micros(unit.to_micros(v))
+            .map(|t| Json::from(t.to_rfc3339()))
+            .unwrap_or(Json::Null),
+        Value::Date32(days) => NaiveDate::default()
+            .checked_add_signed(TimeDelta::days(days.into()))
+            .map(|d| Json::from(d.to_string()))
+            .unwrap_or(Json::Null),
+        Value::Time64(unit, v) => {
+            let micros = unit.to_micros(v);
+            NaiveTime::from_num_seconds_from_midnight_opt(
+                (micros / 1_000_000) as u32,
+                ((micros % 1_000_000) * 1000) as u32,
+            )
+            .map(|t| Json::from(t.to_string()))
+            .unwrap_or(Json::Null)
+        }
+        Value::List(values) | Value::Array(values) => {
+            Json::Array(values.into_iter().map(value_to_json).collect())
+        }
+        Value::Struct(fields) => Json::Object(
+            fields
+                .keys()
+                .cloned()
+                .zip(fields.values().cloned().map(value_to_json))
+                .collect(),
+        ),
+        other => Json::from(format!("{:?}", other)),
+    }
+}
+
+/// 設定で許可されている場合に限り、読み取り専用のクエリを実行して行ごとのオブジェクトを返す
+fn execute_readonly_query(conn: &Connection, sql: &str) -> AppResult<Vec<serde_json::Value>> {
+    let enabled = fetch_setting(conn, READONLY_QUERY_ENABLED_KEY)?;
+    if enabled.as_deref() != Some("true") {
+        return Err(AppError::OperationFailed(format!(
+            "Custom queries are disabled. Set {} to true to enable them",
+            READONLY_QUERY_ENABLED_KEY
+        )));
+    }
+    let statement = validate_readonly_query(sql)?;
+
+    // 書き込みが紛れ込んでも反映されないよう、コミットせずにロールバックする
+    let tx = conn.unchecked_transaction()?;
+    let mut stmt = tx.prepare(statement)?;
+    let mut rows = stmt.query([])?;
+    let mut results = Vec::new();
+    let mut column_names = Vec::new();
+    while let Some(row) = rows.next()? {
+        if column_names.is_empty() {
+            column_names = row.as_ref().column_names();
+        }
+        let mut object = serde_json::Map::with_capacity(column_names.len());
+        for (i, name) in column_names.iter().enumerate() {
+            let value: Value = row.get(i)?;
+            object.insert(name.clone(), value_to_json(value));
+        }
+        results.push(serde_json::Value::Object(object));
+    }
+    drop(rows);
+    drop(stmt);
+    tx.rollback()?;
+    Ok(results)
+}
+
+/// 任意のSELECT/WITHクエリを実行し、列名をキーとした行の配列を返す
+/// （設定 `enable_readonly_query` が "true" の場合のみ利用できる）
+#[tauri::command]
+pub fn run_readonly_query(
+    state: tauri::State<AppState>,
+    sql: String,
+) -> AppResult<Vec<serde_json::Value>> {
+    state
+        .db
+        .with_connection(|conn| execute_readonly_query(conn, &sql))
+}
+
+/// データの概要（件数・期間・合計時間）を取得する
+#[tauri::command]
+pub fn get_database_stats(state: tauri::State<AppState>) -> AppResult<DatabaseStats> {
+    state.db.with_connection(fetch_database_stats)
+}
+
+/// 参照先が存在しないタスク・フォルダ・時間記録・成果物への参照を検査する
+#[tauri::command]
+pub fn check_integrity(state: tauri::State<AppState>) -> AppResult<IntegrityReport> {
+    state.db.with_connection(inspect_integrity)
+}
+
+/// 参照先が存在しない行を、参照をNULLにするか行を削除して修復する
+#[tauri::command]
+pub fn repair_integrity(
+    state: tauri::State<AppState>,
+    strategy: RepairStrategy,
+) -> AppResult<IntegrityRepairResult> {
+    let result = state
+        .db
+        .with_connection(|conn| repair_dangling_references(conn, strategy))?;
+    if result.entries_fixed > 0 || result.tasks_fixed > 0 {
+        state.report_cache.clear();
+    }
+    Ok(result)
+}
+
+/// データベースのスキーマバージョンを取得する
+#[tauri::command]
+pub fn get_schema_version(state: tauri::State<AppState>) -> AppResult<i32> {
+    state.db.with_connection(migrations::get_schema_version)
+}
+
+/// データベースファイルを読み取り専用で開き、適用待ちのマイグレーションを列挙する
+///
+/// マイグレーションは実行しないため、古いバージョンのファイルも変更せずに確認できる。
+fn preview_migrations_at(path: &Path) -> AppResult<Vec<String>> {
+    if !path.exists() {
+        return Err(AppError::NotFound(format!(
+            "Database file not found: {}",
+            path.display()
+        )));
+    }
+    let config = duckdb::Config::default().access_mode(duckdb::AccessMode::ReadOnly)?;
+    let conn = Connection::open_with_flags(path, config).map_err(|e| {
+        AppError::InvalidInput(format!(
+            "Cannot open database file {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    migrations::check_migrations(&conn)
+}
+
+/// 適用待ちのマイグレーションを適用せずに列挙する（pathを省略すると使用中のデータベース）
+#[tauri::command]
+pub fn preview_migrations(
+    state: tauri::State<AppState>,
+    path: Option<String>,
+) -> AppResult<Vec<String>> {
+    match path {
+        Some(path) => preview_migrations_at(Path::new(&path)),
+        None => state.db.with_connection(migrations::check_migrations),
+    }
+}
+
+/// 全記録の作業秒数を再計算し、更新した件数を返す
+#[tauri::command]
+pub fn recompute_durations(state: tauri::State<AppState>) -> AppResult<usize> {
+    let updated = state.db.with_connection(migrations::recompute_durations)?;
+    if updated > 0 {
+        state.report_cache.clear();
+    }
+    Ok(updated)
+}
+
+/// データベースを最適化し、前後のファイルサイズを返す
+#[tauri::command]
+pub fn compact_database(state: tauri::State<AppState>) -> AppResult<CompactResult> {
+    let path = state.db.path();
+    // 接続のロックを保持したまま実行し、書き込み中の操作と競合しないようにする
+    state.db.with_connection(|conn| {
+        let size_before = database_file_size(path);
+        compact(conn)?;
+        let size_after = database_file_size(path);
+        Ok(CompactResult {
+            size_before,
+            size_after,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+
+    mod compact_tests {
+        use super::*;
+
+        #[test]
+        fn ファイルベースのデータベースを最適化できる() {
+            let temp_dir = tempfile::tempdir().unwrap();
+            let db_path = temp_dir.path().join("compact.db");
+            let db = Database::open(&db_path).unwrap();
+
+            db.with_connection(|conn| {
+                conn.execute_batch(
+                    "INSERT INTO time_entries (id, started_at, ended_at, created_at, updated_at)
+                     SELECT 'entry-' || i, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP
+                     FROM range(1000) t(i);
+                     DELETE FROM time_entries;",
+                )?;
+                compact(conn)
+            })
+            .unwrap();
+
+            assert!(database_file_size(db.path()).is_some());
+        }
+
+        #[test]
+        fn メモリ上のデータベースではサイズを返さない() {
+            let db = Database::new_in_memory().unwrap();
+
+            db.with_connection(compact).unwrap();
+
+            assert_eq!(database_file_size(db.path()), None);
+        }
+    }
+
+    mod preview_migrations_tests {
+        use super::*;
+
+        #[test]
+        fn 古いスキーマのファイルは変更せずに適用待ちの手順を返す() {
+            let temp_dir = tempfile::tempdir().unwrap();
+            let db_path = temp_dir.path().join("v1.db");
+            {
+                let conn = Connection::open(&db_path).unwrap();
+                conn.execute_batch(
+                    "CREATE TABLE schema_meta (version INTEGER NOT NULL);
+                     INSERT INTO schema_meta VALUES (1);
+                     CREATE TABLE tasks (
+                        id VARCHAR PRIMARY KEY,
+                        folder_id VARCHAR,
+                        name VARCHAR NOT NULL,
+                        description TEXT,
+                        color VARCHAR(7) NOT NULL DEFAULT '#3b82f6',
+                        archived BOOLEAN NOT NULL DEFAULT FALSE,
+                        created_at TIMESTAMPTZ NOT NULL,
+                        updated_at TIMESTAMPTZ NOT NULL
+                     );",
+                )
+                .unwrap();
+            }
+
+            let steps = preview_migrations_at(&db_path).unwrap();
+            assert!(steps.iter().any(|step| step.starts_with("v2:")));
+            assert!(!steps.iter().any(|step| step.starts_with("v1:")));
+            assert!(steps.contains(&format!(
+                "スキーマバージョンを1から{}に更新",
+                migrations::SCHEMA_VERSION
+            )));
+
+            // プレビューではファイルを変更しない
+            let conn = Connection::open(&db_path).unwrap();
+            assert_eq!(migrations::get_schema_version(&conn).unwrap(), 1);
+            let folders: i64 = conn
+                .query_row(
+                    "SELECT COUNT(*) FROM information_schema.tables WHERE table_name = 'folders'",
+                    [],
+                    |row| row.get(0),
+                )
+                .unwrap();
+            assert_eq!(folders, 0);
+        }
+
+        #[test]
+        fn 存在しないファイルはNotFoundになる() {
+            let temp_dir = tempfile::tempdir().unwrap();
+
+            let result = preview_migrations_at(&temp_dir.path().join("missing.db"));
+
+            assert!(matches!(result, Err(AppError::NotFound(_))));
+        }
+    }
+
+    mod database_stats_tests {
+        use super::*;
+
+        #[test]
+        fn 空のデータベースではすべて0で期間はNoneになる() {
+            let db = Database::new_in_memory().unwrap();
+
+            let stats = db.with_connection(fetch_database_stats).unwrap();
+
+            assert_eq!(stats.active_tasks, 0);
+            assert_eq!(stats.closed_entries, 0);
+            assert_eq!(stats.total_seconds, 0);
+            assert_eq!(stats.earliest_started_at, None);
+            assert_eq!(stats.latest_started_at, None);
+        }
+
+        #[test]
+        fn 件数と期間と合計時間を集計できる() {
+            let db = Database::new_in_memory().unwrap();
+
+            let stats = db
+                .with_connection(|conn| {
+                    conn.execute_batch(
+                        "INSERT INTO folders (id, name, color, sort_order, created_at, updated_at) VALUES
+                         ('f1', 'フォルダ', '#000000', 0, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP);
+                         INSERT INTO tasks (id, name, color, archived, created_at, updated_at) VALUES
+                         ('t1', '進行中', '#000000', false, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('t2', '完了', '#000000', true, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP);
+                         INSERT INTO time_entries (id, started_at, ended_at, created_at, updated_at) VALUES
+                         ('e1', '2024-12-01 09:00:00+00', '2024-12-01 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('e2', '2024-12-03 09:00:00+00', '2024-12-03 09:30:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('e3', '2024-12-05 09:00:00+00', NULL, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP);
+                         INSERT INTO artifacts (id, name, artifact_type, created_at) VALUES
+                         ('a1', '資料', 'document', CURRENT_TIMESTAMP);",
+                    )?;
+                    fetch_database_stats(conn)
+                })
+                .unwrap();
+
+            assert_eq!((stats.active_tasks, stats.archived_tasks), (1, 1));
+            assert_eq!((stats.open_entries, stats.closed_entries), (1, 2));
+            assert_eq!((stats.artifacts, stats.folders), (1, 1));
+            assert_eq!(stats.total_seconds, 5400);
+            assert_eq!(
+                stats.earliest_started_at.map(|t| t.to_rfc3339()),
+                Some("2024-12-01T09:00:00+00:00".to_string())
+            );
+            assert_eq!(
+                stats.latest_started_at.map(|t| t.to_rfc3339()),
+                Some("2024-12-05T09:00:00+00:00".to_string())
+            );
+        }
+    }
+
+    mod integrity_tests {
+        use super::*;
+
+        fn insert_dangling_rows(conn: &Connection) -> AppResult<()> {
+            conn.execute_batch(
+                "INSERT INTO tasks (id, folder_id, name, color, archived, created_at, updated_at) VALUES
+                 ('t1', NULL, '正常', '#000000', false, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                 ('t2', 'missing-folder', 'フォルダなし', '#000000', false, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP);
+                 INSERT INTO time_entries (id, task_id, started_at, ended_at, created_at, updated_at) VALUES
+                 ('e1', 't1', '2024-12-01 09:00:00+00', '2024-12-01 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                 ('e2', 'missing-task', '2024-12-02 09:00:00+00', '2024-12-02 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP);
+                 INSERT INTO artifacts (id, name, artifact_type, created_at) VALUES
+                 ('a1', '資料', 'document', CURRENT_TIMESTAMP);
+                 INSERT INTO entry_artifacts (entry_id, artifact_id) VALUES
+                 ('e1', 'a1'), ('e1', 'missing-artifact'), ('missing-entry', 'a1'), ('e2', 'a1');
+                 INSERT INTO heartbeats (entry_id, last_heartbeat) VALUES
+                 ('missing-entry', CURRENT_TIMESTAMP);
+                 INSERT INTO entry_notes (id, entry_id, text, created_at) VALUES
+                 ('n1', 'e1', '正常なノート', CURRENT_TIMESTAMP),
+                 ('n2', 'missing-entry', '孤立したノート', CURRENT_TIMESTAMP),
+                 ('n3', 'e2', 'タスクを失った記録のノート', CURRENT_TIMESTAMP);",
+            )?;
+            Ok(())
+        }
+
+        #[test]
+        fn 参照先が存在しない行を関係ごとに列挙する() {
+            let db = Database::new_in_memory().unwrap();
+
+            let report = db
+                .with_connection(|conn| {
+                    assert!(inspect_integrity(conn)?.is_clean());
+                    insert_dangling_rows(conn)?;
+                    inspect_integrity(conn)
+                })
+                .unwrap();
+
+            assert_eq!(report.entries_missing_task, vec!["e2".to_string()]);
+            assert_eq!(report.tasks_missing_folder, vec!["t2".to_string()]);
+            assert_eq!(
+                report.links_missing_entry,
+                vec![DanglingLink {
+                    entry_id: "missing-entry".to_string(),
+                    artifact_id: "a1".to_string(),
+                }]
+            );
+            assert_eq!(report.links_missing_artifact.len(), 1);
+            assert_eq!(report.heartbeats_missing_entry, vec!["missing-entry".to_string()]);
+            assert_eq!(report.notes_missing_entry, vec!["n2".to_string()]);
+        }
+
+        #[test]
+        fn 参照をNULLにして修復すると行は残る() {
+            let db = Database::new_in_memory().unwrap();
+
+            db.with_connection(|conn| {
+                insert_dangling_rows(conn)?;
+                let result = repair_dangling_references(conn, RepairStrategy::Nullify)?;
+                assert_eq!(
+                    result,
+                    IntegrityRepairResult {
+                        entries_fixed: 1,
+                        tasks_fixed: 1,
+                        links_removed: 2,
+                        heartbeats_removed: 1,
+                        notes_removed: 1,
+                    }
+                );
+                assert!(inspect_integrity(conn)?.is_clean());
+
+                let entries: i64 =
+                    conn.query_row("SELECT COUNT(*) FROM time_entries", [], |row| row.get(0))?;
+                assert_eq!(entries, 2);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 削除して修復すると参照先のない行がなくなる() {
+            let db = Database::new_in_memory().unwrap();
+
+            db.with_connection(|conn| {
+                insert_dangling_rows(conn)?;
+                let result = repair_dangling_references(conn, RepairStrategy::Delete)?;
+                assert_eq!((result.tasks_fixed, result.entries_fixed), (1, 1));
+                // 削除した記録の紐付けとノートも削除される
+                assert_eq!(result.links_removed, 3);
+                assert_eq!(result.notes_removed, 2);
+                assert_eq!(inspect_integrity(conn)?, IntegrityReport::default());
+
+                let (tasks, entries): (i64, i64) = conn.query_row(
+                    "SELECT (SELECT COUNT(*) FROM tasks), (SELECT COUNT(*) FROM time_entries)",
+                    [],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )?;
+                assert_eq!((tasks, entries), (2, 1));
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 削除して修復してもフォルダを失ったタスクの記録は消えない() {
+            let db = Database::new_in_memory().unwrap();
+
+            db.with_connection(|conn| {
+                insert_dangling_rows(conn)?;
+                conn.execute_batch(
+                    "INSERT INTO time_entries (id, task_id, started_at, ended_at, created_at, updated_at) VALUES
+                     ('e3', 't2', '2024-12-03 09:00:00+00', '2024-12-03 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP);",
+                )?;
+                let report = inspect_integrity(conn)?;
+                let result = repair_dangling_references(conn, RepairStrategy::Delete)?;
+                assert_eq!(result.tasks_fixed, report.tasks_missing_folder.len());
+                assert_eq!(result.entries_fixed, report.entries_missing_task.len());
+
+                let folder_id: Option<String> =
+                    conn.query_row("SELECT folder_id FROM tasks WHERE id = 't2'", [], |row| row.get(0))?;
+                assert_eq!(folder_id, None);
+                let task_id: Option<String> =
+                    conn.query_row("SELECT task_id FROM time_entries WHERE id = 'e3'", [], |row| row.get(0))?;
+                assert_eq!(task_id.as_deref(), Some("t2"));
+                Ok(())
+            })
+            .unwrap();
+        }
+    }
+
+    mod readonly_query_tests {
+        use super::*;
+
+        fn enable_queries(conn: &Connection) -> AppResult<()> {
+            conn.execute(
+                "INSERT INTO settings (key, value, updated_at) VALUES (?, 'true', CURRENT_TIMESTAMP)",
+                [READONLY_QUERY_ENABLED_KEY],
+            )?;
+            Ok(())
+        }
+
+        #[test]
+        fn 設定が無効な場合は実行できない() {
+            let db = Database::new_in_memory().unwrap();
+
+            let result = db.with_connection(|conn| execute_readonly_query(conn, "SELECT 1"));
+
+            assert!(matches!(result, Err(AppError::OperationFailed(_))));
+        }
+
+        #[test]
+        fn 列名をキーとした行を返す() {
+            let db = Database::new_in_memory().unwrap();
+
+            let rows = db
+                .with_connection(|conn| {
+                    enable_queries(conn)?;
+                    conn.execute_batch(
+                        "INSERT INTO tasks (id, name, color, archived, created_at, updated_at) VALUES
+                         ('t1', '設計', '#000000', false, '2024-12-01 09:00:00+00', CURRENT_TIMESTAMP);",
+                    )?;
+                    execute_readonly_query(
+                        conn,
+                        "  with t AS (SELECT name, archived, created_at FROM tasks)
+                         SELECT name, archived, created_at, 42 AS answer, NULL AS nothing FROM t;",
+                    )
+                })
+                .unwrap();
+
+            assert_eq!(
+                rows,
+                vec![serde_json::json!({
+                    "name": "設計",
+                    "archived": false,
+                    "created_at": "2024-12-01T09:00:00+00:00",
+                    "answer": 42,
+                    "nothing": null,
+                })]
+            );
+        }
+
+        #[test]
+        fn 複数の文や更新系の文は拒否する() {
+            assert!(validate_readonly_query("SELECT 1; DELETE FROM tasks").is_err());
+            assert!(validate_readonly_query("DELETE FROM tasks").is_err());
+            assert!(validate_readonly_query("SELECTION").is_err());
+            assert!(validate_readonly_query("   ").is_err());
+            assert_eq!(validate_readonly_query(" select 1 ; ").unwrap(), "select 1");
+        }
+    }
+}
@@ -1,23 +1,90 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
 use chrono::{DateTime, Utc};
 use duckdb::Connection;
 use uuid::Uuid;
 
+use crate::commands::artifacts::{
+    ensure_allowed_artifact_type, insert_artifact, link_artifact_to_entry, validate_create_artifact,
+};
+use crate::commands::audit::record_audit;
+use crate::commands::auto_rules::apply_auto_rules_to_entry;
+use crate::commands::reports::local_date_expr;
+use crate::commands::settings::{
+    fetch_setting, store_setting, AUTO_STOP_IDLE_SECONDS_KEY, MEMO_FROM_TASK_NAME_KEY,
+    MEMO_TEMPLATE_KEY_PREFIX,
+};
+use crate::commands::{contains_pattern, ensure_max_length, parse_optional_datetime, MAX_MEMO_LENGTH};
 use crate::db::{
-    Artifact, EntryFilter, Task, TimeEntry, TimeEntryWithRelations, UpdateEntry,
+    Artifact, AutoStopResult, CreateArtifact, DayEntries, EntryFilter, EntryNote, EntrySwitch, EntrySearchResult, RecoveryCandidate, RunningEntryStatus, SearchFacet,
+    OverlapCluster, SuspiciousEntry, Task, TimeEntry, TimeEntryWithRelations, UpdateEntry, UpdateEntryInfo, UpdatedEntry,
 };
 use crate::error::{AppError, AppResult};
 use crate::AppState;
 
+/// 長すぎる記録とみなす作業秒数の既定値（24時間）
+const DEFAULT_SUSPICIOUS_MAX_SECONDS: i64 = 24 * 60 * 60;
+
+/// 停止時に指定する終了日時として許容する、現在時刻より先の時計のずれ（秒）
+const CLOCK_SKEW_TOLERANCE_SECONDS: i64 = 60;
+
+/// 計測を開始できるタスクを1つに固定するロック
+///
+/// 集中作業中に別のタスクで計測を始めてしまうのを防ぐためのもので、メモリ上にのみ保持し再起動で解除される。
+#[derive(Default)]
+pub struct TaskLock {
+    task_id: Mutex<Option<Uuid>>,
+}
+
+impl TaskLock {
+    pub fn get(&self) -> Option<Uuid> {
+        *self.lock()
+    }
+
+    /// ロックするタスクを設定する（Noneで解除）
+    pub fn set(&self, task_id: Option<Uuid>) {
+        *self.lock() = task_id;
+    }
+
+    /// ロック中はロックしたタスク以外（タスク未設定を含む）での計測開始を拒否する
+    fn ensure_allows(&self, task_id: Option<&Uuid>) -> AppResult<()> {
+        match self.get() {
+            Some(locked) if task_id != Some(&locked) => Err(AppError::OperationFailed(format!(
+                "Timer is locked to task {}",
+                locked
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// 値は単純な上書きのみのため、ロックが壊れていても中身をそのまま使う
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<Uuid>> {
+        self.task_id.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// UUID文字列をパースする（未指定・空文字はNone）
+fn parse_optional_uuid(value: Option<String>) -> AppResult<Option<Uuid>> {
+    match value {
+        Some(s) if !s.trim().is_empty() => Uuid::parse_str(s.trim())
+            .map(Some)
+            .map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", s))),
+        _ => Ok(None),
+    }
+}
+
 /// 時間記録をDBに保存する
 fn insert_entry(conn: &Connection, entry: &TimeEntry) -> AppResult<()> {
     conn.execute(
-        "INSERT INTO time_entries (id, task_id, started_at, ended_at, memo, created_at, updated_at)
-         VALUES (?, ?, ?, ?, ?, ?, ?)",
+        "INSERT INTO time_entries (id, task_id, started_at, ended_at, duration_seconds, memo, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
         duckdb::params![
             entry.id.to_string(),
             entry.task_id.map(|id| id.to_string()),
             entry.started_at,
             entry.ended_at,
+            entry.duration_seconds(),
             &entry.memo,
             entry.created_at,
             entry.updated_at,
@@ -59,8 +126,80 @@ fn fetch_running_entry(conn: &Connection) -> AppResult<Option<TimeEntry>> {
     }
 }
 
+/// 計測中エントリのIDと開始時刻のみを取得し、経過秒数を付ける（結合なし）
+fn fetch_running_status(conn: &Connection, now: DateTime<Utc>) -> AppResult<Option<RunningEntryStatus>> {
+    let result = conn.query_row(
+        "SELECT id, started_at FROM time_entries WHERE ended_at IS NULL LIMIT 1",
+        [],
+        |row| {
+            let id_str: String = row.get(0)?;
+            let started_at: DateTime<Utc> = row.get(1)?;
+            Ok((id_str, started_at))
+        },
+    );
+
+    match result {
+        Ok((id_str, started_at)) => Ok(Some(RunningEntryStatus {
+            id: Uuid::parse_str(&id_str).unwrap(),
+            started_at,
+            elapsed_seconds: (now - started_at).num_seconds().max(0),
+        })),
+        Err(duckdb::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(AppError::Database(e)),
+    }
+}
+
+/// 作業秒数が0以下、または上限を超える完了済みの記録を新しい順に取得する
+fn fetch_suspicious_entries(conn: &Connection, max_seconds: i64) -> AppResult<Vec<SuspiciousEntry>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, task_id, started_at, ended_at, memo, created_at, updated_at, duration
+         FROM (
+             SELECT *,
+                    TRUNC(EPOCH(ended_at::TIMESTAMP) - EPOCH(started_at::TIMESTAMP))::BIGINT AS duration
+             FROM time_entries
+             WHERE ended_at IS NOT NULL
+         )
+         WHERE duration <= 0 OR duration > ?
+         ORDER BY started_at DESC, id",
+    )?;
+
+    let rows = stmt.query_map([max_seconds], |row| {
+        let id_str: String = row.get(0)?;
+        let task_id_str: Option<String> = row.get(1)?;
+        let duration_seconds: i64 = row.get(7)?;
+
+        let reason = if duration_seconds == 0 {
+            "zero_duration"
+        } else if duration_seconds < 0 {
+            "negative_duration"
+        } else {
+            "too_long"
+        };
+
+        Ok(SuspiciousEntry {
+            entry: TimeEntry {
+                id: Uuid::parse_str(&id_str).unwrap(),
+                task_id: task_id_str.and_then(|s| Uuid::parse_str(&s).ok()),
+                started_at: row.get(2)?,
+                ended_at: row.get(3)?,
+                memo: row.get(4)?,
+                created_at: row.get(5)?,
+                updated_at: row.get(6)?,
+            },
+            duration_seconds,
+            reason: reason.to_string(),
+        })
+    })?;
+
+    let mut entries = Vec::new();
+    for row in rows {
+        entries.push(row?);
+    }
+    Ok(entries)
+}
+
 /// IDで時間記録を取得する
-fn fetch_entry_by_id(conn: &Connection, id: &Uuid) -> AppResult<TimeEntry> {
+pub(crate) fn fetch_entry_by_id(conn: &Connection, id: &Uuid) -> AppResult<TimeEntry> {
     let mut stmt = conn.prepare(
         "SELECT id, task_id, started_at, ended_at, memo, created_at, updated_at
          FROM time_entries WHERE id = ?",
@@ -90,10 +229,212 @@ fn fetch_entry_by_id(conn: &Connection, id: &Uuid) -> AppResult<TimeEntry> {
     Ok(entry)
 }
 
+/// 基準時刻におけるローカル時間の直近の0時（UTC）を求める
+fn last_local_midnight(now: DateTime<Utc>, tz_offset_minutes: i32) -> DateTime<Utc> {
+    let offset = chrono::Duration::minutes(tz_offset_minutes as i64);
+    let local_midnight = (now + offset)
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+        .and_utc();
+    local_midnight - offset
+}
+
+/// 計測中のエントリが日付をまたいでいれば直近のローカル0時で分割し、新しいエントリを返す
+fn split_running_at_midnight(
+    conn: &Connection,
+    now: DateTime<Utc>,
+    tz_offset_minutes: i32,
+) -> AppResult<Option<TimeEntry>> {
+    let Some(running) = fetch_running_entry(conn)? else {
+        return Ok(None);
+    };
+
+    let midnight = last_local_midnight(now, tz_offset_minutes);
+    if running.started_at >= midnight {
+        return Ok(None);
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        "UPDATE time_entries SET ended_at = ?, duration_seconds = ?, updated_at = ? WHERE id = ?",
+        duckdb::params![
+            midnight,
+            (midnight - running.started_at).num_seconds(),
+            now,
+            running.id.to_string()
+        ],
+    )?;
+
+    let mut next = TimeEntry::start(running.task_id, running.memo.clone());
+    next.started_at = midnight;
+    insert_entry(&tx, &next)?;
+    tx.commit()?;
+
+    Ok(Some(next))
+}
+
+/// 計測中エントリのハートビートを記録する（既存の記録は上書き）
+fn upsert_heartbeat(conn: &Connection, entry_id: &Uuid, at: DateTime<Utc>) -> AppResult<()> {
+    conn.execute(
+        "INSERT INTO heartbeats (entry_id, last_heartbeat) VALUES (?, ?)
+         ON CONFLICT (entry_id) DO UPDATE SET last_heartbeat = excluded.last_heartbeat",
+        duckdb::params![entry_id.to_string(), at],
+    )?;
+    Ok(())
+}
+
+/// 計測中のエントリと最終ハートビートを取得する（計測中でなければNone）
+fn fetch_recovery_candidate(conn: &Connection) -> AppResult<Option<RecoveryCandidate>> {
+    let Some(entry) = fetch_running_entry(conn)? else {
+        return Ok(None);
+    };
+
+    let result = conn.query_row(
+        "SELECT last_heartbeat FROM heartbeats WHERE entry_id = ?",
+        [entry.id.to_string()],
+        |row| row.get::<_, DateTime<Utc>>(0),
+    );
+    let last_heartbeat = match result {
+        Ok(at) => Some(at),
+        Err(duckdb::Error::QueryReturnedNoRows) => None,
+        Err(e) => return Err(AppError::Database(e)),
+    };
+
+    Ok(Some(RecoveryCandidate {
+        entry,
+        last_heartbeat,
+    }))
+}
+
+/// 計測中のエントリを指定した終了時刻で確定し、ハートビートを削除する
+fn finalize_recovery(
+    conn: &Connection,
+    entry_id: &Uuid,
+    ended_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> AppResult<TimeEntry> {
+    let tx = conn.unchecked_transaction()?;
+    let mut entry = fetch_entry_by_id(&tx, entry_id)?;
+    if !entry.is_running() {
+        return Err(AppError::OperationFailed(
+            "Entry is not running".to_string(),
+        ));
+    }
+    if ended_at < entry.started_at || ended_at > now {
+        return Err(AppError::InvalidInput(
+            "ended_at must be between the start time and now".to_string(),
+        ));
+    }
+
+    entry.ended_at = Some(ended_at);
+    entry.updated_at = now;
+    tx.execute(
+        "UPDATE time_entries SET ended_at = ?, duration_seconds = ?, updated_at = ? WHERE id = ?",
+        duckdb::params![ended_at, entry.duration_seconds(), now, entry.id.to_string()],
+    )?;
+    tx.execute(
+        "DELETE FROM heartbeats WHERE entry_id = ?",
+        [entry.id.to_string()],
+    )?;
+    tx.commit()?;
+
+    Ok(entry)
+}
+
+/// 自動停止までの無操作秒数を取得する（未設定・0は無効としてNone）
+fn fetch_auto_stop_idle_seconds(conn: &Connection) -> AppResult<Option<i64>> {
+    Ok(fetch_setting(conn, AUTO_STOP_IDLE_SECONDS_KEY)?
+        .and_then(|value| value.parse::<i64>().ok())
+        .filter(|seconds| *seconds > 0))
+}
+
+/// 自動停止が有効で、最終ハートビートから無操作秒数を超えていれば最終ハートビートで計測を停止する
+///
+/// ハートビートを一度も受け取っていない計測は判断できないため停止しない。
+fn enforce_auto_stop_at(conn: &Connection, now: DateTime<Utc>) -> AppResult<Option<TimeEntry>> {
+    let Some(idle_seconds) = fetch_auto_stop_idle_seconds(conn)? else {
+        return Ok(None);
+    };
+    let Some(RecoveryCandidate {
+        entry,
+        last_heartbeat: Some(last_heartbeat),
+    }) = fetch_recovery_candidate(conn)?
+    else {
+        return Ok(None);
+    };
+    if (now - last_heartbeat).num_seconds() <= idle_seconds {
+        return Ok(None);
+    }
+
+    let ended_at = last_heartbeat.max(entry.started_at).min(now);
+    finalize_recovery(conn, &entry.id, ended_at, now).map(Some)
+}
+
+/// 最終ハートビートが基準時刻より古い計測中エントリを取得し、終了時刻を最終ハートビートに設定する
+///
+/// applyがfalseの場合は更新せず、適用後の状態のエントリを返すだけにする。
+fn recover_stale(conn: &Connection, cutoff: DateTime<Utc>, apply: bool) -> AppResult<Vec<TimeEntry>> {
+    let mut stmt = conn.prepare(
+        "SELECT e.id, e.task_id, e.started_at, e.ended_at, e.memo, e.created_at, e.updated_at, h.last_heartbeat
+         FROM time_entries e
+         JOIN heartbeats h ON h.entry_id = e.id
+         WHERE e.ended_at IS NULL AND h.last_heartbeat < ?
+         ORDER BY e.started_at ASC",
+    )?;
+
+    let rows = stmt.query_map([cutoff], |row| {
+        let id_str: String = row.get(0)?;
+        let task_id_str: Option<String> = row.get(1)?;
+        let started_at: DateTime<Utc> = row.get(2)?;
+        let created_at: DateTime<Utc> = row.get(5)?;
+        let updated_at: DateTime<Utc> = row.get(6)?;
+        let last_heartbeat: DateTime<Utc> = row.get(7)?;
+
+        Ok(TimeEntry {
+            id: Uuid::parse_str(&id_str).unwrap(),
+            task_id: task_id_str.map(|s| Uuid::parse_str(&s).unwrap()),
+            started_at,
+            // 開始直後にハートビートが途絶えた場合でも開始時刻より前にはしない
+            ended_at: Some(last_heartbeat.max(started_at)),
+            memo: row.get(4)?,
+            created_at,
+            updated_at,
+        })
+    })?;
+
+    let mut entries = Vec::new();
+    for row in rows {
+        entries.push(row?);
+    }
+
+    if apply {
+        let now = Utc::now();
+        for entry in &mut entries {
+            conn.execute(
+                "UPDATE time_entries SET ended_at = ?, duration_seconds = ?, updated_at = ? WHERE id = ?",
+                duckdb::params![
+                    entry.ended_at,
+                    entry.duration_seconds(),
+                    now,
+                    entry.id.to_string()
+                ],
+            )?;
+            conn.execute(
+                "DELETE FROM heartbeats WHERE entry_id = ?",
+                [entry.id.to_string()],
+            )?;
+            entry.updated_at = now;
+        }
+    }
+
+    Ok(entries)
+}
+
 /// タスク情報を取得する
 fn fetch_task_by_id(conn: &Connection, id: &Uuid) -> AppResult<Option<Task>> {
     let mut stmt = conn.prepare(
-        "SELECT id, folder_id, name, description, color, archived, created_at, updated_at
+        "SELECT id, folder_id, name, description, color, archived, created_at, updated_at, hourly_rate, currency
          FROM tasks WHERE id = ?",
     )?;
 
@@ -110,6 +451,8 @@ fn fetch_task_by_id(conn: &Connection, id: &Uuid) -> AppResult<Option<Task>> {
             description: row.get(3)?,
             color: row.get(4)?,
             archived: row.get(5)?,
+            hourly_rate: row.get(8)?,
+            currency: row.get(9)?,
             created_at,
             updated_at,
         })
@@ -122,10 +465,22 @@ fn fetch_task_by_id(conn: &Connection, id: &Uuid) -> AppResult<Option<Task>> {
     }
 }
 
+/// 計測を開始できるタスクか確認する（存在しない場合はNotFound、アーカイブ済みの場合はInvalidInput）
+fn ensure_task_startable(conn: &Connection, task_id: &Uuid) -> AppResult<()> {
+    match fetch_task_by_id(conn, task_id)? {
+        Some(task) if task.archived => Err(AppError::InvalidInput(format!(
+            "Task is archived: {}",
+            task.name
+        ))),
+        Some(_) => Ok(()),
+        None => Err(AppError::NotFound(format!("Task not found: {}", task_id))),
+    }
+}
+
 /// エントリに紐付いた成果物を取得する
 fn fetch_artifacts_for_entry(conn: &Connection, entry_id: &Uuid) -> AppResult<Vec<Artifact>> {
     let mut stmt = conn.prepare(
-        "SELECT a.id, a.name, a.artifact_type, a.reference, a.metadata, a.created_at
+        "SELECT a.id, a.name, a.artifact_type, a.reference, a.metadata, a.created_at, a.updated_at
          FROM artifacts a
          JOIN entry_artifacts ea ON ea.artifact_id = a.id
          WHERE ea.entry_id = ?",
@@ -134,6 +489,7 @@ fn fetch_artifacts_for_entry(conn: &Connection, entry_id: &Uuid) -> AppResult<Ve
     let rows = stmt.query_map([entry_id.to_string()], |row| {
         let id_str: String = row.get(0)?;
         let created_at: DateTime<Utc> = row.get(5)?;
+        let updated_at: DateTime<Utc> = row.get(6)?;
         let metadata_str: Option<String> = row.get(4)?;
 
         Ok(Artifact {
@@ -143,6 +499,7 @@ fn fetch_artifacts_for_entry(conn: &Connection, entry_id: &Uuid) -> AppResult<Ve
             reference: row.get(3)?,
             metadata: metadata_str.and_then(|s| serde_json::from_str(&s).ok()),
             created_at,
+            updated_at,
         })
     })?;
 
@@ -157,6 +514,7 @@ fn fetch_artifacts_for_entry(conn: &Connection, entry_id: &Uuid) -> AppResult<Ve
 fn entry_to_with_relations(
     conn: &Connection,
     entry: TimeEntry,
+    stored_duration: Option<i64>,
 ) -> AppResult<TimeEntryWithRelations> {
     let task = if let Some(task_id) = entry.task_id {
         fetch_task_by_id(conn, &task_id)?
@@ -165,8 +523,10 @@ fn entry_to_with_relations(
     };
 
     let artifacts = fetch_artifacts_for_entry(conn, &entry.id)?;
+    let notes = fetch_entry_notes(conn, &entry.id)?;
 
-    let duration_seconds = entry.ended_at.map(|ended| (ended - entry.started_at).num_seconds());
+    let duration_seconds = resolve_duration(&entry, stored_duration);
+    let is_running = entry.is_running();
 
     Ok(TimeEntryWithRelations {
         id: entry.id,
@@ -177,18 +537,48 @@ fn entry_to_with_relations(
         duration_seconds,
         memo: entry.memo,
         artifacts,
+        notes,
         created_at: entry.created_at,
         updated_at: entry.updated_at,
+        is_running,
+        elapsed_seconds: None,
+        warning: None,
     })
 }
 
+/// 保存済みの作業秒数を優先し、未保存（NULL）の場合のみ計算する
+fn resolve_duration(entry: &TimeEntry, stored_duration: Option<i64>) -> Option<i64> {
+    if entry.is_running() {
+        return None;
+    }
+    stored_duration.or_else(|| entry.duration_seconds())
+}
+
+/// 作業時間の範囲指定を検証する（負の値や下限が上限を超える指定はエラー）
+fn validate_duration_bounds(min: Option<i64>, max: Option<i64>) -> AppResult<()> {
+    if min.is_some_and(|v| v < 0) || max.is_some_and(|v| v < 0) {
+        return Err(AppError::InvalidInput(
+            "Duration bounds must not be negative".to_string(),
+        ));
+    }
+    if let (Some(min), Some(max)) = (min, max) {
+        if min > max {
+            return Err(AppError::InvalidInput(format!(
+                "min_duration_seconds ({}) must not exceed max_duration_seconds ({})",
+                min, max
+            )));
+        }
+    }
+    Ok(())
+}
+
 /// フィルタ条件で時間記録を取得する
 fn fetch_entries_with_filter(
     conn: &Connection,
     filter: &EntryFilter,
 ) -> AppResult<Vec<TimeEntryWithRelations>> {
     let mut sql = String::from(
-        "SELECT id, task_id, started_at, ended_at, memo, created_at, updated_at
+        "SELECT id, task_id, started_at, ended_at, memo, created_at, updated_at, duration_seconds
          FROM time_entries WHERE 1=1",
     );
     let mut params: Vec<Box<dyn duckdb::ToSql>> = Vec::new();
@@ -205,13 +595,36 @@ fn fetch_entries_with_filter(
         sql.push_str(" AND task_id = ?");
         params.push(Box::new(task_id.to_string()));
     }
+    if let Some(query) = filter.memo_query.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+        sql.push_str(" AND memo ILIKE ? ESCAPE '\\'");
+        params.push(Box::new(contains_pattern(query)));
+    }
+    if filter.has_duration_bounds() {
+        validate_duration_bounds(filter.min_duration_seconds, filter.max_duration_seconds)?;
+        sql.push_str(" AND ended_at IS NOT NULL");
+    }
+    if let Some(min) = filter.min_duration_seconds {
+        sql.push_str(" AND EPOCH(ended_at::TIMESTAMP) - EPOCH(started_at::TIMESTAMP) >= ?");
+        params.push(Box::new(min));
+    }
+    if let Some(max) = filter.max_duration_seconds {
+        sql.push_str(" AND EPOCH(ended_at::TIMESTAMP) - EPOCH(started_at::TIMESTAMP) <= ?");
+        params.push(Box::new(max));
+    }
+    if let Some(has_artifacts) = filter.has_artifacts {
+        sql.push_str(if has_artifacts { " AND EXISTS" } else { " AND NOT EXISTS" });
+        sql.push_str(" (SELECT 1 FROM entry_artifacts ea WHERE ea.entry_id = time_entries.id)");
+    }
 
-    sql.push_str(" ORDER BY started_at DESC");
+    sql.push_str(" ORDER BY started_at DESC, id");
 
     if let Some(limit) = filter.limit {
         sql.push_str(&format!(" LIMIT {}", limit));
     }
 
+    #[cfg(debug_assertions)]
+    let started = std::time::Instant::now();
+
     let mut stmt = conn.prepare(&sql)?;
     let param_refs: Vec<&dyn duckdb::ToSql> = params.iter().map(|p| p.as_ref()).collect();
 
@@ -222,531 +635,3414 @@ fn fetch_entries_with_filter(
         let ended_at: Option<DateTime<Utc>> = row.get(3)?;
         let created_at: DateTime<Utc> = row.get(5)?;
         let updated_at: DateTime<Utc> = row.get(6)?;
+        let stored_duration: Option<i64> = row.get(7)?;
 
-        Ok(TimeEntry {
-            id: Uuid::parse_str(&id_str).unwrap(),
-            task_id: task_id_str.map(|s| Uuid::parse_str(&s).unwrap()),
-            started_at,
-            ended_at,
-            memo: row.get(4)?,
-            created_at,
-            updated_at,
-        })
+        Ok((
+            TimeEntry {
+                id: Uuid::parse_str(&id_str).unwrap(),
+                task_id: task_id_str.map(|s| Uuid::parse_str(&s).unwrap()),
+                started_at,
+                ended_at,
+                memo: row.get(4)?,
+                created_at,
+                updated_at,
+            },
+            stored_duration,
+        ))
     })?;
 
     let mut entries = Vec::new();
     for row in rows {
-        let entry = row?;
-        entries.push(entry_to_with_relations(conn, entry)?);
+        entries.push(row?);
     }
-    Ok(entries)
-}
 
-/// 計測を開始する
-#[tauri::command]
-pub fn start_entry(
-    state: tauri::State<AppState>,
-    task_id: Option<String>,
-    memo: Option<String>,
-) -> AppResult<TimeEntry> {
-    let task_uuid = if let Some(ref id) = task_id {
-        Some(
-            Uuid::parse_str(id)
-                .map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", id)))?,
-        )
-    } else {
-        None
-    };
+    let result = entries_to_with_relations(conn, entries)?;
 
-    state.db.with_connection(|conn| {
-        // 既に計測中のエントリがあればエラー
-        if fetch_running_entry(conn)?.is_some() {
-            return Err(AppError::AlreadyExists(
-                "There is already a running entry".to_string(),
-            ));
-        }
+    #[cfg(debug_assertions)]
+    eprintln!(
+        "[fetch_entries_with_filter] {} entries in {:?}",
+        result.len(),
+        started.elapsed()
+    );
 
-        let entry = TimeEntry::start(task_uuid, memo);
-        insert_entry(conn, &entry)?;
-        Ok(entry)
-    })
+    Ok(result)
 }
 
-/// 計測を停止する
-#[tauri::command]
-pub fn stop_entry(state: tauri::State<AppState>, id: Option<String>) -> AppResult<TimeEntry> {
-    state.db.with_connection(|conn| {
-        let entry = if let Some(ref entry_id) = id {
-            let uuid = Uuid::parse_str(entry_id)
-                .map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", entry_id)))?;
-            fetch_entry_by_id(conn, &uuid)?
-        } else {
-            fetch_running_entry(conn)?
-                .ok_or_else(|| AppError::NotFound("No running entry found".to_string()))?
-        };
+/// フィルタ条件で時間記録を取得し、計測中のエントリを条件に関わらず含めて経過秒数を設定する
+///
+/// 計測中のエントリがフィルタ外だった場合は開始日時の降順の位置に追加するため、
+/// limitより1件多くなることがある。
+fn fetch_entries_with_running(
+    conn: &Connection,
+    filter: &EntryFilter,
+    now: DateTime<Utc>,
+) -> AppResult<Vec<TimeEntryWithRelations>> {
+    let mut entries = fetch_entries_with_filter(conn, filter)?;
 
-        if !entry.is_running() {
-            return Err(AppError::OperationFailed(
-                "Entry is not running".to_string(),
-            ));
+    // 作業時間の範囲指定時は計測中の記録を対象外とする
+    let running = if filter.has_duration_bounds() {
+        None
+    } else {
+        fetch_running_entry(conn)?
+    };
+    if let Some(running) = running {
+        if !entries.iter().any(|e| e.id == running.id) {
+            let running = entry_to_with_relations(conn, running, None)?;
+            let index = entries
+                .iter()
+                .position(|e| e.started_at < running.started_at)
+                .unwrap_or(entries.len());
+            entries.insert(index, running);
         }
+    }
+    for entry in entries.iter_mut().filter(|e| e.is_running) {
+        entry.elapsed_seconds = Some((now - entry.started_at).num_seconds().max(0));
+    }
 
-        let now = Utc::now();
-        conn.execute(
-            "UPDATE time_entries SET ended_at = ?, updated_at = ? WHERE id = ?",
-            duckdb::params![now, now, entry.id.to_string()],
-        )?;
-
-        let mut updated = entry;
-        updated.ended_at = Some(now);
-        updated.updated_at = now;
-        Ok(updated)
-    })
+    Ok(entries)
 }
 
-/// 計測中のエントリを取得する
-#[tauri::command]
-pub fn get_running_entry(
-    state: tauri::State<AppState>,
-) -> AppResult<Option<TimeEntryWithRelations>> {
-    state.db.with_connection(|conn| {
-        if let Some(entry) = fetch_running_entry(conn)? {
-            Ok(Some(entry_to_with_relations(conn, entry)?))
-        } else {
-            Ok(None)
+/// 時間記録をタイムゾーンオフセット（分）を適用したローカル日付ごとにまとめる（日付の降順）
+fn group_entries_by_day(
+    entries: Vec<TimeEntryWithRelations>,
+    tz_offset_minutes: i32,
+) -> Vec<DayEntries> {
+    let offset = chrono::Duration::minutes(tz_offset_minutes as i64);
+    let mut days: Vec<DayEntries> = Vec::new();
+
+    for entry in entries {
+        let date = (entry.started_at + offset)
+            .date_naive()
+            .format("%Y-%m-%d")
+            .to_string();
+        let seconds = entry.duration_seconds.unwrap_or(0);
+
+        match days.iter_mut().find(|d| d.date == date) {
+            Some(day) => {
+                day.total_seconds += seconds;
+                day.entries.push(entry);
+            }
+            None => days.push(DayEntries {
+                date,
+                total_seconds: seconds,
+                entries: vec![entry],
+            }),
         }
-    })
-}
+    }
 
-/// 時間記録一覧を取得する
-#[tauri::command]
-pub fn list_entries(
-    state: tauri::State<AppState>,
-    from: Option<String>,
-    to: Option<String>,
-    task_id: Option<String>,
-    limit: Option<i64>,
-) -> AppResult<Vec<TimeEntryWithRelations>> {
-    let filter = EntryFilter {
-        from: from.and_then(|s| DateTime::parse_from_rfc3339(&s).ok().map(|dt| dt.with_timezone(&Utc))),
-        to: to.and_then(|s| DateTime::parse_from_rfc3339(&s).ok().map(|dt| dt.with_timezone(&Utc))),
-        task_id: task_id.and_then(|s| Uuid::parse_str(&s).ok()),
-        limit,
-    };
+    days.sort_by(|a, b| b.date.cmp(&a.date));
+    for day in &mut days {
+        day.entries.sort_by_key(|entry| std::cmp::Reverse(entry.started_at));
+    }
+    days
+}
 
-    state
-        .db
-        .with_connection(|conn| fetch_entries_with_filter(conn, &filter))
+/// `?, ?, ...` 形式のプレースホルダを生成する
+fn placeholders(count: usize) -> String {
+    vec!["?"; count].join(", ")
 }
 
-/// 時間記録を更新する
-#[tauri::command]
-pub fn update_entry(
-    state: tauri::State<AppState>,
-    id: String,
-    update: UpdateEntry,
-) -> AppResult<TimeEntry> {
-    let entry_id = Uuid::parse_str(&id)
-        .map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", id)))?;
+/// 複数タスクをIDで一括取得する
+fn fetch_tasks_by_ids(conn: &Connection, ids: &[String]) -> AppResult<HashMap<Uuid, Task>> {
+    let mut tasks = HashMap::new();
+    if ids.is_empty() {
+        return Ok(tasks);
+    }
 
-    state.db.with_connection(|conn| {
-        let mut entry = fetch_entry_by_id(conn, &entry_id)?;
+    let sql = format!(
+        "SELECT id, folder_id, name, description, color, archived, created_at, updated_at, hourly_rate, currency
+         FROM tasks WHERE id IN ({})",
+        placeholders(ids.len())
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(duckdb::params_from_iter(ids), |row| {
+        let id_str: String = row.get(0)?;
+        let folder_id_str: Option<String> = row.get(1)?;
+        let created_at: DateTime<Utc> = row.get(6)?;
+        let updated_at: DateTime<Utc> = row.get(7)?;
 
-        if let Some(task_id) = update.task_id {
-            entry.task_id = task_id;
-        }
-        if let Some(started_at) = update.started_at {
-            entry.started_at = started_at;
-        }
-        if let Some(ended_at) = update.ended_at {
-            entry.ended_at = ended_at;
+        Ok(Task {
+            id: Uuid::parse_str(&id_str).unwrap(),
+            folder_id: folder_id_str.and_then(|s| Uuid::parse_str(&s).ok()),
+            name: row.get(2)?,
+            description: row.get(3)?,
+            color: row.get(4)?,
+            archived: row.get(5)?,
+            hourly_rate: row.get(8)?,
+            currency: row.get(9)?,
+            created_at,
+            updated_at,
+        })
+    })?;
+
+    for row in rows {
+        let task = row?;
+        tasks.insert(task.id, task);
+    }
+    Ok(tasks)
+}
+
+/// 複数エントリに紐付いた成果物を一括取得する
+fn fetch_artifacts_for_entries(
+    conn: &Connection,
+    entry_ids: &[String],
+) -> AppResult<HashMap<Uuid, Vec<Artifact>>> {
+    let mut artifacts: HashMap<Uuid, Vec<Artifact>> = HashMap::new();
+    if entry_ids.is_empty() {
+        return Ok(artifacts);
+    }
+
+    let sql = format!(
+        "SELECT ea.entry_id, a.id, a.name, a.artifact_type, a.reference, a.metadata, a.created_at, a.updated_at
+         FROM artifacts a
+         JOIN entry_artifacts ea ON ea.artifact_id = a.id
+         WHERE ea.entry_id IN ({})",
+        placeholders(entry_ids.len())
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(duckdb::params_from_iter(entry_ids), |row| {
+        let entry_id_str: String = row.get(0)?;
+        let id_str: String = row.get(1)?;
+        let created_at: DateTime<Utc> = row.get(6)?;
+        let updated_at: DateTime<Utc> = row.get(7)?;
+        let metadata_str: Option<String> = row.get(5)?;
+
+        Ok((
+            Uuid::parse_str(&entry_id_str).unwrap(),
+            Artifact {
+                id: Uuid::parse_str(&id_str).unwrap(),
+                name: row.get(2)?,
+                artifact_type: row.get(3)?,
+                reference: row.get(4)?,
+                metadata: metadata_str.and_then(|s| serde_json::from_str(&s).ok()),
+                created_at,
+                updated_at,
+            },
+        ))
+    })?;
+
+    for row in rows {
+        let (entry_id, artifact) = row?;
+        artifacts.entry(entry_id).or_default().push(artifact);
+    }
+    Ok(artifacts)
+}
+
+/// 時刻付きメモの行を変換する
+fn entry_note_from_row(row: &duckdb::Row) -> duckdb::Result<EntryNote> {
+    let id_str: String = row.get(0)?;
+    let entry_id_str: String = row.get(1)?;
+    Ok(EntryNote {
+        id: Uuid::parse_str(&id_str).unwrap(),
+        entry_id: Uuid::parse_str(&entry_id_str).unwrap(),
+        text: row.get(2)?,
+        created_at: row.get(3)?,
+    })
+}
+
+/// エントリの時刻付きメモを作成日時順に取得する
+fn fetch_entry_notes(conn: &Connection, entry_id: &Uuid) -> AppResult<Vec<EntryNote>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, entry_id, text, created_at FROM entry_notes
+         WHERE entry_id = ?
+         ORDER BY created_at, id",
+    )?;
+    let notes = stmt
+        .query_map([entry_id.to_string()], entry_note_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(notes)
+}
+
+/// 複数エントリの時刻付きメモを一括取得する
+fn fetch_notes_for_entries(
+    conn: &Connection,
+    entry_ids: &[String],
+) -> AppResult<HashMap<Uuid, Vec<EntryNote>>> {
+    let mut notes: HashMap<Uuid, Vec<EntryNote>> = HashMap::new();
+    if entry_ids.is_empty() {
+        return Ok(notes);
+    }
+
+    let sql = format!(
+        "SELECT id, entry_id, text, created_at FROM entry_notes
+         WHERE entry_id IN ({})
+         ORDER BY created_at, id",
+        placeholders(entry_ids.len())
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(duckdb::params_from_iter(entry_ids), entry_note_from_row)?;
+    for row in rows {
+        let note = row?;
+        notes.entry(note.entry_id).or_default().push(note);
+    }
+    Ok(notes)
+}
+
+/// 複数エントリをリレーション付きで変換する（タスクと成果物はまとめて取得する）
+fn entries_to_with_relations(
+    conn: &Connection,
+    entries: Vec<(TimeEntry, Option<i64>)>,
+) -> AppResult<Vec<TimeEntryWithRelations>> {
+    let mut task_ids: Vec<String> = entries
+        .iter()
+        .filter_map(|(e, _)| e.task_id.map(|id| id.to_string()))
+        .collect();
+    task_ids.sort();
+    task_ids.dedup();
+    let entry_ids: Vec<String> = entries.iter().map(|(e, _)| e.id.to_string()).collect();
+
+    let tasks = fetch_tasks_by_ids(conn, &task_ids)?;
+    let mut artifacts = fetch_artifacts_for_entries(conn, &entry_ids)?;
+    let mut notes = fetch_notes_for_entries(conn, &entry_ids)?;
+
+    Ok(entries
+        .into_iter()
+        .map(|(entry, stored_duration)| {
+            let task = entry.task_id.and_then(|id| tasks.get(&id).cloned());
+            let artifacts = artifacts.remove(&entry.id).unwrap_or_default();
+            let notes = notes.remove(&entry.id).unwrap_or_default();
+            let duration_seconds = resolve_duration(&entry, stored_duration);
+            let is_running = entry.is_running();
+
+            TimeEntryWithRelations {
+                id: entry.id,
+                task_id: entry.task_id,
+                task,
+                started_at: entry.started_at,
+                ended_at: entry.ended_at,
+                duration_seconds,
+                memo: entry.memo,
+                artifacts,
+                notes,
+                created_at: entry.created_at,
+                updated_at: entry.updated_at,
+                is_running,
+                elapsed_seconds: None,
+                warning: None,
+            }
+        })
+        .collect())
+}
+
+/// 計測中エントリの一意制約違反（同時に開始された場合など）を判定する
+fn is_running_entry_conflict(error: &AppError) -> bool {
+    match error {
+        AppError::Database(e) => {
+            let message = e.to_string().to_lowercase();
+            message.contains("duplicate key") || message.contains("write-write conflict")
+        }
+        _ => false,
+    }
+}
+
+/// 計測中エントリの一意制約違反をAlreadyExistsに変換する
+fn map_running_entry_conflict(error: AppError) -> AppError {
+    if is_running_entry_conflict(&error) {
+        AppError::AlreadyExists("There is already a running entry".to_string())
+    } else {
+        error
+    }
+}
+
+/// 指定した開始日時で新しい記録を作成する（トランザクションは呼び出し側で管理する）
+///
+/// 設定が有効でメモが未指定ならタスク名をメモに入れる。
+fn open_entry(
+    conn: &Connection,
+    task_id: Option<Uuid>,
+    memo: Option<String>,
+    started_at: DateTime<Utc>,
+) -> AppResult<TimeEntry> {
+    let mut memo = memo;
+    if let Some(ref task_id) = task_id {
+        ensure_task_startable(conn, task_id)?;
+        // 設定が有効ならメモの代わりにタスク名を入れる（保存後は通常のメモとして編集できる）
+        if memo.is_none() && fetch_setting(conn, MEMO_FROM_TASK_NAME_KEY)?.as_deref() == Some("true") {
+            memo = fetch_task_by_id(conn, task_id)?.map(|task| task.name);
+        }
+    }
+
+    let mut entry = TimeEntry::start(task_id, memo);
+    entry.started_at = started_at;
+    insert_entry(conn, &entry).map_err(map_running_entry_conflict)?;
+    Ok(entry)
+}
+
+/// 新しい計測を開始する（計測中エントリの一意性はDBの制約でも保証する）
+fn begin_entry(
+    conn: &Connection,
+    task_id: Option<Uuid>,
+    memo: Option<String>,
+    started_at: Option<DateTime<Utc>>,
+) -> AppResult<TimeEntry> {
+    let now = Utc::now();
+    if started_at.is_some_and(|started_at| started_at > now) {
+        return Err(AppError::InvalidInput(
+            "Start time cannot be in the future".to_string(),
+        ));
+    }
+
+    let tx = conn.unchecked_transaction()?;
+
+    // 既に計測中のエントリがあればエラー
+    if fetch_running_entry(&tx)?.is_some() {
+        return Err(AppError::AlreadyExists(
+            "There is already a running entry".to_string(),
+        ));
+    }
+
+    if let Some(started_at) = started_at {
+        // 遡って開始する場合は直前の記録と重ならないこと
+        ensure_no_overlap(&tx, started_at, now, None)?;
+    }
+    let entry = open_entry(&tx, task_id, memo, started_at.unwrap_or(now))?;
+    tx.commit()
+        .map_err(AppError::from)
+        .map_err(map_running_entry_conflict)?;
+    Ok(entry)
+}
+
+/// 計測を開始する
+#[tauri::command]
+pub fn start_entry(
+    state: tauri::State<AppState>,
+    task_id: Option<String>,
+    memo: Option<String>,
+    started_at: Option<String>,
+) -> AppResult<TimeEntry> {
+    let started_at = parse_optional_datetime(started_at, "started_at")?;
+    let task_uuid = if let Some(ref id) = task_id {
+        Some(
+            Uuid::parse_str(id)
+                .map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", id)))?,
+        )
+    } else {
+        None
+    };
+    state.task_lock.ensure_allows(task_uuid.as_ref())?;
+
+    let entry = state
+        .db
+        .with_connection(|conn| begin_entry(conn, task_uuid, memo, started_at))?;
+    state.report_cache.invalidate(entry.started_at);
+    Ok(entry)
+}
+
+/// メモテンプレートの `{{変数名}}` を値に置き換える
+///
+/// 値のないプレースホルダーは `strict` が false ならそのまま残し、true ならエラーにする。
+fn render_memo_template(
+    template: &str,
+    vars: &HashMap<String, String>,
+    strict: bool,
+) -> AppResult<String> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let placeholder = &rest[start..start + 2 + len + 2];
+        let name = rest[start + 2..start + 2 + len].trim();
+
+        rendered.push_str(&rest[..start]);
+        match vars.get(name) {
+            Some(value) => rendered.push_str(value),
+            None if strict => {
+                return Err(AppError::InvalidInput(format!(
+                    "Missing value for template variable: {}",
+                    name
+                )));
+            }
+            None => rendered.push_str(placeholder),
+        }
+        rest = &rest[start + placeholder.len()..];
+    }
+    rendered.push_str(rest);
+
+    Ok(rendered)
+}
+
+/// 保存済みのメモテンプレートを展開して計測を開始する
+fn begin_entry_from_template(
+    conn: &Connection,
+    task_id: Option<Uuid>,
+    template_name: &str,
+    vars: &HashMap<String, String>,
+    strict: bool,
+) -> AppResult<TimeEntry> {
+    let key = format!("{}{}", MEMO_TEMPLATE_KEY_PREFIX, template_name);
+    let template = fetch_setting(conn, &key)?.ok_or_else(|| {
+        AppError::NotFound(format!("Memo template not found: {}", template_name))
+    })?;
+
+    let memo = render_memo_template(&template, vars, strict)?;
+    ensure_max_length(&memo, "Memo", MAX_MEMO_LENGTH)?;
+    begin_entry(conn, task_id, Some(memo), None)
+}
+
+/// メモテンプレートを展開したメモで計測を開始する（strictがtrueなら未置換の変数をエラーにする）
+#[tauri::command]
+pub fn start_entry_from_template(
+    state: tauri::State<AppState>,
+    task_id: Option<String>,
+    template_name: String,
+    vars: HashMap<String, String>,
+    strict: Option<bool>,
+) -> AppResult<TimeEntry> {
+    let task_uuid = parse_optional_uuid(task_id)?;
+    let strict = strict.unwrap_or(false);
+    state.task_lock.ensure_allows(task_uuid.as_ref())?;
+
+    let entry = state.db.with_connection(|conn| {
+        begin_entry_from_template(conn, task_uuid, &template_name, &vars, strict)
+    })?;
+    state.report_cache.invalidate(entry.started_at);
+    Ok(entry)
+}
+
+/// 計測を開始できるタスクを固定する（Noneで解除する。再起動するとロックは解除される）
+#[tauri::command]
+pub fn set_active_task_lock(
+    state: tauri::State<AppState>,
+    task_id: Option<String>,
+) -> AppResult<Option<Uuid>> {
+    let task_uuid = parse_optional_uuid(task_id)?;
+    if let Some(ref id) = task_uuid {
+        state.db.with_connection(|conn| {
+            fetch_task_by_id(conn, id)?
+                .ok_or_else(|| AppError::NotFound(format!("Task with id {} not found", id)))
+        })?;
+    }
+    state.task_lock.set(task_uuid);
+    Ok(task_uuid)
+}
+
+/// 計測を開始できるタスクのロックを取得する（ロックしていなければNone）
+#[tauri::command]
+pub fn get_active_task_lock(state: tauri::State<AppState>) -> Option<Uuid> {
+    state.task_lock.get()
+}
+
+/// 作業時間が最小時間に満たない場合の警告文を返す（誤操作による短い記録の確認用）
+fn short_duration_warning(
+    duration_seconds: Option<i64>,
+    min_duration_seconds: Option<i64>,
+) -> Option<String> {
+    let duration = duration_seconds?;
+    let min = min_duration_seconds?;
+    (duration < min).then(|| {
+        format!(
+            "Entry lasted only {} seconds (shorter than {} seconds)",
+            duration, min
+        )
+    })
+}
+
+/// 計測中の記録を指定した終了日時で停止する（トランザクションは呼び出し側で管理する）
+///
+/// タスク未設定ならメモから自動割り当てを試み、割り当てた場合は更新後の記録を返す。
+fn close_running_entry(
+    conn: &Connection,
+    entry: TimeEntry,
+    ended_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> AppResult<TimeEntry> {
+    let mut stopped = entry;
+    stopped.ended_at = Some(ended_at);
+    stopped.updated_at = now;
+
+    conn.execute(
+        "UPDATE time_entries SET ended_at = ?, duration_seconds = ?, updated_at = ? WHERE id = ?",
+        duckdb::params![
+            ended_at,
+            stopped.duration_seconds(),
+            now,
+            stopped.id.to_string()
+        ],
+    )?;
+
+    if stopped.task_id.is_none() && apply_auto_rules_to_entry(conn, &stopped.id)?.matched {
+        stopped = fetch_entry_by_id(conn, &stopped.id)?;
+    }
+    Ok(stopped)
+}
+
+/// 計測を停止し、指定された成果物を作成して同じトランザクションで紐付ける（ID未指定なら計測中のエントリ）
+///
+/// タスク未設定の記録にはメモから自動割り当てルールを適用する。
+///
+/// ended_atを指定した場合は現在時刻の代わりに終了日時とする。時計のずれの範囲内で
+/// 現在時刻より先の値は現在時刻に丸める。
+fn finish_entry(
+    conn: &Connection,
+    id: Option<&Uuid>,
+    artifacts: Vec<CreateArtifact>,
+    ended_at: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> AppResult<TimeEntry> {
+    for artifact in &artifacts {
+        validate_create_artifact(artifact)?;
+    }
+    if ended_at
+        .is_some_and(|ended_at| ended_at > now + chrono::Duration::seconds(CLOCK_SKEW_TOLERANCE_SECONDS))
+    {
+        return Err(AppError::InvalidInput(
+            "End time cannot be in the future".to_string(),
+        ));
+    }
+    let explicit_end = ended_at.is_some();
+    let ended_at = ended_at.map_or(now, |ended_at| ended_at.min(now));
+
+    let tx = conn.unchecked_transaction()?;
+    let entry = if let Some(id) = id {
+        fetch_entry_by_id(&tx, id)?
+    } else {
+        fetch_running_entry(&tx)?
+            .ok_or_else(|| AppError::NotFound("No running entry found".to_string()))?
+    };
+
+    if !entry.is_running() {
+        return Err(AppError::OperationFailed(
+            "Entry is not running".to_string(),
+        ));
+    }
+    if explicit_end && ended_at <= entry.started_at {
+        return Err(AppError::InvalidInput(
+            "End time must be after start time".to_string(),
+        ));
+    }
+
+    let updated = close_running_entry(&tx, entry, ended_at, now)?;
+
+    for artifact in artifacts {
+        ensure_allowed_artifact_type(&tx, &artifact.artifact_type)?;
+        let artifact = Artifact::new(
+            artifact.name,
+            artifact.artifact_type,
+            artifact.reference,
+            artifact.metadata,
+        );
+        insert_artifact(&tx, &artifact)?;
+        link_artifact_to_entry(&tx, &updated.id, &artifact.id)?;
+    }
+    tx.commit()?;
+
+    Ok(updated)
+}
+
+/// 計測を停止し、作業時間が最小時間に満たなければ警告を付けて返す
+///
+/// 短い記録も実際の作業の可能性があるため、停止はしたうえで警告だけ返す。
+fn finish_entry_with_warning(
+    conn: &Connection,
+    id: Option<&Uuid>,
+    artifacts: Vec<CreateArtifact>,
+    ended_at: Option<DateTime<Utc>>,
+    min_duration_seconds: Option<i64>,
+    now: DateTime<Utc>,
+) -> AppResult<TimeEntryWithRelations> {
+    if min_duration_seconds.is_some_and(|min| min < 0) {
+        return Err(AppError::InvalidInput(
+            "min_duration_seconds must not be negative".to_string(),
+        ));
+    }
+
+    let entry = finish_entry(conn, id, artifacts, ended_at, now)?;
+    let duration = entry.duration_seconds();
+    let mut stopped = entry_to_with_relations(conn, entry, duration)?;
+    stopped.warning = short_duration_warning(duration, min_duration_seconds);
+    Ok(stopped)
+}
+
+/// 計測を停止する（artifacts指定時は作成して停止したエントリに紐付ける）
+///
+/// ended_at（RFC3339形式）を指定すると、停止し忘れた場合でも正しい終了日時で記録できる。
+#[tauri::command]
+pub fn stop_entry(
+    state: tauri::State<AppState>,
+    id: Option<String>,
+    artifacts: Option<Vec<CreateArtifact>>,
+    ended_at: Option<String>,
+    min_duration_seconds: Option<i64>,
+) -> AppResult<TimeEntryWithRelations> {
+    let entry_uuid = parse_optional_uuid(id)?;
+    let ended_at = parse_optional_datetime(ended_at, "ended_at")?;
+
+    let stopped = state.db.with_connection(|conn| {
+        finish_entry_with_warning(
+            conn,
+            entry_uuid.as_ref(),
+            artifacts.unwrap_or_default(),
+            ended_at,
+            min_duration_seconds,
+            Utc::now(),
+        )
+    })?;
+    state.report_cache.invalidate(stopped.started_at);
+    Ok(stopped)
+}
+
+/// 計測中の記録を停止し、同じ時刻から新しい記録を開始する（1つのトランザクションで行う）
+fn switch_running_entry(
+    conn: &Connection,
+    task_id: Option<Uuid>,
+    memo: Option<String>,
+    now: DateTime<Utc>,
+) -> AppResult<EntrySwitch> {
+    let tx = conn.unchecked_transaction()?;
+
+    let stopped = match fetch_running_entry(&tx)? {
+        Some(running) => {
+            let ended_at = now.max(running.started_at);
+            Some(close_running_entry(&tx, running, ended_at, now)?)
+        }
+        None => None,
+    };
+
+    // 開始できないタスクならここで失敗し、停止もロールバックされる
+    let started_at = stopped
+        .as_ref()
+        .and_then(|entry| entry.ended_at)
+        .unwrap_or(now);
+    let started = open_entry(&tx, task_id, memo, started_at)?;
+    tx.commit()
+        .map_err(AppError::from)
+        .map_err(map_running_entry_conflict)?;
+
+    Ok(EntrySwitch { stopped, started })
+}
+
+/// 計測中の記録を止めて別の記録を開始する（途中で失敗した場合はどちらも行わない）
+#[tauri::command]
+pub fn switch_entry(
+    state: tauri::State<AppState>,
+    task_id: Option<String>,
+    memo: Option<String>,
+) -> AppResult<EntrySwitch> {
+    let task_uuid = parse_optional_uuid(task_id)?;
+    state.task_lock.ensure_allows(task_uuid.as_ref())?;
+
+    let switched = state
+        .db
+        .with_connection(|conn| switch_running_entry(conn, task_uuid, memo, Utc::now()))?;
+    if let Some(ref stopped) = switched.stopped {
+        state.report_cache.invalidate(stopped.started_at);
+    }
+    state.report_cache.invalidate(switched.started.started_at);
+    Ok(switched)
+}
+
+/// 計測中のエントリを直近のローカル0時で分割する（日付をまたいでいなければNone）
+#[tauri::command]
+pub fn split_at_midnight(
+    state: tauri::State<AppState>,
+    tz_offset_minutes: Option<i32>,
+) -> AppResult<Option<TimeEntry>> {
+    let tz_offset_minutes = tz_offset_minutes.unwrap_or(0);
+    let split = state
+        .db
+        .with_connection(|conn| split_running_at_midnight(conn, Utc::now(), tz_offset_minutes))?;
+    if split.is_some() {
+        // 分割前の記録は前日（月をまたぐ場合は前月）にあるため、月を特定せずに破棄する
+        state.report_cache.clear();
+    }
+    Ok(split)
+}
+
+/// 計測中エントリのハートビートを記録する（フロントエンドから定期的に呼び出す）
+#[tauri::command]
+pub fn record_heartbeat(state: tauri::State<AppState>, entry_id: String) -> AppResult<()> {
+    let uuid = Uuid::parse_str(&entry_id)
+        .map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", entry_id)))?;
+
+    state.db.with_connection(|conn| {
+        let entry = fetch_entry_by_id(conn, &uuid)?;
+        if !entry.is_running() {
+            return Err(AppError::OperationFailed(
+                "Entry is not running".to_string(),
+            ));
+        }
+        upsert_heartbeat(conn, &uuid, Utc::now())
+    })
+}
+
+/// ハートビートが途絶えた計測中エントリを取得し、apply指定時は終了時刻を最終ハートビートに設定する
+#[tauri::command]
+pub fn recover_stale_entries(
+    state: tauri::State<AppState>,
+    max_age_seconds: i64,
+    apply: Option<bool>,
+) -> AppResult<Vec<TimeEntry>> {
+    if max_age_seconds < 0 {
+        return Err(AppError::InvalidInput(
+            "max_age_seconds must not be negative".to_string(),
+        ));
+    }
+    let cutoff = Utc::now() - chrono::Duration::seconds(max_age_seconds);
+
+    let apply = apply.unwrap_or(false);
+    let entries = state
+        .db
+        .with_connection(|conn| recover_stale(conn, cutoff, apply))?;
+    if apply {
+        for entry in &entries {
+            state.report_cache.invalidate(entry.started_at);
+        }
+    }
+    Ok(entries)
+}
+
+/// 自動停止までの無操作秒数を設定する（0で無効）
+#[tauri::command]
+pub fn set_auto_stop_policy(state: tauri::State<AppState>, idle_seconds: i64) -> AppResult<()> {
+    if idle_seconds < 0 {
+        return Err(AppError::InvalidInput(
+            "idle_seconds must not be negative".to_string(),
+        ));
+    }
+
+    let value = (idle_seconds > 0).then(|| idle_seconds.to_string());
+    state
+        .db
+        .with_connection(|conn| store_setting(conn, AUTO_STOP_IDLE_SECONDS_KEY, value.as_deref()))
+}
+
+/// 自動停止の条件を満たしていれば計測中のエントリを最終ハートビートで停止する（フォーカス時や定期的に呼び出す）
+#[tauri::command]
+pub fn enforce_auto_stop(state: tauri::State<AppState>) -> AppResult<AutoStopResult> {
+    let stopped = state
+        .db
+        .with_connection(|conn| enforce_auto_stop_at(conn, Utc::now()))?;
+    if let Some(ref entry) = stopped {
+        state.report_cache.invalidate(entry.started_at);
+    }
+    Ok(AutoStopResult {
+        stopped: stopped.is_some(),
+        ended_at: stopped.and_then(|entry| entry.ended_at),
+    })
+}
+
+/// 再起動時に確認する計測中エントリと最終ハートビートを取得する
+#[tauri::command]
+pub fn get_recovery_candidate(state: tauri::State<AppState>) -> AppResult<Option<RecoveryCandidate>> {
+    state.db.with_connection(fetch_recovery_candidate)
+}
+
+/// 計測中のエントリをユーザーが選んだ終了時刻（RFC3339形式）で確定する
+#[tauri::command]
+pub fn apply_recovery(
+    state: tauri::State<AppState>,
+    entry_id: String,
+    ended_at: String,
+) -> AppResult<TimeEntry> {
+    let entry_uuid = Uuid::parse_str(&entry_id)
+        .map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", entry_id)))?;
+    let ended_at = parse_optional_datetime(Some(ended_at), "ended_at")?
+        .ok_or_else(|| AppError::InvalidInput("ended_at is required".to_string()))?;
+
+    let entry = state
+        .db
+        .with_connection(|conn| finalize_recovery(conn, &entry_uuid, ended_at, Utc::now()))?;
+    state.report_cache.invalidate(entry.started_at);
+    Ok(entry)
+}
+
+/// 計測中のエントリを取得する
+#[tauri::command]
+pub fn get_running_entry(
+    state: tauri::State<AppState>,
+) -> AppResult<Option<TimeEntryWithRelations>> {
+    state.db.with_connection(|conn| {
+        if let Some(entry) = fetch_running_entry(conn)? {
+            Ok(Some(entry_to_with_relations(conn, entry, None)?))
+        } else {
+            Ok(None)
+        }
+    })
+}
+
+/// 計測中エントリの経過時間のみを取得する（ポーリング用）
+#[tauri::command]
+pub fn get_running_entry_status(
+    state: tauri::State<AppState>,
+) -> AppResult<Option<RunningEntryStatus>> {
+    state
+        .db
+        .with_connection(|conn| fetch_running_status(conn, Utc::now()))
+}
+
+/// 確認が必要な時間記録を取得する（max_seconds未指定なら24時間超を長すぎる記録とする）
+#[tauri::command]
+pub fn list_suspicious_entries(
+    state: tauri::State<AppState>,
+    max_seconds: Option<i64>,
+) -> AppResult<Vec<SuspiciousEntry>> {
+    let max_seconds = max_seconds.unwrap_or(DEFAULT_SUSPICIOUS_MAX_SECONDS);
+    if max_seconds <= 0 {
+        return Err(AppError::InvalidInput(
+            "max_seconds must be greater than 0".to_string(),
+        ));
+    }
+
+    state
+        .db
+        .with_connection(|conn| fetch_suspicious_entries(conn, max_seconds))
+}
+
+/// 時間記録一覧を取得する
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn list_entries(
+    state: tauri::State<AppState>,
+    from: Option<String>,
+    to: Option<String>,
+    task_id: Option<String>,
+    limit: Option<i64>,
+    include_running: Option<bool>,
+    min_duration_seconds: Option<i64>,
+    max_duration_seconds: Option<i64>,
+    has_artifacts: Option<bool>,
+) -> AppResult<Vec<TimeEntryWithRelations>> {
+    let filter = EntryFilter {
+        from: parse_optional_datetime(from, "from")?,
+        to: parse_optional_datetime(to, "to")?,
+        task_id: parse_optional_uuid(task_id)?,
+        limit,
+        memo_query: None,
+        min_duration_seconds,
+        max_duration_seconds,
+        has_artifacts,
+    };
+
+    state.db.with_connection(|conn| {
+        if include_running.unwrap_or(false) {
+            fetch_entries_with_running(conn, &filter, Utc::now())
+        } else {
+            fetch_entries_with_filter(conn, &filter)
+        }
+    })
+}
+
+/// メモが部分一致する記録の件数をローカルの年月ごとに集計する（新しい月から順に返す）
+fn fetch_search_facets(
+    conn: &Connection,
+    query: &str,
+    tz_offset_minutes: i32,
+) -> AppResult<Vec<SearchFacet>> {
+    let local_date = local_date_expr("started_at", tz_offset_minutes);
+    let sql = format!(
+        r#"
+            SELECT
+                EXTRACT(YEAR FROM {local_date})::INTEGER as year,
+                EXTRACT(MONTH FROM {local_date})::INTEGER as month,
+                COUNT(*)::BIGINT as match_count
+            FROM time_entries
+            WHERE memo ILIKE ? ESCAPE '\'
+            GROUP BY year, month
+            ORDER BY year DESC, month DESC
+        "#
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map([contains_pattern(query)], |row| {
+        Ok(SearchFacet {
+            year: row.get(0)?,
+            month: row.get::<_, i32>(1)? as u32,
+            match_count: row.get(2)?,
+        })
+    })?;
+
+    let mut facets = Vec::new();
+    for row in rows {
+        facets.push(row?);
+    }
+    Ok(facets)
+}
+
+/// メモを部分一致で検索する（with_facets指定時は月別の件数も返す）
+#[tauri::command]
+pub fn search_entries(
+    state: tauri::State<AppState>,
+    query: String,
+    limit: Option<i64>,
+    with_facets: Option<bool>,
+    tz_offset_minutes: Option<i32>,
+) -> AppResult<EntrySearchResult> {
+    let query = query.trim().to_string();
+    if query.is_empty() {
+        return Err(AppError::InvalidInput(
+            "Search query cannot be empty".to_string(),
+        ));
+    }
+    let tz_offset_minutes = tz_offset_minutes.unwrap_or(0);
+    let filter = EntryFilter {
+        limit,
+        memo_query: Some(query.clone()),
+        ..Default::default()
+    };
+
+    state.db.with_connection(|conn| {
+        let entries = fetch_entries_with_filter(conn, &filter)?;
+        let facets = if with_facets.unwrap_or(false) {
+            Some(fetch_search_facets(conn, &query, tz_offset_minutes)?)
+        } else {
+            None
+        };
+        Ok(EntrySearchResult { entries, facets })
+    })
+}
+
+/// 時間記録をローカル日付ごとにまとめて取得する
+#[tauri::command]
+pub fn list_entries_by_day(
+    state: tauri::State<AppState>,
+    filter: EntryFilter,
+    tz_offset_minutes: Option<i32>,
+) -> AppResult<Vec<DayEntries>> {
+    let tz_offset_minutes = tz_offset_minutes.unwrap_or(0);
+    let entries = state
+        .db
+        .with_connection(|conn| fetch_entries_with_filter(conn, &filter))?;
+    Ok(group_entries_by_day(entries, tz_offset_minutes))
+}
+
+/// 時間記録に更新内容を適用し、監査ログと同じトランザクションで保存する
+pub(crate) fn apply_entry_update(conn: &Connection, entry_id: &Uuid, update: UpdateEntry) -> AppResult<TimeEntry> {
+    let tx = conn.unchecked_transaction()?;
+    let entry = write_entry_update(&tx, entry_id, update)?;
+    tx.commit()?;
+
+    Ok(entry)
+}
+
+/// 時間記録に更新内容を適用して監査ログを残す（トランザクションは呼び出し側で管理する）
+pub(crate) fn write_entry_update(conn: &Connection, entry_id: &Uuid, update: UpdateEntry) -> AppResult<TimeEntry> {
+    if let Some(Some(ref memo)) = update.memo {
+        ensure_max_length(memo, "Memo", MAX_MEMO_LENGTH)?;
+    }
+
+    let before = fetch_entry_by_id(conn, entry_id)?;
+    let mut entry = before.clone();
+
+    if let Some(task_id) = update.task_id {
+        entry.task_id = task_id;
+    }
+    if let Some(started_at) = update.started_at {
+        entry.started_at = started_at;
+    }
+    if let Some(ended_at) = update.ended_at {
+        entry.ended_at = ended_at;
+    }
+    if let Some(memo) = update.memo {
+        entry.memo = memo;
+    }
+    entry.updated_at = Utc::now();
+
+    conn.execute(
+        "UPDATE time_entries SET task_id = ?, started_at = ?, ended_at = ?, duration_seconds = ?, memo = ?, updated_at = ? WHERE id = ?",
+        duckdb::params![
+            entry.task_id.map(|id| id.to_string()),
+            entry.started_at,
+            entry.ended_at,
+            entry.duration_seconds(),
+            &entry.memo,
+            entry.updated_at,
+            entry.id.to_string(),
+        ],
+    )?;
+
+    record_audit(
+        conn,
+        "entry",
+        entry_id,
+        "update",
+        Some(&serde_json::to_string(&before)?),
+        Some(&serde_json::to_string(&entry)?),
+    )?;
+
+    Ok(entry)
+}
+
+/// 更新前後の時間記録から、タスクの付け替えに関する確認用情報を作る
+fn describe_entry_update(
+    conn: &Connection,
+    before: &TimeEntry,
+    updated: &TimeEntry,
+) -> AppResult<UpdateEntryInfo> {
+    let mut info = UpdateEntryInfo {
+        task_changed: before.task_id != updated.task_id,
+        ..Default::default()
+    };
+    let Some(task_id) = updated.task_id else {
+        return Ok(info);
+    };
+
+    let result = conn.query_row(
+        "SELECT t.archived, f.name FROM tasks t
+         LEFT JOIN folders f ON t.folder_id = f.id
+         WHERE t.id = ?",
+        [task_id.to_string()],
+        |row| Ok((row.get::<_, bool>(0)?, row.get::<_, Option<String>>(1)?)),
+    );
+    match result {
+        Ok((archived, folder_name)) => {
+            info.task_archived = archived;
+            info.folder_name = folder_name;
+        }
+        Err(duckdb::Error::QueryReturnedNoRows) => {}
+        Err(e) => return Err(AppError::Database(e)),
+    }
+    Ok(info)
+}
+
+/// 時間記録を更新する（タスクの付け替え先の状態を確認用情報として併せて返す）
+#[tauri::command]
+pub fn update_entry(
+    state: tauri::State<AppState>,
+    id: String,
+    update: UpdateEntry,
+) -> AppResult<UpdatedEntry> {
+    let entry_id = Uuid::parse_str(&id)
+        .map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", id)))?;
+
+    let (before, updated) = state.db.with_connection(|conn| {
+        let before = fetch_entry_by_id(conn, &entry_id)?;
+        let updated = apply_entry_update(conn, &entry_id, update)?;
+        let info = describe_entry_update(conn, &before, &updated)?;
+        Ok((
+            before,
+            UpdatedEntry {
+                entry: updated,
+                info,
+            },
+        ))
+    })?;
+    state.report_cache.invalidate(before.started_at);
+    state.report_cache.invalidate(updated.entry.started_at);
+    Ok(updated)
+}
+
+/// 既存のメモの末尾に改行区切りでテキストを追記する（1回のUPDATEで行う）
+///
+/// 追記後のメモが上限の文字数を超える場合は更新しない。
+fn append_memo_text(conn: &Connection, id: &Uuid, text: &str) -> AppResult<TimeEntry> {
+    ensure_max_length(text, "Memo", MAX_MEMO_LENGTH)?;
+
+    let rows_affected = conn.execute(
+        "UPDATE time_entries
+         SET memo = CASE WHEN memo IS NULL OR memo = '' THEN ? ELSE memo || chr(10) || ? END,
+             updated_at = ?
+         WHERE id = ?
+           AND length(CASE WHEN memo IS NULL OR memo = '' THEN ? ELSE memo || chr(10) || ? END) <= ?",
+        duckdb::params![
+            text,
+            text,
+            Utc::now(),
+            id.to_string(),
+            text,
+            text,
+            MAX_MEMO_LENGTH as i64
+        ],
+    )?;
+
+    if rows_affected == 0 {
+        // 記録が存在すれば、追記すると上限を超えるため更新しなかった
+        fetch_entry_by_id(conn, id)?;
+        return Err(AppError::InvalidInput(format!(
+            "Memo must be at most {} characters",
+            MAX_MEMO_LENGTH
+        )));
+    }
+
+    fetch_entry_by_id(conn, id)
+}
+
+/// 時間記録のメモに追記する
+#[tauri::command]
+pub fn append_memo(state: tauri::State<AppState>, id: String, text: String) -> AppResult<TimeEntry> {
+    let entry_id = Uuid::parse_str(&id)
+        .map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", id)))?;
+
+    if text.trim().is_empty() {
+        return Err(AppError::InvalidInput("Memo text cannot be empty".to_string()));
+    }
+
+    let entry = state
+        .db
+        .with_connection(|conn| append_memo_text(conn, &entry_id, &text))?;
+    state.report_cache.invalidate(entry.started_at);
+    Ok(entry)
+}
+
+/// 2つの期間が重なるか判定する（終了時刻なしは計測中として無期限に扱う）
+fn ranges_overlap(
+    started_at: DateTime<Utc>,
+    ended_at: Option<DateTime<Utc>>,
+    other_started_at: DateTime<Utc>,
+    other_ended_at: Option<DateTime<Utc>>,
+) -> bool {
+    other_ended_at.is_none_or(|other_end| started_at < other_end)
+        && ended_at.is_none_or(|end| other_started_at < end)
+}
+
+/// 時間が重なり合う時間記録をまとまりごとに抽出する（重なりのない記録は含めない）
+fn fetch_overlap_clusters(conn: &Connection) -> AppResult<Vec<OverlapCluster>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, task_id, started_at, ended_at, memo, created_at, updated_at
+         FROM time_entries
+         ORDER BY started_at, id",
+    )?;
+    let entries = stmt
+        .query_map([], |row| {
+            let id_str: String = row.get(0)?;
+            let task_id_str: Option<String> = row.get(1)?;
+            Ok(TimeEntry {
+                id: Uuid::parse_str(&id_str).unwrap(),
+                task_id: task_id_str.and_then(|s| Uuid::parse_str(&s).ok()),
+                started_at: row.get(2)?,
+                ended_at: row.get(3)?,
+                memo: row.get(4)?,
+                created_at: row.get(5)?,
+                updated_at: row.get(6)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut clusters = Vec::new();
+    let mut current: Option<OverlapCluster> = None;
+    for entry in entries {
+        match current {
+            Some(ref mut cluster)
+                if ranges_overlap(
+                    entry.started_at,
+                    entry.ended_at,
+                    cluster.started_at,
+                    cluster.ended_at,
+                ) =>
+            {
+                cluster.ended_at = match (cluster.ended_at, entry.ended_at) {
+                    (Some(a), Some(b)) => Some(a.max(b)),
+                    _ => None,
+                };
+                cluster.entries.push(entry);
+            }
+            _ => {
+                if let Some(cluster) = current.take() {
+                    if cluster.entries.len() > 1 {
+                        clusters.push(cluster);
+                    }
+                }
+                current = Some(OverlapCluster {
+                    started_at: entry.started_at,
+                    ended_at: entry.ended_at,
+                    entries: vec![entry],
+                });
+            }
+        }
+    }
+    if let Some(cluster) = current {
+        if cluster.entries.len() > 1 {
+            clusters.push(cluster);
+        }
+    }
+
+    Ok(clusters)
+}
+
+/// 時間が重なっている時間記録をまとまりごとに取得する（マージインポート後の二重計上の確認用）
+#[tauri::command]
+pub fn get_import_conflicts(state: tauri::State<AppState>) -> AppResult<Vec<OverlapCluster>> {
+    state.db.with_connection(fetch_overlap_clusters)
+}
+
+/// 時間記録に時刻付きのメモを追加する
+fn insert_entry_note(
+    conn: &Connection,
+    entry_id: &Uuid,
+    text: &str,
+    now: DateTime<Utc>,
+) -> AppResult<EntryNote> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(AppError::InvalidInput("Note text cannot be empty".to_string()));
+    }
+    ensure_max_length(text, "Note", MAX_MEMO_LENGTH)?;
+    // エントリが存在するか確認
+    fetch_entry_by_id(conn, entry_id)?;
+
+    let note = EntryNote {
+        id: Uuid::new_v4(),
+        entry_id: *entry_id,
+        text: text.to_string(),
+        created_at: now,
+    };
+    conn.execute(
+        "INSERT INTO entry_notes (id, entry_id, text, created_at) VALUES (?, ?, ?, ?)",
+        duckdb::params![
+            note.id.to_string(),
+            note.entry_id.to_string(),
+            note.text,
+            note.created_at
+        ],
+    )?;
+    Ok(note)
+}
+
+/// 時間記録に時刻付きのメモを追加する（メインのメモは変更しない）
+#[tauri::command]
+pub fn add_entry_note(
+    state: tauri::State<AppState>,
+    entry_id: String,
+    text: String,
+) -> AppResult<EntryNote> {
+    let entry_uuid = Uuid::parse_str(&entry_id)
+        .map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", entry_id)))?;
+
+    state
+        .db
+        .with_connection(|conn| insert_entry_note(conn, &entry_uuid, &text, Utc::now()))
+}
+
+/// 時間記録の時刻付きメモを作成日時順に取得する
+#[tauri::command]
+pub fn list_entry_notes(state: tauri::State<AppState>, entry_id: String) -> AppResult<Vec<EntryNote>> {
+    let entry_uuid = Uuid::parse_str(&entry_id)
+        .map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", entry_id)))?;
+
+    state.db.with_connection(|conn| fetch_entry_notes(conn, &entry_uuid))
+}
+
+/// 時刻付きメモを削除する
+#[tauri::command]
+pub fn delete_entry_note(state: tauri::State<AppState>, id: String) -> AppResult<()> {
+    let note_id = Uuid::parse_str(&id)
+        .map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", id)))?;
+
+    state.db.with_connection(|conn| {
+        let deleted = conn.execute("DELETE FROM entry_notes WHERE id = ?", [note_id.to_string()])?;
+        if deleted == 0 {
+            return Err(AppError::NotFound(format!("Note not found: {}", id)));
+        }
+        Ok(())
+    })
+}
+
+/// 指定期間と重なる時間記録がないことを確認する（計測中の記録は終了時刻なしとして扱う）
+fn ensure_no_overlap(
+    conn: &Connection,
+    started_at: DateTime<Utc>,
+    ended_at: DateTime<Utc>,
+    exclude_id: Option<&Uuid>,
+) -> AppResult<()> {
+    let result: Result<String, duckdb::Error> = conn.query_row(
+        "SELECT id FROM time_entries
+         WHERE started_at < ? AND (ended_at IS NULL OR ended_at > ?) AND id IS DISTINCT FROM ?
+         ORDER BY started_at
+         LIMIT 1",
+        duckdb::params![ended_at, started_at, exclude_id.map(|id| id.to_string())],
+        |row| row.get(0),
+    );
+
+    match result {
+        Ok(id) => Err(AppError::InvalidInput(format!(
+            "Entry overlaps with existing entry {}",
+            id
+        ))),
+        Err(duckdb::Error::QueryReturnedNoRows) => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// 完了済みの時間記録を同じ長さで別の開始時刻に複製する（成果物の紐付けも複製する）
+fn clone_entry_at(
+    conn: &Connection,
+    source_id: &Uuid,
+    new_started_at: DateTime<Utc>,
+) -> AppResult<TimeEntry> {
+    let tx = conn.unchecked_transaction()?;
+    let source = fetch_entry_by_id(&tx, source_id)?;
+    let Some(source_ended_at) = source.ended_at else {
+        return Err(AppError::InvalidInput(
+            "Cannot clone a running entry".to_string(),
+        ));
+    };
+
+    let new_ended_at = new_started_at + (source_ended_at - source.started_at);
+    ensure_no_overlap(&tx, new_started_at, new_ended_at, None)?;
+
+    let mut entry = TimeEntry::start(source.task_id, source.memo.clone());
+    entry.started_at = new_started_at;
+    entry.ended_at = Some(new_ended_at);
+    insert_entry(&tx, &entry)?;
+    tx.execute(
+        "INSERT INTO entry_artifacts (entry_id, artifact_id)
+         SELECT ?, artifact_id FROM entry_artifacts WHERE entry_id = ?",
+        [entry.id.to_string(), source.id.to_string()],
+    )?;
+    tx.commit()?;
+
+    Ok(entry)
+}
+
+/// 時間記録と紐付けを削除し、監査ログと同じトランザクションで保存する
+fn delete_entry_by_id(conn: &Connection, entry_id: &Uuid) -> AppResult<()> {
+    let tx = conn.unchecked_transaction()?;
+    // エントリが存在するか確認
+    let before = fetch_entry_by_id(&tx, entry_id)?;
+
+    // 紐付けを削除
+    tx.execute(
+        "DELETE FROM entry_artifacts WHERE entry_id = ?",
+        [entry_id.to_string()],
+    )?;
+    tx.execute(
+        "DELETE FROM heartbeats WHERE entry_id = ?",
+        [entry_id.to_string()],
+    )?;
+    tx.execute(
+        "DELETE FROM entry_notes WHERE entry_id = ?",
+        [entry_id.to_string()],
+    )?;
+
+    // エントリを削除
+    tx.execute(
+        "DELETE FROM time_entries WHERE id = ?",
+        [entry_id.to_string()],
+    )?;
+
+    record_audit(
+        &tx,
+        "entry",
+        entry_id,
+        "delete",
+        Some(&serde_json::to_string(&before)?),
+        None,
+    )?;
+    tx.commit()?;
+
+    Ok(())
+}
+
+/// 完了済みの時間記録を指定した開始時刻（RFC3339形式）に複製する
+#[tauri::command]
+pub fn clone_entry(
+    state: tauri::State<AppState>,
+    id: String,
+    new_started_at: String,
+) -> AppResult<TimeEntryWithRelations> {
+    let entry_id = Uuid::parse_str(&id)
+        .map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", id)))?;
+    let new_started_at = parse_optional_datetime(Some(new_started_at), "new_started_at")?
+        .ok_or_else(|| AppError::InvalidInput("new_started_at is required".to_string()))?;
+
+    let cloned = state.db.with_connection(|conn| {
+        let entry = clone_entry_at(conn, &entry_id, new_started_at)?;
+        let duration = entry.duration_seconds();
+        entry_to_with_relations(conn, entry, duration)
+    })?;
+    state.report_cache.invalidate(cloned.started_at);
+    Ok(cloned)
+}
+
+/// 時間記録を削除する
+#[tauri::command]
+pub fn delete_entry(state: tauri::State<AppState>, id: String) -> AppResult<()> {
+    let entry_id = Uuid::parse_str(&id)
+        .map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", id)))?;
+
+    let deleted = state.db.with_connection(|conn| {
+        let entry = fetch_entry_by_id(conn, &entry_id)?;
+        delete_entry_by_id(conn, &entry_id)?;
+        Ok(entry)
+    })?;
+    state.report_cache.invalidate(deleted.started_at);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::settings::{ARTIFACT_TYPES_ENFORCED_KEY, ARTIFACT_TYPES_KEY};
+    use crate::db::Database;
+
+    fn create_test_db() -> Database {
+        Database::new_in_memory().unwrap()
+    }
+
+    fn at(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value).unwrap().with_timezone(&Utc)
+    }
+
+    /// 指定した開始日時から指定した分数だけ作業した完了済みの記録を作成する
+    fn insert_completed(conn: &Connection, started_at: DateTime<Utc>, minutes: i64) -> AppResult<TimeEntry> {
+        let mut entry = TimeEntry::start(None, None);
+        entry.started_at = started_at;
+        entry.ended_at = Some(started_at + chrono::Duration::minutes(minutes));
+        insert_entry(conn, &entry)?;
+        Ok(entry)
+    }
+
+    /// 成果物を作成して記録に紐付け、作成した成果物のIDを返す
+    fn link_new_artifact(conn: &Connection, entry_id: &Uuid) -> AppResult<Uuid> {
+        let artifact_id = Uuid::new_v4();
+        conn.execute(
+            "INSERT INTO artifacts (id, name, artifact_type, created_at) VALUES (?, '成果物', 'document', ?)",
+            duckdb::params![artifact_id.to_string(), Utc::now()],
+        )?;
+        conn.execute(
+            "INSERT INTO entry_artifacts (entry_id, artifact_id) VALUES (?, ?)",
+            [entry_id.to_string(), artifact_id.to_string()],
+        )?;
+        Ok(artifact_id)
+    }
+
+    mod start_entry_tests {
+        use super::*;
+
+        #[test]
+        fn 計測を開始するとエントリが作成される() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let entry = TimeEntry::start(None, None);
+                insert_entry(conn, &entry)?;
+
+                let fetched = fetch_entry_by_id(conn, &entry.id)?;
+                assert_eq!(fetched.id, entry.id);
+                assert!(fetched.is_running());
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 計測開始時にタスクIDを紐付けできる() {
+            let db = create_test_db();
+            let task_id = Uuid::new_v4();
+
+            db.with_connection(|conn| {
+                // タスクを先に作成
+                conn.execute(
+                    "INSERT INTO tasks (id, name, color, created_at, updated_at) VALUES (?, 'テスト', '#000000', ?, ?)",
+                    duckdb::params![task_id.to_string(), Utc::now(), Utc::now()],
+                )?;
+
+                let entry = TimeEntry::start(Some(task_id), None);
+                insert_entry(conn, &entry)?;
+
+                let fetched = fetch_entry_by_id(conn, &entry.id)?;
+                assert_eq!(fetched.task_id, Some(task_id));
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn アーカイブ済みのタスクでは計測を開始できない() {
+            let db = create_test_db();
+            let task_id = Uuid::new_v4();
+
+            db.with_connection(|conn| {
+                conn.execute(
+                    "INSERT INTO tasks (id, name, color, archived, created_at, updated_at) VALUES (?, 'テスト', '#000000', TRUE, ?, ?)",
+                    duckdb::params![task_id.to_string(), Utc::now(), Utc::now()],
+                )?;
+
+                let result = ensure_task_startable(conn, &task_id);
+                assert!(matches!(result, Err(AppError::InvalidInput(_))));
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 存在しないタスクでは計測を開始できない() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let result = ensure_task_startable(conn, &Uuid::new_v4());
+                assert!(matches!(result, Err(AppError::NotFound(_))));
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 計測開始時にメモを設定できる() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let entry = TimeEntry::start(None, Some("作業開始".to_string()));
+                insert_entry(conn, &entry)?;
+
+                let fetched = fetch_entry_by_id(conn, &entry.id)?;
+                assert_eq!(fetched.memo, Some("作業開始".to_string()));
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 計測中のエントリがある場合は新しい計測を開始できない() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let entry = TimeEntry::start(None, None);
+                insert_entry(conn, &entry)?;
+
+                let running = fetch_running_entry(conn)?;
+                assert!(running.is_some());
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 過去の開始時刻を指定して計測を開始できる() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let started_at = Utc::now() - chrono::Duration::minutes(10);
+                let entry = begin_entry(conn, None, None, Some(started_at))?;
+
+                let fetched = fetch_entry_by_id(conn, &entry.id)?;
+                assert_eq!(fetched.started_at.timestamp(), started_at.timestamp());
+                assert!(fetched.is_running());
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 設定が有効ならメモなしで開始したときタスク名がメモに入る() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let task = Task::new("設計レビュー".to_string(), None, None, None);
+                conn.execute(
+                    "INSERT INTO tasks (id, name, color, created_at, updated_at) VALUES (?, ?, ?, ?, ?)",
+                    duckdb::params![task.id.to_string(), task.name, task.color, task.created_at, task.updated_at],
+                )?;
+
+                // 既定ではメモは空のまま
+                let entry = begin_entry(conn, Some(task.id), None, None)?;
+                assert_eq!(entry.memo, None);
+                delete_entry_by_id(conn, &entry.id)?;
+
+                conn.execute(
+                    "INSERT INTO settings (key, value, updated_at) VALUES (?, 'true', ?)",
+                    duckdb::params![MEMO_FROM_TASK_NAME_KEY, Utc::now()],
+                )?;
+                let entry = begin_entry(conn, Some(task.id), None, None)?;
+                assert_eq!(entry.memo.as_deref(), Some("設計レビュー"));
+                assert_eq!(fetch_entry_by_id(conn, &entry.id)?.memo.as_deref(), Some("設計レビュー"));
+                delete_entry_by_id(conn, &entry.id)?;
+
+                // メモを指定した場合はそちらを使う
+                let entry = begin_entry(conn, Some(task.id), Some("手入力".to_string()), None)?;
+                assert_eq!(entry.memo.as_deref(), Some("手入力"));
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 未来の開始時刻は指定できない() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let started_at = Utc::now() + chrono::Duration::minutes(10);
+                let result = begin_entry(conn, None, None, Some(started_at));
+                assert!(matches!(result, Err(AppError::InvalidInput(_))));
+                assert!(fetch_running_entry(conn)?.is_none());
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 直前の記録と重なる開始時刻は指定できない() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let now = Utc::now();
+                let mut previous = TimeEntry::start(None, None);
+                previous.started_at = now - chrono::Duration::minutes(30);
+                previous.ended_at = Some(now - chrono::Duration::minutes(5));
+                insert_entry(conn, &previous)?;
+
+                let result = begin_entry(conn, None, None, Some(now - chrono::Duration::minutes(10)));
+                assert!(matches!(result, Err(AppError::InvalidInput(_))));
+
+                let entry = begin_entry(conn, None, None, Some(now - chrono::Duration::minutes(5)))?;
+                assert_eq!(entry.started_at, now - chrono::Duration::minutes(5));
+                Ok(())
+            })
+            .unwrap();
+        }
+    }
+
+    mod task_lock_tests {
+        use super::*;
+
+        #[test]
+        fn ロック中はロックしたタスク以外で開始できない() {
+            let lock = TaskLock::default();
+            let locked = Uuid::new_v4();
+            let other = Uuid::new_v4();
+
+            assert!(lock.ensure_allows(Some(&other)).is_ok());
+
+            lock.set(Some(locked));
+            assert_eq!(lock.get(), Some(locked));
+            assert!(lock.ensure_allows(Some(&locked)).is_ok());
+            assert!(matches!(
+                lock.ensure_allows(Some(&other)),
+                Err(AppError::OperationFailed(_))
+            ));
+            assert!(matches!(
+                lock.ensure_allows(None),
+                Err(AppError::OperationFailed(_))
+            ));
+
+            lock.set(None);
+            assert!(lock.ensure_allows(None).is_ok());
+        }
+    }
+
+    mod memo_template_tests {
+        use super::*;
+
+        fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        }
+
+        #[test]
+        fn プレースホルダーが変数の値に置き換わる() {
+            let rendered = render_memo_template(
+                "昨日: {{yesterday}} / 今日: {{ today }}",
+                &vars(&[("yesterday", "設計"), ("today", "実装")]),
+                false,
+            )
+            .unwrap();
+            assert_eq!(rendered, "昨日: 設計 / 今日: 実装");
+        }
+
+        #[test]
+        fn 値のないプレースホルダーはそのまま残りstrictならエラーになる() {
+            let template = "今日: {{today}} / 課題: {{blockers}}";
+            let vars = vars(&[("today", "実装")]);
+
+            let rendered = render_memo_template(template, &vars, false).unwrap();
+            assert_eq!(rendered, "今日: 実装 / 課題: {{blockers}}");
+
+            let result = render_memo_template(template, &vars, true);
+            assert!(matches!(result, Err(AppError::InvalidInput(_))));
+        }
+
+        #[test]
+        fn 保存したテンプレートを展開して計測を開始できる() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                conn.execute(
+                    "INSERT INTO settings (key, value, updated_at) VALUES (?, ?, ?)",
+                    duckdb::params![
+                        format!("{}standup", MEMO_TEMPLATE_KEY_PREFIX),
+                        "今日: {{today}}",
+                        Utc::now()
+                    ],
+                )?;
+
+                let entry = begin_entry_from_template(
+                    conn,
+                    None,
+                    "standup",
+                    &vars(&[("today", "レビュー")]),
+                    false,
+                )?;
+                assert_eq!(entry.memo, Some("今日: レビュー".to_string()));
+                assert!(fetch_running_entry(conn)?.is_some());
+
+                let missing =
+                    begin_entry_from_template(conn, None, "unknown", &HashMap::new(), false);
+                assert!(matches!(missing, Err(AppError::NotFound(_))));
+                Ok(())
+            })
+            .unwrap();
+        }
+    }
+
+    mod concurrent_start_tests {
+        use super::*;
+        use std::sync::{Arc, Barrier};
+
+        #[test]
+        fn 計測中エントリがある状態で制約違反になるとAlreadyExistsになる() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                insert_entry(conn, &TimeEntry::start(None, None))?;
+
+                let result = insert_entry(conn, &TimeEntry::start(None, None))
+                    .map_err(map_running_entry_conflict);
+                assert!(matches!(result, Err(AppError::AlreadyExists(_))));
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 同時に計測を開始しても計測中エントリは1件だけになる() {
+            let db = create_test_db();
+            let conns = db
+                .with_connection(|conn| Ok([conn.try_clone()?, conn.try_clone()?]))
+                .unwrap();
+            let barrier = Arc::new(Barrier::new(2));
+
+            let handles: Vec<_> = conns
+                .into_iter()
+                .map(|conn| {
+                    let barrier = Arc::clone(&barrier);
+                    std::thread::spawn(move || {
+                        let tx = conn.unchecked_transaction()?;
+                        // 両方のスレッドが計測中エントリなしを確認してから挿入する
+                        let running = fetch_running_entry(&tx)?;
+                        barrier.wait();
+                        if running.is_some() {
+                            return Err(AppError::AlreadyExists("running".to_string()));
+                        }
+                        insert_entry(&tx, &TimeEntry::start(None, None))
+                            .map_err(map_running_entry_conflict)?;
+                        tx.commit()
+                            .map_err(AppError::from)
+                            .map_err(map_running_entry_conflict)
+                    })
+                })
+                .collect();
+
+            let results: Vec<AppResult<()>> =
+                handles.into_iter().map(|h| h.join().unwrap()).collect();
+            assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 1);
+            assert!(results
+                .iter()
+                .any(|r| matches!(r, Err(AppError::AlreadyExists(_)))));
+
+            let running: i64 = db
+                .with_connection(|conn| {
+                    Ok(conn.query_row(
+                        "SELECT COUNT(*) FROM time_entries WHERE ended_at IS NULL",
+                        [],
+                        |row| row.get(0),
+                    )?)
+                })
+                .unwrap();
+            assert_eq!(running, 1);
+        }
+    }
+
+    mod stop_entry_tests {
+        use super::*;
+
+        #[test]
+        fn 終了日時を指定して停止できる() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let now = Utc::now();
+                let mut entry = TimeEntry::start(None, None);
+                entry.started_at = now - chrono::Duration::hours(20);
+                insert_entry(conn, &entry)?;
+
+                let ended_at = now - chrono::Duration::hours(12);
+                let stopped = finish_entry(conn, None, Vec::new(), Some(ended_at), now)?;
+                assert_eq!(stopped.ended_at, Some(ended_at));
+                assert_eq!(stopped.duration_seconds(), Some(8 * 60 * 60));
+
+                let fetched = fetch_entry_by_id(conn, &entry.id)?;
+                assert_eq!(
+                    fetched.ended_at.map(|t| t.timestamp()),
+                    Some(ended_at.timestamp())
+                );
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 開始前や未来の終了日時は指定できない() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let now = Utc::now();
+                let mut entry = TimeEntry::start(None, None);
+                entry.started_at = now - chrono::Duration::hours(1);
+                insert_entry(conn, &entry)?;
+
+                let before_start = now - chrono::Duration::hours(2);
+                let result = finish_entry(conn, None, Vec::new(), Some(before_start), now);
+                assert!(matches!(result, Err(AppError::InvalidInput(_))));
+
+                let future = now + chrono::Duration::minutes(10);
+                let result = finish_entry(conn, None, Vec::new(), Some(future), now);
+                assert!(matches!(result, Err(AppError::InvalidInput(_))));
+                assert!(fetch_entry_by_id(conn, &entry.id)?.is_running());
+
+                // 時計のずれの範囲内なら現在時刻として停止する
+                let skewed = now + chrono::Duration::seconds(30);
+                let stopped = finish_entry(conn, None, Vec::new(), Some(skewed), now)?;
+                assert_eq!(stopped.ended_at, Some(now));
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 最小時間に満たない記録は停止したうえで警告する() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let now = Utc::now();
+                let mut entry = TimeEntry::start(None, None);
+                entry.started_at = now - chrono::Duration::seconds(1);
+                insert_entry(conn, &entry)?;
+
+                let stopped =
+                    finish_entry_with_warning(conn, None, Vec::new(), Some(now), Some(60), now)?;
+                assert!(!fetch_entry_by_id(conn, &entry.id)?.is_running());
+                assert_eq!(stopped.id, entry.id);
+                assert!(stopped.warning.is_some_and(|w| w.contains("1 seconds")));
+
+                assert_eq!(short_duration_warning(Some(60), Some(60)), None);
+                assert_eq!(short_duration_warning(Some(1), None), None);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 最小時間に負の値を指定すると停止しない() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let entry = TimeEntry::start(None, None);
+                insert_entry(conn, &entry)?;
+
+                let result = finish_entry_with_warning(conn, None, Vec::new(), None, Some(-1), Utc::now());
+                assert!(matches!(result, Err(AppError::InvalidInput(_))));
+                assert!(fetch_entry_by_id(conn, &entry.id)?.is_running());
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 停止時に指定した成果物が作成されエントリに紐付く() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let entry = TimeEntry::start(None, None);
+                insert_entry(conn, &entry)?;
+
+                let artifacts = vec![CreateArtifact {
+                    name: "PR #42".to_string(),
+                    artifact_type: "pull_request".to_string(),
+                    reference: Some("https://example.com/pull/42".to_string()),
+                    metadata: None,
+                }];
+                let stopped = finish_entry(conn, None, artifacts, None, Utc::now())?;
+                assert!(!stopped.is_running());
+
+                let duration = stopped.duration_seconds();
+                let with_relations = entry_to_with_relations(conn, stopped, duration)?;
+                assert_eq!(with_relations.artifacts.len(), 1);
+                assert_eq!(with_relations.artifacts[0].name, "PR #42");
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 成果物の内容が不正な場合は停止も成果物の作成も行われない() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let entry = TimeEntry::start(None, None);
+                insert_entry(conn, &entry)?;
+
+                let artifacts = vec![CreateArtifact {
+                    name: " ".to_string(),
+                    artifact_type: "document".to_string(),
+                    reference: None,
+                    metadata: None,
+                }];
+                let result = finish_entry(conn, Some(&entry.id), artifacts, None, Utc::now());
+                assert!(matches!(result, Err(AppError::InvalidInput(_))));
+
+                assert!(fetch_entry_by_id(conn, &entry.id)?.is_running());
+                let count: i64 = conn.query_row("SELECT COUNT(*) FROM artifacts", [], |row| row.get(0))?;
+                assert_eq!(count, 0);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 許可されていない種別の成果物を指定すると停止しない() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                store_setting(conn, ARTIFACT_TYPES_KEY, Some(r#"["document"]"#))?;
+                store_setting(conn, ARTIFACT_TYPES_ENFORCED_KEY, Some("true"))?;
+                let entry = TimeEntry::start(None, None);
+                insert_entry(conn, &entry)?;
+
+                let artifacts = vec![CreateArtifact {
+                    name: "PR #42".to_string(),
+                    artifact_type: "pull_request".to_string(),
+                    reference: None,
+                    metadata: None,
+                }];
+                let result = finish_entry(conn, Some(&entry.id), artifacts, None, Utc::now());
+                assert!(matches!(result, Err(AppError::InvalidInput(_))));
+
+                assert!(fetch_entry_by_id(conn, &entry.id)?.is_running());
+                let count: i64 = conn.query_row("SELECT COUNT(*) FROM artifacts", [], |row| row.get(0))?;
+                assert_eq!(count, 0);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 計測を停止すると終了時刻が設定される() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let entry = TimeEntry::start(None, None);
+                insert_entry(conn, &entry)?;
+
+                let now = Utc::now();
+                conn.execute(
+                    "UPDATE time_entries SET ended_at = ?, updated_at = ? WHERE id = ?",
+                    duckdb::params![now, now, entry.id.to_string()],
+                )?;
+
+                let stopped = fetch_entry_by_id(conn, &entry.id)?;
+                assert!(!stopped.is_running());
+                assert!(stopped.ended_at.is_some());
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 終了済みのエントリは作業秒数が保存され計測中はNULLになる() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let running = TimeEntry::start(None, None);
+                insert_entry(conn, &running)?;
+                let mut done = TimeEntry::start(None, None);
+                done.ended_at = Some(done.started_at + chrono::Duration::seconds(90));
+                insert_entry(conn, &done)?;
+
+                let stored = |id: &Uuid| -> AppResult<Option<i64>> {
+                    Ok(conn.query_row(
+                        "SELECT duration_seconds FROM time_entries WHERE id = ?",
+                        [id.to_string()],
+                        |row| row.get(0),
+                    )?)
+                };
+                assert_eq!(stored(&running.id)?, None);
+                assert_eq!(stored(&done.id)?, Some(90));
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 計測中でないエントリは停止できない() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let mut entry = TimeEntry::start(None, None);
+                entry.ended_at = Some(Utc::now());
+                insert_entry(conn, &entry)?;
+
+                let fetched = fetch_entry_by_id(conn, &entry.id)?;
+                assert!(!fetched.is_running());
+                Ok(())
+            })
+            .unwrap();
+        }
+    }
+
+    mod import_conflicts_tests {
+        use super::*;
+
+        #[test]
+        fn 重なり合う記録をまとまりごとに返す() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let base = at("2024-12-01T09:00:00Z");
+                let a = insert_completed(conn, base, 60)?;
+                let b = insert_completed(conn, base + chrono::Duration::minutes(30), 60)?;
+                let c = insert_completed(conn, base + chrono::Duration::minutes(80), 30)?;
+                // 重なりのない記録
+                insert_completed(conn, base + chrono::Duration::hours(3), 30)?;
+                let d = insert_completed(conn, base + chrono::Duration::hours(5), 60)?;
+                let e = insert_completed(conn, base + chrono::Duration::hours(5), 10)?;
+
+                let clusters = fetch_overlap_clusters(conn)?;
+                assert_eq!(clusters.len(), 2);
+
+                let first: Vec<Uuid> = clusters[0].entries.iter().map(|x| x.id).collect();
+                assert_eq!(first, vec![a.id, b.id, c.id]);
+                assert_eq!(clusters[0].started_at, a.started_at);
+                assert_eq!(clusters[0].ended_at, c.ended_at);
+
+                let mut second: Vec<Uuid> = clusters[1].entries.iter().map(|x| x.id).collect();
+                second.sort();
+                let mut expected = vec![d.id, e.id];
+                expected.sort();
+                assert_eq!(second, expected);
+                assert_eq!(clusters[1].ended_at, d.ended_at);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 終了と開始が接している記録は重なりとみなさない() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let base = Utc::now() - chrono::Duration::days(1);
+                insert_completed(conn, base, 30)?;
+                insert_completed(conn, base + chrono::Duration::minutes(30), 30)?;
+
+                assert!(fetch_overlap_clusters(conn)?.is_empty());
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 計測中の記録と重なる場合は終了日時なしのまとまりになる() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let base = Utc::now() - chrono::Duration::hours(2);
+                let mut running = TimeEntry::start(None, None);
+                running.started_at = base;
+                insert_entry(conn, &running)?;
+                insert_completed(conn, base + chrono::Duration::minutes(30), 30)?;
+
+                let clusters = fetch_overlap_clusters(conn)?;
+                assert_eq!(clusters.len(), 1);
+                assert_eq!(clusters[0].entries.len(), 2);
+                assert!(clusters[0].ended_at.is_none());
+                Ok(())
+            })
+            .unwrap();
+        }
+    }
+
+    mod switch_entry_tests {
+        use super::*;
+
+        #[test]
+        fn 計測中の記録を止めて同じ時刻から新しい記録を開始する() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let now = Utc::now();
+                let mut running = TimeEntry::start(None, Some("前の作業".to_string()));
+                running.started_at = now - chrono::Duration::minutes(30);
+                insert_entry(conn, &running)?;
+
+                let switched = switch_running_entry(conn, None, Some("次の作業".to_string()), now)?;
+                let stopped = switched.stopped.unwrap();
+                assert_eq!(stopped.id, running.id);
+                assert_eq!(stopped.ended_at, Some(now));
+                assert_eq!(switched.started.started_at, now);
+                assert_eq!(switched.started.memo.as_deref(), Some("次の作業"));
+
+                let current = fetch_running_entry(conn)?.unwrap();
+                assert_eq!(current.id, switched.started.id);
+                assert!(!fetch_entry_by_id(conn, &running.id)?.is_running());
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 計測中の記録がなければ開始だけ行う() {
+            let db = create_test_db();
+
+            let switched = db
+                .with_connection(|conn| switch_running_entry(conn, None, None, Utc::now()))
+                .unwrap();
+
+            assert!(switched.stopped.is_none());
+            assert!(switched.started.is_running());
+        }
+
+        #[test]
+        fn 開始できないタスクを指定すると計測中の記録も止めない() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let running = TimeEntry::start(None, None);
+                insert_entry(conn, &running)?;
+
+                let result = switch_running_entry(conn, Some(Uuid::new_v4()), None, Utc::now());
+                assert!(matches!(result, Err(AppError::NotFound(_))));
+
+                let current = fetch_running_entry(conn)?.unwrap();
+                assert_eq!(current.id, running.id);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 止めた記録にもメモから自動割り当てルールが適用される() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let task = Task::new("レビュー".to_string(), None, None, None);
+                conn.execute(
+                    "INSERT INTO tasks (id, name, color, created_at, updated_at) VALUES (?, ?, ?, ?, ?)",
+                    duckdb::params![task.id.to_string(), task.name, task.color, task.created_at, task.updated_at],
+                )?;
+                conn.execute(
+                    "INSERT INTO auto_rules (id, pattern, task_id, created_at) VALUES (?, 'review', ?, ?)",
+                    duckdb::params![Uuid::new_v4().to_string(), task.id.to_string(), Utc::now()],
+                )?;
+                let mut running = TimeEntry::start(None, Some("Review対応".to_string()));
+                running.started_at = Utc::now() - chrono::Duration::minutes(30);
+                insert_entry(conn, &running)?;
+
+                let switched = switch_running_entry(conn, None, None, Utc::now())?;
+                assert_eq!(switched.stopped.unwrap().task_id, Some(task.id));
+                assert_eq!(fetch_entry_by_id(conn, &running.id)?.task_id, Some(task.id));
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 設定が有効なら切り替え後の記録のメモにタスク名が入る() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let task = Task::new("設計レビュー".to_string(), None, None, None);
+                conn.execute(
+                    "INSERT INTO tasks (id, name, color, created_at, updated_at) VALUES (?, ?, ?, ?, ?)",
+                    duckdb::params![task.id.to_string(), task.name, task.color, task.created_at, task.updated_at],
+                )?;
+                conn.execute(
+                    "INSERT INTO settings (key, value, updated_at) VALUES (?, 'true', ?)",
+                    duckdb::params![MEMO_FROM_TASK_NAME_KEY, Utc::now()],
+                )?;
+                insert_entry(conn, &TimeEntry::start(None, None))?;
+
+                let switched = switch_running_entry(conn, Some(task.id), None, Utc::now())?;
+                assert_eq!(switched.started.memo.as_deref(), Some("設計レビュー"));
+                Ok(())
+            })
+            .unwrap();
+        }
+    }
+
+    mod split_at_midnight_tests {
+        use super::*;
+
+        #[test]
+        fn ローカル0時はオフセットを考慮して求められる() {
+            // JST 2024-12-02 09:00 の直近の0時は UTC 2024-12-01 15:00
+            assert_eq!(
+                last_local_midnight(at("2024-12-02T00:00:00Z"), 540),
+                at("2024-12-01T15:00:00Z")
+            );
+            assert_eq!(
+                last_local_midnight(at("2024-12-02T10:00:00Z"), 0),
+                at("2024-12-02T00:00:00Z")
+            );
+        }
+
+        #[test]
+        fn 日付をまたいだ計測中エントリは0時で分割される() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let mut entry = TimeEntry::start(None, Some("夜作業".to_string()));
+                entry.started_at = at("2024-12-01T22:00:00Z");
+                insert_entry(conn, &entry)?;
+
+                let next = split_running_at_midnight(conn, at("2024-12-02T01:00:00Z"), 0)?.unwrap();
+
+                let stopped = fetch_entry_by_id(conn, &entry.id)?;
+                assert_eq!(stopped.ended_at, Some(at("2024-12-02T00:00:00Z")));
+                assert_eq!(next.started_at, at("2024-12-02T00:00:00Z"));
+                assert_eq!(next.memo, Some("夜作業".to_string()));
+                assert_eq!(fetch_running_entry(conn)?.unwrap().id, next.id);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 今日開始した計測中エントリは分割されない() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let mut entry = TimeEntry::start(None, None);
+                entry.started_at = at("2024-12-02T08:00:00Z");
+                insert_entry(conn, &entry)?;
+
+                let result = split_running_at_midnight(conn, at("2024-12-02T10:00:00Z"), 0)?;
+                assert!(result.is_none());
+                assert!(fetch_entry_by_id(conn, &entry.id)?.is_running());
+                Ok(())
+            })
+            .unwrap();
+        }
+    }
+
+    mod heartbeat_tests {
+        use super::*;
+
+        #[test]
+        fn 計測中のエントリが最終ハートビートとともに復旧候補として返る() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                assert!(fetch_recovery_candidate(conn)?.is_none());
+
+                let mut entry = TimeEntry::start(None, None);
+                entry.started_at = Utc::now() - chrono::Duration::hours(5);
+                insert_entry(conn, &entry)?;
+                assert!(fetch_recovery_candidate(conn)?.unwrap().last_heartbeat.is_none());
+
+                let last_heartbeat = Utc::now() - chrono::Duration::hours(4);
+                upsert_heartbeat(conn, &entry.id, last_heartbeat)?;
+
+                let candidate = fetch_recovery_candidate(conn)?.unwrap();
+                assert_eq!(candidate.entry.id, entry.id);
+                assert_eq!(
+                    candidate.last_heartbeat.map(|at| at.timestamp()),
+                    Some(last_heartbeat.timestamp())
+                );
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 選んだ終了時刻で計測中のエントリを確定できる() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let now = Utc::now();
+                let mut entry = TimeEntry::start(None, None);
+                entry.started_at = now - chrono::Duration::hours(5);
+                insert_entry(conn, &entry)?;
+                upsert_heartbeat(conn, &entry.id, now - chrono::Duration::hours(4))?;
+
+                // 開始前や未来の終了時刻は指定できない
+                let too_early = finalize_recovery(conn, &entry.id, now - chrono::Duration::hours(6), now);
+                assert!(matches!(too_early, Err(AppError::InvalidInput(_))));
+                let future = finalize_recovery(conn, &entry.id, now + chrono::Duration::hours(1), now);
+                assert!(matches!(future, Err(AppError::InvalidInput(_))));
+                assert!(fetch_entry_by_id(conn, &entry.id)?.is_running());
+
+                let ended_at = now - chrono::Duration::hours(4);
+                let recovered = finalize_recovery(conn, &entry.id, ended_at, now)?;
+                assert_eq!(recovered.duration_seconds(), Some(3600));
+                assert!(!fetch_entry_by_id(conn, &entry.id)?.is_running());
+
+                let heartbeats: i64 = conn.query_row("SELECT COUNT(*) FROM heartbeats", [], |row| row.get(0))?;
+                assert_eq!(heartbeats, 0);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 自動停止が有効なら無操作時間を超えた計測を最終ハートビートで停止する() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let now = Utc::now();
+                let mut entry = TimeEntry::start(None, None);
+                entry.started_at = now - chrono::Duration::hours(2);
+                insert_entry(conn, &entry)?;
+                let last_heartbeat = now - chrono::Duration::minutes(30);
+                upsert_heartbeat(conn, &entry.id, last_heartbeat)?;
+
+                // 未設定なら停止しない
+                assert!(enforce_auto_stop_at(conn, now)?.is_none());
+
+                // 無操作時間を超えていなければ停止しない
+                store_setting(conn, AUTO_STOP_IDLE_SECONDS_KEY, Some("3600"))?;
+                assert!(enforce_auto_stop_at(conn, now)?.is_none());
+                assert!(fetch_entry_by_id(conn, &entry.id)?.is_running());
+
+                store_setting(conn, AUTO_STOP_IDLE_SECONDS_KEY, Some("600"))?;
+                let stopped = enforce_auto_stop_at(conn, now)?.unwrap();
+                assert_eq!(stopped.id, entry.id);
+                assert_eq!(
+                    stopped.ended_at.map(|at| at.timestamp()),
+                    Some(last_heartbeat.timestamp())
+                );
+                assert!(!fetch_entry_by_id(conn, &entry.id)?.is_running());
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn ハートビートのない計測や0の設定では自動停止しない() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let now = Utc::now();
+                let mut entry = TimeEntry::start(None, None);
+                entry.started_at = now - chrono::Duration::hours(2);
+                insert_entry(conn, &entry)?;
+
+                store_setting(conn, AUTO_STOP_IDLE_SECONDS_KEY, Some("60"))?;
+                assert!(enforce_auto_stop_at(conn, now)?.is_none());
+
+                upsert_heartbeat(conn, &entry.id, now - chrono::Duration::hours(1))?;
+                store_setting(conn, AUTO_STOP_IDLE_SECONDS_KEY, Some("0"))?;
+                assert!(enforce_auto_stop_at(conn, now)?.is_none());
+                assert!(fetch_entry_by_id(conn, &entry.id)?.is_running());
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn ハートビートは上書きされる() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let entry = TimeEntry::start(None, None);
+                insert_entry(conn, &entry)?;
+
+                upsert_heartbeat(conn, &entry.id, Utc::now() - chrono::Duration::minutes(10))?;
+                upsert_heartbeat(conn, &entry.id, Utc::now())?;
+
+                let count: i64 = conn.query_row("SELECT COUNT(*) FROM heartbeats", [], |row| row.get(0))?;
+                assert_eq!(count, 1);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 古いハートビートの計測中エントリは最終ハートビートで終了される() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let mut entry = TimeEntry::start(None, None);
+                entry.started_at = Utc::now() - chrono::Duration::hours(3);
+                insert_entry(conn, &entry)?;
+                let last_heartbeat = Utc::now() - chrono::Duration::hours(2);
+                upsert_heartbeat(conn, &entry.id, last_heartbeat)?;
+
+                let cutoff = Utc::now() - chrono::Duration::minutes(30);
+
+                // apply=falseでは更新しない
+                let preview = recover_stale(conn, cutoff, false)?;
+                assert_eq!(preview.len(), 1);
+                assert!(fetch_entry_by_id(conn, &entry.id)?.is_running());
+
+                let recovered = recover_stale(conn, cutoff, true)?;
+                assert_eq!(recovered.len(), 1);
+                let fetched = fetch_entry_by_id(conn, &entry.id)?;
+                assert_eq!(
+                    fetched.ended_at.map(|t| t.timestamp()),
+                    Some(last_heartbeat.timestamp())
+                );
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 新しいハートビートの計測中エントリは対象外() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let entry = TimeEntry::start(None, None);
+                insert_entry(conn, &entry)?;
+                upsert_heartbeat(conn, &entry.id, Utc::now())?;
+
+                let cutoff = Utc::now() - chrono::Duration::minutes(30);
+                assert!(recover_stale(conn, cutoff, true)?.is_empty());
+                Ok(())
+            })
+            .unwrap();
+        }
+    }
+
+    mod suspicious_entries_tests {
+        use super::*;
+
+        #[test]
+        fn 作業時間が0以下や長すぎる記録が理由付きで取得される() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                conn.execute(
+                    "INSERT INTO time_entries (id, started_at, ended_at, created_at, updated_at) VALUES
+                     ('00000000-0000-0000-0000-000000000001', '2024-01-15 09:00:00+00', '2024-01-15 09:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     ('00000000-0000-0000-0000-000000000002', '2024-01-14 10:00:00+00', '2024-01-14 09:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     ('00000000-0000-0000-0000-000000000003', '2024-01-10 09:00:00+00', '2024-01-12 09:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     ('00000000-0000-0000-0000-000000000004', '2024-01-16 09:00:00+00', '2024-01-16 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                    [],
+                )?;
+                insert_entry(conn, &TimeEntry::start(None, None))?;
+
+                let suspicious = fetch_suspicious_entries(conn, DEFAULT_SUSPICIOUS_MAX_SECONDS)?;
+                let reasons: Vec<&str> = suspicious.iter().map(|s| s.reason.as_str()).collect();
+                assert_eq!(reasons, vec!["zero_duration", "negative_duration", "too_long"]);
+                assert_eq!(suspicious[1].duration_seconds, -3600);
+                assert_eq!(suspicious[2].duration_seconds, 2 * 24 * 60 * 60);
+
+                // 上限を下げると通常の記録も長すぎる記録になる
+                assert_eq!(fetch_suspicious_entries(conn, 1800)?.len(), 4);
+                Ok(())
+            })
+            .unwrap();
+        }
+    }
+
+    mod get_running_entry_tests {
+        use super::*;
+
+        #[test]
+        fn 計測中のエントリがない場合はNoneが返る() {
+            let db = create_test_db();
+
+            let result = db.with_connection(fetch_running_entry).unwrap();
+
+            assert!(result.is_none());
+        }
+
+        #[test]
+        fn 計測中のエントリがある場合はそのエントリが返る() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let entry = TimeEntry::start(None, Some("計測中".to_string()));
+                insert_entry(conn, &entry)?;
+
+                let running = fetch_running_entry(conn)?;
+                assert!(running.is_some());
+                assert_eq!(running.unwrap().id, entry.id);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 計測中エントリの状態として経過秒数が返る() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                assert!(fetch_running_status(conn, Utc::now())?.is_none());
+
+                let entry = TimeEntry::start(None, None);
+                insert_entry(conn, &entry)?;
+
+                let now = entry.started_at + chrono::Duration::seconds(125);
+                let status = fetch_running_status(conn, now)?.unwrap();
+                assert_eq!(status.id, entry.id);
+                assert_eq!(status.elapsed_seconds, 125);
+                Ok(())
+            })
+            .unwrap();
+        }
+    }
+
+    mod list_entries_tests {
+        use super::*;
+
+        #[test]
+        fn 計測中のエントリは期間外でも経過秒数付きで含まれる() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let now = Utc::now();
+                let mut running = TimeEntry::start(None, None);
+                running.started_at = now - chrono::Duration::minutes(90);
+                insert_entry(conn, &running)?;
+                let mut done = TimeEntry::start(None, None);
+                done.started_at = now - chrono::Duration::days(3);
+                done.ended_at = Some(now - chrono::Duration::days(3) + chrono::Duration::hours(1));
+                insert_entry(conn, &done)?;
+
+                let filter = EntryFilter {
+                    to: Some(now - chrono::Duration::days(2)),
+                    ..Default::default()
+                };
+                assert_eq!(fetch_entries_with_filter(conn, &filter)?.len(), 1);
+
+                let entries = fetch_entries_with_running(conn, &filter, now)?;
+                assert_eq!(entries.len(), 2);
+                assert_eq!(entries[0].id, running.id);
+                assert!(entries[0].is_running);
+                assert_eq!(entries[0].elapsed_seconds, Some(90 * 60));
+                assert!(!entries[1].is_running);
+                assert_eq!(entries[1].elapsed_seconds, None);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 空のデータベースからエントリ一覧を取得すると空のベクターが返る() {
+            let db = create_test_db();
+
+            let entries = db
+                .with_connection(|conn| fetch_entries_with_filter(conn, &EntryFilter::default()))
+                .unwrap();
+
+            assert!(entries.is_empty());
+        }
+
+        #[test]
+        fn 作成したエントリが一覧に含まれる() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let entry = TimeEntry::start(None, None);
+                insert_entry(conn, &entry)?;
+
+                let entries = fetch_entries_with_filter(conn, &EntryFilter::default())?;
+                assert_eq!(entries.len(), 1);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn limitを指定すると取得件数が制限される() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                for _ in 0..5 {
+                    let mut entry = TimeEntry::start(None, None);
+                    entry.ended_at = Some(Utc::now());
+                    insert_entry(conn, &entry)?;
+                }
+
+                let filter = EntryFilter {
+                    limit: Some(3),
+                    ..Default::default()
+                };
+                let entries = fetch_entries_with_filter(conn, &filter)?;
+                assert_eq!(entries.len(), 3);
+                Ok(())
+            })
+            .unwrap();
         }
-        if let Some(memo) = update.memo {
-            entry.memo = Some(memo);
+
+        #[test]
+        fn task_idでフィルタできる() {
+            let db = create_test_db();
+            let task_id = Uuid::new_v4();
+
+            db.with_connection(|conn| {
+                conn.execute(
+                    "INSERT INTO tasks (id, name, color, created_at, updated_at) VALUES (?, 'テスト', '#000000', ?, ?)",
+                    duckdb::params![task_id.to_string(), Utc::now(), Utc::now()],
+                )?;
+
+                // タスク付きエントリ
+                let mut entry1 = TimeEntry::start(Some(task_id), None);
+                entry1.ended_at = Some(Utc::now());
+                insert_entry(conn, &entry1)?;
+
+                // タスクなしエントリ
+                let mut entry2 = TimeEntry::start(None, None);
+                entry2.ended_at = Some(Utc::now());
+                insert_entry(conn, &entry2)?;
+
+                let filter = EntryFilter {
+                    task_id: Some(task_id),
+                    ..Default::default()
+                };
+                let entries = fetch_entries_with_filter(conn, &filter)?;
+                assert_eq!(entries.len(), 1);
+                assert_eq!(entries[0].task_id, Some(task_id));
+                Ok(())
+            })
+            .unwrap();
         }
-        entry.updated_at = Utc::now();
 
-        conn.execute(
-            "UPDATE time_entries SET task_id = ?, started_at = ?, ended_at = ?, memo = ?, updated_at = ? WHERE id = ?",
-            duckdb::params![
-                entry.task_id.map(|id| id.to_string()),
-                entry.started_at,
-                entry.ended_at,
-                &entry.memo,
-                entry.updated_at,
-                entry.id.to_string(),
-            ],
-        )?;
+        #[test]
+        fn エントリ一覧は開始日時の降順でソートされる() {
+            let db = create_test_db();
 
-        Ok(entry)
-    })
-}
+            db.with_connection(|conn| {
+                let mut entry1 = TimeEntry::start(None, Some("1番目".to_string()));
+                entry1.ended_at = Some(Utc::now());
+                insert_entry(conn, &entry1)?;
 
-/// 時間記録を削除する
-#[tauri::command]
-pub fn delete_entry(state: tauri::State<AppState>, id: String) -> AppResult<()> {
-    let entry_id = Uuid::parse_str(&id)
-        .map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", id)))?;
+                std::thread::sleep(std::time::Duration::from_millis(10));
 
-    state.db.with_connection(|conn| {
-        // エントリが存在するか確認
-        let _ = fetch_entry_by_id(conn, &entry_id)?;
+                let mut entry2 = TimeEntry::start(None, Some("2番目".to_string()));
+                entry2.ended_at = Some(Utc::now());
+                insert_entry(conn, &entry2)?;
 
-        // 紐付けを削除
-        conn.execute(
-            "DELETE FROM entry_artifacts WHERE entry_id = ?",
-            [entry_id.to_string()],
-        )?;
+                let entries = fetch_entries_with_filter(conn, &EntryFilter::default())?;
+                assert_eq!(entries.len(), 2);
+                assert_eq!(entries[0].memo, Some("2番目".to_string())); // 新しい方が先
+                assert_eq!(entries[1].memo, Some("1番目".to_string()));
+                Ok(())
+            })
+            .unwrap();
+        }
+        #[test]
+        fn 一覧取得でも各エントリのタスクと成果物がまとめて取得される() {
+            let db = create_test_db();
+            let task_id = Uuid::new_v4();
 
-        // エントリを削除
-        conn.execute(
-            "DELETE FROM time_entries WHERE id = ?",
-            [entry_id.to_string()],
-        )?;
+            db.with_connection(|conn| {
+                conn.execute(
+                    "INSERT INTO tasks (id, name, color, created_at, updated_at) VALUES (?, 'テスト', '#000000', ?, ?)",
+                    duckdb::params![task_id.to_string(), Utc::now(), Utc::now()],
+                )?;
 
-        Ok(())
-    })
-}
+                let mut with_task = TimeEntry::start(Some(task_id), Some("タスクあり".to_string()));
+                with_task.ended_at = Some(with_task.started_at + chrono::Duration::seconds(60));
+                insert_entry(conn, &with_task)?;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::db::Database;
+                std::thread::sleep(std::time::Duration::from_millis(10));
 
-    fn create_test_db() -> Database {
-        Database::new_in_memory().unwrap()
+                let mut without_task = TimeEntry::start(None, Some("タスクなし".to_string()));
+                without_task.ended_at = Some(without_task.started_at + chrono::Duration::seconds(60));
+                insert_entry(conn, &without_task)?;
+
+                let artifact_id = link_new_artifact(conn, &with_task.id)?;
+
+                let entries = fetch_entries_with_filter(conn, &EntryFilter::default())?;
+                assert_eq!(entries.len(), 2);
+                assert_eq!(entries[0].memo, Some("タスクなし".to_string()));
+                assert!(entries[0].task.is_none());
+                assert!(entries[0].artifacts.is_empty());
+                assert_eq!(entries[1].task.as_ref().unwrap().id, task_id);
+                assert_eq!(entries[1].artifacts.len(), 1);
+                assert_eq!(entries[1].artifacts[0].id, artifact_id);
+                assert_eq!(entries[1].duration_seconds, Some(60));
+                Ok(())
+            })
+            .unwrap();
+        }
     }
 
-    mod start_entry_tests {
+    mod duration_filter_tests {
         use super::*;
 
         #[test]
-        fn 計測を開始するとエントリが作成される() {
+        fn 作業時間の下限と上限で絞り込める() {
             let db = create_test_db();
 
             db.with_connection(|conn| {
-                let entry = TimeEntry::start(None, None);
-                insert_entry(conn, &entry)?;
+                let now = Utc::now();
+                let short = insert_completed(conn, now - chrono::Duration::days(3), 1)?;
+                let middle = insert_completed(conn, now - chrono::Duration::days(2), 60)?;
+                let long = insert_completed(conn, now - chrono::Duration::days(1), 180)?;
+                insert_entry(conn, &TimeEntry::start(None, None))?;
 
-                let fetched = fetch_entry_by_id(conn, &entry.id)?;
-                assert_eq!(fetched.id, entry.id);
-                assert!(fetched.is_running());
+                let filter = EntryFilter {
+                    min_duration_seconds: Some(2 * 3600),
+                    ..Default::default()
+                };
+                let ids: Vec<Uuid> = fetch_entries_with_filter(conn, &filter)?
+                    .iter()
+                    .map(|e| e.id)
+                    .collect();
+                assert_eq!(ids, vec![long.id]);
+
+                let filter = EntryFilter {
+                    max_duration_seconds: Some(120),
+                    ..Default::default()
+                };
+                let ids: Vec<Uuid> = fetch_entries_with_running(conn, &filter, Utc::now())?
+                    .iter()
+                    .map(|e| e.id)
+                    .collect();
+                assert_eq!(ids, vec![short.id]);
+
+                let filter = EntryFilter {
+                    min_duration_seconds: Some(60),
+                    max_duration_seconds: Some(3600),
+                    ..Default::default()
+                };
+                let ids: Vec<Uuid> = fetch_entries_with_filter(conn, &filter)?
+                    .iter()
+                    .map(|e| e.id)
+                    .collect();
+                assert_eq!(ids, vec![middle.id, short.id]);
                 Ok(())
             })
             .unwrap();
         }
 
         #[test]
-        fn 計測開始時にタスクIDを紐付けできる() {
+        fn 下限が上限を超える指定はエラーになる() {
+            let db = create_test_db();
+            let filter = EntryFilter {
+                min_duration_seconds: Some(600),
+                max_duration_seconds: Some(60),
+                ..Default::default()
+            };
+
+            let result = db.with_connection(|conn| fetch_entries_with_filter(conn, &filter));
+
+            assert!(matches!(result, Err(AppError::InvalidInput(_))));
+        }
+    }
+
+    mod has_artifacts_filter_tests {
+        use super::*;
+
+        fn fetch_ids(conn: &Connection, has_artifacts: Option<bool>) -> AppResult<Vec<Uuid>> {
+            let filter = EntryFilter {
+                has_artifacts,
+                ..Default::default()
+            };
+            Ok(fetch_entries_with_filter(conn, &filter)?
+                .iter()
+                .map(|e| e.id)
+                .collect())
+        }
+
+        #[test]
+        fn trueを指定すると成果物のある記録だけを返す() {
             let db = create_test_db();
-            let task_id = Uuid::new_v4();
 
             db.with_connection(|conn| {
-                // タスクを先に作成
-                conn.execute(
-                    "INSERT INTO tasks (id, name, color, created_at, updated_at) VALUES (?, 'テスト', '#000000', ?, ?)",
-                    duckdb::params![task_id.to_string(), Utc::now(), Utc::now()],
-                )?;
+                let with_artifact = insert_completed(conn, Utc::now() - chrono::Duration::hours(2), 30)?;
+                link_new_artifact(conn, &with_artifact.id)?;
+                insert_completed(conn, Utc::now() - chrono::Duration::hours(1), 30)?;
 
-                let entry = TimeEntry::start(Some(task_id), None);
+                assert_eq!(fetch_ids(conn, Some(true))?, vec![with_artifact.id]);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn falseを指定すると成果物のない記録だけを返す() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let with_artifact = insert_completed(conn, Utc::now() - chrono::Duration::hours(2), 30)?;
+                link_new_artifact(conn, &with_artifact.id)?;
+                let without_artifact = insert_completed(conn, Utc::now() - chrono::Duration::hours(1), 30)?;
+
+                assert_eq!(fetch_ids(conn, Some(false))?, vec![without_artifact.id]);
+                assert_eq!(
+                    fetch_ids(conn, None)?,
+                    vec![without_artifact.id, with_artifact.id]
+                );
+                Ok(())
+            })
+            .unwrap();
+        }
+    }
+
+    mod stable_order_tests {
+        use super::*;
+
+        #[test]
+        fn 開始日時が同じ記録は毎回同じ順序で返る() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let started_at = Utc::now() - chrono::Duration::hours(1);
+                let mut expected = Vec::new();
+                for _ in 0..5 {
+                    let mut entry = TimeEntry::start(None, None);
+                    entry.started_at = started_at;
+                    entry.ended_at = Some(started_at + chrono::Duration::minutes(10));
+                    insert_entry(conn, &entry)?;
+                    expected.push(entry.id.to_string());
+                }
+                expected.sort();
+
+                for _ in 0..3 {
+                    let ids: Vec<String> = fetch_entries_with_filter(conn, &EntryFilter::default())?
+                        .iter()
+                        .map(|e| e.id.to_string())
+                        .collect();
+                    assert_eq!(ids, expected);
+                }
+                Ok(())
+            })
+            .unwrap();
+        }
+    }
+
+    mod list_entries_params_tests {
+        use super::*;
+
+        #[test]
+        fn 不正な日時文字列はエラーになる() {
+            let result = parse_optional_datetime(Some("2024-13-01".to_string()), "from");
+            assert!(matches!(result, Err(AppError::InvalidInput(_))));
+        }
+
+        #[test]
+        fn 未指定や空文字の日時は条件なしとして扱われる() {
+            assert!(parse_optional_datetime(None, "from").unwrap().is_none());
+            assert!(parse_optional_datetime(Some("".to_string()), "to").unwrap().is_none());
+            assert!(parse_optional_datetime(Some("2024-12-01T00:00:00+09:00".to_string()), "from")
+                .unwrap()
+                .is_some());
+        }
+
+        #[test]
+        fn 不正なタスクIDはエラーになる() {
+            assert!(matches!(
+                parse_optional_uuid(Some("not-a-uuid".to_string())),
+                Err(AppError::InvalidInput(_))
+            ));
+            assert!(parse_optional_uuid(Some(" ".to_string())).unwrap().is_none());
+            assert!(parse_optional_uuid(Some(Uuid::new_v4().to_string())).unwrap().is_some());
+        }
+    }
+
+    mod list_entries_by_day_tests {
+        use super::*;
+
+        #[test]
+        fn ローカル日付ごとにまとめられ日付の降順で返る() {
+            let db = create_test_db();
+
+            let days = db
+                .with_connection(|conn| {
+                    insert_completed(conn, at("2024-12-01T10:00:00Z"), 30)?;
+                    insert_completed(conn, at("2024-12-01T12:00:00Z"), 60)?;
+                    insert_completed(conn, at("2024-12-02T10:00:00Z"), 15)?;
+                    let entries = fetch_entries_with_filter(conn, &EntryFilter::default())?;
+                    Ok(group_entries_by_day(entries, 0))
+                })
+                .unwrap();
+
+            assert_eq!(days.len(), 2);
+            assert_eq!(days[0].date, "2024-12-02");
+            assert_eq!(days[0].total_seconds, 900);
+            assert_eq!(days[1].date, "2024-12-01");
+            assert_eq!(days[1].total_seconds, 5400);
+            assert_eq!(days[1].entries.len(), 2);
+        }
+
+        #[test]
+        fn タイムゾーンオフセットを適用して日付が決まる() {
+            let db = create_test_db();
+
+            let days = db
+                .with_connection(|conn| {
+                    // UTC 15:30 は JST では翌日 0:30
+                    insert_completed(conn, at("2024-12-01T15:30:00Z"), 30)?;
+                    let entries = fetch_entries_with_filter(conn, &EntryFilter::default())?;
+                    Ok(group_entries_by_day(entries, 540))
+                })
+                .unwrap();
+
+            assert_eq!(days.len(), 1);
+            assert_eq!(days[0].date, "2024-12-02");
+        }
+    }
+
+    mod search_entries_tests {
+        use super::*;
+
+        fn insert_memo_entry(conn: &Connection, started_at: &str, memo: &str) -> AppResult<()> {
+            let started_at = DateTime::parse_from_rfc3339(started_at).unwrap().with_timezone(&Utc);
+            let mut entry = TimeEntry::start(None, Some(memo.to_string()));
+            entry.started_at = started_at;
+            entry.ended_at = Some(started_at + chrono::Duration::hours(1));
+            insert_entry(conn, &entry)
+        }
+
+        #[test]
+        fn メモの部分一致で検索し月別の件数を集計できる() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                insert_memo_entry(conn, "2024-11-05T09:00:00Z", "API設計のレビュー")?;
+                insert_memo_entry(conn, "2024-11-20T09:00:00Z", "api設計の修正")?;
+                insert_memo_entry(conn, "2024-12-01T09:00:00Z", "API設計の最終確認")?;
+                insert_memo_entry(conn, "2024-12-02T09:00:00Z", "定例会議")?;
+
+                let filter = EntryFilter {
+                    memo_query: Some("API設計".to_string()),
+                    limit: Some(2),
+                    ..Default::default()
+                };
+                let entries = fetch_entries_with_filter(conn, &filter)?;
+                assert_eq!(entries.len(), 2);
+                assert_eq!(entries[0].memo.as_deref(), Some("API設計の最終確認"));
+
+                // 件数はlimitに関わらず全件を数える
+                let facets = fetch_search_facets(conn, "API設計", 0)?;
+                assert_eq!(
+                    facets,
+                    vec![
+                        SearchFacet {
+                            year: 2024,
+                            month: 12,
+                            match_count: 1,
+                        },
+                        SearchFacet {
+                            year: 2024,
+                            month: 11,
+                            match_count: 2,
+                        },
+                    ]
+                );
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn ワイルドカード文字は文字どおりに検索される() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                insert_memo_entry(conn, "2024-11-05T09:00:00Z", "進捗100%")?;
+                insert_memo_entry(conn, "2024-11-06T09:00:00Z", "進捗1000")?;
+
+                let filter = EntryFilter {
+                    memo_query: Some("100%".to_string()),
+                    ..Default::default()
+                };
+                let entries = fetch_entries_with_filter(conn, &filter)?;
+                assert_eq!(entries.len(), 1);
+                assert_eq!(entries[0].memo.as_deref(), Some("進捗100%"));
+                Ok(())
+            })
+            .unwrap();
+        }
+    }
+
+    mod update_entry_tests {
+        use super::*;
+
+        #[test]
+        fn タスクを付け替えるとアーカイブ状態とフォルダ名が返る() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                conn.execute_batch(
+                    "INSERT INTO folders (id, name, color, sort_order, created_at, updated_at) VALUES
+                     ('00000000-0000-0000-0000-0000000000f1', '顧客A', '#000000', 0, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP);
+                     INSERT INTO tasks (id, folder_id, name, color, archived, created_at, updated_at) VALUES
+                     ('00000000-0000-0000-0000-000000000001', NULL, '旧タスク', '#000000', false, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     ('00000000-0000-0000-0000-000000000002', '00000000-0000-0000-0000-0000000000f1', '完了済み', '#000000', true, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP);",
+                )?;
+                let old_task = Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap();
+                let new_task = Uuid::parse_str("00000000-0000-0000-0000-000000000002").unwrap();
+                let entry = TimeEntry::start(Some(old_task), None);
                 insert_entry(conn, &entry)?;
 
-                let fetched = fetch_entry_by_id(conn, &entry.id)?;
-                assert_eq!(fetched.task_id, Some(task_id));
+                let update = UpdateEntry {
+                    task_id: Some(Some(new_task)),
+                    ..Default::default()
+                };
+                let updated = apply_entry_update(conn, &entry.id, update)?;
+                let info = describe_entry_update(conn, &entry, &updated)?;
+                assert_eq!(
+                    info,
+                    UpdateEntryInfo {
+                        task_changed: true,
+                        task_archived: true,
+                        folder_name: Some("顧客A".to_string()),
+                    }
+                );
+
+                // タスクを変更しなければ付け替えとはみなさない
+                let unchanged = describe_entry_update(conn, &updated, &updated)?;
+                assert!(!unchanged.task_changed);
                 Ok(())
             })
             .unwrap();
         }
 
         #[test]
-        fn 計測開始時にメモを設定できる() {
+        fn 長すぎるメモには更新できない() {
             let db = create_test_db();
 
             db.with_connection(|conn| {
-                let entry = TimeEntry::start(None, Some("作業開始".to_string()));
+                let entry = TimeEntry::start(None, Some("元のメモ".to_string()));
                 insert_entry(conn, &entry)?;
 
-                let fetched = fetch_entry_by_id(conn, &entry.id)?;
-                assert_eq!(fetched.memo, Some("作業開始".to_string()));
+                let update = UpdateEntry {
+                    memo: Some(Some("あ".repeat(MAX_MEMO_LENGTH + 1))),
+                    ..Default::default()
+                };
+                let result = apply_entry_update(conn, &entry.id, update);
+                assert!(matches!(result, Err(AppError::InvalidInput(_))));
+
+                let stored = fetch_entry_by_id(conn, &entry.id)?;
+                assert_eq!(stored.memo.as_deref(), Some("元のメモ"));
                 Ok(())
             })
             .unwrap();
         }
 
         #[test]
-        fn 計測中のエントリがある場合は新しい計測を開始できない() {
+        fn 更新すると監査ログに変更前後の内容が記録される() {
             let db = create_test_db();
 
             db.with_connection(|conn| {
-                let entry = TimeEntry::start(None, None);
+                let entry = TimeEntry::start(None, Some("旧メモ".to_string()));
                 insert_entry(conn, &entry)?;
 
-                let running = fetch_running_entry(conn)?;
-                assert!(running.is_some());
+                let update = UpdateEntry {
+                    memo: Some(Some("新メモ".to_string())),
+                    ..Default::default()
+                };
+                apply_entry_update(conn, &entry.id, update)?;
+
+                let (old_value, new_value): (String, String) = conn.query_row(
+                    "SELECT old_value, new_value FROM audit_log
+                     WHERE entity_id = ? AND action = 'update'",
+                    [entry.id.to_string()],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )?;
+                let old: TimeEntry = serde_json::from_str(&old_value)?;
+                let new: TimeEntry = serde_json::from_str(&new_value)?;
+                assert_eq!(old.memo.as_deref(), Some("旧メモ"));
+                assert_eq!(new.memo.as_deref(), Some("新メモ"));
                 Ok(())
             })
             .unwrap();
         }
-    }
-
-    mod stop_entry_tests {
-        use super::*;
 
         #[test]
-        fn 計測を停止すると終了時刻が設定される() {
+        fn メモを更新できる() {
             let db = create_test_db();
 
             db.with_connection(|conn| {
                 let entry = TimeEntry::start(None, None);
                 insert_entry(conn, &entry)?;
 
-                let now = Utc::now();
                 conn.execute(
-                    "UPDATE time_entries SET ended_at = ?, updated_at = ? WHERE id = ?",
-                    duckdb::params![now, now, entry.id.to_string()],
+                    "UPDATE time_entries SET memo = ? WHERE id = ?",
+                    duckdb::params!["更新後のメモ", entry.id.to_string()],
                 )?;
 
-                let stopped = fetch_entry_by_id(conn, &entry.id)?;
-                assert!(!stopped.is_running());
-                assert!(stopped.ended_at.is_some());
+                let updated = fetch_entry_by_id(conn, &entry.id)?;
+                assert_eq!(updated.memo, Some("更新後のメモ".to_string()));
                 Ok(())
             })
             .unwrap();
         }
 
         #[test]
-        fn 計測中でないエントリは停止できない() {
+        fn メモにnullを指定すると消え省略すると変わらない() {
             let db = create_test_db();
 
             db.with_connection(|conn| {
-                let mut entry = TimeEntry::start(None, None);
-                entry.ended_at = Some(Utc::now());
+                let entry = TimeEntry::start(None, Some("元のメモ".to_string()));
                 insert_entry(conn, &entry)?;
 
-                let fetched = fetch_entry_by_id(conn, &entry.id)?;
-                assert!(!fetched.is_running());
+                // 省略した場合はメモを変更しない
+                let omitted: UpdateEntry = serde_json::from_str(r#"{"started_at": null}"#)?;
+                assert!(omitted.memo.is_none());
+                apply_entry_update(conn, &entry.id, omitted)?;
+                assert_eq!(fetch_entry_by_id(conn, &entry.id)?.memo.as_deref(), Some("元のメモ"));
+
+                // nullを指定した場合はメモを消す
+                let cleared: UpdateEntry = serde_json::from_str(r#"{"memo": null}"#)?;
+                assert_eq!(cleared.memo, Some(None));
+                apply_entry_update(conn, &entry.id, cleared)?;
+                let stored_memo: Option<String> = conn.query_row(
+                    "SELECT memo FROM time_entries WHERE id = ?",
+                    [entry.id.to_string()],
+                    |row| row.get(0),
+                )?;
+                assert_eq!(stored_memo, None);
                 Ok(())
             })
             .unwrap();
         }
-    }
-
-    mod get_running_entry_tests {
-        use super::*;
 
         #[test]
-        fn 計測中のエントリがない場合はNoneが返る() {
+        fn 開始時刻を修正できる() {
             let db = create_test_db();
 
-            let result = db.with_connection(|conn| fetch_running_entry(conn)).unwrap();
+            db.with_connection(|conn| {
+                let entry = TimeEntry::start(None, None);
+                insert_entry(conn, &entry)?;
+
+                let new_start = Utc::now() - chrono::Duration::hours(1);
+                conn.execute(
+                    "UPDATE time_entries SET started_at = ? WHERE id = ?",
+                    duckdb::params![new_start, entry.id.to_string()],
+                )?;
 
-            assert!(result.is_none());
+                let updated = fetch_entry_by_id(conn, &entry.id)?;
+                assert!(updated.started_at < entry.started_at);
+                Ok(())
+            })
+            .unwrap();
         }
 
         #[test]
-        fn 計測中のエントリがある場合はそのエントリが返る() {
+        fn タスクIDを変更できる() {
             let db = create_test_db();
+            let new_task_id = Uuid::new_v4();
 
             db.with_connection(|conn| {
-                let entry = TimeEntry::start(None, Some("計測中".to_string()));
+                conn.execute(
+                    "INSERT INTO tasks (id, name, color, created_at, updated_at) VALUES (?, 'テスト', '#000000', ?, ?)",
+                    duckdb::params![new_task_id.to_string(), Utc::now(), Utc::now()],
+                )?;
+
+                let entry = TimeEntry::start(None, None);
                 insert_entry(conn, &entry)?;
 
-                let running = fetch_running_entry(conn)?;
-                assert!(running.is_some());
-                assert_eq!(running.unwrap().id, entry.id);
+                conn.execute(
+                    "UPDATE time_entries SET task_id = ? WHERE id = ?",
+                    duckdb::params![new_task_id.to_string(), entry.id.to_string()],
+                )?;
+
+                let updated = fetch_entry_by_id(conn, &entry.id)?;
+                assert_eq!(updated.task_id, Some(new_task_id));
                 Ok(())
             })
             .unwrap();
         }
-    }
-
-    mod list_entries_tests {
-        use super::*;
 
         #[test]
-        fn 空のデータベースからエントリ一覧を取得すると空のベクターが返る() {
+        fn 存在しないエントリを更新しようとするとエラーになる() {
             let db = create_test_db();
+            let non_existent_id = Uuid::new_v4();
 
-            let entries = db
-                .with_connection(|conn| fetch_entries_with_filter(conn, &EntryFilter::default()))
-                .unwrap();
+            let result = db.with_connection(|conn| fetch_entry_by_id(conn, &non_existent_id));
 
-            assert!(entries.is_empty());
+            assert!(result.is_err());
         }
+    }
+
+    mod append_memo_tests {
+        use super::*;
 
         #[test]
-        fn 作成したエントリが一覧に含まれる() {
+        fn メモがない場合は最初の行として設定される() {
             let db = create_test_db();
 
             db.with_connection(|conn| {
                 let entry = TimeEntry::start(None, None);
                 insert_entry(conn, &entry)?;
 
-                let entries = fetch_entries_with_filter(conn, &EntryFilter::default())?;
-                assert_eq!(entries.len(), 1);
+                let updated = append_memo_text(conn, &entry.id, "最初のメモ")?;
+                assert_eq!(updated.memo, Some("最初のメモ".to_string()));
                 Ok(())
             })
             .unwrap();
         }
 
         #[test]
-        fn limitを指定すると取得件数が制限される() {
+        fn 既存のメモに改行区切りで追記される() {
             let db = create_test_db();
 
             db.with_connection(|conn| {
-                for _ in 0..5 {
-                    let mut entry = TimeEntry::start(None, None);
-                    entry.ended_at = Some(Utc::now());
-                    insert_entry(conn, &entry)?;
-                }
+                let entry = TimeEntry::start(None, Some("1行目".to_string()));
+                insert_entry(conn, &entry)?;
 
-                let filter = EntryFilter {
-                    limit: Some(3),
-                    ..Default::default()
-                };
-                let entries = fetch_entries_with_filter(conn, &filter)?;
-                assert_eq!(entries.len(), 3);
+                append_memo_text(conn, &entry.id, "2行目")?;
+                let updated = append_memo_text(conn, &entry.id, "3行目")?;
+                assert_eq!(updated.memo, Some("1行目\n2行目\n3行目".to_string()));
                 Ok(())
             })
             .unwrap();
         }
 
         #[test]
-        fn task_idでフィルタできる() {
+        fn 存在しないエントリへの追記はエラーになる() {
+            let db = create_test_db();
+
+            let result = db.with_connection(|conn| append_memo_text(conn, &Uuid::new_v4(), "メモ"));
+
+            assert!(matches!(result, Err(AppError::NotFound(_))));
+        }
+
+        #[test]
+        fn 追記後のメモが上限を超える場合はエラーになり変更されない() {
             let db = create_test_db();
-            let task_id = Uuid::new_v4();
 
             db.with_connection(|conn| {
-                conn.execute(
-                    "INSERT INTO tasks (id, name, color, created_at, updated_at) VALUES (?, 'テスト', '#000000', ?, ?)",
-                    duckdb::params![task_id.to_string(), Utc::now(), Utc::now()],
-                )?;
+                let memo = "あ".repeat(MAX_MEMO_LENGTH - 2);
+                let entry = TimeEntry::start(None, Some(memo.clone()));
+                insert_entry(conn, &entry)?;
 
-                // タスク付きエントリ
-                let mut entry1 = TimeEntry::start(Some(task_id), None);
-                entry1.ended_at = Some(Utc::now());
-                insert_entry(conn, &entry1)?;
+                let too_long = "い".repeat(MAX_MEMO_LENGTH + 1);
+                let result = append_memo_text(conn, &entry.id, &too_long);
+                assert!(matches!(result, Err(AppError::InvalidInput(_))));
 
-                // タスクなしエントリ
-                let mut entry2 = TimeEntry::start(None, None);
-                entry2.ended_at = Some(Utc::now());
-                insert_entry(conn, &entry2)?;
+                // 改行を含めて上限ちょうどなら追記できる
+                let updated = append_memo_text(conn, &entry.id, "い")?;
+                assert_eq!(updated.memo.as_deref().map(|m| m.chars().count()), Some(MAX_MEMO_LENGTH));
 
-                let filter = EntryFilter {
-                    task_id: Some(task_id),
-                    ..Default::default()
-                };
-                let entries = fetch_entries_with_filter(conn, &filter)?;
-                assert_eq!(entries.len(), 1);
-                assert_eq!(entries[0].task_id, Some(task_id));
+                let result = append_memo_text(conn, &entry.id, "う");
+                assert!(matches!(result, Err(AppError::InvalidInput(ref m)) if m.contains("Memo")));
+                assert_eq!(fetch_entry_by_id(conn, &entry.id)?.memo, updated.memo);
                 Ok(())
             })
             .unwrap();
         }
+    }
+
+    mod entry_notes_tests {
+        use super::*;
 
         #[test]
-        fn エントリ一覧は開始日時の降順でソートされる() {
+        fn 時刻付きメモは作成日時順に取得されメインのメモは変わらない() {
             let db = create_test_db();
 
             db.with_connection(|conn| {
-                let mut entry1 = TimeEntry::start(None, Some("1番目".to_string()));
-                entry1.ended_at = Some(Utc::now());
-                insert_entry(conn, &entry1)?;
+                let entry = TimeEntry::start(None, Some("メインのメモ".to_string()));
+                insert_entry(conn, &entry)?;
+                let now = Utc::now();
 
-                std::thread::sleep(std::time::Duration::from_millis(10));
+                insert_entry_note(conn, &entry.id, "2つ目", now)?;
+                insert_entry_note(conn, &entry.id, "  1つ目  ", now - chrono::Duration::minutes(5))?;
 
-                let mut entry2 = TimeEntry::start(None, Some("2番目".to_string()));
-                entry2.ended_at = Some(Utc::now());
-                insert_entry(conn, &entry2)?;
+                let texts: Vec<String> = fetch_entry_notes(conn, &entry.id)?
+                    .into_iter()
+                    .map(|note| note.text)
+                    .collect();
+                assert_eq!(texts, vec!["1つ目", "2つ目"]);
 
-                let entries = fetch_entries_with_filter(conn, &EntryFilter::default())?;
-                assert_eq!(entries.len(), 2);
-                assert_eq!(entries[0].memo, Some("2番目".to_string())); // 新しい方が先
-                assert_eq!(entries[1].memo, Some("1番目".to_string()));
+                let with_relations = entry_to_with_relations(conn, fetch_entry_by_id(conn, &entry.id)?, None)?;
+                assert_eq!(with_relations.memo.as_deref(), Some("メインのメモ"));
+                assert_eq!(with_relations.notes.len(), 2);
+
+                let listed = fetch_entries_with_filter(conn, &EntryFilter::default())?;
+                assert_eq!(listed[0].notes[0].text, "1つ目");
                 Ok(())
             })
             .unwrap();
         }
-    }
-
-    mod update_entry_tests {
-        use super::*;
 
         #[test]
-        fn メモを更新できる() {
+        fn 空のメモや存在しないエントリへの追加はエラーになる() {
             let db = create_test_db();
 
             db.with_connection(|conn| {
                 let entry = TimeEntry::start(None, None);
                 insert_entry(conn, &entry)?;
 
-                conn.execute(
-                    "UPDATE time_entries SET memo = ? WHERE id = ?",
-                    duckdb::params!["更新後のメモ", entry.id.to_string()],
-                )?;
-
-                let updated = fetch_entry_by_id(conn, &entry.id)?;
-                assert_eq!(updated.memo, Some("更新後のメモ".to_string()));
+                let empty = insert_entry_note(conn, &entry.id, "   ", Utc::now());
+                assert!(matches!(empty, Err(AppError::InvalidInput(_))));
+                let missing = insert_entry_note(conn, &Uuid::new_v4(), "メモ", Utc::now());
+                assert!(matches!(missing, Err(AppError::NotFound(_))));
                 Ok(())
             })
             .unwrap();
         }
 
         #[test]
-        fn 開始時刻を修正できる() {
+        fn エントリを削除すると時刻付きメモも削除される() {
             let db = create_test_db();
 
             db.with_connection(|conn| {
                 let entry = TimeEntry::start(None, None);
                 insert_entry(conn, &entry)?;
+                insert_entry_note(conn, &entry.id, "メモ", Utc::now())?;
 
-                let new_start = Utc::now() - chrono::Duration::hours(1);
-                conn.execute(
-                    "UPDATE time_entries SET started_at = ? WHERE id = ?",
-                    duckdb::params![new_start, entry.id.to_string()],
-                )?;
+                delete_entry_by_id(conn, &entry.id)?;
 
-                let updated = fetch_entry_by_id(conn, &entry.id)?;
-                assert!(updated.started_at < entry.started_at);
+                let count: i64 = conn.query_row("SELECT COUNT(*) FROM entry_notes", [], |row| row.get(0))?;
+                assert_eq!(count, 0);
                 Ok(())
             })
             .unwrap();
         }
+    }
+
+    mod clone_entry_tests {
+        use super::*;
+
+        fn base_time() -> DateTime<Utc> {
+            at("2024-01-15T09:00:00Z")
+        }
 
         #[test]
-        fn タスクIDを変更できる() {
+        fn 元の記録と同じ長さ・メモ・成果物で複製される() {
             let db = create_test_db();
-            let new_task_id = Uuid::new_v4();
 
             db.with_connection(|conn| {
-                conn.execute(
-                    "INSERT INTO tasks (id, name, color, created_at, updated_at) VALUES (?, 'テスト', '#000000', ?, ?)",
-                    duckdb::params![new_task_id.to_string(), Utc::now(), Utc::now()],
-                )?;
+                let source = insert_completed(conn, base_time(), 30)?;
+                append_memo_text(conn, &source.id, "レビュー")?;
+                let artifact_id = link_new_artifact(conn, &source.id)?;
+
+                let new_start = base_time() + chrono::Duration::days(1);
+                let cloned = clone_entry_at(conn, &source.id, new_start)?;
+
+                assert_ne!(cloned.id, source.id);
+                assert_eq!(cloned.started_at, new_start);
+                assert_eq!(cloned.duration_seconds(), Some(1800));
+                assert_eq!(cloned.memo.as_deref(), Some("レビュー"));
+                let artifacts = fetch_artifacts_for_entry(conn, &cloned.id)?;
+                assert_eq!(artifacts.len(), 1);
+                assert_eq!(artifacts[0].id, artifact_id);
+                Ok(())
+            })
+            .unwrap();
+        }
 
-                let entry = TimeEntry::start(None, None);
-                insert_entry(conn, &entry)?;
+        #[test]
+        fn 既存の記録と重なる場合はエラーになり何も作成されない() {
+            let db = create_test_db();
 
-                conn.execute(
-                    "UPDATE time_entries SET task_id = ? WHERE id = ?",
-                    duckdb::params![new_task_id.to_string(), entry.id.to_string()],
-                )?;
+            db.with_connection(|conn| {
+                let source = insert_completed(conn, base_time(), 30)?;
+                let result = clone_entry_at(conn, &source.id, base_time() + chrono::Duration::minutes(10));
+                assert!(matches!(result, Err(AppError::InvalidInput(_))));
 
-                let updated = fetch_entry_by_id(conn, &entry.id)?;
-                assert_eq!(updated.task_id, Some(new_task_id));
+                let count: i64 =
+                    conn.query_row("SELECT COUNT(*) FROM time_entries", [], |row| row.get(0))?;
+                assert_eq!(count, 1);
                 Ok(())
             })
             .unwrap();
         }
 
         #[test]
-        fn 存在しないエントリを更新しようとするとエラーになる() {
+        fn 計測中の記録は複製できない() {
             let db = create_test_db();
-            let non_existent_id = Uuid::new_v4();
 
-            let result = db.with_connection(|conn| fetch_entry_by_id(conn, &non_existent_id));
+            db.with_connection(|conn| {
+                let running = TimeEntry::start(None, None);
+                insert_entry(conn, &running)?;
 
-            assert!(result.is_err());
+                let result = clone_entry_at(conn, &running.id, base_time());
+                assert!(matches!(result, Err(AppError::InvalidInput(_))));
+                Ok(())
+            })
+            .unwrap();
         }
     }
 
@@ -782,6 +4078,45 @@ mod tests {
 
             assert!(result.is_err());
         }
+
+        #[test]
+        fn 削除すると監査ログに削除前の内容が記録される() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let entry = TimeEntry::start(None, None);
+                insert_entry(conn, &entry)?;
+
+                delete_entry_by_id(conn, &entry.id)?;
+                assert!(fetch_entry_by_id(conn, &entry.id).is_err());
+
+                let (old_value, new_value): (Option<String>, Option<String>) = conn.query_row(
+                    "SELECT old_value, new_value FROM audit_log
+                     WHERE entity_id = ? AND action = 'delete'",
+                    [entry.id.to_string()],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )?;
+                assert!(old_value.is_some());
+                assert!(new_value.is_none());
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 存在しないエントリの削除では監査ログを残さない() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                assert!(delete_entry_by_id(conn, &Uuid::new_v4()).is_err());
+
+                let count: i64 =
+                    conn.query_row("SELECT COUNT(*) FROM audit_log", [], |row| row.get(0))?;
+                assert_eq!(count, 0);
+                Ok(())
+            })
+            .unwrap();
+        }
     }
 
     mod entry_with_relations_tests {
@@ -801,7 +4136,7 @@ mod tests {
                 let entry = TimeEntry::start(Some(task_id), None);
                 insert_entry(conn, &entry)?;
 
-                let with_relations = entry_to_with_relations(conn, entry)?;
+                let with_relations = entry_to_with_relations(conn, entry, None)?;
                 assert!(with_relations.task.is_some());
                 assert_eq!(with_relations.task.unwrap().name, "テストタスク");
                 Ok(())
@@ -818,13 +4153,33 @@ mod tests {
                 entry.ended_at = Some(entry.started_at + chrono::Duration::seconds(3600));
                 insert_entry(conn, &entry)?;
 
-                let with_relations = entry_to_with_relations(conn, entry)?;
+                let with_relations = entry_to_with_relations(conn, entry, None)?;
                 assert_eq!(with_relations.duration_seconds, Some(3600));
                 Ok(())
             })
             .unwrap();
         }
 
+        #[test]
+        fn 保存済みのduration_secondsが優先される() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let mut entry = TimeEntry::start(None, None);
+                entry.ended_at = Some(entry.started_at + chrono::Duration::seconds(3600));
+                insert_entry(conn, &entry)?;
+                conn.execute(
+                    "UPDATE time_entries SET duration_seconds = 1234 WHERE id = ?",
+                    [entry.id.to_string()],
+                )?;
+
+                let entries = fetch_entries_with_filter(conn, &EntryFilter::default())?;
+                assert_eq!(entries[0].duration_seconds, Some(1234));
+                Ok(())
+            })
+            .unwrap();
+        }
+
         #[test]
         fn 計測中のエントリのduration_secondsはNone() {
             let db = create_test_db();
@@ -833,7 +4188,7 @@ mod tests {
                 let entry = TimeEntry::start(None, None);
                 insert_entry(conn, &entry)?;
 
-                let with_relations = entry_to_with_relations(conn, entry)?;
+                let with_relations = entry_to_with_relations(conn, entry, None)?;
                 assert!(with_relations.duration_seconds.is_none());
                 Ok(())
             })
@@ -1,18 +1,29 @@
-use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
 use duckdb::Connection;
+use tauri::Emitter;
 use uuid::Uuid;
 
+use crate::commands::parse_optional_datetime;
 use crate::db::{
-    Artifact, EntryArtifact, ExportData, ExportTimeEntry, ImportResult, Task,
+    Artifact, CsvColumnMap, EntryArtifact, EntryNote, ExportBundleManifest, ExportData,
+    ExportProgress, ExportTimeEntry, ImportResult, ImportStrategy, Task, TimeEntry,
 };
-use crate::error::AppResult;
+use crate::error::{AppError, AppResult};
 use crate::AppState;
 
 /// 全タスクを取得する
 fn fetch_all_tasks(conn: &Connection) -> AppResult<Vec<Task>> {
-    let mut stmt = conn.prepare(
-        "SELECT id, folder_id, name, description, color, archived, created_at, updated_at FROM tasks ORDER BY created_at",
-    )?;
+    fetch_tasks_from(conn, "tasks")
+}
+
+/// 指定したテーブル（またはサブクエリ）からタスクを取得する
+fn fetch_tasks_from(conn: &Connection, source: &str) -> AppResult<Vec<Task>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, folder_id, name, description, color, archived, created_at, updated_at, hourly_rate, currency FROM {} ORDER BY created_at",
+        source
+    ))?;
 
     let rows = stmt.query_map([], |row| {
         let id_str: String = row.get(0)?;
@@ -27,6 +38,8 @@ fn fetch_all_tasks(conn: &Connection) -> AppResult<Vec<Task>> {
             description: row.get(3)?,
             color: row.get(4)?,
             archived: row.get(5)?,
+            hourly_rate: row.get(8)?,
+            currency: row.get(9)?,
             created_at,
             updated_at,
         })
@@ -41,13 +54,21 @@ fn fetch_all_tasks(conn: &Connection) -> AppResult<Vec<Task>> {
 
 /// 全成果物を取得する
 fn fetch_all_artifacts(conn: &Connection) -> AppResult<Vec<Artifact>> {
-    let mut stmt = conn.prepare(
-        "SELECT id, name, artifact_type, reference, metadata, created_at FROM artifacts ORDER BY created_at",
-    )?;
+    fetch_artifacts_from(conn, "artifacts")
+}
+
+/// 指定したテーブル（またはサブクエリ）から成果物を取得する
+fn fetch_artifacts_from(conn: &Connection, source: &str) -> AppResult<Vec<Artifact>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, name, artifact_type, reference, metadata, created_at, COALESCE(updated_at, created_at)
+         FROM {} ORDER BY created_at",
+        source
+    ))?;
 
     let rows = stmt.query_map([], |row| {
         let id_str: String = row.get(0)?;
         let created_at: DateTime<Utc> = row.get(5)?;
+        let updated_at: DateTime<Utc> = row.get(6)?;
         let metadata_str: Option<String> = row.get(4)?;
 
         Ok(Artifact {
@@ -57,6 +78,7 @@ fn fetch_all_artifacts(conn: &Connection) -> AppResult<Vec<Artifact>> {
             reference: row.get(3)?,
             metadata: metadata_str.and_then(|s| serde_json::from_str(&s).ok()),
             created_at,
+            updated_at,
         })
     })?;
 
@@ -69,9 +91,15 @@ fn fetch_all_artifacts(conn: &Connection) -> AppResult<Vec<Artifact>> {
 
 /// 全時間記録を取得する（エクスポート用）
 fn fetch_all_entries(conn: &Connection) -> AppResult<Vec<ExportTimeEntry>> {
-    let mut stmt = conn.prepare(
-        "SELECT id, task_id, started_at, ended_at, memo, created_at, updated_at FROM time_entries ORDER BY started_at",
-    )?;
+    fetch_entries_from(conn, "time_entries")
+}
+
+/// 指定したテーブル（またはサブクエリ）から時間記録を取得する
+fn fetch_entries_from(conn: &Connection, source: &str) -> AppResult<Vec<ExportTimeEntry>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, task_id, started_at, ended_at, memo, created_at, updated_at, duration_seconds FROM {} ORDER BY started_at",
+        source
+    ))?;
 
     let rows = stmt.query_map([], |row| {
         let id_str: String = row.get(0)?;
@@ -80,8 +108,10 @@ fn fetch_all_entries(conn: &Connection) -> AppResult<Vec<ExportTimeEntry>> {
         let ended_at: Option<DateTime<Utc>> = row.get(3)?;
         let created_at: DateTime<Utc> = row.get(5)?;
         let updated_at: DateTime<Utc> = row.get(6)?;
+        let stored_duration: Option<i64> = row.get(7)?;
 
-        let duration_seconds = ended_at.map(|ended| (ended - started_at).num_seconds());
+        let duration_seconds = ended_at
+            .map(|ended| stored_duration.unwrap_or_else(|| (ended - started_at).num_seconds()));
 
         Ok(ExportTimeEntry {
             id: Uuid::parse_str(&id_str).unwrap(),
@@ -104,7 +134,12 @@ fn fetch_all_entries(conn: &Connection) -> AppResult<Vec<ExportTimeEntry>> {
 
 /// 全紐付けを取得する
 fn fetch_all_entry_artifacts(conn: &Connection) -> AppResult<Vec<EntryArtifact>> {
-    let mut stmt = conn.prepare("SELECT entry_id, artifact_id FROM entry_artifacts")?;
+    fetch_entry_artifacts_from(conn, "entry_artifacts")
+}
+
+/// 指定したテーブル（またはサブクエリ）から紐付けを取得する
+fn fetch_entry_artifacts_from(conn: &Connection, source: &str) -> AppResult<Vec<EntryArtifact>> {
+    let mut stmt = conn.prepare(&format!("SELECT entry_id, artifact_id FROM {}", source))?;
 
     let rows = stmt.query_map([], |row| {
         let entry_id_str: String = row.get(0)?;
@@ -123,121 +158,777 @@ fn fetch_all_entry_artifacts(conn: &Connection) -> AppResult<Vec<EntryArtifact>>
     Ok(links)
 }
 
+/// 指定したテーブル（またはサブクエリ）から時刻付きメモを取得する
+fn fetch_entry_notes_from(conn: &Connection, source: &str) -> AppResult<Vec<EntryNote>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, entry_id, text, created_at FROM {} ORDER BY created_at, id",
+        source
+    ))?;
+
+    let rows = stmt.query_map([], |row| {
+        let id_str: String = row.get(0)?;
+        let entry_id_str: String = row.get(1)?;
+
+        Ok(EntryNote {
+            id: Uuid::parse_str(&id_str).unwrap(),
+            entry_id: Uuid::parse_str(&entry_id_str).unwrap(),
+            text: row.get(2)?,
+            created_at: row.get(3)?,
+        })
+    })?;
+
+    let mut notes = Vec::new();
+    for row in rows {
+        notes.push(row?);
+    }
+    Ok(notes)
+}
+
+/// 進捗イベント名
+const EXPORT_PROGRESS_EVENT: &str = "export-progress";
+
+/// エクスポート対象のテーブル（エクスポート順）
+const EXPORT_TABLES: [&str; 5] = ["tasks", "artifacts", "time_entries", "entry_artifacts", "entry_notes"];
+
+/// テーブル単位の進捗を生成する
+fn export_progress(step: usize) -> ExportProgress {
+    ExportProgress {
+        step,
+        total: EXPORT_TABLES.len(),
+        table: EXPORT_TABLES[step - 1].to_string(),
+    }
+}
+
+/// 指定したタスクとその時間記録・紐付いた成果物だけをエクスポートする
+fn create_filtered_export_data(conn: &Connection, task_ids: &[Uuid]) -> AppResult<ExportData> {
+    // パース済みのUUIDのみを埋め込むため、文字列連結でもSQLインジェクションは起きない
+    let id_list = task_ids
+        .iter()
+        .map(|id| format!("'{}'", id))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let links = format!(
+        "SELECT ea.entry_id, ea.artifact_id FROM entry_artifacts ea
+         JOIN time_entries e ON ea.entry_id = e.id
+         WHERE e.task_id IN ({})",
+        id_list
+    );
+
+    let tasks = fetch_tasks_from(
+        conn,
+        &format!("(SELECT * FROM tasks WHERE id IN ({})) AS tasks", id_list),
+    )?;
+    if let Some(missing) = task_ids.iter().find(|id| !tasks.iter().any(|t| &t.id == *id)) {
+        return Err(AppError::NotFound(format!("Task with id {} not found", missing)));
+    }
+
+    let artifacts = fetch_artifacts_from(
+        conn,
+        &format!(
+            "(SELECT * FROM artifacts WHERE id IN (SELECT artifact_id FROM ({}))) AS artifacts",
+            links
+        ),
+    )?;
+    let time_entries = fetch_entries_from(
+        conn,
+        &format!(
+            "(SELECT * FROM time_entries WHERE task_id IN ({})) AS time_entries",
+            id_list
+        ),
+    )?;
+    let entry_artifacts = fetch_entry_artifacts_from(conn, &format!("({}) AS entry_artifacts", links))?;
+    let entry_notes = fetch_entry_notes_from(
+        conn,
+        &format!(
+            "(SELECT n.* FROM entry_notes n JOIN time_entries e ON n.entry_id = e.id
+              WHERE e.task_id IN ({})) AS entry_notes",
+            id_list
+        ),
+    )?;
+
+    Ok(ExportData {
+        version: "1.0".to_string(),
+        exported_at: Utc::now(),
+        tasks,
+        artifacts,
+        time_entries,
+        entry_artifacts,
+        entry_notes,
+    })
+}
+
+/// 進捗をフロントエンドに通知する（通知の失敗でエクスポート自体は失敗させない）
+fn emit_export_progress(app: &tauri::AppHandle, progress: ExportProgress) {
+    let _ = app.emit(EXPORT_PROGRESS_EVENT, progress);
+}
+
 /// データをエクスポートする
 fn create_export_data(conn: &Connection) -> AppResult<ExportData> {
+    create_export_data_with_progress(conn, &mut |_| {})
+}
+
+/// テーブルごとに進捗を通知しながらデータをエクスポートする
+fn create_export_data_with_progress(
+    conn: &Connection,
+    on_progress: &mut dyn FnMut(ExportProgress),
+) -> AppResult<ExportData> {
+    let tasks = fetch_all_tasks(conn)?;
+    on_progress(export_progress(1));
+    let artifacts = fetch_all_artifacts(conn)?;
+    on_progress(export_progress(2));
+    let time_entries = fetch_all_entries(conn)?;
+    on_progress(export_progress(3));
+    let entry_artifacts = fetch_all_entry_artifacts(conn)?;
+    on_progress(export_progress(4));
+    let entry_notes = fetch_entry_notes_from(conn, "entry_notes")?;
+    on_progress(export_progress(5));
+
     Ok(ExportData {
         version: "1.0".to_string(),
         exported_at: Utc::now(),
-        tasks: fetch_all_tasks(conn)?,
-        artifacts: fetch_all_artifacts(conn)?,
-        time_entries: fetch_all_entries(conn)?,
-        entry_artifacts: fetch_all_entry_artifacts(conn)?,
+        tasks,
+        artifacts,
+        time_entries,
+        entry_artifacts,
+        entry_notes,
+    })
+}
+
+/// 各テーブルをParquetファイルに書き出す
+///
+/// `batch_rows` を指定した場合、time_entries は開始日時の範囲ごとにおよそその行数ずつ分割し、
+/// `time_entries_0001.parquet`, `time_entries_0002.parquet`, ... （1始まり・4桁ゼロ埋め）に書き出す。
+/// 分割したファイルは `time_entries_*.parquet` でまとめて読み込める。
+fn write_parquet_files(
+    conn: &Connection,
+    output_path: &std::path::Path,
+    batch_rows: Option<usize>,
+    on_progress: &mut dyn FnMut(ExportProgress),
+) -> AppResult<Vec<String>> {
+    if batch_rows == Some(0) {
+        return Err(AppError::InvalidInput(
+            "Batch rows must be greater than 0".to_string(),
+        ));
+    }
+
+    let mut exported_files = Vec::new();
+
+    for (index, table) in EXPORT_TABLES.iter().enumerate() {
+        match batch_rows {
+            Some(batch_rows) if *table == "time_entries" => {
+                exported_files.extend(write_time_entry_parts(conn, output_path, batch_rows)?);
+            }
+            _ => {
+                let file_path = output_path.join(format!("{}.parquet", table));
+                conn.execute(
+                    &format!(
+                        "COPY {} TO '{}' (FORMAT PARQUET)",
+                        table,
+                        file_path.to_string_lossy()
+                    ),
+                    [],
+                )?;
+                exported_files.push(file_path.to_string_lossy().to_string());
+            }
+        }
+        on_progress(export_progress(index + 1));
+    }
+
+    Ok(exported_files)
+}
+
+/// time_entriesを開始日時の範囲で分割してParquetファイルに書き出す
+///
+/// 同じ開始日時のエントリは同じファイルに入るため、1ファイルの行数はbatch_rowsを超えることがある。
+/// 記録がない場合も空の `time_entries_0001.parquet` を1つ書き出す。
+fn write_time_entry_parts(
+    conn: &Connection,
+    output_path: &std::path::Path,
+    batch_rows: usize,
+) -> AppResult<Vec<String>> {
+    // 開始日時順にbatch_rows件ごとの先頭の開始日時を各ファイルの下限とする
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT started_at FROM (
+             SELECT started_at, row_number() OVER (ORDER BY started_at) AS rn
+             FROM time_entries
+         )
+         WHERE (rn - 1) % ? = 0
+         ORDER BY started_at",
+    )?;
+    let bounds = stmt
+        .query_map([batch_rows as i64], |row| row.get::<_, DateTime<Utc>>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let part_count = bounds.len().max(1);
+    let mut files = Vec::with_capacity(part_count);
+    for part in 0..part_count {
+        // 境界はDBから取得した値のため、文字列として埋め込んでも安全
+        let mut conditions = Vec::new();
+        if let Some(lower) = bounds.get(part) {
+            conditions.push(format!("started_at >= '{}'::TIMESTAMPTZ", lower.to_rfc3339()));
+        }
+        if let Some(upper) = bounds.get(part + 1) {
+            conditions.push(format!("started_at < '{}'::TIMESTAMPTZ", upper.to_rfc3339()));
+        }
+        let filter = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", conditions.join(" AND "))
+        };
+
+        let file_path = output_path.join(format!("time_entries_{:04}.parquet", part + 1));
+        conn.execute(
+            &format!(
+                "COPY (SELECT * FROM time_entries{}) TO '{}' (FORMAT PARQUET)",
+                filter,
+                file_path.to_string_lossy()
+            ),
+            [],
+        )?;
+        files.push(file_path.to_string_lossy().to_string());
+    }
+
+    Ok(files)
+}
+
+/// エクスポートバンドル内のマニフェストのファイル名
+const BUNDLE_MANIFEST_FILE: &str = "manifest.json";
+
+/// エクスポートバンドル内のJSONエクスポートのファイル名
+const BUNDLE_DATA_FILE: &str = "data.json";
+
+/// zipの書き込みエラーを変換する
+fn zip_error(e: zip::result::ZipError) -> AppError {
+    AppError::OperationFailed(format!("Failed to write zip: {}", e))
+}
+
+/// エクスポートデータをJSONファイルに直接書き出す（書き込んだバイト数を返す）
+fn write_export_json(
+    data: &ExportData,
+    output_path: &std::path::Path,
+    pretty: bool,
+) -> AppResult<u64> {
+    use std::io::Write;
+
+    let mut writer = std::io::BufWriter::new(std::fs::File::create(output_path)?);
+    if pretty {
+        serde_json::to_writer_pretty(&mut writer, data)?;
+    } else {
+        serde_json::to_writer(&mut writer, data)?;
+    }
+    writer.flush()?;
+
+    Ok(std::fs::metadata(output_path)?.len())
+}
+
+/// JSONエクスポートと各テーブルのParquetファイルを1つのzipにまとめて書き出す
+fn write_export_bundle(conn: &Connection, output_path: &std::path::Path) -> AppResult<ExportBundleManifest> {
+    let data = create_export_data(conn)?;
+    let manifest = ExportBundleManifest {
+        version: data.version.clone(),
+        exported_at: data.exported_at,
+        tasks: data.tasks.len(),
+        artifacts: data.artifacts.len(),
+        time_entries: data.time_entries.len(),
+        entry_artifacts: data.entry_artifacts.len(),
+        entry_notes: data.entry_notes.len(),
+    };
+
+    // Parquetファイルは一時ディレクトリに書き出してからzipに追加し、最後に削除する
+    let work_dir = std::env::temp_dir().join(format!("local-time-tracker-bundle-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&work_dir)?;
+    let result = write_bundle_zip(conn, &work_dir, output_path, &data, &manifest);
+    let _ = std::fs::remove_dir_all(&work_dir);
+    result?;
+
+    Ok(manifest)
+}
+
+/// バンドルのzipを書き出す（Parquetファイルはwork_dirに一時的に作成する）
+fn write_bundle_zip(
+    conn: &Connection,
+    work_dir: &std::path::Path,
+    output_path: &std::path::Path,
+    data: &ExportData,
+    manifest: &ExportBundleManifest,
+) -> AppResult<()> {
+    let parquet_files = write_parquet_files(conn, work_dir, None, &mut |_| {})?;
+
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(output_path)?);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    zip.start_file(BUNDLE_MANIFEST_FILE, options).map_err(zip_error)?;
+    serde_json::to_writer_pretty(&mut zip, manifest)?;
+    zip.start_file(BUNDLE_DATA_FILE, options).map_err(zip_error)?;
+    serde_json::to_writer(&mut zip, data)?;
+
+    for file in &parquet_files {
+        let path = std::path::Path::new(file);
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        zip.start_file(name, options).map_err(zip_error)?;
+        std::io::copy(&mut std::fs::File::open(path)?, &mut zip)?;
+    }
+
+    zip.finish().map_err(zip_error)?;
+    Ok(())
+}
+
+/// Parquetインポートで読み込む各テーブルのカラム（必須, 省略可）
+///
+/// 省略可のカラムは後からスキーマに追加されたもので、古いエクスポートにない場合はNULLとして読む。
+const PARQUET_IMPORT_COLUMNS: [(&str, &[&str], &[&str]); 5] = [
+    (
+        "tasks",
+        &["id", "name", "description", "color", "archived", "created_at", "updated_at"],
+        &["folder_id", "hourly_rate", "currency"],
+    ),
+    (
+        "artifacts",
+        &["id", "name", "artifact_type", "reference", "metadata", "created_at"],
+        &["updated_at"],
+    ),
+    (
+        "time_entries",
+        &["id", "task_id", "started_at", "ended_at", "memo", "created_at", "updated_at"],
+        &["duration_seconds"],
+    ),
+    ("entry_artifacts", &["entry_id", "artifact_id"], &[]),
+    ("entry_notes", &["id", "entry_id", "text", "created_at"], &[]),
+];
+
+/// ファイルごと省略できるテーブル（後から追加されたもので、古いエクスポートにない場合は0件として読む）
+const PARQUET_OPTIONAL_TABLES: &[&str] = &["entry_notes"];
+
+/// Parquetファイルのカラム名を取得する
+fn parquet_column_names(conn: &Connection, file: &str) -> AppResult<Vec<String>> {
+    let mut stmt = conn.prepare(&format!(
+        "DESCRIBE SELECT * FROM read_parquet('{}')",
+        file.replace('\'', "''")
+    ))?;
+    let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+
+    let mut columns = Vec::new();
+    for row in rows {
+        columns.push(row?);
+    }
+    Ok(columns)
+}
+
+/// エクスポートされたParquetファイルを検証し、テーブルごとの読み込み元（サブクエリ）を返す
+fn parquet_import_sources(conn: &Connection, input_dir: &std::path::Path) -> AppResult<Vec<String>> {
+    let mut sources = Vec::new();
+    for (table, required, optional) in PARQUET_IMPORT_COLUMNS {
+        let mut path = input_dir.join(format!("{}.parquet", table));
+        // 分割して書き出したファイルはまとめて読み込む
+        if !path.is_file() && input_dir.join(format!("{}_0001.parquet", table)).is_file() {
+            path = input_dir.join(format!("{}_*.parquet", table));
+        } else if !path.is_file() && PARQUET_OPTIONAL_TABLES.contains(&table) {
+            sources.push(format!(
+                "(SELECT {} FROM {} WHERE false) AS {}",
+                required.join(", "),
+                table,
+                table
+            ));
+            continue;
+        } else if !path.is_file() {
+            return Err(AppError::InvalidInput(format!(
+                "Missing parquet file: {}",
+                path.display()
+            )));
+        }
+        let file = path.to_string_lossy().to_string();
+        let columns = parquet_column_names(conn, &file).map_err(|e| {
+            AppError::InvalidInput(format!("Cannot read parquet file {}: {}", file, e))
+        })?;
+
+        let missing: Vec<&str> = required
+            .iter()
+            .filter(|c| !columns.iter().any(|col| col == *c))
+            .copied()
+            .collect();
+        if !missing.is_empty() {
+            return Err(AppError::InvalidInput(format!(
+                "{}.parquet is missing columns: {}",
+                table,
+                missing.join(", ")
+            )));
+        }
+
+        let mut select: Vec<String> = required.iter().map(|c| c.to_string()).collect();
+        for column in optional {
+            if columns.iter().any(|col| col == column) {
+                select.push(column.to_string());
+            } else {
+                select.push(format!("NULL AS {}", column));
+            }
+        }
+        sources.push(format!(
+            "(SELECT {} FROM read_parquet('{}')) AS {}",
+            select.join(", "),
+            file.replace('\'', "''"),
+            table
+        ));
+    }
+    Ok(sources)
+}
+
+/// Parquetファイルを読み込んでインポートする（検証に失敗した場合はDBを変更しない）
+fn import_parquet_dir(
+    conn: &Connection,
+    input_dir: &std::path::Path,
+    merge: bool,
+    strategy: ImportStrategy,
+) -> AppResult<ImportResult> {
+    let sources = parquet_import_sources(conn, input_dir)?;
+    let data = ExportData {
+        version: "1.0".to_string(),
+        exported_at: Utc::now(),
+        tasks: fetch_tasks_from(conn, &sources[0])?,
+        artifacts: fetch_artifacts_from(conn, &sources[1])?,
+        time_entries: fetch_entries_from(conn, &sources[2])?,
+        entry_artifacts: fetch_entry_artifacts_from(conn, &sources[3])?,
+        entry_notes: fetch_entry_notes_from(conn, &sources[4])?,
+    };
+
+    import_export_data(conn, &data, merge, strategy, false)
+}
+
+/// インポート時の各レコードの処理
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ImportAction {
+    Insert,
+    Update,
+    Skip,
+}
+
+/// 既存レコードの有無と戦略から処理を決める
+///
+/// timestamp_columnは NewerWins で比較する日時カラム（更新日時のないテーブルは作成日時）
+fn resolve_import_action(
+    conn: &Connection,
+    table: &str,
+    timestamp_column: &str,
+    id: &Uuid,
+    incoming: DateTime<Utc>,
+    strategy: ImportStrategy,
+) -> AppResult<ImportAction> {
+    let result = conn.query_row(
+        &format!("SELECT {} FROM {} WHERE id = ?", timestamp_column, table),
+        [id.to_string()],
+        |row| row.get::<_, DateTime<Utc>>(0),
+    );
+
+    let existing = match result {
+        Ok(existing) => existing,
+        Err(duckdb::Error::QueryReturnedNoRows) => return Ok(ImportAction::Insert),
+        Err(e) => return Err(AppError::Database(e)),
+    };
+
+    Ok(match strategy {
+        ImportStrategy::SkipExisting => ImportAction::Skip,
+        ImportStrategy::Overwrite => ImportAction::Update,
+        ImportStrategy::NewerWins if incoming > existing => ImportAction::Update,
+        ImportStrategy::NewerWins => ImportAction::Skip,
     })
 }
 
+/// 重複して現れる値を最初に現れた順に返す（同じ値は1回だけ）
+fn find_duplicates<T: Eq + std::hash::Hash + Clone>(values: impl IntoIterator<Item = T>) -> Vec<T> {
+    let mut counts: HashMap<T, usize> = HashMap::new();
+    let mut duplicates = Vec::new();
+    for value in values {
+        let count = counts.entry(value.clone()).or_insert(0);
+        *count += 1;
+        if *count == 2 {
+            duplicates.push(value);
+        }
+    }
+    duplicates
+}
+
+/// インポートするデータの内容を検証する（DBを変更する前に呼ぶ）
+///
+/// 同じIDが複数含まれていると挿入の途中で主キー制約違反になるため、事前にまとめて検出する。
+fn validate_export_data(data: &ExportData) -> AppResult<()> {
+    let mut problems = Vec::new();
+    let mut report = |label: &str, duplicates: Vec<String>| {
+        if !duplicates.is_empty() {
+            problems.push(format!("{}: {}", label, duplicates.join(", ")));
+        }
+    };
+
+    report(
+        "tasks",
+        find_duplicates(data.tasks.iter().map(|t| t.id))
+            .iter()
+            .map(Uuid::to_string)
+            .collect(),
+    );
+    report(
+        "artifacts",
+        find_duplicates(data.artifacts.iter().map(|a| a.id))
+            .iter()
+            .map(Uuid::to_string)
+            .collect(),
+    );
+    report(
+        "time_entries",
+        find_duplicates(data.time_entries.iter().map(|e| e.id))
+            .iter()
+            .map(Uuid::to_string)
+            .collect(),
+    );
+    report(
+        "entry_artifacts",
+        find_duplicates(
+            data.entry_artifacts
+                .iter()
+                .map(|link| (link.entry_id, link.artifact_id)),
+        )
+        .iter()
+        .map(|(entry_id, artifact_id)| format!("{}/{}", entry_id, artifact_id))
+        .collect(),
+    );
+    report(
+        "entry_notes",
+        find_duplicates(data.entry_notes.iter().map(|n| n.id))
+            .iter()
+            .map(Uuid::to_string)
+            .collect(),
+    );
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::InvalidInput(format!(
+            "Import data contains duplicate ids ({})",
+            problems.join("; ")
+        )))
+    }
+}
+
+/// 同じタスク・開始日時・終了日時の時間記録を探し、あればそのIDを返す（IDは問わない）
+fn find_entry_with_same_content(conn: &Connection, entry: &ExportTimeEntry) -> AppResult<Option<Uuid>> {
+    let result = conn.query_row(
+        "SELECT id FROM time_entries
+         WHERE task_id IS NOT DISTINCT FROM ? AND started_at = ? AND ended_at IS NOT DISTINCT FROM ?
+         ORDER BY created_at, id
+         LIMIT 1",
+        duckdb::params![entry.task_id.map(|id| id.to_string()), entry.started_at, entry.ended_at],
+        |row| row.get::<_, String>(0),
+    );
+    match result {
+        Ok(id) => Ok(Some(Uuid::parse_str(&id).unwrap())),
+        Err(duckdb::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(AppError::Database(e)),
+    }
+}
+
 /// データをインポートする
-fn import_export_data(conn: &Connection, data: &ExportData, merge: bool) -> AppResult<ImportResult> {
+///
+/// マージ時にdedupe_by_contentを指定すると、IDが異なっていても同じ内容の時間記録は取り込まずにスキップする。
+/// スキップした記録への成果物の紐付けは、同じ内容の既存の記録に付け替えて取り込む。
+fn import_export_data(
+    conn: &Connection,
+    data: &ExportData,
+    merge: bool,
+    strategy: ImportStrategy,
+    dedupe_by_content: bool,
+) -> AppResult<ImportResult> {
+    validate_export_data(data)?;
+
+    // 途中で失敗した場合に既存データが消えたり一部だけ取り込まれたりしないよう、1つのトランザクションで行う
+    let tx = conn.unchecked_transaction()?;
+
     if !merge {
         // マージしない場合は既存データを削除
-        conn.execute("DELETE FROM entry_artifacts", [])?;
-        conn.execute("DELETE FROM time_entries", [])?;
-        conn.execute("DELETE FROM artifacts", [])?;
-        conn.execute("DELETE FROM tasks", [])?;
+        tx.execute("DELETE FROM entry_artifacts", [])?;
+        tx.execute("DELETE FROM entry_notes", [])?;
+        tx.execute("DELETE FROM time_entries", [])?;
+        tx.execute("DELETE FROM artifacts", [])?;
+        tx.execute("DELETE FROM tasks", [])?;
     }
 
-    let mut tasks_imported = 0;
-    let mut entries_imported = 0;
-    let mut artifacts_imported = 0;
+    let mut result = ImportResult::default();
 
     // タスクをインポート
     for task in &data.tasks {
-        // マージモードの場合、既存のIDがあればスキップ
-        if merge {
-            let mut stmt =
-                conn.prepare("SELECT COUNT(*) FROM tasks WHERE id = ?")?;
-            let count: i64 = stmt.query_row([task.id.to_string()], |row| row.get(0))?;
-            if count > 0 {
-                continue;
+        let action =
+            resolve_import_action(&tx, "tasks", "updated_at", &task.id, task.updated_at, strategy)?;
+        match action {
+            ImportAction::Insert => {
+                tx.execute(
+                    "INSERT INTO tasks (id, folder_id, name, description, color, archived, created_at, updated_at, hourly_rate, currency)
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                    duckdb::params![
+                        task.id.to_string(),
+                        task.folder_id.map(|id| id.to_string()),
+                        &task.name,
+                        &task.description,
+                        &task.color,
+                        task.archived,
+                        task.created_at,
+                        task.updated_at,
+                        task.hourly_rate,
+                        &task.currency,
+                    ],
+                )?;
+                result.tasks_imported += 1;
+            }
+            ImportAction::Update => {
+                tx.execute(
+                    "UPDATE tasks SET folder_id = ?, name = ?, description = ?, color = ?, archived = ?,
+                            created_at = ?, updated_at = ?, hourly_rate = ?, currency = ?
+                     WHERE id = ?",
+                    duckdb::params![
+                        task.folder_id.map(|id| id.to_string()),
+                        &task.name,
+                        &task.description,
+                        &task.color,
+                        task.archived,
+                        task.created_at,
+                        task.updated_at,
+                        task.hourly_rate,
+                        &task.currency,
+                        task.id.to_string(),
+                    ],
+                )?;
+                result.tasks_updated += 1;
             }
+            ImportAction::Skip => result.skipped += 1,
         }
-
-        conn.execute(
-            "INSERT INTO tasks (id, name, description, color, archived, created_at, updated_at)
-             VALUES (?, ?, ?, ?, ?, ?, ?)",
-            duckdb::params![
-                task.id.to_string(),
-                &task.name,
-                &task.description,
-                &task.color,
-                task.archived,
-                task.created_at,
-                task.updated_at,
-            ],
-        )?;
-        tasks_imported += 1;
     }
 
-    // 成果物をインポート
+    // 成果物をインポート（更新日時のない古いファイルは作成日時を更新日時とみなす）
     for artifact in &data.artifacts {
-        if merge {
-            let mut stmt =
-                conn.prepare("SELECT COUNT(*) FROM artifacts WHERE id = ?")?;
-            let count: i64 = stmt.query_row([artifact.id.to_string()], |row| row.get(0))?;
-            if count > 0 {
-                continue;
+        let updated_at = artifact.updated_at.max(artifact.created_at);
+        let action = resolve_import_action(
+            &tx,
+            "artifacts",
+            "updated_at",
+            &artifact.id,
+            updated_at,
+            strategy,
+        )?;
+        match action {
+            ImportAction::Insert => {
+                tx.execute(
+                    "INSERT INTO artifacts (id, name, artifact_type, reference, metadata, created_at, updated_at)
+                     VALUES (?, ?, ?, ?, ?, ?, ?)",
+                    duckdb::params![
+                        artifact.id.to_string(),
+                        &artifact.name,
+                        &artifact.artifact_type,
+                        &artifact.reference,
+                        artifact.metadata.as_ref().map(|m| m.to_string()),
+                        artifact.created_at,
+                        updated_at,
+                    ],
+                )?;
+                result.artifacts_imported += 1;
+            }
+            ImportAction::Update => {
+                tx.execute(
+                    "UPDATE artifacts SET name = ?, artifact_type = ?, reference = ?, metadata = ?, created_at = ?, updated_at = ?
+                     WHERE id = ?",
+                    duckdb::params![
+                        &artifact.name,
+                        &artifact.artifact_type,
+                        &artifact.reference,
+                        artifact.metadata.as_ref().map(|m| m.to_string()),
+                        artifact.created_at,
+                        updated_at,
+                        artifact.id.to_string(),
+                    ],
+                )?;
+                result.artifacts_updated += 1;
             }
+            ImportAction::Skip => result.skipped += 1,
         }
-
-        conn.execute(
-            "INSERT INTO artifacts (id, name, artifact_type, reference, metadata, created_at)
-             VALUES (?, ?, ?, ?, ?, ?)",
-            duckdb::params![
-                artifact.id.to_string(),
-                &artifact.name,
-                &artifact.artifact_type,
-                &artifact.reference,
-                artifact.metadata.as_ref().map(|m| m.to_string()),
-                artifact.created_at,
-            ],
-        )?;
-        artifacts_imported += 1;
     }
 
-    // 時間記録をインポート
+    // 時間記録をインポート（内容の重複でスキップした記録のIDは既存の記録のIDに対応付ける）
+    let mut merged_entry_ids: HashMap<Uuid, Uuid> = HashMap::new();
     for entry in &data.time_entries {
-        if merge {
-            let mut stmt =
-                conn.prepare("SELECT COUNT(*) FROM time_entries WHERE id = ?")?;
-            let count: i64 = stmt.query_row([entry.id.to_string()], |row| row.get(0))?;
-            if count > 0 {
-                continue;
+        // ファイル上の値は信用せず、開始・終了日時から作業秒数を求める
+        let duration_seconds = entry
+            .ended_at
+            .map(|ended| (ended - entry.started_at).num_seconds());
+        let action = resolve_import_action(
+            &tx,
+            "time_entries",
+            "updated_at",
+            &entry.id,
+            entry.updated_at,
+            strategy,
+        )?;
+        let same_content = if merge && dedupe_by_content && action == ImportAction::Insert {
+            find_entry_with_same_content(&tx, entry)?
+        } else {
+            None
+        };
+        // 手動で作り直した記録など、IDだけが異なる重複は取り込まない
+        if let Some(existing_id) = same_content {
+            merged_entry_ids.insert(entry.id, existing_id);
+            result.skipped += 1;
+            continue;
+        }
+        match action {
+            ImportAction::Insert => {
+                tx.execute(
+                    "INSERT INTO time_entries (id, task_id, started_at, ended_at, duration_seconds, memo, created_at, updated_at)
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                    duckdb::params![
+                        entry.id.to_string(),
+                        entry.task_id.map(|id| id.to_string()),
+                        entry.started_at,
+                        entry.ended_at,
+                        duration_seconds,
+                        &entry.memo,
+                        entry.created_at,
+                        entry.updated_at,
+                    ],
+                )?;
+                result.entries_imported += 1;
+            }
+            ImportAction::Update => {
+                tx.execute(
+                    "UPDATE time_entries SET task_id = ?, started_at = ?, ended_at = ?,
+                            duration_seconds = ?, memo = ?, created_at = ?, updated_at = ?
+                     WHERE id = ?",
+                    duckdb::params![
+                        entry.task_id.map(|id| id.to_string()),
+                        entry.started_at,
+                        entry.ended_at,
+                        duration_seconds,
+                        &entry.memo,
+                        entry.created_at,
+                        entry.updated_at,
+                        entry.id.to_string(),
+                    ],
+                )?;
+                result.entries_updated += 1;
             }
+            ImportAction::Skip => result.skipped += 1,
         }
-
-        conn.execute(
-            "INSERT INTO time_entries (id, task_id, started_at, ended_at, memo, created_at, updated_at)
-             VALUES (?, ?, ?, ?, ?, ?, ?)",
-            duckdb::params![
-                entry.id.to_string(),
-                entry.task_id.map(|id| id.to_string()),
-                entry.started_at,
-                entry.ended_at,
-                &entry.memo,
-                entry.created_at,
-                entry.updated_at,
-            ],
-        )?;
-        entries_imported += 1;
     }
 
     // 紐付けをインポート
     for link in &data.entry_artifacts {
+        let entry_id = merged_entry_ids
+            .get(&link.entry_id)
+            .copied()
+            .unwrap_or(link.entry_id);
         if merge {
-            let mut stmt = conn.prepare(
+            let mut stmt = tx.prepare(
                 "SELECT COUNT(*) FROM entry_artifacts WHERE entry_id = ? AND artifact_id = ?",
             )?;
             let count: i64 = stmt
-                .query_row([link.entry_id.to_string(), link.artifact_id.to_string()], |row| {
+                .query_row([entry_id.to_string(), link.artifact_id.to_string()], |row| {
                     row.get(0)
                 })?;
             if count > 0 {
@@ -247,113 +938,682 @@ fn import_export_data(conn: &Connection, data: &ExportData, merge: bool) -> AppR
 
         // 紐付け先のエントリと成果物が存在するか確認
         let mut entry_stmt =
-            conn.prepare("SELECT COUNT(*) FROM time_entries WHERE id = ?")?;
+            tx.prepare("SELECT COUNT(*) FROM time_entries WHERE id = ?")?;
         let entry_exists: i64 =
-            entry_stmt.query_row([link.entry_id.to_string()], |row| row.get(0))?;
+            entry_stmt.query_row([entry_id.to_string()], |row| row.get(0))?;
 
         let mut artifact_stmt =
-            conn.prepare("SELECT COUNT(*) FROM artifacts WHERE id = ?")?;
+            tx.prepare("SELECT COUNT(*) FROM artifacts WHERE id = ?")?;
         let artifact_exists: i64 =
             artifact_stmt.query_row([link.artifact_id.to_string()], |row| row.get(0))?;
 
         if entry_exists > 0 && artifact_exists > 0 {
-            conn.execute(
+            tx.execute(
                 "INSERT INTO entry_artifacts (entry_id, artifact_id) VALUES (?, ?)",
-                duckdb::params![link.entry_id.to_string(), link.artifact_id.to_string()],
+                duckdb::params![entry_id.to_string(), link.artifact_id.to_string()],
             )?;
         }
     }
 
-    Ok(ImportResult {
-        tasks_imported,
-        entries_imported,
-        artifacts_imported,
-    })
-}
+    // 時刻付きメモをインポート（追記のみで変更されないため、既存のIDはスキップする。
+    // 内容の重複でスキップした記録のメモは既存の記録に付け替える）
+    for note in &data.entry_notes {
+        let entry_id = merged_entry_ids
+            .get(&note.entry_id)
+            .copied()
+            .unwrap_or(note.entry_id);
+        let (note_exists, entry_exists): (i64, i64) = tx.query_row(
+            "SELECT (SELECT COUNT(*) FROM entry_notes WHERE id = ?),
+                    (SELECT COUNT(*) FROM time_entries WHERE id = ?)",
+            [note.id.to_string(), entry_id.to_string()],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        if note_exists == 0 && entry_exists > 0 {
+            tx.execute(
+                "INSERT INTO entry_notes (id, entry_id, text, created_at) VALUES (?, ?, ?, ?)",
+                duckdb::params![
+                    note.id.to_string(),
+                    entry_id.to_string(),
+                    &note.text,
+                    note.created_at,
+                ],
+            )?;
+        }
+    }
 
-/// JSONエクスポート
-#[tauri::command]
-pub fn export_data(state: tauri::State<AppState>) -> AppResult<ExportData> {
-    state.db.with_connection(create_export_data)
+    tx.commit()?;
+    Ok(result)
 }
 
-/// JSONインポート
-#[tauri::command]
-pub fn import_data(
-    state: tauri::State<AppState>,
-    data: ExportData,
+/// 時間記録だけを取り込む（タスク・成果物は変更しない）
+///
+/// 存在しないタスクを参照する記録はタスクを外して取り込み、警告に残す。
+/// マージ時は同じIDの記録をスキップし、マージしない場合は既存の時間記録と紐付けを置き換える。
+fn import_entry_fragment(
+    conn: &Connection,
+    entries: &[ExportTimeEntry],
     merge: bool,
 ) -> AppResult<ImportResult> {
-    state
-        .db
-        .with_connection(|conn| import_export_data(conn, &data, merge))
-}
-
-/// Parquetエクスポート
-#[tauri::command]
-pub fn export_parquet(state: tauri::State<AppState>, output_dir: String) -> AppResult<Vec<String>> {
-    use std::path::Path;
-
-    let output_path = Path::new(&output_dir);
-    if !output_path.exists() {
-        std::fs::create_dir_all(output_path)?;
+    let duplicates = find_duplicates(entries.iter().map(|e| e.id));
+    if !duplicates.is_empty() {
+        return Err(AppError::InvalidInput(format!(
+            "Import data contains duplicate ids (time_entries: {})",
+            duplicates.iter().map(Uuid::to_string).collect::<Vec<_>>().join(", ")
+        )));
     }
 
-    state.db.with_connection(|conn| {
-        let mut exported_files = Vec::new();
+    let tx = conn.unchecked_transaction()?;
+    if !merge {
+        tx.execute("DELETE FROM entry_artifacts", [])?;
+        tx.execute("DELETE FROM entry_notes", [])?;
+        tx.execute("DELETE FROM time_entries", [])?;
+    }
 
-        // tasks
-        let tasks_path = output_path.join("tasks.parquet");
-        conn.execute(
-            &format!(
-                "COPY tasks TO '{}' (FORMAT PARQUET)",
-                tasks_path.to_string_lossy()
-            ),
-            [],
+    let mut result = ImportResult::default();
+    for entry in entries {
+        let action = resolve_import_action(
+            &tx,
+            "time_entries",
+            "updated_at",
+            &entry.id,
+            entry.updated_at,
+            ImportStrategy::SkipExisting,
         )?;
-        exported_files.push(tasks_path.to_string_lossy().to_string());
+        if action == ImportAction::Skip {
+            result.skipped += 1;
+            continue;
+        }
 
-        // artifacts
-        let artifacts_path = output_path.join("artifacts.parquet");
-        conn.execute(
-            &format!(
-                "COPY artifacts TO '{}' (FORMAT PARQUET)",
-                artifacts_path.to_string_lossy()
-            ),
-            [],
+        let mut task_id = entry.task_id;
+        if let Some(id) = task_id {
+            let exists: i64 = tx.query_row(
+                "SELECT COUNT(*) FROM tasks WHERE id = ?",
+                [id.to_string()],
+                |row| row.get(0),
+            )?;
+            if exists == 0 {
+                result.warnings.push(format!(
+                    "Entry {}: task {} not found, imported without a task",
+                    entry.id, id
+                ));
+                task_id = None;
+            }
+        }
+
+        // ファイル上の値は信用せず、開始・終了日時から作業秒数を求める
+        let duration_seconds = entry
+            .ended_at
+            .map(|ended| (ended - entry.started_at).num_seconds());
+        tx.execute(
+            "INSERT INTO time_entries (id, task_id, started_at, ended_at, duration_seconds, memo, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            duckdb::params![
+                entry.id.to_string(),
+                task_id.map(|id| id.to_string()),
+                entry.started_at,
+                entry.ended_at,
+                duration_seconds,
+                &entry.memo,
+                entry.created_at,
+                entry.updated_at,
+            ],
         )?;
-        exported_files.push(artifacts_path.to_string_lossy().to_string());
+        result.entries_imported += 1;
+    }
 
-        // time_entries
-        let entries_path = output_path.join("time_entries.parquet");
-        conn.execute(
-            &format!(
-                "COPY time_entries TO '{}' (FORMAT PARQUET)",
-                entries_path.to_string_lossy()
-            ),
-            [],
-        )?;
-        exported_files.push(entries_path.to_string_lossy().to_string());
+    tx.commit()?;
+    Ok(result)
+}
 
-        // entry_artifacts
-        let links_path = output_path.join("entry_artifacts.parquet");
-        conn.execute(
-            &format!(
-                "COPY entry_artifacts TO '{}' (FORMAT PARQUET)",
-                links_path.to_string_lossy()
-            ),
-            [],
+/// RFC4180形式のCSVを行とフィールドに分割する
+fn parse_csv(content: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' => in_quotes = true,
+            ',' => row.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    // 空行は除外する
+    rows.retain(|r| !(r.len() == 1 && r[0].trim().is_empty()));
+    rows
+}
+
+/// CSVの日時をパースする（RFC3339、またはタイムゾーンなしの場合はUTCとして扱う）
+fn parse_csv_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M", "%Y/%m/%d %H:%M:%S", "%Y/%m/%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .map(|naive| naive.and_utc())
+}
+
+/// CSVの時間記録をインポートする（タスクは名前で照合し、なければ作成する）
+fn import_csv_content(
+    conn: &Connection,
+    content: &str,
+    column_map: &CsvColumnMap,
+    merge: bool,
+) -> AppResult<ImportResult> {
+    let mut rows = parse_csv(content).into_iter();
+    let header = rows
+        .next()
+        .ok_or_else(|| AppError::InvalidInput("CSV is empty".to_string()))?;
+
+    let column_index = |name: &str| -> AppResult<usize> {
+        header
+            .iter()
+            .position(|h| h.trim() == name)
+            .ok_or_else(|| AppError::InvalidInput(format!("Column not found in CSV: {}", name)))
+    };
+    let started_at_idx = column_index(&column_map.started_at)?;
+    let ended_at_idx = column_index(&column_map.ended_at)?;
+    let task_name_idx = column_map.task_name.as_deref().map(column_index).transpose()?;
+    let memo_idx = column_map.memo.as_deref().map(column_index).transpose()?;
+
+    // 途中で失敗した場合に既存データが消えたままにならないよう、削除と取り込みを1つのトランザクションで行う
+    let tx = conn.unchecked_transaction()?;
+    if !merge {
+        // マージしない場合は既存データを削除
+        tx.execute("DELETE FROM entry_artifacts", [])?;
+        tx.execute("DELETE FROM entry_notes", [])?;
+        tx.execute("DELETE FROM time_entries", [])?;
+        tx.execute("DELETE FROM artifacts", [])?;
+        tx.execute("DELETE FROM tasks", [])?;
+    }
+
+    // 既存タスクを名前で引けるようにする
+    let mut task_ids: HashMap<String, Uuid> = HashMap::new();
+    {
+        let mut stmt = tx.prepare("SELECT id, name FROM tasks ORDER BY created_at")?;
+        let rows = stmt.query_map([], |row| {
+            let id_str: String = row.get(0)?;
+            let name: String = row.get(1)?;
+            Ok((name, Uuid::parse_str(&id_str).unwrap()))
+        })?;
+        for row in rows {
+            let (name, id) = row?;
+            task_ids.entry(name).or_insert(id);
+        }
+    }
+
+    let mut tasks_imported = 0;
+    let mut entries_imported = 0;
+    let mut warnings = Vec::new();
+
+    for (i, row) in rows.enumerate() {
+        // ヘッダー行を1行目として数える
+        let line = i + 2;
+        let cell = |idx: usize| row.get(idx).map(|s| s.trim()).unwrap_or("");
+
+        let Some(started_at) = parse_csv_timestamp(cell(started_at_idx)) else {
+            warnings.push(format!("Line {}: invalid started_at: {}", line, cell(started_at_idx)));
+            continue;
+        };
+        let Some(ended_at) = parse_csv_timestamp(cell(ended_at_idx)) else {
+            warnings.push(format!("Line {}: invalid ended_at: {}", line, cell(ended_at_idx)));
+            continue;
+        };
+        if ended_at < started_at {
+            warnings.push(format!("Line {}: ended_at is before started_at", line));
+            continue;
+        }
+
+        let task_id = match task_name_idx.map(cell).filter(|name| !name.is_empty()) {
+            Some(name) => match task_ids.get(name) {
+                Some(id) => Some(*id),
+                None => {
+                    let task = Task::new(name.to_string(), None, None, None);
+                    tx.execute(
+                        "INSERT INTO tasks (id, name, description, color, archived, created_at, updated_at)
+                         VALUES (?, ?, ?, ?, ?, ?, ?)",
+                        duckdb::params![
+                            task.id.to_string(),
+                            &task.name,
+                            &task.description,
+                            &task.color,
+                            task.archived,
+                            task.created_at,
+                            task.updated_at,
+                        ],
+                    )?;
+                    task_ids.insert(task.name.clone(), task.id);
+                    tasks_imported += 1;
+                    Some(task.id)
+                }
+            },
+            None => None,
+        };
+
+        let memo = memo_idx.map(cell).filter(|m| !m.is_empty()).map(|m| m.to_string());
+        let mut entry = TimeEntry::start(task_id, memo);
+        entry.started_at = started_at;
+        entry.ended_at = Some(ended_at);
+
+        tx.execute(
+            "INSERT INTO time_entries (id, task_id, started_at, ended_at, duration_seconds, memo, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            duckdb::params![
+                entry.id.to_string(),
+                entry.task_id.map(|id| id.to_string()),
+                entry.started_at,
+                entry.ended_at,
+                entry.duration_seconds(),
+                &entry.memo,
+                entry.created_at,
+                entry.updated_at,
+            ],
         )?;
-        exported_files.push(links_path.to_string_lossy().to_string());
+        entries_imported += 1;
+    }
+    tx.commit()?;
+
+    Ok(ImportResult {
+        tasks_imported,
+        entries_imported,
+        warnings,
+        ..Default::default()
+    })
+}
+
+/// CSVのフィールドをRFC4180に従ってエスケープする
+pub(crate) fn escape_csv_field(value: &str) -> String {
+    if value.contains(',') || value.contains('"') || value.contains('\n') || value.contains('\r') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// タスクの完了済み時間記録をCSVファイルに書き出す
+fn write_task_entries_csv(
+    conn: &Connection,
+    task_id: &Uuid,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    output_path: &std::path::Path,
+) -> AppResult<usize> {
+    let exists: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM tasks WHERE id = ?",
+        [task_id.to_string()],
+        |row| row.get(0),
+    )?;
+    if exists == 0 {
+        return Err(AppError::NotFound(format!("Task with id {} not found", task_id)));
+    }
+
+    let mut sql = String::from(
+        "SELECT e.started_at, e.ended_at, e.memo,
+                (SELECT string_agg(a.name, ',' ORDER BY a.name)
+                 FROM entry_artifacts ea
+                 JOIN artifacts a ON a.id = ea.artifact_id
+                 WHERE ea.entry_id = e.id) as artifact_names
+         FROM time_entries e
+         WHERE e.task_id = ? AND e.ended_at IS NOT NULL",
+    );
+    let mut params: Vec<Box<dyn duckdb::ToSql>> = vec![Box::new(task_id.to_string())];
+
+    if let Some(from) = from {
+        sql.push_str(" AND e.started_at >= ?");
+        params.push(Box::new(from));
+    }
+    if let Some(to) = to {
+        sql.push_str(" AND e.started_at <= ?");
+        params.push(Box::new(to));
+    }
+    sql.push_str(" ORDER BY e.started_at");
+
+    let mut stmt = conn.prepare(&sql)?;
+    let param_refs: Vec<&dyn duckdb::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+
+    let rows = stmt.query_map(param_refs.as_slice(), |row| {
+        let started_at: DateTime<Utc> = row.get(0)?;
+        let ended_at: DateTime<Utc> = row.get(1)?;
+        let memo: Option<String> = row.get(2)?;
+        let artifact_names: Option<String> = row.get(3)?;
+        Ok((started_at, ended_at, memo, artifact_names))
+    })?;
+
+    let mut csv = String::from("started_at,ended_at,duration_seconds,memo,artifacts\r\n");
+    let mut count = 0;
+    for row in rows {
+        let (started_at, ended_at, memo, artifact_names) = row?;
+        csv.push_str(&format!(
+            "{},{},{},{},{}\r\n",
+            started_at.to_rfc3339(),
+            ended_at.to_rfc3339(),
+            (ended_at - started_at).num_seconds(),
+            escape_csv_field(memo.as_deref().unwrap_or("")),
+            escape_csv_field(artifact_names.as_deref().unwrap_or("")),
+        ));
+        count += 1;
+    }
+
+    std::fs::write(output_path, csv)?;
+    Ok(count)
+}
+
+/// iCalendarのテキスト値をRFC5545に従ってエスケープする
+fn escape_ics_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace(['\n', '\r'], "\\n")
+}
+
+/// iCalendarの行を75オクテットごとに折り返す（マルチバイト文字の途中では分割しない）
+fn fold_ics_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            // 継続行の先頭の空白も1オクテットとして数える
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded
+}
+
+/// iCalendarのUTC日時形式に変換する
+fn format_ics_datetime(dt: DateTime<Utc>) -> String {
+    dt.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// 完了済みの時間記録をiCalendarファイルに書き出す（計測中の記録は含めない）
+fn write_entries_ics(
+    conn: &Connection,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    output_path: &std::path::Path,
+) -> AppResult<usize> {
+    let mut sql = String::from(
+        "SELECT e.id, e.started_at, e.ended_at, e.memo, e.updated_at, COALESCE(t.name, '未分類')
+         FROM time_entries e
+         LEFT JOIN tasks t ON e.task_id = t.id
+         WHERE e.ended_at IS NOT NULL",
+    );
+    let mut params: Vec<DateTime<Utc>> = Vec::new();
+    if let Some(from) = from {
+        sql.push_str(" AND e.started_at >= ?");
+        params.push(from);
+    }
+    if let Some(to) = to {
+        sql.push_str(" AND e.started_at <= ?");
+        params.push(to);
+    }
+    sql.push_str(" ORDER BY e.started_at");
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(duckdb::params_from_iter(params), |row| {
+        let id: String = row.get(0)?;
+        let started_at: DateTime<Utc> = row.get(1)?;
+        let ended_at: DateTime<Utc> = row.get(2)?;
+        let memo: Option<String> = row.get(3)?;
+        let updated_at: DateTime<Utc> = row.get(4)?;
+        let task_name: String = row.get(5)?;
+        Ok((id, started_at, ended_at, memo, updated_at, task_name))
+    })?;
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//local-time-tracker//JP".to_string(),
+    ];
+    let mut count = 0;
+    for row in rows {
+        let (id, started_at, ended_at, memo, updated_at, task_name) = row?;
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}@local-time-tracker", id));
+        lines.push(format!("DTSTAMP:{}", format_ics_datetime(updated_at)));
+        lines.push(format!("DTSTART:{}", format_ics_datetime(started_at)));
+        lines.push(format!("DTEND:{}", format_ics_datetime(ended_at)));
+        lines.push(format!("SUMMARY:{}", escape_ics_text(&task_name)));
+        if let Some(memo) = memo.filter(|m| !m.is_empty()) {
+            lines.push(format!("DESCRIPTION:{}", escape_ics_text(&memo)));
+        }
+        lines.push("END:VEVENT".to_string());
+        count += 1;
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut ics = String::new();
+    for line in lines {
+        ics.push_str(&fold_ics_line(&line));
+        ics.push_str("\r\n");
+    }
+
+    std::fs::write(output_path, ics)?;
+    Ok(count)
+}
+
+/// JSONエクスポート
+#[tauri::command]
+pub fn export_data(app: tauri::AppHandle, state: tauri::State<AppState>) -> AppResult<ExportData> {
+    state.db.with_connection(|conn| {
+        create_export_data_with_progress(conn, &mut |progress| {
+            emit_export_progress(&app, progress)
+        })
+    })
+}
+
+/// JSONエクスポートをファイルに直接書き出す（大きなデータをIPCで受け渡さないため）
+#[tauri::command]
+pub fn export_data_to_file(
+    app: tauri::AppHandle,
+    state: tauri::State<AppState>,
+    path: String,
+    pretty: bool,
+) -> AppResult<u64> {
+    let data = state.db.with_connection(|conn| {
+        create_export_data_with_progress(conn, &mut |progress| {
+            emit_export_progress(&app, progress)
+        })
+    })?;
+
+    write_export_json(&data, std::path::Path::new(&path), pretty)
+}
+
+/// 指定したタスクのデータのみをJSONエクスポートする
+#[tauri::command]
+pub fn export_data_filtered(
+    state: tauri::State<AppState>,
+    task_ids: Vec<String>,
+) -> AppResult<ExportData> {
+    if task_ids.is_empty() {
+        return Err(AppError::InvalidInput(
+            "At least one task id is required".to_string(),
+        ));
+    }
+    let mut task_uuids = Vec::new();
+    for id in &task_ids {
+        let uuid = Uuid::parse_str(id)
+            .map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", id)))?;
+        if !task_uuids.contains(&uuid) {
+            task_uuids.push(uuid);
+        }
+    }
+
+    state
+        .db
+        .with_connection(|conn| create_filtered_export_data(conn, &task_uuids))
+}
+
+/// JSONインポート
+#[tauri::command]
+pub fn import_data(
+    state: tauri::State<AppState>,
+    data: ExportData,
+    merge: bool,
+    strategy: Option<ImportStrategy>,
+    dedupe_by_content: Option<bool>,
+) -> AppResult<ImportResult> {
+    let strategy = strategy.unwrap_or_default();
+    let dedupe_by_content = dedupe_by_content.unwrap_or(false);
+    let result = state.db.with_connection(|conn| {
+        import_export_data(conn, &data, merge, strategy, dedupe_by_content)
+    })?;
+    state.report_cache.clear();
+    Ok(result)
+}
+
+/// 時間記録の配列だけをインポートする（クリップボードから貼り付けたJSONなど）
+#[tauri::command]
+pub fn import_entries(
+    state: tauri::State<AppState>,
+    entries: Vec<ExportTimeEntry>,
+    merge: bool,
+) -> AppResult<ImportResult> {
+    let result = state
+        .db
+        .with_connection(|conn| import_entry_fragment(conn, &entries, merge))?;
+    state.report_cache.clear();
+    Ok(result)
+}
+
+/// Parquetインポート（export_parquetで書き出したディレクトリから読み込む）
+#[tauri::command]
+pub fn import_parquet(
+    state: tauri::State<AppState>,
+    input_dir: String,
+    merge: bool,
+    strategy: Option<ImportStrategy>,
+) -> AppResult<ImportResult> {
+    let strategy = strategy.unwrap_or_default();
+    let result = state.db.with_connection(|conn| {
+        import_parquet_dir(conn, std::path::Path::new(&input_dir), merge, strategy)
+    })?;
+    state.report_cache.clear();
+    Ok(result)
+}
+
+/// CSVインポート
+#[tauri::command]
+pub fn import_csv(
+    state: tauri::State<AppState>,
+    path: String,
+    column_map: CsvColumnMap,
+    merge: bool,
+) -> AppResult<ImportResult> {
+    let content = std::fs::read_to_string(&path)?;
+
+    let result = state
+        .db
+        .with_connection(|conn| import_csv_content(conn, &content, &column_map, merge))?;
+    state.report_cache.clear();
+    Ok(result)
+}
+
+/// Parquetエクスポート
+#[tauri::command]
+pub fn export_parquet(
+    app: tauri::AppHandle,
+    state: tauri::State<AppState>,
+    output_dir: String,
+    batch_rows: Option<usize>,
+) -> AppResult<Vec<String>> {
+    use std::path::Path;
+
+    let output_path = Path::new(&output_dir);
+    if !output_path.exists() {
+        std::fs::create_dir_all(output_path)?;
+    }
 
-        Ok(exported_files)
+    state.db.with_connection(|conn| {
+        write_parquet_files(conn, output_path, batch_rows, &mut |progress| {
+            emit_export_progress(&app, progress)
+        })
     })
 }
 
+/// JSONとParquetファイルをまとめたzipバンドルをエクスポートする（書き出したパスを返す）
+#[tauri::command]
+pub fn export_bundle(state: tauri::State<AppState>, output_path: String) -> AppResult<String> {
+    state
+        .db
+        .with_connection(|conn| write_export_bundle(conn, std::path::Path::new(&output_path)))?;
+    Ok(output_path)
+}
+
+/// タスク単位の時間記録CSVエクスポート
+#[tauri::command]
+pub fn export_task_entries_csv(
+    state: tauri::State<AppState>,
+    task_id: String,
+    from: Option<String>,
+    to: Option<String>,
+    output_path: String,
+) -> AppResult<String> {
+    let task_uuid = Uuid::parse_str(&task_id)
+        .map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", task_id)))?;
+    let from = parse_optional_datetime(from, "from")?;
+    let to = parse_optional_datetime(to, "to")?;
+
+    state.db.with_connection(|conn| {
+        write_task_entries_csv(conn, &task_uuid, from, to, std::path::Path::new(&output_path))
+    })?;
+
+    Ok(output_path)
+}
+
+/// 時間記録のiCalendarエクスポート
+#[tauri::command]
+pub fn export_ics(
+    state: tauri::State<AppState>,
+    from: Option<String>,
+    to: Option<String>,
+    output_path: String,
+) -> AppResult<String> {
+    let from = parse_optional_datetime(from, "from")?;
+    let to = parse_optional_datetime(to, "to")?;
+
+    state
+        .db
+        .with_connection(|conn| write_entries_ics(conn, from, to, std::path::Path::new(&output_path)))?;
+
+    Ok(output_path)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::db::{Database, TimeEntry};
+    use crate::db::Database;
 
     fn create_test_db() -> Database {
         Database::new_in_memory().unwrap()
@@ -509,6 +1769,92 @@ mod tests {
         }
     }
 
+    mod export_progress_tests {
+        use super::*;
+
+        #[test]
+        fn JSONエクスポートでもテーブルごとに進捗が通知される() {
+            let db = create_test_db();
+            let mut steps = Vec::new();
+
+            db.with_connection(|conn| {
+                create_export_data_with_progress(conn, &mut |p| steps.push(p.step))
+            })
+            .unwrap();
+
+            assert_eq!(steps, vec![1, 2, 3, 4, 5]);
+        }
+    }
+
+    mod export_data_filtered_tests {
+        use super::*;
+
+        #[test]
+        fn 指定したタスクの記録と紐付いた成果物だけがエクスポートされ再インポートできる() {
+            let db = create_test_db();
+            let shared = Uuid::new_v4();
+            let target = Uuid::new_v4();
+            let other = Uuid::new_v4();
+
+            db.with_connection(|conn| {
+                conn.execute(
+                    "INSERT INTO tasks (id, name, color, created_at, updated_at) VALUES
+                     (?, '対象', '#000000', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     (?, '対象外', '#000000', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                    [target.to_string(), other.to_string()],
+                )?;
+                conn.execute(
+                    "INSERT INTO time_entries (id, task_id, started_at, ended_at, created_at, updated_at) VALUES
+                     ('00000000-0000-0000-0000-000000000001', ?, '2024-01-15 09:00:00+00', '2024-01-15 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     ('00000000-0000-0000-0000-000000000002', ?, '2024-01-15 11:00:00+00', '2024-01-15 12:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                    [target.to_string(), other.to_string()],
+                )?;
+                conn.execute(
+                    "INSERT INTO artifacts (id, name, artifact_type, created_at) VALUES
+                     (?, '共有', 'document', CURRENT_TIMESTAMP),
+                     ('00000000-0000-0000-0000-0000000000a2', '対象外の成果物', 'document', CURRENT_TIMESTAMP)",
+                    [shared.to_string()],
+                )?;
+                conn.execute(
+                    "INSERT INTO entry_artifacts (entry_id, artifact_id) VALUES
+                     ('00000000-0000-0000-0000-000000000001', ?),
+                     ('00000000-0000-0000-0000-000000000002', ?),
+                     ('00000000-0000-0000-0000-000000000002', '00000000-0000-0000-0000-0000000000a2')",
+                    [shared.to_string(), shared.to_string()],
+                )?;
+                Ok(())
+            })
+            .unwrap();
+
+            let export = db
+                .with_connection(|conn| create_filtered_export_data(conn, &[target]))
+                .unwrap();
+
+            assert_eq!(export.tasks.len(), 1);
+            assert_eq!(export.tasks[0].id, target);
+            assert_eq!(export.time_entries.len(), 1);
+            assert_eq!(export.artifacts.len(), 1);
+            assert_eq!(export.artifacts[0].id, shared);
+            assert_eq!(export.entry_artifacts.len(), 1);
+            assert_eq!(export.entry_artifacts[0].entry_id, export.time_entries[0].id);
+
+            let fresh = create_test_db();
+            let result = fresh
+                .with_connection(|conn| import_export_data(conn, &export, false, ImportStrategy::default(), false))
+                .unwrap();
+            assert_eq!(result.entries_imported, 1);
+            assert_eq!(result.artifacts_imported, 1);
+        }
+
+        #[test]
+        fn 存在しないタスクを指定するとエラーになる() {
+            let db = create_test_db();
+
+            let result = db.with_connection(|conn| create_filtered_export_data(conn, &[Uuid::new_v4()]));
+            assert!(matches!(result, Err(AppError::NotFound(_))));
+        }
+    }
+
     mod import_data_tests {
         use super::*;
 
@@ -541,41 +1887,285 @@ mod tests {
                     entry_id: entry.id,
                     artifact_id: artifact.id,
                 }],
+                entry_notes: vec![EntryNote {
+                    id: Uuid::new_v4(),
+                    entry_id: entry.id,
+                    text: "途中経過".to_string(),
+                    created_at: entry.started_at,
+                }],
             }
         }
 
+        fn count_notes(conn: &Connection) -> AppResult<i64> {
+            Ok(conn.query_row("SELECT COUNT(*) FROM entry_notes", [], |row| row.get(0))?)
+        }
+
         #[test]
-        fn データをインポートできる() {
+        fn 時刻付きメモはJSONのエクスポートとインポートで復元される() {
             let db = create_test_db();
             let export_data = create_test_export_data();
+            db.with_connection(|conn| {
+                import_export_data(conn, &export_data, false, ImportStrategy::SkipExisting, false)
+            })
+            .unwrap();
 
-            let result = db
-                .with_connection(|conn| import_export_data(conn, &export_data, false))
+            let json = db
+                .with_connection(|conn| Ok(serde_json::to_string(&create_export_data(conn)?)?))
                 .unwrap();
+            let restored: ExportData = serde_json::from_str(&json).unwrap();
+            assert_eq!(restored.entry_notes.len(), 1);
 
-            assert_eq!(result.tasks_imported, 1);
-            assert_eq!(result.entries_imported, 1);
-            assert_eq!(result.artifacts_imported, 1);
+            let fresh = create_test_db();
+            let notes = fresh
+                .with_connection(|conn| {
+                    import_export_data(conn, &restored, false, ImportStrategy::SkipExisting, false)?;
+                    let mut stmt = conn.prepare("SELECT id, entry_id, text FROM entry_notes")?;
+                    let notes = stmt
+                        .query_map([], |row| {
+                            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+                        })?
+                        .collect::<Result<Vec<_>, _>>()?;
+                    Ok(notes)
+                })
+                .unwrap();
+            let note = &export_data.entry_notes[0];
+            assert_eq!(
+                notes,
+                vec![(note.id.to_string(), note.entry_id.to_string(), "途中経過".to_string())]
+            );
         }
 
         #[test]
-        fn マージモードでは既存データが保持される() {
+        fn マージせずにインポートすると既存の時刻付きメモも置き換えられる() {
             let db = create_test_db();
+            let first = create_test_export_data();
+            let second = create_test_export_data();
 
-            // 既存データを作成
             db.with_connection(|conn| {
-                conn.execute(
-                    "INSERT INTO tasks (id, name, color, created_at, updated_at)
-                     VALUES (uuid(), '既存タスク', '#000000', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                import_export_data(conn, &first, false, ImportStrategy::SkipExisting, false)?;
+                import_export_data(conn, &second, false, ImportStrategy::SkipExisting, false)?;
+                let orphaned: i64 = conn.query_row(
+                    "SELECT COUNT(*) FROM entry_notes n
+                     WHERE NOT EXISTS (SELECT 1 FROM time_entries e WHERE e.id = n.entry_id)",
                     [],
+                    |row| row.get(0),
                 )?;
+                assert_eq!(orphaned, 0);
+                assert_eq!(count_notes(conn)?, 1);
+
+                import_entry_fragment(conn, &second.time_entries, false)?;
+                assert_eq!(count_notes(conn)?, 0);
                 Ok(())
             })
             .unwrap();
+        }
 
+        #[test]
+        fn データをインポートできる() {
+            let db = create_test_db();
             let export_data = create_test_export_data();
 
-            db.with_connection(|conn| import_export_data(conn, &export_data, true))
+            let result = db
+                .with_connection(|conn| import_export_data(conn, &export_data, false, ImportStrategy::SkipExisting, false))
+                .unwrap();
+
+            assert_eq!(result.tasks_imported, 1);
+            assert_eq!(result.entries_imported, 1);
+            assert_eq!(result.artifacts_imported, 1);
+        }
+
+        #[test]
+        fn 内容で重複排除するとIDが異なる同じ記録はスキップされる() {
+            let db = create_test_db();
+            let mut export_data = create_test_export_data();
+            let started_at = export_data.time_entries[0].started_at;
+            export_data.time_entries[0].ended_at = Some(started_at + chrono::Duration::hours(1));
+            db.with_connection(|conn| {
+                import_export_data(conn, &export_data, false, ImportStrategy::SkipExisting, false)
+            })
+            .unwrap();
+
+            // 同じ内容のエントリを別IDで作り直し、成果物を1件追加で紐付けたデータ
+            let original_id = export_data.time_entries[0].id;
+            let mut recreated = export_data.clone();
+            let recreated_id = Uuid::new_v4();
+            recreated.time_entries[0].id = recreated_id;
+            recreated.entry_artifacts[0].entry_id = recreated_id;
+            let added = Artifact::new("追加成果物".to_string(), "document".to_string(), None, None);
+            recreated.entry_artifacts.push(EntryArtifact {
+                entry_id: recreated_id,
+                artifact_id: added.id,
+            });
+            recreated.artifacts.push(added.clone());
+
+            let result = db
+                .with_connection(|conn| {
+                    import_export_data(conn, &recreated, true, ImportStrategy::SkipExisting, true)
+                })
+                .unwrap();
+            assert_eq!(result.entries_imported, 0);
+            // タスク・成果物・エントリの3件
+            assert_eq!(result.skipped, 3);
+
+            // スキップした記録の紐付けは既存の記録に付け替えられ、既にある紐付けは重複しない
+            let links = db
+                .with_connection(|conn| {
+                    let mut stmt = conn.prepare(
+                        "SELECT entry_id, artifact_id FROM entry_artifacts ORDER BY artifact_id",
+                    )?;
+                    let rows = stmt.query_map([], |row| {
+                        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+                    })?;
+                    Ok(rows.collect::<Result<Vec<_>, _>>()?)
+                })
+                .unwrap();
+            let mut expected = vec![
+                (original_id.to_string(), export_data.artifacts[0].id.to_string()),
+                (original_id.to_string(), added.id.to_string()),
+            ];
+            expected.sort_by(|a, b| a.1.cmp(&b.1));
+            assert_eq!(links, expected);
+
+            let result = db
+                .with_connection(|conn| {
+                    import_export_data(conn, &recreated, true, ImportStrategy::SkipExisting, false)
+                })
+                .unwrap();
+            assert_eq!(result.entries_imported, 1);
+        }
+
+        #[test]
+        fn 内容で重複排除した記録の時刻付きメモは既存の記録に付け替えられる() {
+            let db = create_test_db();
+            let mut export_data = create_test_export_data();
+            let started_at = export_data.time_entries[0].started_at;
+            export_data.time_entries[0].ended_at = Some(started_at + chrono::Duration::hours(1));
+            db.with_connection(|conn| {
+                import_export_data(conn, &export_data, false, ImportStrategy::SkipExisting, false)
+            })
+            .unwrap();
+
+            // 同じ内容のエントリを別IDで作り直し、メモを1件追加したデータ
+            let original_id = export_data.time_entries[0].id;
+            let mut recreated = export_data.clone();
+            let recreated_id = Uuid::new_v4();
+            recreated.time_entries[0].id = recreated_id;
+            recreated.entry_artifacts[0].entry_id = recreated_id;
+            recreated.entry_notes[0].entry_id = recreated_id;
+            recreated.entry_notes.push(EntryNote {
+                id: Uuid::new_v4(),
+                entry_id: recreated_id,
+                text: "追加のメモ".to_string(),
+                created_at: started_at,
+            });
+
+            db.with_connection(|conn| {
+                import_export_data(conn, &recreated, true, ImportStrategy::SkipExisting, true)
+            })
+            .unwrap();
+
+            let notes = db
+                .with_connection(|conn| {
+                    let mut stmt =
+                        conn.prepare("SELECT entry_id, text FROM entry_notes ORDER BY text")?;
+                    let rows = stmt.query_map([], |row| {
+                        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+                    })?;
+                    Ok(rows.collect::<Result<Vec<_>, _>>()?)
+                })
+                .unwrap();
+            let mut expected = vec![
+                (original_id.to_string(), "途中経過".to_string()),
+                (original_id.to_string(), "追加のメモ".to_string()),
+            ];
+            expected.sort_by(|a, b| a.1.cmp(&b.1));
+            assert_eq!(notes, expected);
+        }
+
+        #[test]
+        fn 取り込みの途中で失敗すると既存のデータは残る() {
+            let db = create_test_db();
+            let mut export_data = create_test_export_data();
+            export_data.entry_notes[0].text = "失敗".to_string();
+
+            db.with_connection(|conn| {
+                // 特定のメモの挿入だけが失敗するようにentry_notesを作り直す
+                conn.execute_batch(
+                    "DROP TABLE entry_notes;
+                     CREATE TABLE entry_notes (
+                         id VARCHAR PRIMARY KEY,
+                         entry_id VARCHAR NOT NULL,
+                         text TEXT NOT NULL CHECK (text <> '失敗'),
+                         created_at TIMESTAMPTZ NOT NULL
+                     );
+                     INSERT INTO tasks (id, name, color, created_at, updated_at)
+                     VALUES (uuid(), '既存タスク', '#000000', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP);",
+                )?;
+
+                let result = import_export_data(conn, &export_data, false, ImportStrategy::SkipExisting, false);
+                assert!(matches!(result, Err(AppError::Database(_))));
+
+                let tasks: Vec<String> = fetch_all_tasks(conn)?.into_iter().map(|t| t.name).collect();
+                assert_eq!(tasks, vec!["既存タスク".to_string()]);
+                let entries: i64 =
+                    conn.query_row("SELECT COUNT(*) FROM time_entries", [], |row| row.get(0))?;
+                assert_eq!(entries, 0);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 同じIDが重複したデータはデータベースを変更せずにエラーになる() {
+            let db = create_test_db();
+            let mut export_data = create_test_export_data();
+            export_data.tasks.push(export_data.tasks[0].clone());
+            let link = export_data.entry_artifacts[0].clone();
+            export_data.entry_artifacts.push(link);
+            let duplicated_id = export_data.tasks[0].id;
+
+            db.with_connection(|conn| {
+                conn.execute(
+                    "INSERT INTO tasks (id, name, color, created_at, updated_at)
+                     VALUES (uuid(), '既存タスク', '#000000', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                    [],
+                )?;
+
+                let result = import_export_data(conn, &export_data, false, ImportStrategy::SkipExisting, false);
+                match result {
+                    Err(AppError::InvalidInput(message)) => {
+                        assert!(message.contains(&duplicated_id.to_string()));
+                        assert!(message.contains("entry_artifacts"));
+                        assert!(!message.contains("time_entries"));
+                    }
+                    other => panic!("Expected InvalidInput, got {:?}", other),
+                }
+
+                let count: i64 = conn.query_row("SELECT COUNT(*) FROM tasks", [], |row| row.get(0))?;
+                assert_eq!(count, 1);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn マージモードでは既存データが保持される() {
+            let db = create_test_db();
+
+            // 既存データを作成
+            db.with_connection(|conn| {
+                conn.execute(
+                    "INSERT INTO tasks (id, name, color, created_at, updated_at)
+                     VALUES (uuid(), '既存タスク', '#000000', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                    [],
+                )?;
+                Ok(())
+            })
+            .unwrap();
+
+            let export_data = create_test_export_data();
+
+            db.with_connection(|conn| import_export_data(conn, &export_data, true, ImportStrategy::SkipExisting, false))
                 .unwrap();
 
             let tasks = db.with_connection(fetch_all_tasks).unwrap();
@@ -599,7 +2189,7 @@ mod tests {
 
             let export_data = create_test_export_data();
 
-            db.with_connection(|conn| import_export_data(conn, &export_data, false))
+            db.with_connection(|conn| import_export_data(conn, &export_data, false, ImportStrategy::SkipExisting, false))
                 .unwrap();
 
             let tasks = db.with_connection(fetch_all_tasks).unwrap();
@@ -614,17 +2204,74 @@ mod tests {
 
             // 1回目のインポート
             let result1 = db
-                .with_connection(|conn| import_export_data(conn, &export_data, false))
+                .with_connection(|conn| import_export_data(conn, &export_data, false, ImportStrategy::SkipExisting, false))
                 .unwrap();
 
             // 2回目のインポート（マージ）
             let result2 = db
-                .with_connection(|conn| import_export_data(conn, &export_data, true))
+                .with_connection(|conn| import_export_data(conn, &export_data, true, ImportStrategy::SkipExisting, false))
                 .unwrap();
 
             // 2回目は全てスキップされる
             assert_eq!(result1.tasks_imported, 1);
             assert_eq!(result2.tasks_imported, 0);
+            assert_eq!(result2.skipped, 3);
+        }
+
+        #[test]
+        fn 上書き戦略では既存データが更新される() {
+            let db = create_test_db();
+            let mut export_data = create_test_export_data();
+
+            db.with_connection(|conn| {
+                import_export_data(conn, &export_data, false, ImportStrategy::SkipExisting, false)
+            })
+            .unwrap();
+
+            export_data.tasks[0].name = "編集後".to_string();
+            let result = db
+                .with_connection(|conn| {
+                    import_export_data(conn, &export_data, true, ImportStrategy::Overwrite, false)
+                })
+                .unwrap();
+
+            assert_eq!(result.tasks_updated, 1);
+            assert_eq!(result.entries_updated, 1);
+            assert_eq!(result.artifacts_updated, 1);
+            assert_eq!(result.tasks_imported, 0);
+            let tasks = db.with_connection(fetch_all_tasks).unwrap();
+            assert_eq!(tasks.len(), 1);
+            assert_eq!(tasks[0].name, "編集後");
+        }
+
+        #[test]
+        fn 新しい方優先の戦略では更新日時が新しいデータだけが反映される() {
+            let db = create_test_db();
+            let mut export_data = create_test_export_data();
+
+            db.with_connection(|conn| {
+                import_export_data(conn, &export_data, false, ImportStrategy::SkipExisting, false)
+            })
+            .unwrap();
+
+            // タスクは新しく、時間記録は古い
+            export_data.tasks[0].name = "新しい".to_string();
+            export_data.tasks[0].updated_at += chrono::Duration::hours(1);
+            export_data.time_entries[0].memo = Some("古い".to_string());
+            export_data.time_entries[0].updated_at -= chrono::Duration::hours(1);
+
+            let result = db
+                .with_connection(|conn| {
+                    import_export_data(conn, &export_data, true, ImportStrategy::NewerWins, false)
+                })
+                .unwrap();
+
+            assert_eq!(result.tasks_updated, 1);
+            assert_eq!(result.entries_updated, 0);
+            let tasks = db.with_connection(fetch_all_tasks).unwrap();
+            let entries = db.with_connection(fetch_all_entries).unwrap();
+            assert_eq!(tasks[0].name, "新しい");
+            assert_eq!(entries[0].memo, Some("インポートメモ".to_string()));
         }
 
         #[test]
@@ -632,7 +2279,7 @@ mod tests {
             let db = create_test_db();
             let export_data = create_test_export_data();
 
-            db.with_connection(|conn| import_export_data(conn, &export_data, false))
+            db.with_connection(|conn| import_export_data(conn, &export_data, false, ImportStrategy::SkipExisting, false))
                 .unwrap();
 
             let tasks = db.with_connection(fetch_all_tasks).unwrap();
@@ -677,5 +2324,721 @@ mod tests {
             assert_eq!(files.len(), 1);
             assert!(std::path::Path::new(&files[0]).exists());
         }
+
+        #[test]
+        fn テーブルごとに進捗が通知される() {
+            let db = create_test_db();
+            let temp_dir = tempfile::tempdir().unwrap();
+            let mut progress = Vec::new();
+
+            let files = db
+                .with_connection(|conn| {
+                    write_parquet_files(conn, temp_dir.path(), None, &mut |p| progress.push(p))
+                })
+                .unwrap();
+
+            assert_eq!(files.len(), 5);
+            assert!(files.iter().all(|f| std::path::Path::new(f).exists()));
+            assert_eq!(progress.len(), 5);
+            assert_eq!(progress[0], export_progress(1));
+            assert_eq!(progress[3].table, "entry_artifacts");
+            assert_eq!(progress[4].table, "entry_notes");
+            assert!(progress.iter().all(|p| p.total == 5));
+        }
+
+        #[test]
+        fn 行数を指定するとtime_entriesが連番のファイルに分割される() {
+            let db = create_test_db();
+            let temp_dir = tempfile::tempdir().unwrap();
+
+            let files = db
+                .with_connection(|conn| {
+                    conn.execute_batch(
+                        "INSERT INTO time_entries (id, started_at, ended_at, created_at, updated_at) VALUES
+                         ('00000000-0000-0000-0000-000000000001', '2024-12-01 09:00:00+00', '2024-12-01 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('00000000-0000-0000-0000-000000000002', '2024-12-02 09:00:00+00', '2024-12-02 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('00000000-0000-0000-0000-000000000003', '2024-12-03 09:00:00+00', '2024-12-03 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('00000000-0000-0000-0000-000000000004', '2024-12-04 09:00:00+00', '2024-12-04 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('00000000-0000-0000-0000-000000000005', '2024-12-05 09:00:00+00', '2024-12-05 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP);",
+                    )?;
+                    write_parquet_files(conn, temp_dir.path(), Some(2), &mut |_| {})
+                })
+                .unwrap();
+
+            let parts: Vec<&String> = files.iter().filter(|f| f.contains("time_entries_")).collect();
+            assert_eq!(parts.len(), 3);
+            assert!(parts[0].ends_with("time_entries_0001.parquet"));
+            assert!(parts[2].ends_with("time_entries_0003.parquet"));
+            assert!(!temp_dir.path().join("time_entries.parquet").exists());
+
+            let rows: Vec<i64> = db
+                .with_connection(|conn| {
+                    parts
+                        .iter()
+                        .map(|part| {
+                            conn.query_row(
+                                &format!("SELECT COUNT(*) FROM read_parquet('{}')", part),
+                                [],
+                                |row| row.get(0),
+                            )
+                            .map_err(AppError::from)
+                        })
+                        .collect()
+                })
+                .unwrap();
+            assert_eq!(rows, vec![2, 2, 1]);
+
+            let target = create_test_db();
+            let result = target
+                .with_connection(|conn| {
+                    import_parquet_dir(conn, temp_dir.path(), false, ImportStrategy::default())
+                })
+                .unwrap();
+            assert_eq!(result.entries_imported, 5);
+        }
+
+        #[test]
+        fn 分割行数に0は指定できない() {
+            let db = create_test_db();
+            let temp_dir = tempfile::tempdir().unwrap();
+
+            let result =
+                db.with_connection(|conn| write_parquet_files(conn, temp_dir.path(), Some(0), &mut |_| {}));
+            assert!(matches!(result, Err(AppError::InvalidInput(_))));
+        }
+    }
+
+    mod import_parquet_tests {
+        use super::*;
+
+        fn seed(conn: &Connection) -> AppResult<(Uuid, Uuid)> {
+            let task = Task::new("レビュー".to_string(), None, None, None);
+            let entry_id = Uuid::new_v4();
+            let artifact_id = Uuid::new_v4();
+            conn.execute(
+                "INSERT INTO tasks (id, name, color, created_at, updated_at) VALUES (?, ?, ?, ?, ?)",
+                duckdb::params![task.id.to_string(), &task.name, &task.color, task.created_at, task.updated_at],
+            )?;
+            conn.execute(
+                "INSERT INTO time_entries (id, task_id, started_at, ended_at, memo, created_at, updated_at)
+                 VALUES (?, ?, '2024-01-15 09:00:00+00', '2024-01-15 10:00:00+00', 'メモ', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                [entry_id.to_string(), task.id.to_string()],
+            )?;
+            conn.execute(
+                "INSERT INTO artifacts (id, name, artifact_type, metadata, created_at)
+                 VALUES (?, '成果物', 'document', '{\"pr\": 1}', CURRENT_TIMESTAMP)",
+                [artifact_id.to_string()],
+            )?;
+            conn.execute(
+                "INSERT INTO entry_artifacts (entry_id, artifact_id) VALUES (?, ?)",
+                [entry_id.to_string(), artifact_id.to_string()],
+            )?;
+            Ok((entry_id, artifact_id))
+        }
+
+        #[test]
+        fn エクスポートしたparquetファイルを別のデータベースに取り込める() {
+            let source = create_test_db();
+            let temp_dir = tempfile::tempdir().unwrap();
+
+            let (entry_id, artifact_id) = source
+                .with_connection(|conn| {
+                    let ids = seed(conn)?;
+                    write_parquet_files(conn, temp_dir.path(), None, &mut |_| {})?;
+                    Ok(ids)
+                })
+                .unwrap();
+
+            let target = create_test_db();
+            let result = target
+                .with_connection(|conn| {
+                    import_parquet_dir(conn, temp_dir.path(), false, ImportStrategy::default())
+                })
+                .unwrap();
+
+            assert_eq!(result.tasks_imported, 1);
+            assert_eq!(result.entries_imported, 1);
+            assert_eq!(result.artifacts_imported, 1);
+
+            let export = target.with_connection(create_export_data).unwrap();
+            assert_eq!(export.time_entries[0].id, entry_id);
+            assert_eq!(export.time_entries[0].duration_seconds, Some(3600));
+            assert_eq!(export.time_entries[0].memo.as_deref(), Some("メモ"));
+            assert_eq!(export.artifacts[0].id, artifact_id);
+            assert_eq!(export.artifacts[0].metadata, Some(serde_json::json!({"pr": 1})));
+        }
+
+        #[test]
+        fn マージせずにparquetファイルを取り込むと時刻付きメモも置き換えられる() {
+            let source = create_test_db();
+            let temp_dir = tempfile::tempdir().unwrap();
+            let note_id = Uuid::new_v4();
+
+            source
+                .with_connection(|conn| {
+                    let (entry_id, _) = seed(conn)?;
+                    conn.execute(
+                        "INSERT INTO entry_notes (id, entry_id, text, created_at) VALUES (?, ?, '途中経過', CURRENT_TIMESTAMP)",
+                        [note_id.to_string(), entry_id.to_string()],
+                    )?;
+                    write_parquet_files(conn, temp_dir.path(), None, &mut |_| {})?;
+                    Ok(())
+                })
+                .unwrap();
+
+            let target = create_test_db();
+            let notes = target
+                .with_connection(|conn| {
+                    let (existing_entry, _) = seed(conn)?;
+                    conn.execute(
+                        "INSERT INTO entry_notes (id, entry_id, text, created_at) VALUES (?, ?, '既存のメモ', CURRENT_TIMESTAMP)",
+                        [Uuid::new_v4().to_string(), existing_entry.to_string()],
+                    )?;
+                    import_parquet_dir(conn, temp_dir.path(), false, ImportStrategy::default())?;
+                    fetch_entry_notes_from(conn, "entry_notes")
+                })
+                .unwrap();
+
+            assert_eq!(notes.len(), 1);
+            assert_eq!(notes[0].id, note_id);
+            assert_eq!(notes[0].text, "途中経過");
+        }
+
+        #[test]
+        fn 時刻付きメモのファイルがない古いエクスポートも取り込める() {
+            let source = create_test_db();
+            let temp_dir = tempfile::tempdir().unwrap();
+
+            source
+                .with_connection(|conn| {
+                    seed(conn)?;
+                    write_parquet_files(conn, temp_dir.path(), None, &mut |_| {})
+                })
+                .unwrap();
+            std::fs::remove_file(temp_dir.path().join("entry_notes.parquet")).unwrap();
+
+            let target = create_test_db();
+            let result = target
+                .with_connection(|conn| {
+                    import_parquet_dir(conn, temp_dir.path(), false, ImportStrategy::default())
+                })
+                .unwrap();
+            assert_eq!(result.entries_imported, 1);
+        }
+
+        #[test]
+        fn ファイルが足りない場合はデータベースを変更せずにエラーになる() {
+            let db = create_test_db();
+            let temp_dir = tempfile::tempdir().unwrap();
+
+            db.with_connection(|conn| {
+                seed(conn)?;
+                write_parquet_files(conn, temp_dir.path(), None, &mut |_| {})?;
+                Ok(())
+            })
+            .unwrap();
+            std::fs::remove_file(temp_dir.path().join("entry_artifacts.parquet")).unwrap();
+
+            db.with_connection(|conn| {
+                let result = import_parquet_dir(conn, temp_dir.path(), false, ImportStrategy::default());
+                assert!(matches!(result, Err(AppError::InvalidInput(_))));
+
+                let count: i64 =
+                    conn.query_row("SELECT COUNT(*) FROM time_entries", [], |row| row.get(0))?;
+                assert_eq!(count, 1);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 必要なカラムがない場合はエラーになる() {
+            let db = create_test_db();
+            let temp_dir = tempfile::tempdir().unwrap();
+
+            db.with_connection(|conn| {
+                write_parquet_files(conn, temp_dir.path(), None, &mut |_| {})?;
+                let tasks_path = temp_dir.path().join("tasks.parquet");
+                conn.execute(
+                    &format!(
+                        "COPY (SELECT id, name FROM tasks) TO '{}' (FORMAT PARQUET)",
+                        tasks_path.to_string_lossy()
+                    ),
+                    [],
+                )?;
+
+                let result = import_parquet_dir(conn, temp_dir.path(), true, ImportStrategy::default());
+                assert!(matches!(result, Err(AppError::InvalidInput(_))));
+                Ok(())
+            })
+            .unwrap();
+        }
+    }
+
+    mod export_bundle_tests {
+        use super::*;
+
+        #[test]
+        fn マニフェストとJSONとParquetファイルが1つのzipにまとめられる() {
+            let db = create_test_db();
+            let temp_dir = tempfile::tempdir().unwrap();
+            let bundle_path = temp_dir.path().join("export.zip");
+
+            let manifest = db
+                .with_connection(|conn| {
+                    conn.execute(
+                        "INSERT INTO tasks (id, name, color, created_at, updated_at)
+                         VALUES (uuid(), 'テストタスク', '#000000', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                        [],
+                    )?;
+                    write_export_bundle(conn, &bundle_path)
+                })
+                .unwrap();
+            assert_eq!(manifest.tasks, 1);
+            assert_eq!(manifest.time_entries, 0);
+
+            let mut archive = zip::ZipArchive::new(std::fs::File::open(&bundle_path).unwrap()).unwrap();
+            let mut names: Vec<String> = archive.file_names().map(|n| n.to_string()).collect();
+            names.sort();
+            assert_eq!(
+                names,
+                vec![
+                    "artifacts.parquet",
+                    "data.json",
+                    "entry_artifacts.parquet",
+                    "entry_notes.parquet",
+                    "manifest.json",
+                    "tasks.parquet",
+                    "time_entries.parquet",
+                ]
+            );
+
+            let data: ExportData =
+                serde_json::from_reader(archive.by_name(BUNDLE_DATA_FILE).unwrap()).unwrap();
+            assert_eq!(data.tasks[0].name, "テストタスク");
+            let stored: ExportBundleManifest =
+                serde_json::from_reader(archive.by_name(BUNDLE_MANIFEST_FILE).unwrap()).unwrap();
+            assert_eq!(stored.tasks, 1);
+        }
+    }
+
+    mod export_data_to_file_tests {
+        use super::*;
+
+        fn export_to(db: &Database, path: &std::path::Path, pretty: bool) -> u64 {
+            db.with_connection(|conn| {
+                conn.execute(
+                    "INSERT INTO tasks (id, name, color, created_at, updated_at)
+                     VALUES (uuid(), 'テストタスク', '#000000', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                    [],
+                )?;
+                let data = create_export_data(conn)?;
+                write_export_json(&data, path, pretty)
+            })
+            .unwrap()
+        }
+
+        #[test]
+        fn 整形ありでは改行付きのJSONを書き出し書き込んだバイト数を返す() {
+            let db = create_test_db();
+            let temp_dir = tempfile::tempdir().unwrap();
+            let path = temp_dir.path().join("export.json");
+
+            let written = export_to(&db, &path, true);
+
+            let content = std::fs::read_to_string(&path).unwrap();
+            assert_eq!(written, content.len() as u64);
+            assert!(content.contains('\n'));
+            let data: ExportData = serde_json::from_str(&content).unwrap();
+            assert_eq!(data.tasks[0].name, "テストタスク");
+        }
+
+        #[test]
+        fn 整形なしでは1行のJSONを書き出す() {
+            let db = create_test_db();
+            let temp_dir = tempfile::tempdir().unwrap();
+            let path = temp_dir.path().join("export.json");
+
+            let written = export_to(&db, &path, false);
+
+            let content = std::fs::read_to_string(&path).unwrap();
+            assert_eq!(written, content.len() as u64);
+            assert!(!content.contains('\n'));
+            let data: ExportData = serde_json::from_str(&content).unwrap();
+            assert_eq!(data.tasks.len(), 1);
+        }
+    }
+
+    mod export_task_entries_csv_tests {
+        use super::*;
+
+        fn insert_task_with_entry(conn: &Connection, task_name: &str, memo: &str) -> AppResult<Uuid> {
+            let task = Task::new(task_name.to_string(), None, None, None);
+            conn.execute(
+                "INSERT INTO tasks (id, name, color, archived, created_at, updated_at)
+                 VALUES (?, ?, ?, ?, ?, ?)",
+                duckdb::params![
+                    task.id.to_string(),
+                    &task.name,
+                    &task.color,
+                    task.archived,
+                    task.created_at,
+                    task.updated_at,
+                ],
+            )?;
+
+            let mut entry = TimeEntry::start(Some(task.id), Some(memo.to_string()));
+            entry.ended_at = Some(entry.started_at + chrono::Duration::seconds(1800));
+            conn.execute(
+                "INSERT INTO time_entries (id, task_id, started_at, ended_at, memo, created_at, updated_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?)",
+                duckdb::params![
+                    entry.id.to_string(),
+                    task.id.to_string(),
+                    entry.started_at,
+                    entry.ended_at,
+                    &entry.memo,
+                    entry.created_at,
+                    entry.updated_at,
+                ],
+            )?;
+
+            Ok(task.id)
+        }
+
+        #[test]
+        fn 指定したタスクの時間記録のみがCSVに出力される() {
+            let db = create_test_db();
+            let temp_dir = tempfile::tempdir().unwrap();
+            let output_path = temp_dir.path().join("entries.csv");
+
+            let count = db
+                .with_connection(|conn| {
+                    let task_id = insert_task_with_entry(conn, "対象タスク", "対象のメモ")?;
+                    insert_task_with_entry(conn, "別タスク", "別のメモ")?;
+                    write_task_entries_csv(conn, &task_id, None, None, &output_path)
+                })
+                .unwrap();
+
+            let content = std::fs::read_to_string(&output_path).unwrap();
+            assert_eq!(count, 1);
+            assert!(content.starts_with("started_at,ended_at,duration_seconds,memo,artifacts"));
+            assert!(content.contains("対象のメモ"));
+            assert!(content.contains(",1800,"));
+            assert!(!content.contains("別のメモ"));
+        }
+
+        #[test]
+        fn 存在しないタスクを指定するとエラーになる() {
+            let db = create_test_db();
+            let temp_dir = tempfile::tempdir().unwrap();
+            let output_path = temp_dir.path().join("entries.csv");
+
+            let result = db.with_connection(|conn| {
+                write_task_entries_csv(conn, &Uuid::new_v4(), None, None, &output_path)
+            });
+
+            assert!(matches!(result, Err(AppError::NotFound(_))));
+        }
+
+        #[test]
+        fn カンマや引用符を含むフィールドはエスケープされる() {
+            assert_eq!(escape_csv_field("plain"), "plain");
+            assert_eq!(escape_csv_field("a,b"), "\"a,b\"");
+            assert_eq!(escape_csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+            assert_eq!(escape_csv_field("line1\nline2"), "\"line1\nline2\"");
+        }
+    }
+
+    mod export_ics_tests {
+        use super::*;
+
+        #[test]
+        fn テキストのカンマ・セミコロン・改行がエスケープされる() {
+            assert_eq!(
+                escape_ics_text("a,b;c\\d\r\ne\nf"),
+                "a\\,b\\;c\\\\d\\ne\\nf"
+            );
+        }
+
+        #[test]
+        fn 長い行は75オクテットで折り返される() {
+            let line = format!("DESCRIPTION:{}", "あ".repeat(40));
+            let folded = fold_ics_line(&line);
+
+            assert!(folded.split("\r\n").all(|l| l.len() <= 75));
+            assert_eq!(folded.replace("\r\n ", ""), line);
+        }
+
+        #[test]
+        fn 完了済みのエントリのみVEVENTとして出力される() {
+            let db = create_test_db();
+            let temp_dir = tempfile::tempdir().unwrap();
+            let output_path = temp_dir.path().join("entries.ics");
+            let task_id = Uuid::new_v4();
+            let done_id = Uuid::new_v4();
+
+            db.with_connection(|conn| {
+                conn.execute(
+                    "INSERT INTO tasks (id, name, color, created_at, updated_at)
+                     VALUES (?, 'レビュー', '#ff0000', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                    [task_id.to_string()],
+                )?;
+                conn.execute(
+                    "INSERT INTO time_entries (id, task_id, started_at, ended_at, memo, created_at, updated_at) VALUES
+                     (?, ?, '2024-01-15 09:00:00+00', '2024-01-15 09:30:00+00', 'PR, 修正', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     (?, NULL, '2024-01-15 10:00:00+00', NULL, NULL, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                    [done_id.to_string(), task_id.to_string(), Uuid::new_v4().to_string()],
+                )?;
+
+                let count = write_entries_ics(conn, None, None, &output_path)?;
+                assert_eq!(count, 1);
+                Ok(())
+            })
+            .unwrap();
+
+            let ics = std::fs::read_to_string(&output_path).unwrap();
+            assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+            assert_eq!(ics.matches("BEGIN:VEVENT").count(), 1);
+            assert!(ics.contains(&format!("UID:{}@local-time-tracker\r\n", done_id)));
+            assert!(ics.contains("DTSTART:20240115T090000Z\r\n"));
+            assert!(ics.contains("DTEND:20240115T093000Z\r\n"));
+            assert!(ics.contains("SUMMARY:レビュー\r\n"));
+            assert!(ics.contains("DESCRIPTION:PR\\, 修正\r\n"));
+        }
+    }
+
+    mod import_entries_tests {
+        use super::*;
+
+        fn export_entry(task_id: Option<Uuid>, hours_ago: i64) -> ExportTimeEntry {
+            let started_at = Utc::now() - chrono::Duration::hours(hours_ago);
+            ExportTimeEntry {
+                id: Uuid::new_v4(),
+                task_id,
+                started_at,
+                ended_at: Some(started_at + chrono::Duration::minutes(30)),
+                duration_seconds: None,
+                memo: Some("貼り付け".to_string()),
+                created_at: started_at,
+                updated_at: started_at,
+            }
+        }
+
+        #[test]
+        fn 存在しないタスクを参照する記録はタスクを外して取り込み警告を返す() {
+            let db = create_test_db();
+            let task = Task::new("既存タスク".to_string(), None, None, None);
+
+            let result = db
+                .with_connection(|conn| {
+                    conn.execute(
+                        "INSERT INTO tasks (id, name, color, archived, created_at, updated_at)
+                         VALUES (?, ?, ?, ?, ?, ?)",
+                        duckdb::params![
+                            task.id.to_string(),
+                            &task.name,
+                            &task.color,
+                            task.archived,
+                            task.created_at,
+                            task.updated_at,
+                        ],
+                    )?;
+                    let entries = vec![
+                        export_entry(Some(task.id), 3),
+                        export_entry(Some(Uuid::new_v4()), 2),
+                    ];
+                    import_entry_fragment(conn, &entries, true)
+                })
+                .unwrap();
+
+            assert_eq!(result.entries_imported, 2);
+            assert_eq!(result.tasks_imported, 0);
+            assert_eq!(result.warnings.len(), 1);
+
+            let untasked: i64 = db
+                .with_connection(|conn| {
+                    Ok(conn.query_row(
+                        "SELECT COUNT(*) FROM time_entries WHERE task_id IS NULL",
+                        [],
+                        |row| row.get(0),
+                    )?)
+                })
+                .unwrap();
+            assert_eq!(untasked, 1);
+        }
+
+        #[test]
+        fn マージ時は同じIDの記録をスキップする() {
+            let db = create_test_db();
+            let entries = vec![export_entry(None, 3)];
+
+            db.with_connection(|conn| import_entry_fragment(conn, &entries, true))
+                .unwrap();
+            let result = db
+                .with_connection(|conn| import_entry_fragment(conn, &entries, true))
+                .unwrap();
+
+            assert_eq!(result.entries_imported, 0);
+            assert_eq!(result.skipped, 1);
+        }
+    }
+
+    mod import_csv_tests {
+        use super::*;
+
+        fn column_map() -> CsvColumnMap {
+            CsvColumnMap {
+                started_at: "Start".to_string(),
+                ended_at: "End".to_string(),
+                task_name: Some("Project".to_string()),
+                memo: Some("Note".to_string()),
+            }
+        }
+
+        #[test]
+        fn 引用符や改行を含むCSVをパースできる() {
+            let rows = parse_csv("a,b,c\r\n\"x,y\",\"he said \"\"hi\"\"\",\"line1\nline2\"\r\n");
+
+            assert_eq!(rows.len(), 2);
+            assert_eq!(rows[1], vec!["x,y", "he said \"hi\"", "line1\nline2"]);
+        }
+
+        #[test]
+        fn CSVの時間記録をインポートしタスクを名前で自動作成する() {
+            let db = create_test_db();
+            let csv = "Start,End,Project,Note\n\
+                       2024-12-15T09:00:00Z,2024-12-15T10:00:00Z,設計,午前\n\
+                       2024-12-15 13:00:00,2024-12-15 14:30:00,設計,午後\n\
+                       2024-12-16T09:00:00+09:00,2024-12-16T10:00:00+09:00,,\n";
+
+            let result = db
+                .with_connection(|conn| import_csv_content(conn, csv, &column_map(), true))
+                .unwrap();
+
+            assert_eq!(result.tasks_imported, 1);
+            assert_eq!(result.entries_imported, 3);
+            assert!(result.warnings.is_empty());
+
+            let tasks = db.with_connection(fetch_all_tasks).unwrap();
+            let entries = db.with_connection(fetch_all_entries).unwrap();
+            assert_eq!(tasks.len(), 1);
+            assert_eq!(tasks[0].name, "設計");
+            assert_eq!(entries[1].duration_seconds, Some(5400));
+            assert_eq!(entries[1].task_id, Some(tasks[0].id));
+            assert!(entries[2].task_id.is_none());
+        }
+
+        #[test]
+        fn 既存のタスク名と一致する場合は既存タスクに紐付ける() {
+            let db = create_test_db();
+            let existing = Task::new("既存".to_string(), None, None, None);
+            db.with_connection(|conn| {
+                conn.execute(
+                    "INSERT INTO tasks (id, name, color, created_at, updated_at) VALUES (?, ?, ?, ?, ?)",
+                    duckdb::params![
+                        existing.id.to_string(),
+                        &existing.name,
+                        &existing.color,
+                        existing.created_at,
+                        existing.updated_at,
+                    ],
+                )?;
+                Ok(())
+            })
+            .unwrap();
+
+            let csv = "Start,End,Project,Note\n2024-12-15T09:00:00Z,2024-12-15T10:00:00Z,既存,メモ\n";
+            let result = db
+                .with_connection(|conn| import_csv_content(conn, csv, &column_map(), true))
+                .unwrap();
+
+            let entries = db.with_connection(fetch_all_entries).unwrap();
+            assert_eq!(result.tasks_imported, 0);
+            assert_eq!(entries[0].task_id, Some(existing.id));
+        }
+
+        #[test]
+        fn 日時をパースできない行は警告として返され他の行はインポートされる() {
+            let db = create_test_db();
+            let csv = "Start,End,Project,Note\n\
+                       not-a-date,2024-12-15T10:00:00Z,設計,壊れた行\n\
+                       2024-12-15T11:00:00Z,2024-12-15T12:00:00Z,設計,正常な行\n";
+
+            let result = db
+                .with_connection(|conn| import_csv_content(conn, csv, &column_map(), true))
+                .unwrap();
+
+            assert_eq!(result.entries_imported, 1);
+            assert_eq!(result.warnings.len(), 1);
+            assert!(result.warnings[0].contains("Line 2"));
+        }
+
+        #[test]
+        fn 取り込みの途中で失敗すると既存のデータは残る() {
+            let db = create_test_db();
+            let existing = Task::new("既存".to_string(), None, None, None);
+            db.with_connection(|conn| {
+                // 特定のメモの挿入だけが失敗するようにtime_entriesを作り直す
+                conn.execute_batch(
+                    "DROP INDEX idx_time_entries_single_running;
+                     DROP TABLE time_entries;
+                     CREATE TABLE time_entries (
+                         id VARCHAR PRIMARY KEY,
+                         task_id VARCHAR,
+                         started_at TIMESTAMPTZ NOT NULL,
+                         ended_at TIMESTAMPTZ,
+                         duration_seconds BIGINT,
+                         memo TEXT CHECK (memo IS DISTINCT FROM '失敗'),
+                         created_at TIMESTAMPTZ NOT NULL,
+                         updated_at TIMESTAMPTZ NOT NULL
+                     );
+                     INSERT INTO time_entries (id, started_at, ended_at, created_at, updated_at)
+                     VALUES ('existing-entry', '2024-12-01 09:00:00+00', '2024-12-01 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP);",
+                )?;
+                conn.execute(
+                    "INSERT INTO tasks (id, name, color, created_at, updated_at) VALUES (?, ?, ?, ?, ?)",
+                    duckdb::params![
+                        existing.id.to_string(),
+                        &existing.name,
+                        &existing.color,
+                        existing.created_at,
+                        existing.updated_at,
+                    ],
+                )?;
+                Ok(())
+            })
+            .unwrap();
+
+            let csv = "Start,End,Project,Note\n\
+                       2024-12-15T09:00:00Z,2024-12-15T10:00:00Z,設計,成功\n\
+                       2024-12-15T11:00:00Z,2024-12-15T12:00:00Z,設計,失敗\n";
+            db.with_connection(|conn| {
+                let result = import_csv_content(conn, csv, &column_map(), false);
+                assert!(matches!(result, Err(AppError::Database(_))));
+
+                let tasks: Vec<String> = fetch_all_tasks(conn)?.into_iter().map(|t| t.name).collect();
+                assert_eq!(tasks, vec!["既存".to_string()]);
+                let entry_ids: Vec<String> = {
+                    let mut stmt = conn.prepare("SELECT id FROM time_entries")?;
+                    let rows = stmt.query_map([], |row| row.get(0))?;
+                    rows.collect::<Result<_, _>>()?
+                };
+                assert_eq!(entry_ids, vec!["existing-entry".to_string()]);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 指定した列が存在しない場合はエラーになる() {
+            let db = create_test_db();
+            let csv = "Begin,End\n2024-12-15T09:00:00Z,2024-12-15T10:00:00Z\n";
+
+            let result =
+                db.with_connection(|conn| import_csv_content(conn, csv, &column_map(), true));
+
+            assert!(matches!(result, Err(AppError::InvalidInput(_))));
+        }
     }
 }
@@ -3,9 +3,16 @@ use duckdb::Connection;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::commands::settings::{fetch_setting, DEFAULT_FOLDER_COLOR_KEY};
+use crate::commands::tasks::fetch_tasks;
+use crate::commands::{ensure_max_length, MAX_MEMO_LENGTH, MAX_NAME_LENGTH};
+use crate::db::Task;
 use crate::error::{AppError, AppResult};
 use crate::AppState;
 
+/// フォルダの色の初期値
+const DEFAULT_FOLDER_COLOR: &str = "#6b7280";
+
 /// フォルダ
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Folder {
@@ -13,6 +20,8 @@ pub struct Folder {
     pub name: String,
     pub color: String,
     pub icon: Option<String>,
+    /// フォルダについてのメモ（取引先の請求条件など）
+    pub description: Option<String>,
     pub sort_order: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -24,6 +33,8 @@ pub struct CreateFolder {
     pub name: String,
     pub color: Option<String>,
     pub icon: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
 }
 
 /// フォルダ更新リクエスト
@@ -32,13 +43,30 @@ pub struct UpdateFolder {
     pub name: Option<String>,
     pub color: Option<String>,
     pub icon: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
     pub sort_order: Option<i32>,
 }
 
+/// フォルダとその配下のタスク（folderがNoneの要素はフォルダ未所属のタスク）
+#[derive(Debug, Clone, Serialize)]
+pub struct FolderTreeNode {
+    pub folder: Option<Folder>,
+    pub tasks: Vec<Task>,
+}
+
+/// フォルダごとのタスク数と時間記録数（folder_idがNoneの要素はフォルダ未所属のタスク分）
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct FolderCounts {
+    pub folder_id: Option<Uuid>,
+    pub task_count: i64,
+    pub entry_count: i64,
+}
+
 /// フォルダ一覧を取得する
 fn fetch_folders(conn: &Connection) -> AppResult<Vec<Folder>> {
     let sql = r#"
-        SELECT id, name, color, icon, sort_order, created_at, updated_at
+        SELECT id, name, color, icon, sort_order, created_at, updated_at, description
         FROM folders
         ORDER BY sort_order ASC, created_at ASC
     "#;
@@ -51,6 +79,7 @@ fn fetch_folders(conn: &Connection) -> AppResult<Vec<Folder>> {
             name: row.get(1)?,
             color: row.get(2)?,
             icon: row.get(3)?,
+            description: row.get(7)?,
             sort_order: row.get(4)?,
             created_at: row.get(5)?,
             updated_at: row.get(6)?,
@@ -64,16 +93,46 @@ fn fetch_folders(conn: &Connection) -> AppResult<Vec<Folder>> {
     Ok(folders)
 }
 
+/// 同名のフォルダが存在しないか確認する（前後の空白を除き大文字小文字を区別しない）
+fn ensure_unique_folder_name(conn: &Connection, name: &str, exclude_id: Option<Uuid>) -> AppResult<()> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM folders WHERE LOWER(TRIM(name)) = LOWER(?) AND id != ?",
+        duckdb::params![
+            name.trim(),
+            exclude_id.map(|id| id.to_string()).unwrap_or_default(),
+        ],
+        |row| row.get(0),
+    )?;
+
+    if count > 0 {
+        return Err(AppError::AlreadyExists(format!(
+            "同じ名前のフォルダが既に存在します: {}",
+            name.trim()
+        )));
+    }
+    Ok(())
+}
+
 /// フォルダを作成する
 fn create_folder_impl(conn: &Connection, input: CreateFolder) -> AppResult<Folder> {
     if input.name.trim().is_empty() {
         return Err(AppError::InvalidInput("フォルダ名は必須です".to_string()));
     }
+    ensure_max_length(&input.name, "Folder name", MAX_NAME_LENGTH)?;
+    if let Some(description) = &input.description {
+        ensure_max_length(description, "Folder description", MAX_MEMO_LENGTH)?;
+    }
+    ensure_unique_folder_name(conn, &input.name, None)?;
 
     let id = Uuid::new_v4();
     let now = Utc::now();
-    let color = input.color.unwrap_or_else(|| "#6b7280".to_string());
+    let color = match input.color {
+        Some(color) => color,
+        None => fetch_setting(conn, DEFAULT_FOLDER_COLOR_KEY)?
+            .unwrap_or_else(|| DEFAULT_FOLDER_COLOR.to_string()),
+    };
     let icon = input.icon;
+    let description = input.description;
 
     // Get max sort_order
     let max_order: i32 = conn
@@ -81,8 +140,8 @@ fn create_folder_impl(conn: &Connection, input: CreateFolder) -> AppResult<Folde
         .unwrap_or(0);
 
     let sql = r#"
-        INSERT INTO folders (id, name, color, icon, sort_order, created_at, updated_at)
-        VALUES (?, ?, ?, ?, ?, ?, ?)
+        INSERT INTO folders (id, name, color, icon, description, sort_order, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
     "#;
 
     conn.execute(sql, duckdb::params![
@@ -90,6 +149,7 @@ fn create_folder_impl(conn: &Connection, input: CreateFolder) -> AppResult<Folde
         input.name.trim().to_string(),
         color.clone(),
         icon.clone(),
+        description.clone(),
         max_order + 1,
         now.to_rfc3339(),
         now.to_rfc3339(),
@@ -100,6 +160,7 @@ fn create_folder_impl(conn: &Connection, input: CreateFolder) -> AppResult<Folde
         name: input.name.trim().to_string(),
         color,
         icon,
+        description,
         sort_order: max_order + 1,
         created_at: now,
         updated_at: now,
@@ -128,6 +189,8 @@ fn update_folder_impl(conn: &Connection, id: Uuid, input: UpdateFolder) -> AppRe
         if name.trim().is_empty() {
             return Err(AppError::InvalidInput("フォルダ名は必須です".to_string()));
         }
+        ensure_max_length(name, "Folder name", MAX_NAME_LENGTH)?;
+        ensure_unique_folder_name(conn, name, Some(id))?;
         updates.push("name = ?".to_string());
         params.push(name.trim().to_string());
     }
@@ -142,6 +205,12 @@ fn update_folder_impl(conn: &Connection, id: Uuid, input: UpdateFolder) -> AppRe
         params.push(icon.clone());
     }
 
+    if let Some(description) = &input.description {
+        ensure_max_length(description, "Folder description", MAX_MEMO_LENGTH)?;
+        updates.push("description = ?".to_string());
+        params.push(description.clone());
+    }
+
     if let Some(sort_order) = input.sort_order {
         updates.push("sort_order = ?".to_string());
         params.push(sort_order.to_string());
@@ -158,7 +227,7 @@ fn update_folder_impl(conn: &Connection, id: Uuid, input: UpdateFolder) -> AppRe
 
     // Fetch updated folder
     let folder = conn.query_row(
-        "SELECT id, name, color, icon, sort_order, created_at, updated_at FROM folders WHERE id = ?",
+        "SELECT id, name, color, icon, sort_order, created_at, updated_at, description FROM folders WHERE id = ?",
         [id.to_string()],
         |row| {
             let id_str: String = row.get(0)?;
@@ -167,6 +236,7 @@ fn update_folder_impl(conn: &Connection, id: Uuid, input: UpdateFolder) -> AppRe
                 name: row.get(1)?,
                 color: row.get(2)?,
                 icon: row.get(3)?,
+                description: row.get(7)?,
                 sort_order: row.get(4)?,
                 created_at: row.get(5)?,
                 updated_at: row.get(6)?,
@@ -178,6 +248,9 @@ fn update_folder_impl(conn: &Connection, id: Uuid, input: UpdateFolder) -> AppRe
 }
 
 /// フォルダを削除する（タスクのfolder_idはnullになる）
+///
+/// フォルダは階層を持たないため、削除時に付け替えが必要な子フォルダはない。
+/// タスクの付け替えと削除は同じトランザクションで行う。
 fn delete_folder_impl(conn: &Connection, id: Uuid) -> AppResult<()> {
     // Check folder exists
     let exists: i64 = conn.query_row(
@@ -190,24 +263,131 @@ fn delete_folder_impl(conn: &Connection, id: Uuid) -> AppResult<()> {
         return Err(AppError::NotFound("フォルダが見つかりません".to_string()));
     }
 
+    let tx = conn.unchecked_transaction()?;
+
     // Set tasks' folder_id to null
-    conn.execute(
+    tx.execute(
         "UPDATE tasks SET folder_id = NULL WHERE folder_id = ?",
         [id.to_string()],
     )?;
 
     // Delete folder
-    conn.execute("DELETE FROM folders WHERE id = ?", [id.to_string()])?;
+    tx.execute("DELETE FROM folders WHERE id = ?", [id.to_string()])?;
 
+    tx.commit()?;
     Ok(())
 }
 
+/// フォルダごとにタスクをまとめたツリーを取得する（末尾はフォルダ未所属のタスク）
+fn fetch_folder_tree(conn: &Connection, include_archived: bool) -> AppResult<Vec<FolderTreeNode>> {
+    let folders = fetch_folders(conn)?;
+    let tasks = fetch_tasks(conn, include_archived, None)?;
+
+    let mut nodes: Vec<FolderTreeNode> = folders
+        .into_iter()
+        .map(|folder| FolderTreeNode {
+            folder: Some(folder),
+            tasks: Vec::new(),
+        })
+        .collect();
+    let mut unfiled = Vec::new();
+
+    // タスクは作成日時の降順で取得済みなので、順に振り分ければ各フォルダ内の順序も保たれる
+    for task in tasks {
+        let node = task.folder_id.and_then(|folder_id| {
+            nodes
+                .iter_mut()
+                .find(|node| node.folder.as_ref().is_some_and(|f| f.id == folder_id))
+        });
+        match node {
+            Some(node) => node.tasks.push(task),
+            None => unfiled.push(task),
+        }
+    }
+
+    nodes.push(FolderTreeNode {
+        folder: None,
+        tasks: unfiled,
+    });
+    Ok(nodes)
+}
+
+/// フォルダごとのタスク数と時間記録数を集計する（末尾はフォルダ未所属のタスク分）
+///
+/// アーカイブ済みタスクはタスク数に含めない。その時間記録を数えるかは
+/// `count_archived_entries` で切り替える。タスクのない時間記録はどこにも数えない。
+fn fetch_folder_counts(conn: &Connection, count_archived_entries: bool) -> AppResult<Vec<FolderCounts>> {
+    let mut stmt = conn.prepare(
+        "SELECT f.id,
+                COUNT(DISTINCT t.id) FILTER (WHERE NOT t.archived) as task_count,
+                COUNT(e.id) FILTER (WHERE ? OR NOT t.archived) as entry_count
+         FROM folders f
+         LEFT JOIN tasks t ON t.folder_id = f.id
+         LEFT JOIN time_entries e ON e.task_id = t.id
+         GROUP BY f.id, f.sort_order, f.created_at
+         ORDER BY f.sort_order ASC, f.created_at ASC",
+    )?;
+    let rows = stmt.query_map([count_archived_entries], |row| {
+        let id_str: String = row.get(0)?;
+        Ok(FolderCounts {
+            folder_id: Some(Uuid::parse_str(&id_str).unwrap()),
+            task_count: row.get(1)?,
+            entry_count: row.get(2)?,
+        })
+    })?;
+    let mut counts = Vec::new();
+    for row in rows {
+        counts.push(row?);
+    }
+
+    // ツリーと同じく、存在しないフォルダを指すタスクも未所属として数える
+    let (task_count, entry_count) = conn.query_row(
+        "SELECT COUNT(DISTINCT t.id) FILTER (WHERE NOT t.archived),
+                COUNT(e.id) FILTER (WHERE ? OR NOT t.archived)
+         FROM tasks t
+         LEFT JOIN time_entries e ON e.task_id = t.id
+         WHERE t.folder_id IS NULL OR t.folder_id NOT IN (SELECT id FROM folders)",
+        [count_archived_entries],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    counts.push(FolderCounts {
+        folder_id: None,
+        task_count,
+        entry_count,
+    });
+    Ok(counts)
+}
+
 /// フォルダ一覧を取得する
 #[tauri::command]
 pub fn list_folders(state: tauri::State<AppState>) -> AppResult<Vec<Folder>> {
     state.db.with_connection(fetch_folders)
 }
 
+/// フォルダとタスクのツリーを取得する
+#[tauri::command]
+pub fn get_folder_tree(
+    state: tauri::State<AppState>,
+    include_archived: Option<bool>,
+) -> AppResult<Vec<FolderTreeNode>> {
+    let include_archived = include_archived.unwrap_or(false);
+    state
+        .db
+        .with_connection(|conn| fetch_folder_tree(conn, include_archived))
+}
+
+/// フォルダごとのタスク数と時間記録数を取得する
+#[tauri::command]
+pub fn get_folder_counts(
+    state: tauri::State<AppState>,
+    count_archived_entries: Option<bool>,
+) -> AppResult<Vec<FolderCounts>> {
+    let count_archived_entries = count_archived_entries.unwrap_or(true);
+    state
+        .db
+        .with_connection(|conn| fetch_folder_counts(conn, count_archived_entries))
+}
+
 /// フォルダを作成する
 #[tauri::command]
 pub fn create_folder(state: tauri::State<AppState>, folder: CreateFolder) -> AppResult<Folder> {
@@ -229,7 +409,10 @@ pub fn update_folder(
 #[tauri::command]
 pub fn delete_folder(state: tauri::State<AppState>, id: String) -> AppResult<()> {
     let uuid = Uuid::parse_str(&id).map_err(|_| AppError::InvalidInput("無効なIDです".to_string()))?;
-    state.db.with_connection(|conn| delete_folder_impl(conn, uuid))
+    state.db.with_connection(|conn| delete_folder_impl(conn, uuid))?;
+    // フォルダ指定のレポートから配下だったタスクが外れるため破棄する
+    state.report_cache.clear();
+    Ok(())
 }
 
 #[cfg(test)]
@@ -250,6 +433,8 @@ mod tests {
                 create_folder_impl(conn, CreateFolder {
                     name: "開発".to_string(),
                     color: Some("#ff0000".to_string()),
+                    icon: None,
+                    description: None,
                 })
             })
             .unwrap();
@@ -266,6 +451,8 @@ mod tests {
             create_folder_impl(conn, CreateFolder {
                 name: "フォルダ1".to_string(),
                 color: None,
+                icon: None,
+                description: None,
             })
         })
         .unwrap();
@@ -274,6 +461,8 @@ mod tests {
             create_folder_impl(conn, CreateFolder {
                 name: "フォルダ2".to_string(),
                 color: None,
+                icon: None,
+                description: None,
             })
         })
         .unwrap();
@@ -291,6 +480,8 @@ mod tests {
                 create_folder_impl(conn, CreateFolder {
                     name: "旧名".to_string(),
                     color: None,
+                    icon: None,
+                    description: None,
                 })
             })
             .unwrap();
@@ -300,6 +491,8 @@ mod tests {
                 update_folder_impl(conn, folder.id, UpdateFolder {
                     name: Some("新名".to_string()),
                     color: None,
+                    icon: None,
+                    description: None,
                     sort_order: None,
                 })
             })
@@ -317,6 +510,8 @@ mod tests {
                 create_folder_impl(conn, CreateFolder {
                     name: "削除対象".to_string(),
                     color: None,
+                    icon: None,
+                    description: None,
                 })
             })
             .unwrap();
@@ -326,4 +521,308 @@ mod tests {
         let folders = db.with_connection(fetch_folders).unwrap();
         assert!(folders.is_empty());
     }
+
+    #[test]
+    fn 同じ名前のフォルダは作成できない() {
+        let db = create_test_db();
+
+        db.with_connection(|conn| {
+            create_folder_impl(conn, CreateFolder {
+                name: "Dev".to_string(),
+                color: None,
+                icon: None,
+                description: None,
+            })
+        })
+        .unwrap();
+
+        let result = db.with_connection(|conn| {
+            create_folder_impl(conn, CreateFolder {
+                name: "  dev ".to_string(),
+                color: None,
+                icon: None,
+                description: None,
+            })
+        });
+
+        assert!(matches!(result, Err(AppError::AlreadyExists(_))));
+    }
+
+    #[test]
+    fn 他のフォルダと同じ名前には更新できない() {
+        let db = create_test_db();
+
+        let (first, second) = db
+            .with_connection(|conn| {
+                let first = create_folder_impl(conn, CreateFolder {
+                    name: "開発".to_string(),
+                    color: None,
+                    icon: None,
+                    description: None,
+                })?;
+                let second = create_folder_impl(conn, CreateFolder {
+                    name: "運用".to_string(),
+                    color: None,
+                    icon: None,
+                    description: None,
+                })?;
+                Ok((first, second))
+            })
+            .unwrap();
+
+        let result = db.with_connection(|conn| {
+            update_folder_impl(conn, second.id, UpdateFolder {
+                name: Some("開発".to_string()),
+                color: None,
+                icon: None,
+                description: None,
+                sort_order: None,
+            })
+        });
+        assert!(matches!(result, Err(AppError::AlreadyExists(_))));
+
+        // 自分自身の名前のままの更新は許可される
+        let unchanged = db.with_connection(|conn| {
+            update_folder_impl(conn, first.id, UpdateFolder {
+                name: Some("開発".to_string()),
+                color: None,
+                icon: None,
+                description: None,
+                sort_order: None,
+            })
+        });
+        assert!(unchanged.is_ok());
+    }
+
+    #[test]
+    fn 長すぎる名前のフォルダは作成できない() {
+        let db = create_test_db();
+
+        let result = db.with_connection(|conn| {
+            create_folder_impl(conn, CreateFolder {
+                name: "フ".repeat(MAX_NAME_LENGTH + 1),
+                color: None,
+                icon: None,
+                description: None,
+            })
+        });
+        assert!(matches!(result, Err(AppError::InvalidInput(_))));
+
+        let ok = db.with_connection(|conn| {
+            create_folder_impl(conn, CreateFolder {
+                name: "フ".repeat(MAX_NAME_LENGTH),
+                color: None,
+                icon: None,
+                description: None,
+            })
+        });
+        assert!(ok.is_ok());
+    }
+
+    #[test]
+    fn 色を指定しない場合は設定のデフォルト色が使われる() {
+        let db = create_test_db();
+
+        let folder = db
+            .with_connection(|conn| {
+                conn.execute(
+                    "INSERT INTO settings (key, value, updated_at) VALUES (?, '#123456', CURRENT_TIMESTAMP)",
+                    [DEFAULT_FOLDER_COLOR_KEY],
+                )?;
+                create_folder_impl(conn, CreateFolder {
+                    name: "設定色".to_string(),
+                    color: None,
+                    icon: None,
+                    description: None,
+                })
+            })
+            .unwrap();
+
+        assert_eq!(folder.color, "#123456");
+    }
+
+    #[test]
+    fn フォルダごとにタスクをまとめたツリーを取得できる() {
+        let db = create_test_db();
+
+        let tree = db
+            .with_connection(|conn| {
+                let second = create_folder_impl(conn, CreateFolder {
+                    name: "後".to_string(),
+                    color: None,
+                    icon: None,
+                    description: None,
+                })?;
+                let first = create_folder_impl(conn, CreateFolder {
+                    name: "先".to_string(),
+                    color: None,
+                    icon: None,
+                    description: None,
+                })?;
+                update_folder_impl(conn, second.id, UpdateFolder {
+                    name: None,
+                    color: None,
+                    icon: None,
+                    description: None,
+                    sort_order: Some(2),
+                })?;
+                update_folder_impl(conn, first.id, UpdateFolder {
+                    name: None,
+                    color: None,
+                    icon: None,
+                    description: None,
+                    sort_order: Some(1),
+                })?;
+
+                let tasks = [
+                    ("古いタスク", Some(first.id), false, "2024-01-01 00:00:00+00"),
+                    ("新しいタスク", Some(first.id), false, "2024-01-02 00:00:00+00"),
+                    ("アーカイブ済み", Some(second.id), true, "2024-01-03 00:00:00+00"),
+                    ("未所属", None, false, "2024-01-04 00:00:00+00"),
+                ];
+                for (name, folder_id, archived, created_at) in tasks {
+                    conn.execute(
+                        "INSERT INTO tasks (id, folder_id, name, color, archived, created_at, updated_at)
+                         VALUES (?, ?, ?, '#000000', ?, ?::TIMESTAMPTZ, ?::TIMESTAMPTZ)",
+                        duckdb::params![
+                            Uuid::new_v4().to_string(),
+                            folder_id.map(|id| id.to_string()),
+                            name,
+                            archived,
+                            created_at,
+                            created_at
+                        ],
+                    )?;
+                }
+
+                fetch_folder_tree(conn, false)
+            })
+            .unwrap();
+
+        assert_eq!(tree.len(), 3);
+        assert_eq!(tree[0].folder.as_ref().unwrap().name, "先");
+        let names: Vec<&str> = tree[0].tasks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["新しいタスク", "古いタスク"]);
+        assert_eq!(tree[1].folder.as_ref().unwrap().name, "後");
+        assert!(tree[1].tasks.is_empty());
+        assert!(tree[2].folder.is_none());
+        assert_eq!(tree[2].tasks[0].name, "未所属");
+
+        let with_archived = db.with_connection(|conn| fetch_folder_tree(conn, true)).unwrap();
+        assert_eq!(with_archived[1].tasks.len(), 1);
+    }
+
+    #[test]
+    fn フォルダのメモを作成時と更新時に保存できる() {
+        let db = create_test_db();
+
+        let (created, updated, listed) = db
+            .with_connection(|conn| {
+                let created = create_folder_impl(conn, CreateFolder {
+                    name: "A社".to_string(),
+                    color: None,
+                    icon: None,
+                    description: Some("月末締め".to_string()),
+                })?;
+                let updated = update_folder_impl(conn, created.id, UpdateFolder {
+                    name: None,
+                    color: None,
+                    icon: None,
+                    description: Some("月末締め・翌月払い".to_string()),
+                    sort_order: None,
+                })?;
+                Ok((created, updated, fetch_folders(conn)?))
+            })
+            .unwrap();
+
+        assert_eq!(created.description.as_deref(), Some("月末締め"));
+        assert_eq!(updated.description.as_deref(), Some("月末締め・翌月払い"));
+        assert_eq!(listed[0].description.as_deref(), Some("月末締め・翌月払い"));
+    }
+
+    #[test]
+    fn 長すぎるフォルダのメモはエラーになる() {
+        let db = create_test_db();
+
+        let result = db.with_connection(|conn| {
+            create_folder_impl(conn, CreateFolder {
+                name: "A社".to_string(),
+                color: None,
+                icon: None,
+                description: Some("あ".repeat(MAX_MEMO_LENGTH + 1)),
+            })
+        });
+
+        assert!(matches!(result, Err(AppError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn フォルダごとのタスク数と時間記録数を集計できる() {
+        let db = create_test_db();
+
+        let (folder, empty, counts, with_archived) = db
+            .with_connection(|conn| {
+                let folder = create_folder_impl(conn, CreateFolder {
+                    name: "開発".to_string(),
+                    color: None,
+                    icon: None,
+                    description: None,
+                })?;
+                let empty = create_folder_impl(conn, CreateFolder {
+                    name: "空".to_string(),
+                    color: None,
+                    icon: None,
+                    description: None,
+                })?;
+
+                let tasks = [(Some(folder.id), false, 2), (Some(folder.id), true, 3), (None, false, 1)];
+                for (folder_id, archived, entry_count) in tasks {
+                    let task_id = Uuid::new_v4();
+                    conn.execute(
+                        "INSERT INTO tasks (id, folder_id, name, color, archived, created_at, updated_at)
+                         VALUES (?, ?, 'タスク', '#000000', ?, ?, ?)",
+                        duckdb::params![
+                            task_id.to_string(),
+                            folder_id.map(|id| id.to_string()),
+                            archived,
+                            Utc::now(),
+                            Utc::now()
+                        ],
+                    )?;
+                    for _ in 0..entry_count {
+                        conn.execute(
+                            "INSERT INTO time_entries (id, task_id, started_at, ended_at, created_at, updated_at)
+                             VALUES (?, ?, ?, ?, ?, ?)",
+                            duckdb::params![
+                                Uuid::new_v4().to_string(),
+                                task_id.to_string(),
+                                Utc::now(),
+                                Utc::now(),
+                                Utc::now(),
+                                Utc::now()
+                            ],
+                        )?;
+                    }
+                }
+
+                Ok((
+                    folder,
+                    empty,
+                    fetch_folder_counts(conn, false)?,
+                    fetch_folder_counts(conn, true)?,
+                ))
+            })
+            .unwrap();
+
+        assert_eq!(
+            counts,
+            vec![
+                FolderCounts { folder_id: Some(folder.id), task_count: 1, entry_count: 2 },
+                FolderCounts { folder_id: Some(empty.id), task_count: 0, entry_count: 0 },
+                FolderCounts { folder_id: None, task_count: 1, entry_count: 1 },
+            ]
+        );
+        assert_eq!(with_archived[0].task_count, 1);
+        assert_eq!(with_archived[0].entry_count, 5);
+    }
 }
@@ -1,6 +1,74 @@
 pub mod artifacts;
+pub mod audit;
+pub mod auto_rules;
+pub mod database;
 pub mod entries;
 pub mod export;
 pub mod folders;
 pub mod reports;
+pub mod settings;
 pub mod tasks;
+
+use chrono::{DateTime, Utc};
+
+use crate::error::{AppError, AppResult};
+
+/// タスク名・フォルダ名・成果物名の最大文字数
+pub(crate) const MAX_NAME_LENGTH: usize = 200;
+
+/// メモの最大文字数
+pub(crate) const MAX_MEMO_LENGTH: usize = 10000;
+
+/// 文字列の長さが上限以内か確認する（バイト数ではなく文字数で数える）
+pub(crate) fn ensure_max_length(value: &str, field: &str, max: usize) -> AppResult<()> {
+    if value.chars().count() > max {
+        return Err(AppError::InvalidInput(format!(
+            "{} must be at most {} characters",
+            field, max
+        )));
+    }
+    Ok(())
+}
+
+/// RFC3339形式の日時文字列をパースする（未指定・空文字はNone）
+pub(crate) fn parse_optional_datetime(
+    value: Option<String>,
+    field: &str,
+) -> AppResult<Option<DateTime<Utc>>> {
+    match value {
+        Some(s) if !s.trim().is_empty() => DateTime::parse_from_rfc3339(s.trim())
+            .map(|dt| Some(dt.with_timezone(&Utc)))
+            .map_err(|_| AppError::InvalidInput(format!("Invalid {} datetime: {}", field, s))),
+        _ => Ok(None),
+    }
+}
+
+/// 部分一致検索用のLIKEパターンを作る（`ESCAPE '\'` と組み合わせて使う）
+pub(crate) fn contains_pattern(query: &str) -> String {
+    let escaped = query
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn 文字数は日本語でもバイト数ではなく文字単位で数える() {
+        let japanese = "あ".repeat(MAX_NAME_LENGTH);
+        assert!(ensure_max_length(&japanese, "name", MAX_NAME_LENGTH).is_ok());
+
+        let too_long = "a".repeat(MAX_NAME_LENGTH + 1);
+        let result = ensure_max_length(&too_long, "name", MAX_NAME_LENGTH);
+        assert!(matches!(result, Err(AppError::InvalidInput(msg)) if msg.contains("200")));
+    }
+
+    #[test]
+    fn 部分一致パターンではワイルドカード文字がエスケープされる() {
+        assert_eq!(contains_pattern("設計"), "%設計%");
+        assert_eq!(contains_pattern("100%_a\\b"), "%100\\%\\_a\\\\b%");
+    }
+}
@@ -1,9 +1,15 @@
-use chrono::NaiveDate;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Datelike, NaiveDate, Utc, Weekday};
 use duckdb::Connection;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::error::AppResult;
+use crate::commands::export::escape_csv_field;
+use crate::commands::parse_optional_datetime;
+use crate::commands::tasks::fetch_task_by_id;
+use crate::error::{AppError, AppResult};
 use crate::AppState;
 
 /// タスク別の集計データ
@@ -14,6 +20,9 @@ pub struct TaskSummary {
     pub task_color: String,
     pub total_seconds: i64,
     pub entry_count: i64,
+    /// 全体の合計に対する割合（%）。合計が0の場合は0.0
+    #[serde(default)]
+    pub percentage: f64,
 }
 
 /// 日別の集計データ
@@ -22,6 +31,12 @@ pub struct DailySummary {
     pub date: String,
     pub total_seconds: i64,
     pub entry_count: i64,
+    /// 1日の目標秒数を達成したか（目標指定時のみ）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub met_target: Option<bool>,
+    /// 目標秒数との差（超過は正、不足は負。目標指定時のみ）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delta_seconds: Option<i64>,
 }
 
 /// 月次レポートデータ
@@ -37,29 +52,436 @@ pub struct MonthlyReport {
     pub daily_summaries: Vec<DailySummary>,
 }
 
+/// 2つの期間の間のタスク別の差分（片方の期間にしかないタスクはもう片方を0とする）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskDelta {
+    pub task_id: Option<Uuid>,
+    pub task_name: String,
+    pub task_color: String,
+    pub seconds_a: i64,
+    pub seconds_b: i64,
+    pub delta_seconds: i64,
+    pub entries_a: i64,
+    pub entries_b: i64,
+    pub delta_entries: i64,
+}
+
+/// 期間Aから期間Bへの変化量（B - A）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportDiff {
+    pub delta_total_seconds: i64,
+    pub delta_entries: i64,
+    pub task_deltas: Vec<TaskDelta>,
+}
+
+/// 2つの月次レポートの比較
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportComparison {
+    pub period_a: MonthlyReport,
+    pub period_b: MonthlyReport,
+    pub diff: ReportDiff,
+}
+
+/// 今日（ローカル日付）のここまでの集計
+///
+/// 計測中のエントリも現在時刻までの経過秒数を含めるため、ポーリングするたびに合計が増える。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TodayReport {
+    pub date: String,
+    pub total_seconds: i64,
+    pub entry_count: i64,
+    pub task_summaries: Vec<TaskSummary>,
+}
+
+/// タスクの連続記録日数（ローカル日付で完了済みエントリがある日が続いた日数）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TaskStreak {
+    /// 今日または昨日で終わる連続日数（途切れていれば0）
+    pub current_days: i64,
+    pub current_start: Option<String>,
+    pub current_end: Option<String>,
+    /// これまでで最長の連続日数（同じ長さなら新しい方）
+    pub longest_days: i64,
+    pub longest_start: Option<String>,
+    pub longest_end: Option<String>,
+}
+
+/// 選択したタスクをまとめた集計
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TasksReport {
+    pub total_seconds: i64,
+    pub entry_count: i64,
+    pub task_summaries: Vec<TaskSummary>,
+}
+
+/// 月別のアクティブタスク数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonthlyActiveTasks {
+    pub year: i32,
+    pub month: u32,
+    pub task_count: i64,
+    pub has_untasked: bool,
+}
+
+/// 連続した作業ブロック
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkBlock {
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    /// ブロック内で実際に記録された秒数（ギャップは含まない）
+    pub worked_seconds: i64,
+    pub entry_count: i64,
+}
+
+/// タスク別の請求明細
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BillingLine {
+    pub task_id: Option<Uuid>,
+    pub task_name: String,
+    pub hourly_rate: f64,
+    pub currency: Option<String>,
+    pub total_seconds: i64,
+    pub amount: f64,
+}
+
+/// 通貨別の請求合計
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BillingTotal {
+    pub currency: Option<String>,
+    pub total_seconds: i64,
+    pub amount: f64,
+}
+
+/// 単価が未設定のタスクの作業時間
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnbilledLine {
+    pub task_id: Option<Uuid>,
+    pub task_name: String,
+    pub total_seconds: i64,
+}
+
+/// 請求レポート
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BillingReport {
+    pub lines: Vec<BillingLine>,
+    /// 通貨ごとの合計（異なる通貨は合算しない）
+    pub totals: Vec<BillingTotal>,
+    pub unbilled: Vec<UnbilledLine>,
+    pub unbilled_seconds: i64,
+}
+
+/// 作業時間ヒストグラムのバケット
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DurationBucket {
+    pub min_seconds: i64,
+    /// 上限（この値を含まない）。最後のバケットはNone
+    pub max_seconds: Option<i64>,
+    pub count: i64,
+    pub total_seconds: i64,
+}
+
+/// エントリ間の記録されていない時間
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Gap {
+    /// 直前のエントリの終了時刻
+    pub started_at: DateTime<Utc>,
+    /// 次のエントリの開始時刻
+    pub ended_at: DateTime<Utc>,
+    pub gap_seconds: i64,
+}
+
+/// 日別のギャップ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyGaps {
+    pub date: String,
+    pub total_gap_seconds: i64,
+    pub gaps: Vec<Gap>,
+}
+
+/// 累積作業時間のタイムライン上の点（完了済みエントリ1件ごと）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelinePoint {
+    pub entry_id: Uuid,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    pub duration_seconds: i64,
+    /// このエントリまでの作業秒数の合計
+    pub cumulative_seconds: i64,
+    pub task_id: Option<Uuid>,
+    pub task_color: String,
+}
+
+/// タスクの色別の作業時間（colorがNoneはタスクなしの未分類）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColorSummary {
+    pub color: Option<String>,
+    pub total_seconds: i64,
+    pub entry_count: i64,
+}
+
+/// 1件あたりの作業時間が最も長い完了済みエントリ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LongestSession {
+    pub entry_id: Uuid,
+    pub task_id: Option<Uuid>,
+    pub task_name: String,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    pub duration_seconds: i64,
+}
+
+/// 記録となった日（dateはローカル日付の "YYYY-MM-DD"）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayRecord {
+    pub date: String,
+    pub total_seconds: i64,
+    pub entry_count: i64,
+}
+
+/// 統計ページ用の最高記録（データがなければ各項目はNone）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportRecords {
+    pub longest_session: Option<LongestSession>,
+    /// 合計作業時間が最も長い日
+    pub busiest_day: Option<DayRecord>,
+    /// 記録件数が最も多い日
+    pub most_entries_day: Option<DayRecord>,
+}
+
+/// 曜日別・時間帯別の作業時間の分布
+///
+/// 複数の時間帯にまたがるエントリも、開始時刻（ローカル時刻）の曜日と時間帯にすべて計上する。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityDistribution {
+    /// 曜日別の合計秒数（0=日曜〜6=土曜の7要素）
+    pub by_day_of_week: Vec<i64>,
+    /// 時間帯別の合計秒数（0〜23時の24要素）
+    pub by_hour: Vec<i64>,
+}
+
+/// 丸めの方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundMode {
+    /// 切り上げ
+    Up,
+    /// 四捨五入（ちょうど半分は切り上げ）
+    Nearest,
+    /// 切り捨て
+    Down,
+}
+
+/// 記録ごとの作業時間の丸め設定
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Rounding {
+    pub increment_minutes: u32,
+    pub mode: RoundMode,
+}
+
+impl Rounding {
+    /// 丸め単位が0分の設定はエラーにする
+    fn validate(&self) -> AppResult<()> {
+        if self.increment_minutes == 0 {
+            return Err(AppError::InvalidInput(
+                "Rounding increment must be greater than 0".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// 秒数のSQL式を丸め単位の倍数に丸めるSQL式を生成する
+    fn apply_expr(&self, seconds_expr: &str) -> String {
+        let increment = self.increment_minutes as i64 * 60;
+        let units = match self.mode {
+            RoundMode::Up => format!("CEIL(({})::DOUBLE / {})", seconds_expr, increment),
+            RoundMode::Nearest => format!(
+                "FLOOR((({})::DOUBLE + {}) / {})",
+                seconds_expr,
+                increment as f64 / 2.0,
+                increment
+            ),
+            RoundMode::Down => format!("FLOOR(({})::DOUBLE / {})", seconds_expr, increment),
+        };
+        format!("({} * {})", units, increment)
+    }
+}
+
+/// 集計で参照する時間記録・タスクのリレーション名
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SummaryRelations {
+    entries: &'static str,
+    tasks: &'static str,
+}
+
+impl Default for SummaryRelations {
+    fn default() -> Self {
+        Self {
+            entries: "time_entries",
+            tasks: "tasks",
+        }
+    }
+}
+
+/// タスク別・日別の集計条件
+#[derive(Debug, Clone, Default)]
+struct SummaryOptions<'a> {
+    folder_id: Option<&'a Uuid>,
+    /// 集計から除外するタスク（未分類のエントリは除外しない）
+    exclude_task_ids: &'a [Uuid],
+    /// 記録ごとの作業時間の丸め（Noneなら丸めない）
+    rounding: Option<Rounding>,
+    /// 集計対象のリレーション（合算レポートでは一時ビューを指定する）
+    relations: SummaryRelations,
+}
+
+/// 完了済みエントリ1件の作業秒数のSQL式を生成する（丸め指定時は記録ごとに丸める）
+fn entry_seconds_expr(rounding: Option<&Rounding>) -> String {
+    let seconds = "COALESCE(e.duration_seconds, EPOCH(e.ended_at::TIMESTAMP) - EPOCH(e.started_at::TIMESTAMP))";
+    match rounding {
+        Some(rounding) => rounding.apply_expr(seconds),
+        None => seconds.to_string(),
+    }
+}
+
+/// 月次レポートのキャッシュキー（集計条件が異なれば別のレポートとして扱う）
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ReportCacheKey {
+    year: i32,
+    month: u32,
+    tz_offset_minutes: i32,
+    folder_id: Option<Uuid>,
+    exclude_task_ids: Vec<Uuid>,
+    daily_target_seconds: Option<i64>,
+    rounding: Option<Rounding>,
+}
+
+/// 計算済みの月次レポートのキャッシュ
+///
+/// 過去の月は記録が変更されない限り結果が変わらないため再計算を省く。
+/// 記録が作成・更新・削除された場合は、その開始日時を含む月のキャッシュを破棄する。
+#[derive(Default)]
+pub struct ReportCache {
+    reports: Mutex<HashMap<ReportCacheKey, MonthlyReport>>,
+}
+
+impl ReportCache {
+    fn get(&self, key: &ReportCacheKey) -> Option<MonthlyReport> {
+        self.lock().get(key).cloned()
+    }
+
+    fn insert(&self, key: ReportCacheKey, report: MonthlyReport) {
+        self.lock().insert(key, report);
+    }
+
+    /// 指定した開始日時を含む月のキャッシュを破棄する（月はキーごとのタイムゾーンで判定する）
+    pub fn invalidate(&self, started_at: DateTime<Utc>) {
+        self.lock().retain(|key, _| {
+            let local = started_at + chrono::Duration::minutes(key.tz_offset_minutes as i64);
+            (local.year(), local.month()) != (key.year, key.month)
+        });
+    }
+
+    /// すべてのキャッシュを破棄する（タスク名の変更やインポートなど月を特定できない変更向け）
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// キャッシュは再計算で復元できるため、ロックが壊れていても中身をそのまま使う
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<ReportCacheKey, MonthlyReport>> {
+        self.reports.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// 指定した年月が基準時刻のローカル時間で当月かどうか
+fn is_current_month(year: i32, month: u32, tz_offset_minutes: i32, now: DateTime<Utc>) -> bool {
+    let local = now + chrono::Duration::minutes(tz_offset_minutes as i64);
+    local.year() == year && local.month() == month
+}
+
+/// 各タスクの合計に対する割合を設定する（合計が0ならすべて0.0）
+fn fill_percentages(summaries: &mut [TaskSummary], total_seconds: i64) {
+    for summary in summaries {
+        summary.percentage = if total_seconds > 0 {
+            summary.total_seconds as f64 / total_seconds as f64 * 100.0
+        } else {
+            0.0
+        };
+    }
+}
+
+/// 指定カラムをタイムゾーンオフセット（分）を適用したローカル日時に変換するSQL式を生成する
+///
+/// オフセットは固定値として扱い（夏時間は考慮しない）、東はJST(+540)のような正の値、
+/// 西はEST(-300)のような負の値を符号付きのまま加算する。
+fn local_timestamp_expr(col: &str, tz_offset_minutes: i32) -> String {
+    format!(
+        "({}::TIMESTAMP + INTERVAL ({}) MINUTE)",
+        col, tz_offset_minutes
+    )
+}
+
+/// 指定カラムをタイムゾーンオフセット（分）を適用したローカル日付に変換するSQL式を生成する
+///
+/// 日付への切り捨ては必ずオフセットを加算した後に行う（先に切り捨てると日付がずれる）。
+pub(crate) fn local_date_expr(col: &str, tz_offset_minutes: i32) -> String {
+    format!("CAST({} AS DATE)", local_timestamp_expr(col, tz_offset_minutes))
+}
+
+/// 指定したタスクを集計から除外する条件を追加する（未分類のエントリは除外しない）
+fn push_exclude_tasks_clause(sql: &mut String, params: &mut Vec<String>, exclude_task_ids: &[Uuid]) {
+    if exclude_task_ids.is_empty() {
+        return;
+    }
+    let placeholders = vec!["?"; exclude_task_ids.len()].join(", ");
+    sql.push_str(&format!(
+        " AND (e.task_id IS NULL OR e.task_id NOT IN ({}))",
+        placeholders
+    ));
+    params.extend(exclude_task_ids.iter().map(|id| id.to_string()));
+}
+
 /// 月次レポートを取得する
-fn fetch_monthly_report(conn: &Connection, year: i32, month: u32, folder_id: Option<&Uuid>) -> AppResult<MonthlyReport> {
-    // 月の開始日と終了日を計算
-    let start_date = NaiveDate::from_ymd_opt(year, month, 1).unwrap();
-    let end_date = if month == 12 {
-        NaiveDate::from_ymd_opt(year + 1, 1, 1).unwrap()
-    } else {
-        NaiveDate::from_ymd_opt(year, month + 1, 1).unwrap()
-    };
+fn fetch_monthly_report(
+    conn: &Connection,
+    year: i32,
+    month: u32,
+    tz_offset_minutes: i32,
+    options: &SummaryOptions,
+    daily_target_seconds: Option<i64>,
+) -> AppResult<MonthlyReport> {
+    // 月の開始日と終了日を計算（ローカル日付で比較する）
+    let start_date = NaiveDate::from_ymd_opt(year, month, 1)
+        .ok_or_else(|| AppError::InvalidInput(format!("Invalid month: {}-{}", year, month)))?;
+    let end_date = next_month(start_date);
 
     let start_str = start_date.format("%Y-%m-%d").to_string();
     let end_str = end_date.format("%Y-%m-%d").to_string();
 
     // タスク別集計
-    let task_summaries = fetch_task_summaries(conn, &start_str, &end_str, folder_id)?;
+    let mut task_summaries =
+        fetch_task_summaries(conn, &start_str, &end_str, tz_offset_minutes, options)?;
 
-    // 日別集計
-    let daily_summaries = fetch_daily_summaries(conn, &start_str, &end_str, folder_id)?;
+    // 日別集計（目標指定時は記録のない日も含めて目標との差を付ける）
+    let mut daily_summaries = fetch_daily_summaries(
+        conn,
+        &start_str,
+        &end_str,
+        tz_offset_minutes,
+        options,
+        daily_target_seconds.is_some(),
+    )?;
+    if let Some(target) = daily_target_seconds {
+        for summary in &mut daily_summaries {
+            summary.met_target = Some(summary.total_seconds >= target);
+            summary.delta_seconds = Some(summary.total_seconds - target);
+        }
+    }
 
     // 全体集計
     let total_seconds: i64 = task_summaries.iter().map(|t| t.total_seconds).sum();
     let total_entries: i64 = task_summaries.iter().map(|t| t.entry_count).sum();
-    let working_days = daily_summaries.len() as i64;
+    fill_percentages(&mut task_summaries, total_seconds);
+    let working_days = daily_summaries.iter().filter(|d| d.entry_count > 0).count() as i64;
     let average_seconds_per_day = if working_days > 0 {
         total_seconds / working_days
     } else {
@@ -79,57 +501,50 @@ fn fetch_monthly_report(conn: &Connection, year: i32, month: u32, folder_id: Opt
 }
 
 /// タスク別の集計を取得
-fn fetch_task_summaries(conn: &Connection, start: &str, end: &str, folder_id: Option<&Uuid>) -> AppResult<Vec<TaskSummary>> {
-    let (sql, params): (String, Vec<String>) = if let Some(fid) = folder_id {
-        (
-            r#"
-                SELECT
-                    e.task_id,
-                    COALESCE(t.name, '未分類') as task_name,
-                    COALESCE(t.color, '#6b7280') as task_color,
-                    SUM(
-                        CASE
-                            WHEN e.ended_at IS NOT NULL
-                            THEN EPOCH(e.ended_at::TIMESTAMP) - EPOCH(e.started_at::TIMESTAMP)
-                            ELSE 0
-                        END
-                    )::BIGINT as total_seconds,
-                    COUNT(*)::BIGINT as entry_count
-                FROM time_entries e
-                LEFT JOIN tasks t ON e.task_id = t.id
-                WHERE CAST(e.started_at::TIMESTAMP AS DATE) >= ? AND CAST(e.started_at::TIMESTAMP AS DATE) < ?
-                  AND e.ended_at IS NOT NULL
-                  AND t.folder_id = ?
-                GROUP BY e.task_id, t.name, t.color
-                ORDER BY total_seconds DESC
-            "#.to_string(),
-            vec![start.to_string(), end.to_string(), fid.to_string()],
-        )
-    } else {
-        (
-            r#"
-                SELECT
-                    e.task_id,
-                    COALESCE(t.name, '未分類') as task_name,
-                    COALESCE(t.color, '#6b7280') as task_color,
-                    SUM(
-                        CASE
-                            WHEN e.ended_at IS NOT NULL
-                            THEN EPOCH(e.ended_at::TIMESTAMP) - EPOCH(e.started_at::TIMESTAMP)
-                            ELSE 0
-                        END
-                    )::BIGINT as total_seconds,
-                    COUNT(*)::BIGINT as entry_count
-                FROM time_entries e
-                LEFT JOIN tasks t ON e.task_id = t.id
-                WHERE CAST(e.started_at::TIMESTAMP AS DATE) >= ? AND CAST(e.started_at::TIMESTAMP AS DATE) < ?
-                  AND e.ended_at IS NOT NULL
-                GROUP BY e.task_id, t.name, t.color
-                ORDER BY total_seconds DESC
-            "#.to_string(),
-            vec![start.to_string(), end.to_string()],
-        )
-    };
+fn fetch_task_summaries(
+    conn: &Connection,
+    start: &str,
+    end: &str,
+    tz_offset_minutes: i32,
+    options: &SummaryOptions,
+) -> AppResult<Vec<TaskSummary>> {
+    let local_date = local_date_expr("e.started_at", tz_offset_minutes);
+    let entry_seconds = entry_seconds_expr(options.rounding.as_ref());
+    let SummaryRelations { entries, tasks } = options.relations;
+    let mut sql = format!(
+        r#"
+            SELECT
+                e.task_id,
+                COALESCE(t.name, '未分類') as task_name,
+                COALESCE(t.color, '#6b7280') as task_color,
+                SUM(
+                    CASE
+                        WHEN e.ended_at IS NOT NULL
+                        THEN {entry_seconds}
+                        ELSE 0
+                    END
+                )::BIGINT as total_seconds,
+                COUNT(*)::BIGINT as entry_count
+            FROM {entries} e
+            LEFT JOIN {tasks} t ON e.task_id = t.id
+            WHERE {local_date} >= ? AND {local_date} < ?
+              AND e.ended_at IS NOT NULL
+        "#
+    );
+    let mut params = vec![start.to_string(), end.to_string()];
+
+    if let Some(fid) = options.folder_id {
+        sql.push_str(" AND t.folder_id = ?");
+        params.push(fid.to_string());
+    }
+    push_exclude_tasks_clause(&mut sql, &mut params, options.exclude_task_ids);
+
+    sql.push_str(
+        r#"
+            GROUP BY e.task_id, t.name, t.color
+            ORDER BY total_seconds DESC
+        "#,
+    );
 
     let mut stmt = conn.prepare(&sql)?;
     let rows = stmt.query_map(duckdb::params_from_iter(params), |row| {
@@ -140,6 +555,7 @@ fn fetch_task_summaries(conn: &Connection, start: &str, end: &str, folder_id: Op
             task_color: row.get(2)?,
             total_seconds: row.get(3)?,
             entry_count: row.get(4)?,
+            percentage: 0.0,
         })
     })?;
 
@@ -150,53 +566,64 @@ fn fetch_task_summaries(conn: &Connection, start: &str, end: &str, folder_id: Op
     Ok(summaries)
 }
 
-/// 日別の集計を取得
-fn fetch_daily_summaries(conn: &Connection, start: &str, end: &str, folder_id: Option<&Uuid>) -> AppResult<Vec<DailySummary>> {
-    let (sql, params): (String, Vec<String>) = if let Some(fid) = folder_id {
-        (
-            r#"
-                SELECT
-                    CAST(CAST(e.started_at::TIMESTAMP AS DATE) AS VARCHAR) as date,
-                    SUM(
-                        CASE
-                            WHEN e.ended_at IS NOT NULL
-                            THEN EPOCH(e.ended_at::TIMESTAMP) - EPOCH(e.started_at::TIMESTAMP)
-                            ELSE 0
-                        END
-                    )::BIGINT as total_seconds,
-                    COUNT(*)::BIGINT as entry_count
-                FROM time_entries e
-                LEFT JOIN tasks t ON e.task_id = t.id
-                WHERE CAST(e.started_at::TIMESTAMP AS DATE) >= ? AND CAST(e.started_at::TIMESTAMP AS DATE) < ?
-                  AND e.ended_at IS NOT NULL
-                  AND t.folder_id = ?
-                GROUP BY CAST(e.started_at::TIMESTAMP AS DATE)
-                ORDER BY date ASC
-            "#.to_string(),
-            vec![start.to_string(), end.to_string(), fid.to_string()],
-        )
-    } else {
-        (
+/// 日別の集計を取得（fill_missing_daysの場合は記録のない日も0件として含める）
+fn fetch_daily_summaries(
+    conn: &Connection,
+    start: &str,
+    end: &str,
+    tz_offset_minutes: i32,
+    options: &SummaryOptions,
+    fill_missing_days: bool,
+) -> AppResult<Vec<DailySummary>> {
+    let local_date = local_date_expr("e.started_at", tz_offset_minutes);
+    let entry_seconds = entry_seconds_expr(options.rounding.as_ref());
+    let SummaryRelations { entries, tasks } = options.relations;
+    let mut sql = format!(
+        r#"
+            SELECT
+                CAST({local_date} AS VARCHAR) as date,
+                SUM(
+                    CASE
+                        WHEN e.ended_at IS NOT NULL
+                        THEN {entry_seconds}
+                        ELSE 0
+                    END
+                )::BIGINT as total_seconds,
+                COUNT(*)::BIGINT as entry_count
+            FROM {entries} e
+            LEFT JOIN {tasks} t ON e.task_id = t.id
+            WHERE {local_date} >= ? AND {local_date} < ?
+              AND e.ended_at IS NOT NULL
+        "#
+    );
+    let mut params = vec![start.to_string(), end.to_string()];
+
+    if let Some(fid) = options.folder_id {
+        sql.push_str(" AND t.folder_id = ?");
+        params.push(fid.to_string());
+    }
+    push_exclude_tasks_clause(&mut sql, &mut params, options.exclude_task_ids);
+
+    sql.push_str(&format!(
+        r#"
+            GROUP BY {local_date}
+        "#
+    ));
+
+    if fill_missing_days {
+        sql = format!(
             r#"
                 SELECT
-                    CAST(CAST(started_at::TIMESTAMP AS DATE) AS VARCHAR) as date,
-                    SUM(
-                        CASE
-                            WHEN ended_at IS NOT NULL
-                            THEN EPOCH(ended_at::TIMESTAMP) - EPOCH(started_at::TIMESTAMP)
-                            ELSE 0
-                        END
-                    )::BIGINT as total_seconds,
-                    COUNT(*)::BIGINT as entry_count
-                FROM time_entries
-                WHERE CAST(started_at::TIMESTAMP AS DATE) >= ? AND CAST(started_at::TIMESTAMP AS DATE) < ?
-                  AND ended_at IS NOT NULL
-                GROUP BY CAST(started_at::TIMESTAMP AS DATE)
-                ORDER BY date ASC
-            "#.to_string(),
-            vec![start.to_string(), end.to_string()],
-        )
-    };
+                    CAST(CAST(d.range AS DATE) AS VARCHAR) as date,
+                    COALESCE(a.total_seconds, 0)::BIGINT as total_seconds,
+                    COALESCE(a.entry_count, 0)::BIGINT as entry_count
+                FROM range(?::TIMESTAMP, ?::TIMESTAMP, INTERVAL 1 DAY) d
+                LEFT JOIN ({sql}) a ON a.date = CAST(CAST(d.range AS DATE) AS VARCHAR)
+            "#
+        );
+        params.splice(0..0, [start.to_string(), end.to_string()]);
+    }
+    sql.push_str(" ORDER BY date ASC");
 
     let mut stmt = conn.prepare(&sql)?;
     let rows = stmt.query_map(duckdb::params_from_iter(params), |row| {
@@ -204,6 +631,8 @@ fn fetch_daily_summaries(conn: &Connection, start: &str, end: &str, folder_id: O
             date: row.get(0)?,
             total_seconds: row.get(1)?,
             entry_count: row.get(2)?,
+            met_target: None,
+            delta_seconds: None,
         })
     })?;
 
@@ -215,17 +644,20 @@ fn fetch_daily_summaries(conn: &Connection, start: &str, end: &str, folder_id: O
 }
 
 /// 利用可能な月のリストを取得
-fn fetch_available_months(conn: &Connection) -> AppResult<Vec<(i32, u32)>> {
-    let sql = r#"
-        SELECT DISTINCT
-            EXTRACT(YEAR FROM started_at)::INTEGER as year,
-            EXTRACT(MONTH FROM started_at)::INTEGER as month
-        FROM time_entries
-        WHERE ended_at IS NOT NULL
-        ORDER BY year DESC, month DESC
-    "#;
-
-    let mut stmt = conn.prepare(sql)?;
+fn fetch_available_months(conn: &Connection, tz_offset_minutes: i32) -> AppResult<Vec<(i32, u32)>> {
+    let local_date = local_date_expr("started_at", tz_offset_minutes);
+    let sql = format!(
+        r#"
+            SELECT DISTINCT
+                EXTRACT(YEAR FROM {local_date})::INTEGER as year,
+                EXTRACT(MONTH FROM {local_date})::INTEGER as month
+            FROM time_entries
+            WHERE ended_at IS NOT NULL
+            ORDER BY year DESC, month DESC
+        "#
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
     let rows = stmt.query_map([], |row| {
         Ok((row.get::<_, i32>(0)?, row.get::<_, i32>(1)? as u32))
     })?;
@@ -237,201 +669,3158 @@ fn fetch_available_months(conn: &Connection) -> AppResult<Vec<(i32, u32)>> {
     Ok(months)
 }
 
-/// 月次レポートを取得する
-#[tauri::command]
-pub fn get_monthly_report(
-    state: tauri::State<AppState>,
-    year: i32,
-    month: u32,
-    folder_id: Option<String>,
-) -> AppResult<MonthlyReport> {
-    let folder_uuid = folder_id.and_then(|s| Uuid::parse_str(&s).ok());
-    state
-        .db
-        .with_connection(|conn| fetch_monthly_report(conn, year, month, folder_uuid.as_ref()))
+/// 月次レポートのタスク別集計をCSV形式に整形する
+fn format_report_csv(report: &MonthlyReport) -> String {
+    let mut csv = String::from("task_name,total_seconds,entry_count,percentage\r\n");
+    for summary in &report.task_summaries {
+        csv.push_str(&format!(
+            "{},{},{},{:.2}\r\n",
+            escape_csv_field(&summary.task_name),
+            summary.total_seconds,
+            summary.entry_count,
+            summary.percentage
+        ));
+    }
+    csv
 }
 
-/// 利用可能な月のリストを取得する
-#[tauri::command]
-pub fn get_available_months(state: tauri::State<AppState>) -> AppResult<Vec<(i32, u32)>> {
-    state.db.with_connection(fetch_available_months)
+/// 記録のあるすべての月の月次レポートを `report_YYYY_MM.csv` として書き出し、書き出したパスを返す
+fn write_all_monthly_report_csvs(
+    conn: &Connection,
+    output_dir: &std::path::Path,
+    tz_offset_minutes: i32,
+) -> AppResult<Vec<String>> {
+    std::fs::create_dir_all(output_dir)?;
+
+    let mut written = Vec::new();
+    for (year, month) in fetch_available_months(conn, tz_offset_minutes)? {
+        let report = fetch_monthly_report(
+            conn,
+            year,
+            month,
+            tz_offset_minutes,
+            &SummaryOptions::default(),
+            None,
+        )?;
+        let path = output_dir.join(format!("report_{:04}_{:02}.csv", year, month));
+        std::fs::write(&path, format_report_csv(&report))?;
+        written.push(path.to_string_lossy().into_owned());
+    }
+    Ok(written)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::db::Database;
+/// 秒数を `Hh Mm` 形式で表す（負の値は先頭に `-` を付ける）
+fn format_hours_minutes(seconds: i64) -> String {
+    let sign = if seconds < 0 { "-" } else { "" };
+    let minutes = seconds.abs() / 60;
+    format!("{}{}h {}m", sign, minutes / 60, minutes % 60)
+}
 
-    fn create_test_db() -> Database {
-        Database::new_in_memory().unwrap()
+/// 週次ダイジェストの本文を組み立てる（同じ入力なら常に同じ文字列になる）
+fn format_weekly_digest(
+    week_start: NaiveDate,
+    tasks: &[TaskSummary],
+    working_days: i64,
+    previous_total_seconds: i64,
+) -> String {
+    let week = week_start.iso_week();
+    let week_end = week_start + chrono::Duration::days(6);
+    let total_seconds: i64 = tasks.iter().map(|t| t.total_seconds).sum();
+
+    // 同じ時間のタスクは名前順に並べて出力を安定させる
+    let mut top_tasks: Vec<&TaskSummary> = tasks.iter().collect();
+    top_tasks.sort_by(|a, b| {
+        b.total_seconds
+            .cmp(&a.total_seconds)
+            .then_with(|| a.task_name.cmp(&b.task_name))
+    });
+
+    let mut lines = vec![
+        format!(
+            "Week {}-W{:02} ({} - {})",
+            week.year(),
+            week.week(),
+            week_start.format("%Y-%m-%d"),
+            week_end.format("%Y-%m-%d")
+        ),
+        format!("Total: {}", format_hours_minutes(total_seconds)),
+        format!("Working days: {}", working_days),
+        "Top tasks:".to_string(),
+    ];
+    if top_tasks.is_empty() {
+        lines.push("  (none)".to_string());
     }
+    for (i, task) in top_tasks.iter().take(3).enumerate() {
+        lines.push(format!(
+            "  {}. {} - {}",
+            i + 1,
+            task.task_name,
+            format_hours_minutes(task.total_seconds)
+        ));
+    }
+    let delta = total_seconds - previous_total_seconds;
+    lines.push(format!(
+        "vs previous week: {}{} (previous: {})",
+        if delta >= 0 { "+" } else { "" },
+        format_hours_minutes(delta),
+        format_hours_minutes(previous_total_seconds)
+    ));
+    lines.join("\n")
+}
 
-    mod monthly_report_tests {
-        use super::*;
+/// ISO週（月曜始まり）の週次ダイジェストを集計して本文を返す
+fn fetch_weekly_digest_text(
+    conn: &Connection,
+    year: i32,
+    week: u32,
+    tz_offset_minutes: i32,
+) -> AppResult<String> {
+    let week_start = NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)
+        .ok_or_else(|| AppError::InvalidInput(format!("Invalid week: {}-W{}", year, week)))?;
+    let week_end = week_start + chrono::Duration::days(7);
+    let previous_start = week_start - chrono::Duration::days(7);
+    let date = |d: NaiveDate| d.format("%Y-%m-%d").to_string();
 
-        #[test]
-        fn 空のデータベースから月次レポートを取得すると空のレポートが返る() {
-            let db = create_test_db();
+    let options = SummaryOptions::default();
 
-            let report = db
-                .with_connection(|conn| fetch_monthly_report(conn, 2024, 12, None))
-                .unwrap();
+    let tasks = fetch_task_summaries(
+        conn,
+        &date(week_start),
+        &date(week_end),
+        tz_offset_minutes,
+        &options,
+    )?;
+    let working_days = fetch_daily_summaries(
+        conn,
+        &date(week_start),
+        &date(week_end),
+        tz_offset_minutes,
+        &options,
+        false,
+    )?
+    .iter()
+    .filter(|d| d.entry_count > 0)
+    .count() as i64;
+    let previous_total_seconds: i64 = fetch_task_summaries(
+        conn,
+        &date(previous_start),
+        &date(week_start),
+        tz_offset_minutes,
+        &options,
+    )?
+    .iter()
+    .map(|t| t.total_seconds)
+    .sum();
 
-            assert_eq!(report.year, 2024);
-            assert_eq!(report.month, 12);
-            assert_eq!(report.total_seconds, 0);
-            assert_eq!(report.total_entries, 0);
-            assert!(report.task_summaries.is_empty());
-            assert!(report.daily_summaries.is_empty());
-        }
+    Ok(format_weekly_digest(
+        week_start,
+        &tasks,
+        working_days,
+        previous_total_seconds,
+    ))
+}
 
-        #[test]
-        fn 時間記録がある月のレポートを取得できる() {
-            let db = create_test_db();
+/// "YYYY-MM" 形式の文字列を月初日に変換する
+fn parse_year_month(value: &str) -> AppResult<NaiveDate> {
+    NaiveDate::parse_from_str(&format!("{}-01", value.trim()), "%Y-%m-%d")
+        .map_err(|_| AppError::InvalidInput(format!("Invalid month: {}. Expected YYYY-MM", value)))
+}
 
-            db.with_connection(|conn| {
-                // タスクを作成
-                conn.execute(
-                    "INSERT INTO tasks (id, name, color, created_at, updated_at)
-                     VALUES ('task-1', 'テストタスク', '#ff0000', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
-                    [],
-                )?;
+/// 翌月の月初日を返す
+fn next_month(date: NaiveDate) -> NaiveDate {
+    if date.month() == 12 {
+        NaiveDate::from_ymd_opt(date.year() + 1, 1, 1).unwrap()
+    } else {
+        NaiveDate::from_ymd_opt(date.year(), date.month() + 1, 1).unwrap()
+    }
+}
 
-                // 時間記録を作成（1時間）
-                conn.execute(
-                    "INSERT INTO time_entries (id, task_id, started_at, ended_at, created_at, updated_at)
-                     VALUES ('entry-1', 'task-1',
-                             '2024-12-15 09:00:00+00',
-                             '2024-12-15 10:00:00+00',
-                             CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
-                    [],
-                )?;
+/// 月別のアクティブタスク数を取得（データのない月は0で埋める）
+fn fetch_active_task_counts(
+    conn: &Connection,
+    from: NaiveDate,
+    to: NaiveDate,
+    tz_offset_minutes: i32,
+) -> AppResult<Vec<MonthlyActiveTasks>> {
+    let end = next_month(to);
+    let local_date = local_date_expr("started_at", tz_offset_minutes);
+    let sql = format!(
+        r#"
+            SELECT
+                EXTRACT(YEAR FROM {local_date})::INTEGER as year,
+                EXTRACT(MONTH FROM {local_date})::INTEGER as month,
+                COUNT(DISTINCT task_id)::BIGINT as task_count,
+                BOOL_OR(task_id IS NULL) as has_untasked
+            FROM time_entries
+            WHERE {local_date} >= ? AND {local_date} < ?
+              AND ended_at IS NOT NULL
+            GROUP BY year, month
+        "#
+    );
 
-                Ok(())
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(
+        [from.format("%Y-%m-%d").to_string(), end.format("%Y-%m-%d").to_string()],
+        |row| {
+            Ok(MonthlyActiveTasks {
+                year: row.get(0)?,
+                month: row.get::<_, i32>(1)? as u32,
+                task_count: row.get(2)?,
+                has_untasked: row.get(3)?,
             })
-            .unwrap();
-
-            let report = db
-                .with_connection(|conn| fetch_monthly_report(conn, 2024, 12, None))
-                .unwrap();
+        },
+    )?;
 
-            assert_eq!(report.total_seconds, 3600);
-            assert_eq!(report.total_entries, 1);
-            assert_eq!(report.working_days, 1);
-            assert_eq!(report.task_summaries.len(), 1);
-            assert_eq!(report.task_summaries[0].task_name, "テストタスク");
-        }
+    let mut found = Vec::new();
+    for row in rows {
+        found.push(row?);
+    }
+
+    let mut months = Vec::new();
+    let mut current = from;
+    while current < end {
+        let summary = found
+            .iter()
+            .find(|m| m.year == current.year() && m.month == current.month())
+            .cloned()
+            .unwrap_or(MonthlyActiveTasks {
+                year: current.year(),
+                month: current.month(),
+                task_count: 0,
+                has_untasked: false,
+            });
+        months.push(summary);
+        current = next_month(current);
+    }
+    Ok(months)
+}
+
+/// 最長の連続作業ブロックを取得（ギャップがmerge_gap_seconds以下のエントリは結合する）
+fn fetch_longest_work_block(conn: &Connection, merge_gap_seconds: i64) -> AppResult<Option<WorkBlock>> {
+    let mut stmt = conn.prepare(
+        "SELECT started_at, ended_at FROM time_entries
+         WHERE ended_at IS NOT NULL
+         ORDER BY started_at ASC, ended_at ASC",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        let started_at: DateTime<Utc> = row.get(0)?;
+        let ended_at: DateTime<Utc> = row.get(1)?;
+        Ok((started_at, ended_at))
+    })?;
+
+    let mut longest: Option<WorkBlock> = None;
+    let mut current: Option<WorkBlock> = None;
+
+    for row in rows {
+        let (started_at, ended_at) = row?;
+
+        if let Some(block) = current.as_mut() {
+            if (started_at - block.ended_at).num_seconds() <= merge_gap_seconds {
+                // 重複部分は二重に数えない
+                let counted_from = started_at.max(block.ended_at);
+                if ended_at > counted_from {
+                    block.worked_seconds += (ended_at - counted_from).num_seconds();
+                }
+                block.ended_at = block.ended_at.max(ended_at);
+                block.entry_count += 1;
+                continue;
+            }
+        }
+
+        if let Some(block) = current.take() {
+            longest = pick_longer(longest, block);
+        }
+        current = Some(WorkBlock {
+            started_at,
+            ended_at,
+            worked_seconds: (ended_at - started_at).num_seconds().max(0),
+            entry_count: 1,
+        });
+    }
+
+    if let Some(block) = current {
+        longest = pick_longer(longest, block);
+    }
+    Ok(longest)
+}
+
+/// ブロックの長さ（開始から終了まで）が長い方を返す
+fn pick_longer(longest: Option<WorkBlock>, candidate: WorkBlock) -> Option<WorkBlock> {
+    match longest {
+        Some(block) if block.ended_at - block.started_at >= candidate.ended_at - candidate.started_at => {
+            Some(block)
+        }
+        _ => Some(candidate),
+    }
+}
+
+/// 期間内のタスク別作業時間に時間単価を掛けた請求レポートを取得
+fn fetch_billing_report(
+    conn: &Connection,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> AppResult<BillingReport> {
+    let mut sql = String::from(
+        r#"
+            SELECT
+                e.task_id,
+                COALESCE(t.name, '未分類') as task_name,
+                t.hourly_rate,
+                t.currency,
+                SUM(COALESCE(
+                    e.duration_seconds,
+                    EPOCH(e.ended_at::TIMESTAMP) - EPOCH(e.started_at::TIMESTAMP)
+                ))::BIGINT as total_seconds
+            FROM time_entries e
+            LEFT JOIN tasks t ON e.task_id = t.id
+            WHERE e.ended_at IS NOT NULL
+        "#,
+    );
+    let mut params: Vec<DateTime<Utc>> = Vec::new();
+    if let Some(from) = from {
+        sql.push_str(" AND e.started_at >= ?");
+        params.push(from);
+    }
+    if let Some(to) = to {
+        sql.push_str(" AND e.started_at < ?");
+        params.push(to);
+    }
+    sql.push_str(
+        r#"
+            GROUP BY e.task_id, t.name, t.hourly_rate, t.currency
+            ORDER BY total_seconds DESC, task_name ASC
+        "#,
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(duckdb::params_from_iter(params), |row| {
+        let task_id_str: Option<String> = row.get(0)?;
+        let task_name: String = row.get(1)?;
+        let hourly_rate: Option<f64> = row.get(2)?;
+        let currency: Option<String> = row.get(3)?;
+        let total_seconds: i64 = row.get(4)?;
+        Ok((
+            task_id_str.and_then(|s| Uuid::parse_str(&s).ok()),
+            task_name,
+            hourly_rate,
+            currency,
+            total_seconds,
+        ))
+    })?;
+
+    let mut lines = Vec::new();
+    let mut totals: Vec<BillingTotal> = Vec::new();
+    let mut unbilled = Vec::new();
+    let mut unbilled_seconds = 0;
+
+    for row in rows {
+        let (task_id, task_name, hourly_rate, currency, total_seconds) = row?;
+        match hourly_rate {
+            Some(rate) => {
+                let amount = round_amount(total_seconds as f64 / 3600.0 * rate);
+                match totals.iter_mut().find(|t| t.currency == currency) {
+                    Some(total) => {
+                        total.total_seconds += total_seconds;
+                        total.amount = round_amount(total.amount + amount);
+                    }
+                    None => totals.push(BillingTotal {
+                        currency: currency.clone(),
+                        total_seconds,
+                        amount,
+                    }),
+                }
+                lines.push(BillingLine {
+                    task_id,
+                    task_name,
+                    hourly_rate: rate,
+                    currency,
+                    total_seconds,
+                    amount,
+                });
+            }
+            None => {
+                unbilled_seconds += total_seconds;
+                unbilled.push(UnbilledLine {
+                    task_id,
+                    task_name,
+                    total_seconds,
+                });
+            }
+        }
+    }
+
+    Ok(BillingReport {
+        lines,
+        totals,
+        unbilled,
+        unbilled_seconds,
+    })
+}
+
+/// 金額を小数点以下2桁に丸める
+fn round_amount(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+/// バケット境界（秒）が正の値で昇順になっているか検証する
+fn validate_buckets(buckets: &[i64]) -> AppResult<()> {
+    if buckets.iter().any(|&b| b <= 0) {
+        return Err(AppError::InvalidInput(
+            "Bucket boundaries must be positive".to_string(),
+        ));
+    }
+    if buckets.windows(2).any(|w| w[0] >= w[1]) {
+        return Err(AppError::InvalidInput(
+            "Bucket boundaries must be strictly increasing".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// 完了済みエントリの作業時間をバケット境界で分類したヒストグラムを取得
+fn fetch_duration_histogram(
+    conn: &Connection,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    buckets: &[i64],
+) -> AppResult<Vec<DurationBucket>> {
+    let mut histogram: Vec<DurationBucket> = Vec::with_capacity(buckets.len() + 1);
+    let mut lower = 0;
+    for &upper in buckets {
+        histogram.push(DurationBucket {
+            min_seconds: lower,
+            max_seconds: Some(upper),
+            count: 0,
+            total_seconds: 0,
+        });
+        lower = upper;
+    }
+    histogram.push(DurationBucket {
+        min_seconds: lower,
+        max_seconds: None,
+        count: 0,
+        total_seconds: 0,
+    });
+
+    let mut sql = String::from(
+        "SELECT COALESCE(
+             duration_seconds,
+             (EPOCH(ended_at::TIMESTAMP) - EPOCH(started_at::TIMESTAMP))::BIGINT
+         ) as duration
+         FROM time_entries
+         WHERE ended_at IS NOT NULL",
+    );
+    let mut params: Vec<DateTime<Utc>> = Vec::new();
+    if let Some(from) = from {
+        sql.push_str(" AND started_at >= ?");
+        params.push(from);
+    }
+    if let Some(to) = to {
+        sql.push_str(" AND started_at < ?");
+        params.push(to);
+    }
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(duckdb::params_from_iter(params), |row| row.get::<_, i64>(0))?;
+
+    for row in rows {
+        let duration = row?.max(0);
+        let index = buckets.partition_point(|&boundary| boundary <= duration);
+        histogram[index].count += 1;
+        histogram[index].total_seconds += duration;
+    }
+
+    Ok(histogram)
+}
+
+/// 完了済みエントリを開始日時順に並べ、作業秒数の累計を付けて取得する
+fn fetch_cumulative_timeline(
+    conn: &Connection,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> AppResult<Vec<TimelinePoint>> {
+    let mut sql = String::from(
+        "SELECT e.id, e.started_at, e.ended_at,
+                COALESCE(
+                    e.duration_seconds,
+                    (EPOCH(e.ended_at::TIMESTAMP) - EPOCH(e.started_at::TIMESTAMP))::BIGINT
+                ) as duration,
+                e.task_id, COALESCE(t.color, '#6b7280') as task_color
+         FROM time_entries e
+         LEFT JOIN tasks t ON e.task_id = t.id
+         WHERE e.ended_at IS NOT NULL",
+    );
+    let mut params: Vec<DateTime<Utc>> = Vec::new();
+    if let Some(from) = from {
+        sql.push_str(" AND e.started_at >= ?");
+        params.push(from);
+    }
+    if let Some(to) = to {
+        sql.push_str(" AND e.started_at < ?");
+        params.push(to);
+    }
+    sql.push_str(" ORDER BY e.started_at, e.id");
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(duckdb::params_from_iter(params), |row| {
+        let id_str: String = row.get(0)?;
+        let task_id_str: Option<String> = row.get(4)?;
+        Ok(TimelinePoint {
+            entry_id: Uuid::parse_str(&id_str).unwrap(),
+            started_at: row.get(1)?,
+            ended_at: row.get(2)?,
+            duration_seconds: row.get::<_, i64>(3)?.max(0),
+            cumulative_seconds: 0,
+            task_id: task_id_str.and_then(|s| Uuid::parse_str(&s).ok()),
+            task_color: row.get(5)?,
+        })
+    })?;
+
+    let mut points = Vec::new();
+    let mut cumulative_seconds = 0;
+    for row in rows {
+        let mut point = row?;
+        cumulative_seconds += point.duration_seconds;
+        point.cumulative_seconds = cumulative_seconds;
+        points.push(point);
+    }
+    Ok(points)
+}
+
+/// 完了済みエントリをタスクの色別に集計（作業時間の降順、同じなら色の昇順で未分類は最後）
+fn fetch_color_summaries(
+    conn: &Connection,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> AppResult<Vec<ColorSummary>> {
+    let mut sql = String::from(
+        "SELECT t.color,
+                SUM(COALESCE(
+                    e.duration_seconds,
+                    EPOCH(e.ended_at::TIMESTAMP) - EPOCH(e.started_at::TIMESTAMP)
+                ))::BIGINT as total_seconds,
+                COUNT(*)::BIGINT as entry_count
+         FROM time_entries e
+         LEFT JOIN tasks t ON e.task_id = t.id
+         WHERE e.ended_at IS NOT NULL",
+    );
+    let mut params: Vec<DateTime<Utc>> = Vec::new();
+    if let Some(from) = from {
+        sql.push_str(" AND e.started_at >= ?");
+        params.push(from);
+    }
+    if let Some(to) = to {
+        sql.push_str(" AND e.started_at < ?");
+        params.push(to);
+    }
+    sql.push_str(" GROUP BY t.color ORDER BY total_seconds DESC, t.color NULLS LAST");
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(duckdb::params_from_iter(params), |row| {
+        Ok(ColorSummary {
+            color: row.get(0)?,
+            total_seconds: row.get(1)?,
+            entry_count: row.get(2)?,
+        })
+    })?;
+
+    let mut summaries = Vec::new();
+    for row in rows {
+        summaries.push(row?);
+    }
+    Ok(summaries)
+}
+
+/// 作業時間が最も長い完了済みエントリを取得（同じ長さなら先に開始したもの）
+fn fetch_longest_session(conn: &Connection) -> AppResult<Option<LongestSession>> {
+    let result = conn.query_row(
+        "SELECT e.id, e.task_id, COALESCE(t.name, '未分類') as task_name, e.started_at, e.ended_at,
+                COALESCE(
+                    e.duration_seconds,
+                    (EPOCH(e.ended_at::TIMESTAMP) - EPOCH(e.started_at::TIMESTAMP))::BIGINT
+                ) as duration
+         FROM time_entries e
+         LEFT JOIN tasks t ON e.task_id = t.id
+         WHERE e.ended_at IS NOT NULL
+         ORDER BY duration DESC, e.started_at, e.id
+         LIMIT 1",
+        [],
+        |row| {
+            let id_str: String = row.get(0)?;
+            let task_id_str: Option<String> = row.get(1)?;
+            Ok(LongestSession {
+                entry_id: Uuid::parse_str(&id_str).unwrap(),
+                task_id: task_id_str.and_then(|s| Uuid::parse_str(&s).ok()),
+                task_name: row.get(2)?,
+                started_at: row.get(3)?,
+                ended_at: row.get(4)?,
+                duration_seconds: row.get::<_, i64>(5)?.max(0),
+            })
+        },
+    );
+
+    match result {
+        Ok(session) => Ok(Some(session)),
+        Err(duckdb::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// ローカル日付ごとに集計し、order_byの並びで先頭の日を取得（同順位なら古い日）
+fn fetch_top_day(conn: &Connection, tz_offset_minutes: i32, order_by: &str) -> AppResult<Option<DayRecord>> {
+    let local_date = local_date_expr("started_at", tz_offset_minutes);
+    let sql = format!(
+        "SELECT CAST({local_date} AS VARCHAR) as date,
+                SUM(COALESCE(
+                    duration_seconds,
+                    EPOCH(ended_at::TIMESTAMP) - EPOCH(started_at::TIMESTAMP)
+                ))::BIGINT as total_seconds,
+                COUNT(*)::BIGINT as entry_count
+         FROM time_entries
+         WHERE ended_at IS NOT NULL
+         GROUP BY {local_date}
+         ORDER BY {order_by}, date
+         LIMIT 1"
+    );
+    let result = conn.query_row(&sql, [], |row| {
+        Ok(DayRecord {
+            date: row.get(0)?,
+            total_seconds: row.get(1)?,
+            entry_count: row.get(2)?,
+        })
+    });
+
+    match result {
+        Ok(day) => Ok(Some(day)),
+        Err(duckdb::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// 最長のエントリ・作業時間が最も長い日・記録件数が最も多い日をまとめて取得
+fn fetch_report_records(conn: &Connection, tz_offset_minutes: i32) -> AppResult<ReportRecords> {
+    Ok(ReportRecords {
+        longest_session: fetch_longest_session(conn)?,
+        busiest_day: fetch_top_day(conn, tz_offset_minutes, "total_seconds DESC")?,
+        most_entries_day: fetch_top_day(conn, tz_offset_minutes, "entry_count DESC, total_seconds DESC")?,
+    })
+}
+
+/// 同じローカル日付内で連続する完了済みエントリ間の、min_gap_secondsを超えるギャップを取得
+fn fetch_gaps(
+    conn: &Connection,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    min_gap_seconds: i64,
+    tz_offset_minutes: i32,
+) -> AppResult<Vec<DailyGaps>> {
+    let local_date = local_date_expr("started_at", tz_offset_minutes);
+    let mut sql = format!(
+        "SELECT CAST({local_date} AS VARCHAR) as date, started_at, ended_at
+         FROM time_entries
+         WHERE ended_at IS NOT NULL"
+    );
+    let mut params: Vec<DateTime<Utc>> = Vec::new();
+    if let Some(from) = from {
+        sql.push_str(" AND started_at >= ?");
+        params.push(from);
+    }
+    if let Some(to) = to {
+        sql.push_str(" AND started_at < ?");
+        params.push(to);
+    }
+    sql.push_str(" ORDER BY started_at ASC, ended_at ASC");
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(duckdb::params_from_iter(params), |row| {
+        let date: String = row.get(0)?;
+        let started_at: DateTime<Utc> = row.get(1)?;
+        let ended_at: DateTime<Utc> = row.get(2)?;
+        Ok((date, started_at, ended_at))
+    })?;
+
+    let mut days: Vec<DailyGaps> = Vec::new();
+    // 現在の日付と、その日のこれまでの最も遅い終了時刻
+    let mut current: Option<(String, DateTime<Utc>)> = None;
+
+    for row in rows {
+        let (date, started_at, ended_at) = row?;
+
+        if let Some((ref current_date, latest_end)) = current {
+            if *current_date == date {
+                // 重複するエントリはギャップが0以下になるため対象外
+                let gap_seconds = (started_at - latest_end).num_seconds();
+                if gap_seconds > 0 && gap_seconds > min_gap_seconds {
+                    let gap = Gap {
+                        started_at: latest_end,
+                        ended_at: started_at,
+                        gap_seconds,
+                    };
+                    match days.last_mut().filter(|d| d.date == date) {
+                        Some(day) => {
+                            day.total_gap_seconds += gap_seconds;
+                            day.gaps.push(gap);
+                        }
+                        None => days.push(DailyGaps {
+                            date: date.clone(),
+                            total_gap_seconds: gap_seconds,
+                            gaps: vec![gap],
+                        }),
+                    }
+                }
+                current = Some((date, latest_end.max(ended_at)));
+                continue;
+            }
+        }
+
+        current = Some((date, ended_at));
+    }
+
+    Ok(days)
+}
+
+/// 完了済みエントリの作業時間を開始時刻の曜日・時間帯ごとに集計する
+fn fetch_activity_distribution(
+    conn: &Connection,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    tz_offset_minutes: i32,
+) -> AppResult<ActivityDistribution> {
+    let local = local_timestamp_expr("started_at", tz_offset_minutes);
+    let mut sql = format!(
+        "SELECT dayofweek({local}) as dow, hour({local}) as hour,
+                SUM(COALESCE(
+                    duration_seconds,
+                    (EPOCH(ended_at::TIMESTAMP) - EPOCH(started_at::TIMESTAMP))::BIGINT
+                ))::BIGINT as total
+         FROM time_entries
+         WHERE ended_at IS NOT NULL"
+    );
+    let mut params: Vec<DateTime<Utc>> = Vec::new();
+    if let Some(from) = from {
+        sql.push_str(" AND started_at >= ?");
+        params.push(from);
+    }
+    if let Some(to) = to {
+        sql.push_str(" AND started_at < ?");
+        params.push(to);
+    }
+    sql.push_str(" GROUP BY dow, hour");
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(duckdb::params_from_iter(params), |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, i64>(2)?))
+    })?;
+
+    let mut distribution = ActivityDistribution {
+        by_day_of_week: vec![0; 7],
+        by_hour: vec![0; 24],
+    };
+    for row in rows {
+        let (dow, hour, total) = row?;
+        distribution.by_day_of_week[dow as usize] += total;
+        distribution.by_hour[hour as usize] += total;
+    }
+
+    Ok(distribution)
+}
+
+/// 基準時刻のローカル日付に開始したエントリを、計測中のものは基準時刻までとして集計する
+fn fetch_today_report(
+    conn: &Connection,
+    tz_offset_minutes: i32,
+    now: DateTime<Utc>,
+) -> AppResult<TodayReport> {
+    let today = (now + chrono::Duration::minutes(tz_offset_minutes as i64))
+        .format("%Y-%m-%d")
+        .to_string();
+    let local_date = local_date_expr("e.started_at", tz_offset_minutes);
+    let sql = format!(
+        r#"
+            SELECT
+                e.task_id,
+                COALESCE(t.name, '未分類') as task_name,
+                COALESCE(t.color, '#6b7280') as task_color,
+                SUM(
+                    CASE
+                        WHEN e.ended_at IS NOT NULL
+                        THEN COALESCE(
+                            e.duration_seconds,
+                            EPOCH(e.ended_at::TIMESTAMP) - EPOCH(e.started_at::TIMESTAMP)
+                        )
+                        ELSE GREATEST(EPOCH(?::TIMESTAMPTZ::TIMESTAMP) - EPOCH(e.started_at::TIMESTAMP), 0)
+                    END
+                )::BIGINT as total_seconds,
+                COUNT(*)::BIGINT as entry_count
+            FROM time_entries e
+            LEFT JOIN tasks t ON e.task_id = t.id
+            WHERE {local_date} = ?::DATE
+            GROUP BY e.task_id, t.name, t.color
+            ORDER BY total_seconds DESC
+        "#
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(duckdb::params![now, &today], |row| {
+        let task_id_str: Option<String> = row.get(0)?;
+        Ok(TaskSummary {
+            task_id: task_id_str.and_then(|s| Uuid::parse_str(&s).ok()),
+            task_name: row.get(1)?,
+            task_color: row.get(2)?,
+            total_seconds: row.get(3)?,
+            entry_count: row.get(4)?,
+            percentage: 0.0,
+        })
+    })?;
+
+    let mut task_summaries = Vec::new();
+    for row in rows {
+        task_summaries.push(row?);
+    }
+    let total_seconds = task_summaries.iter().map(|s| s.total_seconds).sum();
+    let entry_count = task_summaries.iter().map(|s| s.entry_count).sum();
+    fill_percentages(&mut task_summaries, total_seconds);
+
+    Ok(TodayReport {
+        date: today,
+        total_seconds,
+        entry_count,
+        task_summaries,
+    })
+}
+
+/// タスクの完了済みエントリがあるローカル日付を昇順で取得する
+fn fetch_task_active_days(
+    conn: &Connection,
+    task_id: &Uuid,
+    tz_offset_minutes: i32,
+) -> AppResult<Vec<NaiveDate>> {
+    let local_date = local_date_expr("started_at", tz_offset_minutes);
+    let mut stmt = conn.prepare(&format!(
+        "SELECT DISTINCT CAST({local_date} AS VARCHAR) as date
+         FROM time_entries
+         WHERE task_id = ? AND ended_at IS NOT NULL
+         ORDER BY date"
+    ))?;
+    let rows = stmt.query_map([task_id.to_string()], |row| row.get::<_, String>(0))?;
+
+    let mut days = Vec::new();
+    for row in rows {
+        let date = row?;
+        let day = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+            .map_err(|e| AppError::OperationFailed(format!("Invalid date {}: {}", date, e)))?;
+        days.push(day);
+    }
+    Ok(days)
+}
+
+/// 昇順の日付から現在と最長の連続日数を求める
+fn compute_streaks(days: &[NaiveDate], today: NaiveDate) -> TaskStreak {
+    let format_day = |day: NaiveDate| day.format("%Y-%m-%d").to_string();
+
+    // 連続する日付の区間（開始日, 終了日, 日数）
+    let mut runs: Vec<(NaiveDate, NaiveDate, i64)> = Vec::new();
+    for &day in days {
+        match runs.last_mut() {
+            Some((_, end, length)) if end.succ_opt() == Some(day) => {
+                *end = day;
+                *length += 1;
+            }
+            _ => runs.push((day, day, 1)),
+        }
+    }
+
+    let current = runs
+        .last()
+        .filter(|(_, end, _)| *end == today || end.succ_opt() == Some(today));
+    // max_by_keyは同じ値なら後の要素を返すため、同じ長さなら新しい区間になる
+    let longest = runs.iter().max_by_key(|(_, _, length)| *length);
+
+    TaskStreak {
+        current_days: current.map_or(0, |run| run.2),
+        current_start: current.map(|run| format_day(run.0)),
+        current_end: current.map(|run| format_day(run.1)),
+        longest_days: longest.map_or(0, |run| run.2),
+        longest_start: longest.map(|run| format_day(run.0)),
+        longest_end: longest.map(|run| format_day(run.1)),
+    }
+}
+
+/// 指定したタスクだけを対象に合計とタスク別の内訳を集計する（記録のないタスクは0件として含める）
+fn fetch_tasks_report(
+    conn: &Connection,
+    task_ids: &[Uuid],
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> AppResult<TasksReport> {
+    let mut sql = String::from(
+        r#"
+            SELECT
+                t.id,
+                t.name,
+                t.color,
+                COALESCE(SUM(COALESCE(
+                    e.duration_seconds,
+                    EPOCH(e.ended_at::TIMESTAMP) - EPOCH(e.started_at::TIMESTAMP)
+                )), 0)::BIGINT as total_seconds,
+                COUNT(e.id)::BIGINT as entry_count
+            FROM tasks t
+            LEFT JOIN time_entries e ON e.task_id = t.id AND e.ended_at IS NOT NULL
+        "#,
+    );
+    let mut params: Vec<Box<dyn duckdb::ToSql>> = Vec::new();
+    if let Some(from) = from {
+        sql.push_str(" AND e.started_at >= ?");
+        params.push(Box::new(from));
+    }
+    if let Some(to) = to {
+        sql.push_str(" AND e.started_at < ?");
+        params.push(Box::new(to));
+    }
+    let placeholders = vec!["?"; task_ids.len()].join(", ");
+    sql.push_str(&format!(" WHERE t.id IN ({})", placeholders));
+    params.extend(
+        task_ids
+            .iter()
+            .map(|id| Box::new(id.to_string()) as Box<dyn duckdb::ToSql>),
+    );
+    sql.push_str(
+        r#"
+            GROUP BY t.id, t.name, t.color
+            ORDER BY total_seconds DESC, t.name ASC
+        "#,
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+    let param_refs: Vec<&dyn duckdb::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+    let rows = stmt.query_map(param_refs.as_slice(), |row| {
+        let task_id_str: String = row.get(0)?;
+        Ok(TaskSummary {
+            task_id: Uuid::parse_str(&task_id_str).ok(),
+            task_name: row.get(1)?,
+            task_color: row.get(2)?,
+            total_seconds: row.get(3)?,
+            entry_count: row.get(4)?,
+            percentage: 0.0,
+        })
+    })?;
+
+    let mut task_summaries = Vec::new();
+    for row in rows {
+        task_summaries.push(row?);
+    }
+    let total_seconds = task_summaries.iter().map(|t| t.total_seconds).sum();
+    fill_percentages(&mut task_summaries, total_seconds);
+
+    Ok(TasksReport {
+        total_seconds,
+        entry_count: task_summaries.iter().map(|t| t.entry_count).sum(),
+        task_summaries,
+    })
+}
+
+/// 月次レポートを取得する（当月以外は計算結果をキャッシュする）
+///
+/// `rounding` を指定すると、記録ごとの作業時間を丸めてから集計する。
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn get_monthly_report(
+    state: tauri::State<AppState>,
+    year: i32,
+    month: u32,
+    folder_id: Option<String>,
+    tz_offset_minutes: Option<i32>,
+    exclude_task_ids: Option<Vec<Uuid>>,
+    daily_target_seconds: Option<i64>,
+    rounding: Option<Rounding>,
+) -> AppResult<MonthlyReport> {
+    if daily_target_seconds.is_some_and(|target| target <= 0) {
+        return Err(AppError::InvalidInput(
+            "Daily target must be greater than 0".to_string(),
+        ));
+    }
+    if let Some(rounding) = &rounding {
+        rounding.validate()?;
+    }
+
+    let folder_uuid = folder_id.and_then(|s| Uuid::parse_str(&s).ok());
+    let tz_offset_minutes = tz_offset_minutes.unwrap_or(0);
+    let mut exclude_task_ids = exclude_task_ids.unwrap_or_default();
+    exclude_task_ids.sort();
+    exclude_task_ids.dedup();
+
+    load_monthly_report(
+        &state,
+        ReportCacheKey {
+            year,
+            month,
+            tz_offset_minutes,
+            folder_id: folder_uuid,
+            exclude_task_ids,
+            daily_target_seconds,
+            rounding,
+        },
+    )
+}
+
+/// 月次レポートをキャッシュから取得し、なければ集計してキャッシュする（当月はキャッシュしない）
+fn load_monthly_report(state: &AppState, key: ReportCacheKey) -> AppResult<MonthlyReport> {
+    // 当月は記録が増え続けるためキャッシュしない
+    let cacheable = !is_current_month(key.year, key.month, key.tz_offset_minutes, Utc::now());
+    if cacheable {
+        if let Some(report) = state.report_cache.get(&key) {
+            return Ok(report);
+        }
+    }
+
+    let report = state.db.with_connection(|conn| {
+        let options = SummaryOptions {
+            folder_id: key.folder_id.as_ref(),
+            exclude_task_ids: &key.exclude_task_ids,
+            rounding: key.rounding,
+            ..Default::default()
+        };
+        fetch_monthly_report(
+            conn,
+            key.year,
+            key.month,
+            key.tz_offset_minutes,
+            &options,
+            key.daily_target_seconds,
+        )
+    })?;
+
+    if cacheable {
+        state.report_cache.insert(key, report.clone());
+    }
+    Ok(report)
+}
+
+/// 合算レポート用にアタッチするデータベースの別名の接頭辞
+const COMBINED_DB_ALIAS_PREFIX: &str = "combined_db_";
+
+/// 合算対象のデータベースファイルを正規化し、開けないファイルや重複を弾く
+fn resolve_combined_db_paths(
+    main_path: Option<&std::path::Path>,
+    other_db_paths: &[String],
+) -> AppResult<Vec<std::path::PathBuf>> {
+    let main_path = main_path.and_then(|path| std::fs::canonicalize(path).ok());
+    let mut resolved: Vec<std::path::PathBuf> = Vec::new();
+    for path in other_db_paths {
+        let canonical = std::fs::canonicalize(path).map_err(|e| {
+            AppError::InvalidInput(format!("Cannot open database file {}: {}", path, e))
+        })?;
+        if !canonical.is_file() {
+            return Err(AppError::InvalidInput(format!(
+                "Not a database file: {}",
+                path
+            )));
+        }
+        if main_path.as_ref() == Some(&canonical) {
+            return Err(AppError::InvalidInput(format!(
+                "Database file {} is the current database",
+                path
+            )));
+        }
+        if resolved.contains(&canonical) {
+            return Err(AppError::InvalidInput(format!(
+                "Database file {} is specified more than once",
+                path
+            )));
+        }
+        resolved.push(canonical);
+    }
+    Ok(resolved)
+}
+
+/// 合算レポートで集計する一時ビュー（既存のテーブルを隠さないよう別名にする）
+const COMBINED_RELATIONS: SummaryRelations = SummaryRelations {
+    entries: "combined_time_entries",
+    tasks: "combined_tasks",
+};
+
+/// アタッチしたデータベースと合わせた time_entries / tasks の一時ビューを作る
+///
+/// 同じIDの記録・タスクが複数のファイルにある場合は現在のデータベースのものを優先する。
+fn create_combined_views(conn: &Connection, aliases: &[String]) -> AppResult<()> {
+    let main_db: String = conn.query_row("SELECT current_database()", [], |row| row.get(0))?;
+    let main_db = format!("\"{}\"", main_db.replace('"', "\"\""));
+
+    let mut entry_sources = vec![format!(
+        "SELECT id, task_id, started_at, ended_at, duration_seconds, memo, 0 AS source
+         FROM {main_db}.main.time_entries"
+    )];
+    let mut task_sources = vec![format!(
+        "SELECT id, folder_id, name, color, 0 AS source FROM {main_db}.main.tasks"
+    )];
+    for (i, alias) in aliases.iter().enumerate() {
+        let source = i + 1;
+        entry_sources.push(format!(
+            "SELECT id, task_id, started_at, ended_at, NULL::BIGINT, memo, {source}
+             FROM {alias}.main.time_entries"
+        ));
+        task_sources.push(format!(
+            "SELECT id, NULL::VARCHAR, name, color, {source} FROM {alias}.main.tasks"
+        ));
+    }
+
+    conn.execute_batch(&format!(
+        "CREATE OR REPLACE TEMP VIEW {} AS
+         SELECT id, task_id, started_at, ended_at, duration_seconds, memo
+         FROM ({})
+         QUALIFY ROW_NUMBER() OVER (PARTITION BY id ORDER BY source) = 1;
+         CREATE OR REPLACE TEMP VIEW {} AS
+         SELECT id, folder_id, name, color
+         FROM ({})
+         QUALIFY ROW_NUMBER() OVER (PARTITION BY id ORDER BY source) = 1;",
+        COMBINED_RELATIONS.entries,
+        entry_sources.join(" UNION ALL "),
+        COMBINED_RELATIONS.tasks,
+        task_sources.join(" UNION ALL "),
+    ))?;
+    Ok(())
+}
+
+/// 合算レポート用の一時ビューとアタッチしたデータベースを後片付けするガード
+///
+/// `release` で片付けの失敗を呼び出し元に返す。途中のエラーやパニックで `release` されずに
+/// 破棄された場合も、共有の接続に残らないよう `Drop` で片付ける。
+struct CombinedSources<'a> {
+    conn: &'a Connection,
+    aliases: Vec<String>,
+    released: bool,
+}
+
+impl<'a> CombinedSources<'a> {
+    fn new(conn: &'a Connection) -> Self {
+        Self {
+            conn,
+            aliases: Vec::new(),
+            released: false,
+        }
+    }
+
+    /// 一時ビューを削除し、アタッチしたデータベースを切り離す
+    fn cleanup(&mut self) -> AppResult<()> {
+        self.conn.execute_batch(&format!(
+            "DROP VIEW IF EXISTS temp.main.{}; DROP VIEW IF EXISTS temp.main.{};",
+            COMBINED_RELATIONS.entries, COMBINED_RELATIONS.tasks
+        ))?;
+        while let Some(alias) = self.aliases.last() {
+            self.conn
+                .execute_batch(&format!("DETACH DATABASE IF EXISTS {}", alias))?;
+            self.aliases.pop();
+        }
+        Ok(())
+    }
+
+    fn release(mut self) -> AppResult<()> {
+        self.released = true;
+        self.cleanup()
+    }
+}
+
+impl Drop for CombinedSources<'_> {
+    fn drop(&mut self) {
+        if !self.released {
+            // 呼び出し元は既にエラーかパニックで抜けているため、片付けの失敗は返せない
+            let _ = self.cleanup();
+        }
+    }
+}
+
+/// データベースファイルを読み取り専用でアタッチする（アタッチできた別名をaliasesに追加する）
+fn attach_read_only(
+    conn: &Connection,
+    paths: &[std::path::PathBuf],
+    aliases: &mut Vec<String>,
+) -> AppResult<()> {
+    for (i, path) in paths.iter().enumerate() {
+        let alias = format!("{}{}", COMBINED_DB_ALIAS_PREFIX, i);
+        let escaped = path.to_string_lossy().replace('\'', "''");
+        conn.execute_batch(&format!("ATTACH '{}' AS {} (READ_ONLY)", escaped, alias))
+            .map_err(|e| {
+                AppError::InvalidInput(format!(
+                    "Cannot open database file {}: {}",
+                    path.display(),
+                    e
+                ))
+            })?;
+        aliases.push(alias);
+    }
+    Ok(())
+}
+
+/// 他のデータベースファイルを読み取り専用でアタッチし、現在のデータと合算した月次レポートを作る
+///
+/// 集計後は成功・失敗に関わらず一時ビューを削除し、アタッチしたファイルを切り離す。
+fn fetch_combined_monthly_report(
+    conn: &Connection,
+    main_path: Option<&std::path::Path>,
+    other_db_paths: &[String],
+    year: i32,
+    month: u32,
+    tz_offset_minutes: i32,
+) -> AppResult<MonthlyReport> {
+    let paths = resolve_combined_db_paths(main_path, other_db_paths)?;
+
+    let mut sources = CombinedSources::new(conn);
+    attach_read_only(conn, &paths, &mut sources.aliases)?;
+    create_combined_views(conn, &sources.aliases)?;
+    let options = SummaryOptions {
+        relations: COMBINED_RELATIONS,
+        ..Default::default()
+    };
+    let report = fetch_monthly_report(conn, year, month, tz_offset_minutes, &options, None);
+
+    // 集計のエラーを優先して返し、片付けのエラーはその次に返す
+    let cleanup = sources.release();
+    let report = report?;
+    cleanup?;
+    Ok(report)
+}
+
+/// 他のデータベースファイルの記録を合算した月次レポートを取得する（キャッシュしない）
+#[tauri::command]
+pub fn get_combined_report(
+    state: tauri::State<AppState>,
+    other_db_paths: Vec<String>,
+    year: i32,
+    month: u32,
+    tz_offset_minutes: Option<i32>,
+) -> AppResult<MonthlyReport> {
+    let tz_offset_minutes = tz_offset_minutes.unwrap_or(0);
+    let main_path = state.db.path();
+    state.db.with_connection(|conn| {
+        fetch_combined_monthly_report(
+            conn,
+            main_path,
+            &other_db_paths,
+            year,
+            month,
+            tz_offset_minutes,
+        )
+    })
+}
+
+/// 2つの月次レポートのタスク別・合計の差分（B - A）を計算する
+fn compute_report_diff(a: &MonthlyReport, b: &MonthlyReport) -> ReportDiff {
+    let mut task_deltas: Vec<TaskDelta> = Vec::new();
+    for summary in &b.task_summaries {
+        task_deltas.push(TaskDelta {
+            task_id: summary.task_id,
+            task_name: summary.task_name.clone(),
+            task_color: summary.task_color.clone(),
+            seconds_a: 0,
+            seconds_b: summary.total_seconds,
+            delta_seconds: 0,
+            entries_a: 0,
+            entries_b: summary.entry_count,
+            delta_entries: 0,
+        });
+    }
+    for summary in &a.task_summaries {
+        match task_deltas.iter_mut().find(|d| d.task_id == summary.task_id) {
+            Some(delta) => {
+                delta.seconds_a = summary.total_seconds;
+                delta.entries_a = summary.entry_count;
+            }
+            None => task_deltas.push(TaskDelta {
+                task_id: summary.task_id,
+                task_name: summary.task_name.clone(),
+                task_color: summary.task_color.clone(),
+                seconds_a: summary.total_seconds,
+                seconds_b: 0,
+                delta_seconds: 0,
+                entries_a: summary.entry_count,
+                entries_b: 0,
+                delta_entries: 0,
+            }),
+        }
+    }
+    for delta in &mut task_deltas {
+        delta.delta_seconds = delta.seconds_b - delta.seconds_a;
+        delta.delta_entries = delta.entries_b - delta.entries_a;
+    }
+
+    ReportDiff {
+        delta_total_seconds: b.total_seconds - a.total_seconds,
+        delta_entries: b.total_entries - a.total_entries,
+        task_deltas,
+    }
+}
+
+/// 2つの月（(年, 月)の組）の月次レポートと差分（B - A）を取得する
+#[tauri::command]
+pub fn compare_reports(
+    state: tauri::State<AppState>,
+    period_a: (i32, u32),
+    period_b: (i32, u32),
+    tz_offset_minutes: Option<i32>,
+) -> AppResult<ReportComparison> {
+    let tz_offset_minutes = tz_offset_minutes.unwrap_or(0);
+    let key = |(year, month): (i32, u32)| ReportCacheKey {
+        year,
+        month,
+        tz_offset_minutes,
+        folder_id: None,
+        exclude_task_ids: Vec::new(),
+        daily_target_seconds: None,
+        rounding: None,
+    };
+
+    let report_a = load_monthly_report(&state, key(period_a))?;
+    let report_b = load_monthly_report(&state, key(period_b))?;
+    let diff = compute_report_diff(&report_a, &report_b);
+
+    Ok(ReportComparison {
+        period_a: report_a,
+        period_b: report_b,
+        diff,
+    })
+}
+
+/// 月次レポートのキャッシュを破棄する
+#[tauri::command]
+pub fn clear_report_cache(state: tauri::State<AppState>) {
+    state.report_cache.clear();
+}
+
+/// 利用可能な月のリストを取得する
+#[tauri::command]
+pub fn get_available_months(
+    state: tauri::State<AppState>,
+    tz_offset_minutes: Option<i32>,
+) -> AppResult<Vec<(i32, u32)>> {
+    let tz_offset_minutes = tz_offset_minutes.unwrap_or(0);
+    state
+        .db
+        .with_connection(|conn| fetch_available_months(conn, tz_offset_minutes))
+}
+
+/// 記録のあるすべての月の月次レポートを月ごとのCSVファイルに書き出す
+#[tauri::command]
+pub fn export_all_monthly_reports(
+    state: tauri::State<AppState>,
+    output_dir: String,
+    tz_offset_minutes: Option<i32>,
+) -> AppResult<Vec<String>> {
+    let tz_offset_minutes = tz_offset_minutes.unwrap_or(0);
+    state.db.with_connection(|conn| {
+        write_all_monthly_report_csvs(conn, std::path::Path::new(&output_dir), tz_offset_minutes)
+    })
+}
+
+/// 週次の集計をチャットに貼り付けられるテキストとして取得する（weekはISO週番号）
+#[tauri::command]
+pub fn get_weekly_digest_text(
+    state: tauri::State<AppState>,
+    year: i32,
+    week: u32,
+    tz_offset_minutes: Option<i32>,
+) -> AppResult<String> {
+    let tz_offset_minutes = tz_offset_minutes.unwrap_or(0);
+    state
+        .db
+        .with_connection(|conn| fetch_weekly_digest_text(conn, year, week, tz_offset_minutes))
+}
+
+/// 月別のアクティブタスク数を取得する（from/toは "YYYY-MM" 形式）
+#[tauri::command]
+pub fn get_active_task_counts(
+    state: tauri::State<AppState>,
+    from: String,
+    to: String,
+    tz_offset_minutes: Option<i32>,
+) -> AppResult<Vec<MonthlyActiveTasks>> {
+    let tz_offset_minutes = tz_offset_minutes.unwrap_or(0);
+    let from_date = parse_year_month(&from)?;
+    let to_date = parse_year_month(&to)?;
+    if from_date > to_date {
+        return Err(AppError::InvalidInput(format!(
+            "Invalid range: {} is after {}",
+            from, to
+        )));
+    }
+
+    state
+        .db
+        .with_connection(|conn| fetch_active_task_counts(conn, from_date, to_date, tz_offset_minutes))
+}
+
+/// 最長の連続作業ブロックを取得する
+#[tauri::command]
+pub fn get_longest_work_block(
+    state: tauri::State<AppState>,
+    merge_gap_seconds: i64,
+) -> AppResult<Option<WorkBlock>> {
+    if merge_gap_seconds < 0 {
+        return Err(AppError::InvalidInput(
+            "merge_gap_seconds must not be negative".to_string(),
+        ));
+    }
+
+    state
+        .db
+        .with_connection(|conn| fetch_longest_work_block(conn, merge_gap_seconds))
+}
+
+/// 請求レポートを取得する（from/toはRFC3339形式、未指定なら全期間）
+#[tauri::command]
+pub fn get_billing_report(
+    state: tauri::State<AppState>,
+    from: Option<String>,
+    to: Option<String>,
+) -> AppResult<BillingReport> {
+    let from = parse_optional_datetime(from, "from")?;
+    let to = parse_optional_datetime(to, "to")?;
+
+    state
+        .db
+        .with_connection(|conn| fetch_billing_report(conn, from, to))
+}
+
+/// 作業時間のヒストグラムを取得する（bucketsは秒単位の境界値、例: [900, 1800, 3600, 7200]）
+#[tauri::command]
+pub fn get_duration_histogram(
+    state: tauri::State<AppState>,
+    from: Option<String>,
+    to: Option<String>,
+    buckets: Vec<i64>,
+) -> AppResult<Vec<DurationBucket>> {
+    let from = parse_optional_datetime(from, "from")?;
+    let to = parse_optional_datetime(to, "to")?;
+    validate_buckets(&buckets)?;
+
+    state
+        .db
+        .with_connection(|conn| fetch_duration_histogram(conn, from, to, &buckets))
+}
+
+/// 累積作業時間のタイムラインを取得する（from/toはRFC3339形式、未指定なら全期間）
+#[tauri::command]
+pub fn get_cumulative_timeline(
+    state: tauri::State<AppState>,
+    from: Option<String>,
+    to: Option<String>,
+) -> AppResult<Vec<TimelinePoint>> {
+    let from = parse_optional_datetime(from, "from")?;
+    let to = parse_optional_datetime(to, "to")?;
+
+    state
+        .db
+        .with_connection(|conn| fetch_cumulative_timeline(conn, from, to))
+}
+
+/// タスクの色別の作業時間を取得する（from/toはRFC3339形式、未指定なら全期間）
+#[tauri::command]
+pub fn get_color_report(
+    state: tauri::State<AppState>,
+    from: Option<String>,
+    to: Option<String>,
+) -> AppResult<Vec<ColorSummary>> {
+    let from = parse_optional_datetime(from, "from")?;
+    let to = parse_optional_datetime(to, "to")?;
+
+    state
+        .db
+        .with_connection(|conn| fetch_color_summaries(conn, from, to))
+}
+
+/// 統計ページ用の最高記録を取得する（完了済みエントリのみが対象）
+#[tauri::command]
+pub fn get_records(
+    state: tauri::State<AppState>,
+    tz_offset_minutes: Option<i32>,
+) -> AppResult<ReportRecords> {
+    let tz_offset_minutes = tz_offset_minutes.unwrap_or(0);
+    state
+        .db
+        .with_connection(|conn| fetch_report_records(conn, tz_offset_minutes))
+}
+
+/// 指定した複数のタスクをまとめた集計を取得する（from/toはRFC3339形式、未指定なら全期間）
+#[tauri::command]
+pub fn get_tasks_report(
+    state: tauri::State<AppState>,
+    task_ids: Vec<String>,
+    from: Option<String>,
+    to: Option<String>,
+) -> AppResult<TasksReport> {
+    if task_ids.is_empty() {
+        return Err(AppError::InvalidInput(
+            "At least one task id is required".to_string(),
+        ));
+    }
+    let task_uuids = task_ids
+        .iter()
+        .map(|id| {
+            Uuid::parse_str(id).map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", id)))
+        })
+        .collect::<AppResult<Vec<Uuid>>>()?;
+    let from = parse_optional_datetime(from, "from")?;
+    let to = parse_optional_datetime(to, "to")?;
+
+    state
+        .db
+        .with_connection(|conn| fetch_tasks_report(conn, &task_uuids, from, to))
+}
+
+/// エントリ間の記録されていない時間を日別に取得する
+#[tauri::command]
+pub fn get_gaps_report(
+    state: tauri::State<AppState>,
+    from: Option<String>,
+    to: Option<String>,
+    min_gap_seconds: i64,
+    tz_offset_minutes: Option<i32>,
+) -> AppResult<Vec<DailyGaps>> {
+    let from = parse_optional_datetime(from, "from")?;
+    let to = parse_optional_datetime(to, "to")?;
+    let tz_offset_minutes = tz_offset_minutes.unwrap_or(0);
+
+    state.db.with_connection(|conn| {
+        fetch_gaps(conn, from, to, min_gap_seconds.max(0), tz_offset_minutes)
+    })
+}
+
+/// タスクの現在と最長の連続記録日数を取得する
+#[tauri::command]
+pub fn get_task_streak(
+    state: tauri::State<AppState>,
+    task_id: String,
+    tz_offset_minutes: Option<i32>,
+) -> AppResult<TaskStreak> {
+    let task_uuid = Uuid::parse_str(&task_id)
+        .map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", task_id)))?;
+    let tz_offset_minutes = tz_offset_minutes.unwrap_or(0);
+    let today = (Utc::now() + chrono::Duration::minutes(tz_offset_minutes as i64)).date_naive();
+
+    state.db.with_connection(|conn| {
+        fetch_task_by_id(conn, &task_uuid)?;
+        let days = fetch_task_active_days(conn, &task_uuid, tz_offset_minutes)?;
+        Ok(compute_streaks(&days, today))
+    })
+}
+
+/// 今日のここまでの作業時間を取得する（計測中のエントリは現在までの経過秒数を含む）
+#[tauri::command]
+pub fn get_today_report(
+    state: tauri::State<AppState>,
+    tz_offset_minutes: Option<i32>,
+) -> AppResult<TodayReport> {
+    let tz_offset_minutes = tz_offset_minutes.unwrap_or(0);
+
+    state
+        .db
+        .with_connection(|conn| fetch_today_report(conn, tz_offset_minutes, Utc::now()))
+}
+
+/// 曜日別・時間帯別の作業時間の分布を取得する（from/toはRFC3339形式、未指定なら全期間）
+#[tauri::command]
+pub fn get_activity_distribution(
+    state: tauri::State<AppState>,
+    from: Option<String>,
+    to: Option<String>,
+    tz_offset_minutes: Option<i32>,
+) -> AppResult<ActivityDistribution> {
+    let from = parse_optional_datetime(from, "from")?;
+    let to = parse_optional_datetime(to, "to")?;
+    let tz_offset_minutes = tz_offset_minutes.unwrap_or(0);
+
+    state
+        .db
+        .with_connection(|conn| fetch_activity_distribution(conn, from, to, tz_offset_minutes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+
+    fn create_test_db() -> Database {
+        Database::new_in_memory().unwrap()
+    }
+
+    mod monthly_report_tests {
+        use super::*;
+
+        #[test]
+        fn タスク別の割合は合計に対する百分率になり合計0なら0になる() {
+            let summary = |total_seconds: i64| TaskSummary {
+                task_id: None,
+                task_name: "タスク".to_string(),
+                task_color: "#000000".to_string(),
+                total_seconds,
+                entry_count: 1,
+                percentage: 0.0,
+            };
+
+            let mut summaries = vec![summary(5400), summary(1800)];
+            fill_percentages(&mut summaries, 7200);
+            assert_eq!(summaries[0].percentage, 75.0);
+            assert_eq!(summaries[1].percentage, 25.0);
+
+            let mut empty = vec![summary(0)];
+            fill_percentages(&mut empty, 0);
+            assert_eq!(empty[0].percentage, 0.0);
+        }
+
+        #[test]
+        fn 空のデータベースから月次レポートを取得すると空のレポートが返る() {
+            let db = create_test_db();
+
+            let report = db
+                .with_connection(|conn| fetch_monthly_report(conn, 2024, 12, 0, &SummaryOptions::default(), None))
+                .unwrap();
+
+            assert_eq!(report.year, 2024);
+            assert_eq!(report.month, 12);
+            assert_eq!(report.total_seconds, 0);
+            assert_eq!(report.total_entries, 0);
+            assert!(report.task_summaries.is_empty());
+            assert!(report.daily_summaries.is_empty());
+        }
+
+        #[test]
+        fn 時間記録がある月のレポートを取得できる() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                // タスクを作成
+                conn.execute(
+                    "INSERT INTO tasks (id, name, color, created_at, updated_at)
+                     VALUES ('task-1', 'テストタスク', '#ff0000', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                    [],
+                )?;
+
+                // 時間記録を作成（1時間）
+                conn.execute(
+                    "INSERT INTO time_entries (id, task_id, started_at, ended_at, created_at, updated_at)
+                     VALUES ('entry-1', 'task-1',
+                             '2024-12-15 09:00:00+00',
+                             '2024-12-15 10:00:00+00',
+                             CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                    [],
+                )?;
+
+                Ok(())
+            })
+            .unwrap();
+
+            let report = db
+                .with_connection(|conn| fetch_monthly_report(conn, 2024, 12, 0, &SummaryOptions::default(), None))
+                .unwrap();
+
+            assert_eq!(report.total_seconds, 3600);
+            assert_eq!(report.total_entries, 1);
+            assert_eq!(report.working_days, 1);
+            assert_eq!(report.task_summaries.len(), 1);
+            assert_eq!(report.task_summaries[0].task_name, "テストタスク");
+        }
+
+        #[test]
+        fn タスク別に集計される() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                conn.execute(
+                    "INSERT INTO tasks (id, name, color, created_at, updated_at) VALUES
+                     ('task-1', 'タスクA', '#ff0000', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     ('task-2', 'タスクB', '#00ff00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                    [],
+                )?;
+
+                conn.execute(
+                    "INSERT INTO time_entries (id, task_id, started_at, ended_at, created_at, updated_at) VALUES
+                     ('entry-1', 'task-1', '2024-12-15 09:00:00+00', '2024-12-15 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     ('entry-2', 'task-1', '2024-12-15 11:00:00+00', '2024-12-15 12:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     ('entry-3', 'task-2', '2024-12-15 14:00:00+00', '2024-12-15 15:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                    [],
+                )?;
+
+                Ok(())
+            })
+            .unwrap();
+
+            let report = db
+                .with_connection(|conn| fetch_monthly_report(conn, 2024, 12, 0, &SummaryOptions::default(), None))
+                .unwrap();
+
+            assert_eq!(report.task_summaries.len(), 2);
+            // タスクAが2時間で最初
+            assert_eq!(report.task_summaries[0].task_name, "タスクA");
+            assert_eq!(report.task_summaries[0].total_seconds, 7200);
+            assert_eq!(report.task_summaries[0].entry_count, 2);
+            // タスクBが1時間
+            assert_eq!(report.task_summaries[1].task_name, "タスクB");
+            assert_eq!(report.task_summaries[1].total_seconds, 3600);
+        }
+
+        #[test]
+        fn 日別に集計される() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                conn.execute(
+                    "INSERT INTO time_entries (id, started_at, ended_at, created_at, updated_at) VALUES
+                     ('entry-1', '2024-12-15 09:00:00+00', '2024-12-15 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     ('entry-2', '2024-12-16 09:00:00+00', '2024-12-16 11:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                    [],
+                )?;
+                Ok(())
+            })
+            .unwrap();
+
+            let report = db
+                .with_connection(|conn| fetch_monthly_report(conn, 2024, 12, 0, &SummaryOptions::default(), None))
+                .unwrap();
+
+            assert_eq!(report.daily_summaries.len(), 2);
+            assert_eq!(report.daily_summaries[0].date, "2024-12-15");
+            assert_eq!(report.daily_summaries[0].total_seconds, 3600);
+            assert_eq!(report.daily_summaries[1].date, "2024-12-16");
+            assert_eq!(report.daily_summaries[1].total_seconds, 7200);
+        }
+
+        #[test]
+        fn 未分類のエントリも集計される() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                conn.execute(
+                    "INSERT INTO time_entries (id, started_at, ended_at, created_at, updated_at)
+                     VALUES ('entry-1', '2024-12-15 09:00:00+00', '2024-12-15 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                    [],
+                )?;
+                Ok(())
+            })
+            .unwrap();
+
+            let report = db
+                .with_connection(|conn| fetch_monthly_report(conn, 2024, 12, 0, &SummaryOptions::default(), None))
+                .unwrap();
+
+            assert_eq!(report.task_summaries.len(), 1);
+            assert_eq!(report.task_summaries[0].task_name, "未分類");
+        }
+    }
+
+    mod combined_report_tests {
+        use super::*;
+
+        /// 1件のタスクと記録を持つデータベースファイルを作成して閉じる
+        fn create_db_file(path: &std::path::Path, task_id: &str, task_name: &str, entry_id: &str) {
+            let db = Database::open(path).unwrap();
+            db.with_connection(|conn| {
+                conn.execute(
+                    "INSERT INTO tasks (id, name, color, created_at, updated_at)
+                     VALUES (?, ?, '#00ff00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                    [task_id, task_name],
+                )?;
+                conn.execute(
+                    "INSERT INTO time_entries (id, task_id, started_at, ended_at, created_at, updated_at)
+                     VALUES (?, ?, '2024-12-16 09:00:00+00', '2024-12-16 11:00:00+00',
+                             CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                    [entry_id, task_id],
+                )?;
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        fn insert_main_entry(db: &Database) {
+            db.with_connection(|conn| {
+                conn.execute(
+                    "INSERT INTO tasks (id, name, color, created_at, updated_at)
+                     VALUES ('task-main', '仕事', '#ff0000', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                    [],
+                )?;
+                conn.execute(
+                    "INSERT INTO time_entries (id, task_id, started_at, ended_at, created_at, updated_at)
+                     VALUES ('entry-main', 'task-main', '2024-12-15 09:00:00+00', '2024-12-15 10:00:00+00',
+                             CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                    [],
+                )?;
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 他のデータベースの記録を合算したレポートを返し終了後は切り離す() {
+            let db = create_test_db();
+            insert_main_entry(&db);
+            let temp_dir = tempfile::tempdir().unwrap();
+            let other = temp_dir.path().join("personal.duckdb");
+            create_db_file(&other, "task-personal", "個人", "entry-personal");
+
+            let report = db
+                .with_connection(|conn| {
+                    fetch_combined_monthly_report(
+                        conn,
+                        None,
+                        &[other.to_string_lossy().to_string()],
+                        2024,
+                        12,
+                        0,
+                    )
+                })
+                .unwrap();
+
+            assert_eq!(report.total_seconds, 3 * 3600);
+            assert_eq!(report.total_entries, 2);
+            assert_eq!(report.working_days, 2);
+            let names: Vec<&str> = report.task_summaries.iter().map(|t| t.task_name.as_str()).collect();
+            assert_eq!(names, vec!["個人", "仕事"]);
+
+            // 元のテーブルだけが参照される状態に戻っている
+            let single = db
+                .with_connection(|conn| fetch_monthly_report(conn, 2024, 12, 0, &SummaryOptions::default(), None))
+                .unwrap();
+            assert_eq!(single.total_seconds, 3600);
+            let attached: i64 = db
+                .with_connection(|conn| {
+                    Ok(conn.query_row(
+                        "SELECT COUNT(*) FROM duckdb_databases() WHERE database_name LIKE 'combined_db_%'",
+                        [],
+                        |row| row.get(0),
+                    )?)
+                })
+                .unwrap();
+            assert_eq!(attached, 0);
+        }
+
+        /// 合算用に作った一時ビューの数
+        fn count_temp_views(conn: &Connection) -> AppResult<i64> {
+            Ok(conn.query_row(
+                "SELECT COUNT(*) FROM duckdb_views() WHERE temporary AND view_name LIKE 'combined_%'",
+                [],
+                |row| row.get(0),
+            )?)
+        }
+
+        #[test]
+        fn 集計に失敗しても一時ビューとアタッチは残らず既存のテーブルに書き込める() {
+            let db = create_test_db();
+            insert_main_entry(&db);
+            let temp_dir = tempfile::tempdir().unwrap();
+            let other = temp_dir.path().join("personal.duckdb");
+            create_db_file(&other, "task-personal", "個人", "entry-personal");
+
+            db.with_connection(|conn| {
+                // アタッチとビューの作成後に集計が失敗する
+                let result = fetch_combined_monthly_report(
+                    conn,
+                    None,
+                    &[other.to_string_lossy().to_string()],
+                    2024,
+                    13,
+                    0,
+                );
+                assert!(matches!(result, Err(AppError::InvalidInput(_))));
+                assert_eq!(count_temp_views(conn)?, 0);
+                let attached: i64 = conn.query_row(
+                    "SELECT COUNT(*) FROM duckdb_databases() WHERE database_name LIKE 'combined_db_%'",
+                    [],
+                    |row| row.get(0),
+                )?;
+                assert_eq!(attached, 0);
+
+                conn.execute(
+                    "INSERT INTO time_entries (id, started_at, ended_at, created_at, updated_at)
+                     VALUES ('entry-new', '2024-12-20 09:00:00+00', '2024-12-20 09:30:00+00',
+                             CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                    [],
+                )?;
+                let report = fetch_monthly_report(conn, 2024, 12, 0, &SummaryOptions::default(), None)?;
+                assert_eq!(report.total_seconds, 3600 + 1800);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 同じファイルの重複指定や開けないファイルはエラーになる() {
+            let db = create_test_db();
+            insert_main_entry(&db);
+            let temp_dir = tempfile::tempdir().unwrap();
+            let other = temp_dir.path().join("personal.duckdb");
+            create_db_file(&other, "task-personal", "個人", "entry-personal");
+            let other = other.to_string_lossy().to_string();
+            let broken = temp_dir.path().join("broken.duckdb");
+            std::fs::write(&broken, b"not a database").unwrap();
+
+            db.with_connection(|conn| {
+                let duplicated =
+                    fetch_combined_monthly_report(conn, None, &[other.clone(), other.clone()], 2024, 12, 0);
+                assert!(matches!(duplicated, Err(AppError::InvalidInput(_))));
+
+                let missing = fetch_combined_monthly_report(
+                    conn,
+                    None,
+                    &[temp_dir.path().join("missing.duckdb").to_string_lossy().to_string()],
+                    2024,
+                    12,
+                    0,
+                );
+                assert!(matches!(missing, Err(AppError::InvalidInput(_))));
+
+                let unreadable = fetch_combined_monthly_report(
+                    conn,
+                    None,
+                    &[other.clone(), broken.to_string_lossy().to_string()],
+                    2024,
+                    12,
+                    0,
+                );
+                assert!(matches!(unreadable, Err(AppError::InvalidInput(_))));
+
+                // 失敗後もアタッチやビューが残っていない
+                let report = fetch_monthly_report(conn, 2024, 12, 0, &SummaryOptions::default(), None)?;
+                assert_eq!(report.total_seconds, 3600);
+                assert_eq!(count_temp_views(conn)?, 0);
+                let attached: i64 = conn.query_row(
+                    "SELECT COUNT(*) FROM duckdb_databases() WHERE database_name LIKE 'combined_db_%'",
+                    [],
+                    |row| row.get(0),
+                )?;
+                assert_eq!(attached, 0);
+                Ok(())
+            })
+            .unwrap();
+        }
+    }
+
+    mod rounding_tests {
+        use super::*;
+
+        /// 2024-12-10に7分の記録を1件入れたデータベースで12月のレポートを丸めて集計する
+        fn report_with(mode: RoundMode) -> MonthlyReport {
+            let db = create_test_db();
+            db.with_connection(|conn| {
+                conn.execute_batch(
+                    "INSERT INTO time_entries (id, started_at, ended_at, created_at, updated_at) VALUES
+                     ('e1', '2024-12-10 09:00:00+00', '2024-12-10 09:07:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP);",
+                )?;
+                let options = SummaryOptions {
+                    rounding: Some(Rounding {
+                        increment_minutes: 15,
+                        mode,
+                    }),
+                    ..Default::default()
+                };
+                fetch_monthly_report(conn, 2024, 12, 0, &options, None)
+            })
+            .unwrap()
+        }
+
+        #[test]
+        fn 切り上げでは7分の記録が15分になる() {
+            let report = report_with(RoundMode::Up);
+            assert_eq!(report.total_seconds, 15 * 60);
+            assert_eq!(report.task_summaries[0].total_seconds, 15 * 60);
+            assert_eq!(report.daily_summaries[0].total_seconds, 15 * 60);
+        }
+
+        #[test]
+        fn 四捨五入では7分の記録が0分になる() {
+            let report = report_with(RoundMode::Nearest);
+            assert_eq!(report.total_seconds, 0);
+            assert_eq!(report.daily_summaries[0].total_seconds, 0);
+        }
+
+        #[test]
+        fn 切り捨てでは7分の記録が0分になる() {
+            let report = report_with(RoundMode::Down);
+            assert_eq!(report.total_seconds, 0);
+            assert_eq!(report.daily_summaries[0].total_seconds, 0);
+        }
+
+        #[test]
+        fn 四捨五入ではちょうど半分は切り上げる() {
+            let rounding = Rounding {
+                increment_minutes: 15,
+                mode: RoundMode::Nearest,
+            };
+            let db = create_test_db();
+            let rounded: i64 = db
+                .with_connection(|conn| {
+                    Ok(conn.query_row(
+                        &format!("SELECT {}::BIGINT", rounding.apply_expr("450")),
+                        [],
+                        |row| row.get(0),
+                    )?)
+                })
+                .unwrap();
+            assert_eq!(rounded, 15 * 60);
+        }
+
+        #[test]
+        fn 丸め単位が0分の設定はエラーになる() {
+            let rounding = Rounding {
+                increment_minutes: 0,
+                mode: RoundMode::Up,
+            };
+            assert!(matches!(rounding.validate(), Err(AppError::InvalidInput(_))));
+        }
+    }
+
+    mod exclude_tasks_tests {
+        use super::*;
+
+        #[test]
+        fn 除外したタスクは内訳にも合計にも含まれない() {
+            let db = create_test_db();
+            let work_id = Uuid::new_v4();
+            let lunch_id = Uuid::new_v4();
+
+            db.with_connection(|conn| {
+                conn.execute(
+                    "INSERT INTO tasks (id, name, color, created_at, updated_at) VALUES
+                     (?, '作業', '#ff0000', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     (?, '昼休み', '#00ff00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                    [work_id.to_string(), lunch_id.to_string()],
+                )?;
+                conn.execute(
+                    "INSERT INTO time_entries (id, task_id, started_at, ended_at, created_at, updated_at) VALUES
+                     ('e1', ?, '2024-12-02 09:00:00+00', '2024-12-02 11:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     ('e2', ?, '2024-12-02 12:00:00+00', '2024-12-02 13:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     ('e3', ?, '2024-12-03 12:00:00+00', '2024-12-03 13:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     ('e4', NULL, '2024-12-04 09:00:00+00', '2024-12-04 09:30:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                    [work_id.to_string(), lunch_id.to_string(), lunch_id.to_string()],
+                )?;
+                Ok(())
+            })
+            .unwrap();
+
+            let all = db
+                .with_connection(|conn| fetch_monthly_report(conn, 2024, 12, 0, &SummaryOptions::default(), None))
+                .unwrap();
+            assert_eq!(all.total_seconds, 7200 + 3600 + 3600 + 1800);
+            assert_eq!(all.working_days, 3);
+
+            let report = db
+                .with_connection(|conn| {
+                    let options = SummaryOptions {
+                        exclude_task_ids: &[lunch_id],
+                        ..Default::default()
+                    };
+                    fetch_monthly_report(conn, 2024, 12, 0, &options, None)
+                })
+                .unwrap();
+
+            // 未分類のエントリは除外されない
+            assert_eq!(report.total_seconds, 7200 + 1800);
+            assert_eq!(report.working_days, 2);
+            assert_eq!(report.average_seconds_per_day, (7200 + 1800) / 2);
+            assert!(report.task_summaries.iter().all(|t| t.task_id != Some(lunch_id)));
+        }
+
+        #[test]
+        fn 目標を指定すると記録のない日も含めて達成状況が付く() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                conn.execute(
+                    "INSERT INTO time_entries (id, started_at, ended_at, created_at, updated_at) VALUES
+                     ('e1', '2024-02-01 09:00:00+00', '2024-02-01 17:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     ('e2', '2024-02-02 09:00:00+00', '2024-02-02 12:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                    [],
+                )?;
+                Ok(())
+            })
+            .unwrap();
+
+            let target = 6 * 3600;
+            let report = db
+                .with_connection(|conn| fetch_monthly_report(conn, 2024, 2, 0, &SummaryOptions::default(), Some(target)))
+                .unwrap();
+
+            // 2024年2月はうるう年で29日
+            assert_eq!(report.daily_summaries.len(), 29);
+            assert_eq!(report.working_days, 2);
+
+            let first = &report.daily_summaries[0];
+            assert_eq!(first.date, "2024-02-01");
+            assert_eq!(first.met_target, Some(true));
+            assert_eq!(first.delta_seconds, Some(2 * 3600));
+
+            let second = &report.daily_summaries[1];
+            assert_eq!(second.met_target, Some(false));
+            assert_eq!(second.delta_seconds, Some(-3 * 3600));
+
+            let empty = &report.daily_summaries[28];
+            assert_eq!(empty.date, "2024-02-29");
+            assert_eq!(empty.entry_count, 0);
+            assert_eq!(empty.met_target, Some(false));
+            assert_eq!(empty.delta_seconds, Some(-target));
+        }
+
+        #[test]
+        fn 目標を指定しない場合は記録のある日のみで達成状況は付かない() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                conn.execute(
+                    "INSERT INTO time_entries (id, started_at, ended_at, created_at, updated_at) VALUES
+                     ('e1', '2024-02-01 09:00:00+00', '2024-02-01 17:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                    [],
+                )?;
+                Ok(())
+            })
+            .unwrap();
+
+            let report = db
+                .with_connection(|conn| fetch_monthly_report(conn, 2024, 2, 0, &SummaryOptions::default(), None))
+                .unwrap();
+
+            assert_eq!(report.daily_summaries.len(), 1);
+            assert_eq!(report.daily_summaries[0].met_target, None);
+            assert_eq!(report.daily_summaries[0].delta_seconds, None);
+        }
+    }
+
+    mod timezone_tests {
+        use super::*;
+
+        #[test]
+        fn タイムゾーンオフセットを指定すると深夜のエントリがローカルの日付と月に集計される() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                // JSTでは 2025-01-01 00:30 (UTCでは 2024-12-31 15:30)
+                conn.execute(
+                    "INSERT INTO time_entries (id, started_at, ended_at, created_at, updated_at) VALUES
+                     ('entry-1', '2024-12-31 15:30:00+00', '2024-12-31 16:30:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                    [],
+                )?;
+                Ok(())
+            })
+            .unwrap();
+
+            let utc_december = db
+                .with_connection(|conn| fetch_monthly_report(conn, 2024, 12, 0, &SummaryOptions::default(), None))
+                .unwrap();
+            let jst_december = db
+                .with_connection(|conn| fetch_monthly_report(conn, 2024, 12, 540, &SummaryOptions::default(), None))
+                .unwrap();
+            let jst_january = db
+                .with_connection(|conn| fetch_monthly_report(conn, 2025, 1, 540, &SummaryOptions::default(), None))
+                .unwrap();
+
+            assert_eq!(utc_december.total_seconds, 3600);
+            assert_eq!(utc_december.daily_summaries[0].date, "2024-12-31");
+            assert_eq!(jst_december.total_seconds, 0);
+            assert_eq!(jst_january.total_seconds, 3600);
+            assert_eq!(jst_january.daily_summaries[0].date, "2025-01-01");
+
+            let months = db
+                .with_connection(|conn| fetch_available_months(conn, 540))
+                .unwrap();
+            assert_eq!(months, vec![(2025, 1)]);
+        }
+
+        #[test]
+        fn 東西のオフセットで深夜前後のエントリが期待するローカル日付に集計される() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                // e1: JSTでは 2025-03-02 00:10、UTCでは 2025-03-01 15:10
+                // e2: ESTでは 2025-03-01 23:50、UTCでは 2025-03-02 04:50
+                conn.execute_batch(
+                    "INSERT INTO time_entries (id, started_at, ended_at, created_at, updated_at) VALUES
+                     ('e1', '2025-03-01 15:10:00+00', '2025-03-01 15:40:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     ('e2', '2025-03-02 04:50:00+00', '2025-03-02 05:50:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP);",
+                )?;
+                Ok(())
+            })
+            .unwrap();
+
+            let daily = |tz_offset_minutes: i32| {
+                db.with_connection(|conn| {
+                    fetch_monthly_report(conn, 2025, 3, tz_offset_minutes, &SummaryOptions::default(), None)
+                })
+                .unwrap()
+                .daily_summaries
+                .into_iter()
+                .map(|d| (d.date, d.total_seconds))
+                .collect::<Vec<_>>()
+            };
+
+            assert_eq!(daily(540), vec![("2025-03-02".to_string(), 90 * 60)]);
+            assert_eq!(daily(-300), vec![("2025-03-01".to_string(), 90 * 60)]);
+            assert_eq!(
+                daily(0),
+                vec![
+                    ("2025-03-01".to_string(), 30 * 60),
+                    ("2025-03-02".to_string(), 60 * 60),
+                ]
+            );
+        }
+
+        #[test]
+        fn 負のオフセットでは月初の深夜のエントリが前月に数えられる() {
+            let db = create_test_db();
+
+            let counts = db
+                .with_connection(|conn| {
+                    // ESTでは 2025-01-31 22:00、UTCでは 2025-02-01 03:00
+                    conn.execute_batch(
+                        "INSERT INTO time_entries (id, task_id, started_at, ended_at, created_at, updated_at) VALUES
+                         ('e1', 't1', '2025-02-01 03:00:00+00', '2025-02-01 04:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP);",
+                    )?;
+                    let from = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+                    let to = NaiveDate::from_ymd_opt(2025, 2, 1).unwrap();
+                    fetch_active_task_counts(conn, from, to, -300)
+                })
+                .unwrap();
+
+            assert_eq!(counts[0].task_count, 1);
+            assert_eq!(counts[1].task_count, 0);
+        }
+    }
+
+    mod tasks_report_tests {
+        use super::*;
+
+        #[test]
+        fn 指定したタスクだけを合計しタスク別の内訳を返す() {
+            let db = create_test_db();
+            let a = Uuid::new_v4();
+            let b = Uuid::new_v4();
+            let other = Uuid::new_v4();
+            let idle = Uuid::new_v4();
+
+            db.with_connection(|conn| {
+                conn.execute(
+                    "INSERT INTO tasks (id, name, color, created_at, updated_at) VALUES
+                     (?, 'A', '#ff0000', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     (?, 'B', '#00ff00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     (?, '対象外', '#0000ff', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     (?, '記録なし', '#000000', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                    [a.to_string(), b.to_string(), other.to_string(), idle.to_string()],
+                )?;
+                conn.execute(
+                    "INSERT INTO time_entries (id, task_id, started_at, ended_at, created_at, updated_at) VALUES
+                     ('e1', ?, '2024-12-02 09:00:00+00', '2024-12-02 11:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     ('e2', ?, '2024-12-03 09:00:00+00', '2024-12-03 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     ('e3', ?, '2024-12-02 13:00:00+00', '2024-12-02 13:30:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     ('e4', ?, '2024-12-02 14:00:00+00', '2024-12-02 18:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     ('e5', ?, '2025-01-02 09:00:00+00', '2025-01-02 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                    [a.to_string(), a.to_string(), b.to_string(), other.to_string(), a.to_string()],
+                )?;
+                Ok(())
+            })
+            .unwrap();
+
+            let from = DateTime::parse_from_rfc3339("2024-12-01T00:00:00Z").unwrap().with_timezone(&Utc);
+            let to = DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z").unwrap().with_timezone(&Utc);
+            let report = db
+                .with_connection(|conn| fetch_tasks_report(conn, &[a, b, idle], Some(from), Some(to)))
+                .unwrap();
+
+            assert_eq!(report.total_seconds, 7200 + 3600 + 1800);
+            assert_eq!(report.entry_count, 3);
+            assert_eq!(report.task_summaries.len(), 3);
+            assert_eq!(report.task_summaries[0].task_id, Some(a));
+            assert_eq!(report.task_summaries[0].total_seconds, 7200 + 3600);
+            assert_eq!(report.task_summaries[1].task_id, Some(b));
+            assert_eq!(report.task_summaries[2].task_id, Some(idle));
+            assert_eq!(report.task_summaries[2].entry_count, 0);
+            assert!(report.task_summaries.iter().all(|t| t.task_id != Some(other)));
+        }
+    }
+
+    mod report_cache_tests {
+        use super::*;
+
+        fn key(year: i32, month: u32, tz_offset_minutes: i32) -> ReportCacheKey {
+            ReportCacheKey {
+                year,
+                month,
+                tz_offset_minutes,
+                folder_id: None,
+                exclude_task_ids: Vec::new(),
+                daily_target_seconds: None,
+                rounding: None,
+            }
+        }
+
+        fn report(year: i32, month: u32) -> MonthlyReport {
+            MonthlyReport {
+                year,
+                month,
+                total_seconds: 0,
+                total_entries: 0,
+                working_days: 0,
+                average_seconds_per_day: 0,
+                task_summaries: Vec::new(),
+                daily_summaries: Vec::new(),
+            }
+        }
+
+        #[test]
+        fn 記録の開始日時を含む月のキャッシュだけが破棄される() {
+            let cache = ReportCache::default();
+            cache.insert(key(2024, 11, 0), report(2024, 11));
+            cache.insert(key(2024, 12, 0), report(2024, 12));
+            // UTCでは11月30日でも、+9時間のローカル時間では12月1日になる
+            cache.insert(key(2024, 12, 540), report(2024, 12));
+
+            let started_at = DateTime::parse_from_rfc3339("2024-11-30T20:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc);
+            cache.invalidate(started_at);
+
+            assert!(cache.get(&key(2024, 11, 0)).is_none());
+            assert!(cache.get(&key(2024, 12, 0)).is_some());
+            assert!(cache.get(&key(2024, 12, 540)).is_none());
+
+            cache.clear();
+            assert!(cache.get(&key(2024, 12, 0)).is_none());
+        }
+
+        #[test]
+        fn ルールでタスクを割り当てるとキャッシュ済みの月次レポートに反映される() {
+            let state = AppState {
+                db: create_test_db(),
+                report_cache: ReportCache::default(),
+                task_lock: Default::default(),
+            };
+            let task_id = Uuid::new_v4();
+            let entry_id = Uuid::new_v4();
+            state
+                .db
+                .with_connection(|conn| {
+                    conn.execute(
+                        "INSERT INTO tasks (id, name, color, created_at, updated_at)
+                         VALUES (?, '障害対応', '#ff0000', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                        [task_id.to_string()],
+                    )?;
+                    conn.execute(
+                        "INSERT INTO time_entries (id, started_at, ended_at, memo, created_at, updated_at)
+                         VALUES (?, '2024-01-15 09:00:00+00', '2024-01-15 10:00:00+00', '障害の調査',
+                                 CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                        [entry_id.to_string()],
+                    )?;
+                    conn.execute(
+                        "INSERT INTO auto_rules (id, pattern, task_id, created_at)
+                         VALUES (?, '障害', ?, CURRENT_TIMESTAMP)",
+                        [Uuid::new_v4().to_string(), task_id.to_string()],
+                    )?;
+                    Ok(())
+                })
+                .unwrap();
+
+            let before = load_monthly_report(&state, key(2024, 1, 0)).unwrap();
+            assert_eq!(before.task_summaries[0].task_id, None);
+
+            let result =
+                crate::commands::auto_rules::assign_task_by_rules(&state, &entry_id).unwrap();
+            assert!(result.matched);
+
+            let after = load_monthly_report(&state, key(2024, 1, 0)).unwrap();
+            assert_eq!(after.task_summaries[0].task_id, Some(task_id));
+        }
+
+        #[test]
+        fn 当月かどうかはローカル時間で判定する() {
+            let now = DateTime::parse_from_rfc3339("2024-11-30T20:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc);
+
+            assert!(is_current_month(2024, 11, 0, now));
+            assert!(is_current_month(2024, 12, 540, now));
+            assert!(!is_current_month(2024, 11, 540, now));
+        }
+    }
+
+    mod available_months_tests {
+        use super::*;
 
         #[test]
-        fn タスク別に集計される() {
+        fn 利用可能な月のリストを取得できる() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                conn.execute(
+                    "INSERT INTO time_entries (id, started_at, ended_at, created_at, updated_at) VALUES
+                     ('entry-1', '2024-11-15 09:00:00+00', '2024-11-15 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     ('entry-2', '2024-12-15 09:00:00+00', '2024-12-15 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                    [],
+                )?;
+                Ok(())
+            })
+            .unwrap();
+
+            let months = db
+                .with_connection(|conn| fetch_available_months(conn, 0))
+                .unwrap();
+
+            assert_eq!(months.len(), 2);
+            assert_eq!(months[0], (2024, 12));
+            assert_eq!(months[1], (2024, 11));
+        }
+
+        #[test]
+        fn 記録のある月ごとにレポートCSVを書き出す() {
+            let db = create_test_db();
+            let temp_dir = tempfile::tempdir().unwrap();
+            let output_dir = temp_dir.path().join("reports");
+
+            let written = db
+                .with_connection(|conn| {
+                    conn.execute_batch(
+                        "INSERT INTO tasks (id, name, color, archived, created_at, updated_at) VALUES
+                         ('t1', '設計, レビュー', '#000000', false, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP);
+                         INSERT INTO time_entries (id, task_id, started_at, ended_at, created_at, updated_at) VALUES
+                         ('entry-1', 't1', '2024-11-15 09:00:00+00', '2024-11-15 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('entry-2', 't1', '2024-12-15 09:00:00+00', '2024-12-15 09:30:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP);",
+                    )?;
+                    write_all_monthly_report_csvs(conn, &output_dir, 0)
+                })
+                .unwrap();
+
+            assert_eq!(
+                written,
+                vec![
+                    output_dir.join("report_2024_12.csv").to_string_lossy().into_owned(),
+                    output_dir.join("report_2024_11.csv").to_string_lossy().into_owned(),
+                ]
+            );
+            let november = std::fs::read_to_string(&written[1]).unwrap();
+            assert_eq!(
+                november,
+                "task_name,total_seconds,entry_count,percentage\r\n\"設計, レビュー\",3600,1,100.00\r\n"
+            );
+        }
+    }
+
+    mod weekly_digest_tests {
+        use super::*;
+
+        #[test]
+        fn 時間は時と分で表される() {
+            assert_eq!(format_hours_minutes(0), "0h 0m");
+            assert_eq!(format_hours_minutes(5 * 3600 + 7 * 60 + 59), "5h 7m");
+            assert_eq!(format_hours_minutes(-90 * 60), "-1h 30m");
+        }
+
+        #[test]
+        fn 週次ダイジェストに合計と上位3タスクと前週比が含まれる() {
+            let db = create_test_db();
+
+            let text = db
+                .with_connection(|conn| {
+                    // 2024-W49 は 2024-12-02(月) から 2024-12-08(日)
+                    conn.execute_batch(
+                        "INSERT INTO tasks (id, name, color, archived, created_at, updated_at) VALUES
+                         ('t1', '設計', '#000000', false, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('t2', '実装', '#000000', false, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('t3', 'レビュー', '#000000', false, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('t4', '会議', '#000000', false, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP);
+                         INSERT INTO time_entries (id, task_id, started_at, ended_at, created_at, updated_at) VALUES
+                         ('e1', 't1', '2024-12-02 09:00:00+00', '2024-12-02 12:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('e2', 't2', '2024-12-03 09:00:00+00', '2024-12-03 11:30:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('e3', 't3', '2024-12-03 13:00:00+00', '2024-12-03 13:45:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('e4', 't4', '2024-12-06 09:00:00+00', '2024-12-06 09:15:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('e5', 't1', '2024-11-28 09:00:00+00', '2024-11-28 14:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP);",
+                    )?;
+                    fetch_weekly_digest_text(conn, 2024, 49, 0)
+                })
+                .unwrap();
+
+            assert_eq!(
+                text,
+                "Week 2024-W49 (2024-12-02 - 2024-12-08)\n\
+                 Total: 6h 30m\n\
+                 Working days: 3\n\
+                 Top tasks:\n  \
+                 1. 設計 - 3h 0m\n  \
+                 2. 実装 - 2h 30m\n  \
+                 3. レビュー - 0h 45m\n\
+                 vs previous week: +1h 30m (previous: 5h 0m)"
+            );
+        }
+
+        #[test]
+        fn 存在しない週番号はエラーになる() {
+            let db = create_test_db();
+
+            let result = db.with_connection(|conn| fetch_weekly_digest_text(conn, 2024, 53, 0));
+
+            assert!(matches!(result, Err(AppError::InvalidInput(_))));
+        }
+    }
+
+    mod active_task_counts_tests {
+        use super::*;
+
+        #[test]
+        fn 月ごとに異なるタスク数が集計され空の月は0で埋められる() {
             let db = create_test_db();
 
             db.with_connection(|conn| {
                 conn.execute(
                     "INSERT INTO tasks (id, name, color, created_at, updated_at) VALUES
                      ('task-1', 'タスクA', '#ff0000', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
-                     ('task-2', 'タスクB', '#00ff00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                     ('task-2', 'タスクB', '#00ff00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     ('task-3', 'タスクC', '#0000ff', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
                     [],
                 )?;
 
                 conn.execute(
                     "INSERT INTO time_entries (id, task_id, started_at, ended_at, created_at, updated_at) VALUES
-                     ('entry-1', 'task-1', '2024-12-15 09:00:00+00', '2024-12-15 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
-                     ('entry-2', 'task-1', '2024-12-15 11:00:00+00', '2024-12-15 12:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
-                     ('entry-3', 'task-2', '2024-12-15 14:00:00+00', '2024-12-15 15:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                     ('entry-1', 'task-1', '2024-10-15 09:00:00+00', '2024-10-15 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     ('entry-2', 'task-1', '2024-10-16 09:00:00+00', '2024-10-16 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     ('entry-3', 'task-1', '2024-12-01 09:00:00+00', '2024-12-01 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     ('entry-4', 'task-2', '2024-12-02 09:00:00+00', '2024-12-02 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     ('entry-5', 'task-3', '2024-12-03 09:00:00+00', '2024-12-03 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     ('entry-6', NULL, '2024-12-04 09:00:00+00', '2024-12-04 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
                     [],
                 )?;
-
                 Ok(())
             })
             .unwrap();
 
-            let report = db
-                .with_connection(|conn| fetch_monthly_report(conn, 2024, 12, None))
+            let from = NaiveDate::from_ymd_opt(2024, 10, 1).unwrap();
+            let to = NaiveDate::from_ymd_opt(2024, 12, 1).unwrap();
+            let months = db
+                .with_connection(|conn| fetch_active_task_counts(conn, from, to, 0))
                 .unwrap();
 
-            assert_eq!(report.task_summaries.len(), 2);
-            // タスクAが2時間で最初
-            assert_eq!(report.task_summaries[0].task_name, "タスクA");
-            assert_eq!(report.task_summaries[0].total_seconds, 7200);
-            assert_eq!(report.task_summaries[0].entry_count, 2);
-            // タスクBが1時間
-            assert_eq!(report.task_summaries[1].task_name, "タスクB");
-            assert_eq!(report.task_summaries[1].total_seconds, 3600);
+            assert_eq!(months.len(), 3);
+            assert_eq!((months[0].year, months[0].month), (2024, 10));
+            assert_eq!(months[0].task_count, 1);
+            assert!(!months[0].has_untasked);
+            assert_eq!((months[1].year, months[1].month), (2024, 11));
+            assert_eq!(months[1].task_count, 0);
+            assert_eq!((months[2].year, months[2].month), (2024, 12));
+            assert_eq!(months[2].task_count, 3);
+            assert!(months[2].has_untasked);
         }
 
         #[test]
-        fn 日別に集計される() {
+        fn 不正な月の形式はエラーになる() {
+            assert!(parse_year_month("2024-13").is_err());
+            assert!(parse_year_month("abc").is_err());
+            assert_eq!(
+                parse_year_month("2024-02").unwrap(),
+                NaiveDate::from_ymd_opt(2024, 2, 1).unwrap()
+            );
+        }
+    }
+
+    mod longest_work_block_tests {
+        use super::*;
+
+        #[test]
+        fn エントリがない場合はNoneが返る() {
+            let db = create_test_db();
+
+            let block = db
+                .with_connection(|conn| fetch_longest_work_block(conn, 0))
+                .unwrap();
+
+            assert!(block.is_none());
+        }
+
+        #[test]
+        fn 連続したエントリは結合されて単体より長いブロックになる() {
             let db = create_test_db();
 
             db.with_connection(|conn| {
                 conn.execute(
                     "INSERT INTO time_entries (id, started_at, ended_at, created_at, updated_at) VALUES
                      ('entry-1', '2024-12-15 09:00:00+00', '2024-12-15 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
-                     ('entry-2', '2024-12-16 09:00:00+00', '2024-12-16 11:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                     ('entry-2', '2024-12-15 10:00:00+00', '2024-12-15 11:30:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     ('entry-3', '2024-12-16 09:00:00+00', '2024-12-16 11:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
                     [],
                 )?;
                 Ok(())
             })
             .unwrap();
 
-            let report = db
-                .with_connection(|conn| fetch_monthly_report(conn, 2024, 12, None))
+            let block = db
+                .with_connection(|conn| fetch_longest_work_block(conn, 0))
+                .unwrap()
                 .unwrap();
 
-            assert_eq!(report.daily_summaries.len(), 2);
-            assert_eq!(report.daily_summaries[0].date, "2024-12-15");
-            assert_eq!(report.daily_summaries[0].total_seconds, 3600);
-            assert_eq!(report.daily_summaries[1].date, "2024-12-16");
-            assert_eq!(report.daily_summaries[1].total_seconds, 7200);
+            assert_eq!(block.worked_seconds, 9000);
+            assert_eq!(block.entry_count, 2);
+            assert_eq!(block.started_at.to_rfc3339(), "2024-12-15T09:00:00+00:00");
+            assert_eq!(block.ended_at.to_rfc3339(), "2024-12-15T11:30:00+00:00");
         }
 
         #[test]
-        fn 未分類のエントリも集計される() {
+        fn 許容ギャップ以内のエントリは結合されギャップは作業時間に含まれない() {
             let db = create_test_db();
 
             db.with_connection(|conn| {
                 conn.execute(
-                    "INSERT INTO time_entries (id, started_at, ended_at, created_at, updated_at)
-                     VALUES ('entry-1', '2024-12-15 09:00:00+00', '2024-12-15 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                    "INSERT INTO time_entries (id, started_at, ended_at, created_at, updated_at) VALUES
+                     ('entry-1', '2024-12-15 09:00:00+00', '2024-12-15 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     ('entry-2', '2024-12-15 10:05:00+00', '2024-12-15 11:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
                     [],
                 )?;
                 Ok(())
             })
             .unwrap();
 
+            let separate = db
+                .with_connection(|conn| fetch_longest_work_block(conn, 60))
+                .unwrap()
+                .unwrap();
+            let merged = db
+                .with_connection(|conn| fetch_longest_work_block(conn, 300))
+                .unwrap()
+                .unwrap();
+
+            assert_eq!(separate.entry_count, 1);
+            assert_eq!(merged.entry_count, 2);
+            assert_eq!(merged.worked_seconds, 3600 + 3300);
+        }
+    }
+    mod billing_report_tests {
+        use super::*;
+
+        fn insert_billing_fixture(conn: &Connection) -> AppResult<()> {
+            conn.execute_batch(
+                "INSERT INTO tasks (id, name, color, hourly_rate, currency, created_at, updated_at) VALUES
+                 ('task-a', 'クライアントA', '#ff0000', 6000, 'JPY', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                 ('task-b', 'クライアントB', '#00ff00', 50.5, 'USD', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                 ('task-c', '社内作業', '#0000ff', NULL, NULL, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP);
+                 INSERT INTO time_entries (id, task_id, started_at, ended_at, created_at, updated_at) VALUES
+                 ('e1', 'task-a', '2024-12-01 09:00:00+00', '2024-12-01 10:30:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                 ('e2', 'task-b', '2024-12-02 09:00:00+00', '2024-12-02 11:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                 ('e3', 'task-c', '2024-12-03 09:00:00+00', '2024-12-03 09:45:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                 ('e4', NULL, '2024-12-04 09:00:00+00', '2024-12-04 09:15:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                 ('e5', 'task-a', '2024-12-05 09:00:00+00', NULL, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP);",
+            )?;
+            Ok(())
+        }
+
+        #[test]
+        fn 単価のあるタスクは金額が計算され通貨別に合計される() {
+            let db = create_test_db();
+
             let report = db
-                .with_connection(|conn| fetch_monthly_report(conn, 2024, 12, None))
+                .with_connection(|conn| {
+                    insert_billing_fixture(conn)?;
+                    fetch_billing_report(conn, None, None)
+                })
                 .unwrap();
 
-            assert_eq!(report.task_summaries.len(), 1);
-            assert_eq!(report.task_summaries[0].task_name, "未分類");
+            assert_eq!(report.lines.len(), 2);
+            let jpy = report.lines.iter().find(|l| l.task_name == "クライアントA").unwrap();
+            assert_eq!(jpy.total_seconds, 5400);
+            assert_eq!(jpy.amount, 9000.0);
+            let usd = report.lines.iter().find(|l| l.task_name == "クライアントB").unwrap();
+            assert_eq!(usd.amount, 101.0);
+
+            assert_eq!(report.totals.len(), 2);
+            let jpy_total = report
+                .totals
+                .iter()
+                .find(|t| t.currency.as_deref() == Some("JPY"))
+                .unwrap();
+            assert_eq!(jpy_total.amount, 9000.0);
+        }
+
+        #[test]
+        fn 単価のないタスクと未分類は未請求時間として報告される() {
+            let db = create_test_db();
+
+            let report = db
+                .with_connection(|conn| {
+                    insert_billing_fixture(conn)?;
+                    fetch_billing_report(conn, None, None)
+                })
+                .unwrap();
+
+            assert_eq!(report.unbilled.len(), 2);
+            assert_eq!(report.unbilled_seconds, 2700 + 900);
+        }
+
+        #[test]
+        fn 期間外のエントリは集計されない() {
+            let db = create_test_db();
+            let from = DateTime::parse_from_rfc3339("2024-12-02T00:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc);
+            let to = DateTime::parse_from_rfc3339("2024-12-03T00:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc);
+
+            let report = db
+                .with_connection(|conn| {
+                    insert_billing_fixture(conn)?;
+                    fetch_billing_report(conn, Some(from), Some(to))
+                })
+                .unwrap();
+
+            assert_eq!(report.lines.len(), 1);
+            assert_eq!(report.lines[0].task_name, "クライアントB");
+            assert!(report.unbilled.is_empty());
         }
     }
+    mod color_report_tests {
+        use super::*;
 
-    mod available_months_tests {
+        #[test]
+        fn 完了済みエントリをタスクの色別に集計し未分類は色なしになる() {
+            let db = create_test_db();
+
+            let summaries = db
+                .with_connection(|conn| {
+                    conn.execute(
+                        "INSERT INTO tasks (id, name, color, created_at, updated_at) VALUES
+                         ('task-1', '障害対応', '#ff0000', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('task-2', '緊急レビュー', '#ff0000', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('task-3', '開発', '#0000ff', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                        [],
+                    )?;
+                    conn.execute(
+                        "INSERT INTO time_entries (id, task_id, started_at, ended_at, created_at, updated_at) VALUES
+                         ('entry-1', 'task-1', '2024-12-01 09:00:00+00', '2024-12-01 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('entry-2', 'task-2', '2024-12-01 10:00:00+00', '2024-12-01 11:30:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('entry-3', 'task-3', '2024-12-01 13:00:00+00', '2024-12-01 14:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('entry-4', NULL, '2024-12-01 15:00:00+00', '2024-12-01 15:30:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('entry-5', 'task-3', '2024-12-02 09:00:00+00', NULL, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('entry-6', 'task-3', '2024-11-30 09:00:00+00', '2024-11-30 12:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                        [],
+                    )?;
+                    let from = "2024-12-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+                    fetch_color_summaries(conn, Some(from), None)
+                })
+                .unwrap();
+
+            assert_eq!(summaries.len(), 3);
+            assert_eq!(summaries[0].color.as_deref(), Some("#ff0000"));
+            assert_eq!(summaries[0].total_seconds, 9000);
+            assert_eq!(summaries[0].entry_count, 2);
+            assert_eq!(summaries[1].color.as_deref(), Some("#0000ff"));
+            assert_eq!(summaries[1].total_seconds, 3600);
+            assert_eq!(summaries[2].color, None);
+            assert_eq!(summaries[2].total_seconds, 1800);
+        }
+    }
+
+    mod records_tests {
         use super::*;
 
         #[test]
-        fn 利用可能な月のリストを取得できる() {
+        fn データがなければ各記録はNoneになる() {
             let db = create_test_db();
 
-            db.with_connection(|conn| {
-                conn.execute(
-                    "INSERT INTO time_entries (id, started_at, ended_at, created_at, updated_at) VALUES
-                     ('entry-1', '2024-11-15 09:00:00+00', '2024-11-15 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
-                     ('entry-2', '2024-12-15 09:00:00+00', '2024-12-15 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
-                    [],
-                )?;
-                Ok(())
-            })
-            .unwrap();
+            let records = db
+                .with_connection(|conn| fetch_report_records(conn, 0))
+                .unwrap();
 
-            let months = db.with_connection(fetch_available_months).unwrap();
+            assert!(records.longest_session.is_none());
+            assert!(records.busiest_day.is_none());
+            assert!(records.most_entries_day.is_none());
+        }
 
-            assert_eq!(months.len(), 2);
-            assert_eq!(months[0], (2024, 12));
-            assert_eq!(months[1], (2024, 11));
+        #[test]
+        fn 最長のエントリと最も長く作業した日と最も記録の多い日を返す() {
+            let db = create_test_db();
+            let task_id = Uuid::new_v4();
+            let longest_id = Uuid::new_v4();
+
+            let records = db
+                .with_connection(|conn| {
+                    conn.execute(
+                        "INSERT INTO tasks (id, name, color, created_at, updated_at)
+                         VALUES (?, '開発', '#ff0000', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                        [task_id.to_string()],
+                    )?;
+                    conn.execute(
+                        "INSERT INTO time_entries (id, task_id, started_at, ended_at, created_at, updated_at) VALUES
+                         (?, ?, '2024-12-01 09:00:00+00', '2024-12-01 12:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         (?, NULL, '2024-12-02 09:00:00+00', '2024-12-02 09:30:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         (?, NULL, '2024-12-02 10:00:00+00', '2024-12-02 10:30:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         (?, NULL, '2024-12-02 11:00:00+00', '2024-12-02 11:30:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         (?, NULL, '2024-12-03 09:00:00+00', NULL, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                        duckdb::params![
+                            longest_id.to_string(),
+                            task_id.to_string(),
+                            Uuid::new_v4().to_string(),
+                            Uuid::new_v4().to_string(),
+                            Uuid::new_v4().to_string(),
+                            Uuid::new_v4().to_string()
+                        ],
+                    )?;
+                    fetch_report_records(conn, 0)
+                })
+                .unwrap();
+
+            let longest = records.longest_session.unwrap();
+            assert_eq!(longest.entry_id, longest_id);
+            assert_eq!(longest.task_id, Some(task_id));
+            assert_eq!(longest.task_name, "開発");
+            assert_eq!(longest.duration_seconds, 3 * 3600);
+
+            let busiest = records.busiest_day.unwrap();
+            assert_eq!(busiest.date, "2024-12-01");
+            assert_eq!(busiest.total_seconds, 3 * 3600);
+
+            let most_entries = records.most_entries_day.unwrap();
+            assert_eq!(most_entries.date, "2024-12-02");
+            assert_eq!(most_entries.entry_count, 3);
+            assert_eq!(most_entries.total_seconds, 5400);
+        }
+    }
+
+    mod cumulative_timeline_tests {
+        use super::*;
+
+        #[test]
+        fn 完了済みエントリが開始日時順に累計付きで返る() {
+            let db = create_test_db();
+            let task_id = Uuid::new_v4();
+
+            let points = db
+                .with_connection(|conn| {
+                    conn.execute(
+                        "INSERT INTO tasks (id, name, color, created_at, updated_at)
+                         VALUES (?, '開発', '#ff0000', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                        [task_id.to_string()],
+                    )?;
+                    conn.execute(
+                        "INSERT INTO time_entries (id, task_id, started_at, ended_at, created_at, updated_at) VALUES
+                         (?, ?, '2024-12-01 13:00:00+00', '2024-12-01 14:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         (?, NULL, '2024-12-01 09:00:00+00', '2024-12-01 09:30:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         (?, NULL, '2024-12-01 17:00:00+00', NULL, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                        duckdb::params![
+                            Uuid::new_v4().to_string(),
+                            task_id.to_string(),
+                            Uuid::new_v4().to_string(),
+                            Uuid::new_v4().to_string()
+                        ],
+                    )?;
+                    fetch_cumulative_timeline(conn, None, None)
+                })
+                .unwrap();
+
+            assert_eq!(points.len(), 2);
+            assert_eq!(points[0].duration_seconds, 1800);
+            assert_eq!(points[0].cumulative_seconds, 1800);
+            assert_eq!(points[0].task_id, None);
+            assert_eq!(points[0].task_color, "#6b7280");
+            assert_eq!(points[1].cumulative_seconds, 1800 + 3600);
+            assert_eq!(points[1].task_id, Some(task_id));
+            assert_eq!(points[1].task_color, "#ff0000");
+        }
+    }
+
+    mod duration_histogram_tests {
+        use super::*;
+
+        #[test]
+        fn 作業時間がバケット境界で分類される() {
+            let db = create_test_db();
+
+            let histogram = db
+                .with_connection(|conn| {
+                    conn.execute_batch(
+                        "INSERT INTO time_entries (id, started_at, ended_at, created_at, updated_at) VALUES
+                         ('e1', '2024-12-01 09:00:00+00', '2024-12-01 09:10:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('e2', '2024-12-01 10:00:00+00', '2024-12-01 10:15:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('e3', '2024-12-01 11:00:00+00', '2024-12-01 11:45:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('e4', '2024-12-01 13:00:00+00', '2024-12-01 16:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('e5', '2024-12-01 17:00:00+00', NULL, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP);",
+                    )?;
+                    fetch_duration_histogram(conn, None, None, &[900, 1800, 3600])
+                })
+                .unwrap();
+
+            assert_eq!(histogram.len(), 4);
+            // 15分未満
+            assert_eq!(histogram[0].count, 1);
+            assert_eq!(histogram[0].total_seconds, 600);
+            // 15分ちょうどは次のバケット
+            assert_eq!(histogram[1].count, 1);
+            assert_eq!(histogram[1].min_seconds, 900);
+            // 30分〜1時間
+            assert_eq!(histogram[2].count, 1);
+            // 最後のバケットは上限なし
+            assert_eq!(histogram[3].count, 1);
+            assert_eq!(histogram[3].max_seconds, None);
+            assert_eq!(histogram[3].total_seconds, 10800);
+        }
+
+        #[test]
+        fn 境界値が昇順でない場合はエラーになる() {
+            assert!(validate_buckets(&[1800, 900]).is_err());
+            assert!(validate_buckets(&[0, 900]).is_err());
+            assert!(validate_buckets(&[900, 900]).is_err());
+            assert!(validate_buckets(&[]).is_ok());
+            assert!(validate_buckets(&[900, 1800, 3600, 7200]).is_ok());
+        }
+    }
+
+    mod gaps_report_tests {
+        use super::*;
+
+        #[test]
+        fn 閾値を超えるギャップが日別に返る() {
+            let db = create_test_db();
+
+            let days = db
+                .with_connection(|conn| {
+                    conn.execute_batch(
+                        "INSERT INTO time_entries (id, started_at, ended_at, created_at, updated_at) VALUES
+                         ('e1', '2024-12-02 09:00:00+00', '2024-12-02 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('e2', '2024-12-02 10:05:00+00', '2024-12-02 11:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('e3', '2024-12-02 12:00:00+00', '2024-12-02 13:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('e4', '2024-12-03 09:00:00+00', '2024-12-03 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP);",
+                    )?;
+                    fetch_gaps(conn, None, None, 600, 0)
+                })
+                .unwrap();
+
+            // 5分のギャップは閾値未満、日をまたぐ間隔はギャップとしない
+            assert_eq!(days.len(), 1);
+            assert_eq!(days[0].date, "2024-12-02");
+            assert_eq!(days[0].gaps.len(), 1);
+            assert_eq!(days[0].total_gap_seconds, 3600);
+        }
+
+        #[test]
+        fn 閾値ちょうどのギャップは含まれない() {
+            let db = create_test_db();
+
+            let days = db
+                .with_connection(|conn| {
+                    conn.execute_batch(
+                        "INSERT INTO time_entries (id, started_at, ended_at, created_at, updated_at) VALUES
+                         ('e1', '2024-12-02 09:00:00+00', '2024-12-02 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('e2', '2024-12-02 10:10:00+00', '2024-12-02 11:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('e3', '2024-12-02 11:10:01+00', '2024-12-02 12:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP);",
+                    )?;
+                    fetch_gaps(conn, None, None, 600, 0)
+                })
+                .unwrap();
+
+            // 600秒のギャップは閾値を超えないため、601秒のギャップだけが返る
+            assert_eq!(days.len(), 1);
+            assert_eq!(days[0].gaps.len(), 1);
+            assert_eq!(days[0].gaps[0].gap_seconds, 601);
+        }
+
+        #[test]
+        fn 重複するエントリはギャップとして扱われない() {
+            let db = create_test_db();
+
+            let days = db
+                .with_connection(|conn| {
+                    conn.execute_batch(
+                        "INSERT INTO time_entries (id, started_at, ended_at, created_at, updated_at) VALUES
+                         ('e1', '2024-12-02 09:00:00+00', '2024-12-02 12:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('e2', '2024-12-02 10:00:00+00', '2024-12-02 10:30:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('e3', '2024-12-02 11:30:00+00', '2024-12-02 12:30:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP);",
+                    )?;
+                    fetch_gaps(conn, None, None, 0, 0)
+                })
+                .unwrap();
+
+            assert!(days.is_empty());
+        }
+    }
+
+    mod activity_distribution_tests {
+        use super::*;
+
+        #[test]
+        fn 開始時刻のローカル曜日と時間帯に作業時間が計上される() {
+            let db = create_test_db();
+
+            let distribution = db
+                .with_connection(|conn| {
+                    // 2024-12-01は日曜日
+                    conn.execute_batch(
+                        "INSERT INTO time_entries (id, started_at, ended_at, created_at, updated_at) VALUES
+                         ('e1', '2024-12-01 09:30:00+00', '2024-12-01 11:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('e2', '2024-12-01 23:00:00+00', '2024-12-01 23:30:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('e3', '2024-12-02 09:00:00+00', NULL, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP);",
+                    )?;
+                    fetch_activity_distribution(conn, None, None, 540)
+                })
+                .unwrap();
+
+            assert_eq!(distribution.by_day_of_week.len(), 7);
+            assert_eq!(distribution.by_hour.len(), 24);
+            // JSTでは e1 は日曜18時台、e2 は月曜8時台（進行中の e3 は対象外）
+            assert_eq!(distribution.by_day_of_week[0], 5400);
+            assert_eq!(distribution.by_day_of_week[1], 1800);
+            assert_eq!(distribution.by_hour[18], 5400);
+            assert_eq!(distribution.by_hour[8], 1800);
+            assert_eq!(distribution.by_hour.iter().sum::<i64>(), 7200);
+        }
+    }
+
+    mod compare_reports_tests {
+        use super::*;
+
+        fn summary(task_id: Option<Uuid>, name: &str, total_seconds: i64, entry_count: i64) -> TaskSummary {
+            TaskSummary {
+                task_id,
+                task_name: name.to_string(),
+                task_color: "#3b82f6".to_string(),
+                total_seconds,
+                entry_count,
+                percentage: 0.0,
+            }
+        }
+
+        fn report(month: u32, task_summaries: Vec<TaskSummary>) -> MonthlyReport {
+            MonthlyReport {
+                year: 2024,
+                month,
+                total_seconds: task_summaries.iter().map(|s| s.total_seconds).sum(),
+                total_entries: task_summaries.iter().map(|s| s.entry_count).sum(),
+                working_days: 0,
+                average_seconds_per_day: 0,
+                task_summaries,
+                daily_summaries: Vec::new(),
+            }
+        }
+
+        #[test]
+        fn タスクIDで対応付けて片方にしかないタスクは0として差分を計算する() {
+            let shared = Uuid::new_v4();
+            let only_a = Uuid::new_v4();
+            let only_b = Uuid::new_v4();
+            let a = report(
+                11,
+                vec![summary(Some(shared), "共通", 3600, 2), summary(Some(only_a), "先月のみ", 1800, 1)],
+            );
+            let b = report(
+                12,
+                vec![
+                    summary(Some(shared), "共通", 5400, 3),
+                    summary(Some(only_b), "今月のみ", 600, 1),
+                    summary(None, "未分類", 300, 1),
+                ],
+            );
+
+            let diff = compute_report_diff(&a, &b);
+            assert_eq!(diff.delta_total_seconds, 6300 - 5400);
+            assert_eq!(diff.delta_entries, 5 - 3);
+            assert_eq!(diff.task_deltas.len(), 4);
+
+            let delta_of = |task_id: Option<Uuid>| {
+                diff.task_deltas
+                    .iter()
+                    .find(|d| d.task_id == task_id)
+                    .unwrap()
+                    .clone()
+            };
+            let shared_delta = delta_of(Some(shared));
+            assert_eq!((shared_delta.seconds_a, shared_delta.seconds_b), (3600, 5400));
+            assert_eq!(shared_delta.delta_seconds, 1800);
+            assert_eq!(shared_delta.delta_entries, 1);
+            let a_delta = delta_of(Some(only_a));
+            assert_eq!((a_delta.seconds_b, a_delta.delta_seconds), (0, -1800));
+            let b_delta = delta_of(Some(only_b));
+            assert_eq!((b_delta.seconds_a, b_delta.delta_seconds), (0, 600));
+            assert_eq!(delta_of(None).delta_entries, 1);
+        }
+    }
+
+    mod today_report_tests {
+        use super::*;
+
+        #[test]
+        fn 今日の完了済みエントリと計測中エントリの経過時間を合計する() {
+            let db = create_test_db();
+            let now = DateTime::parse_from_rfc3339("2024-12-02T03:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc);
+
+            let report = db
+                .with_connection(|conn| {
+                    conn.execute_batch(
+                        "INSERT INTO tasks (id, name, color, created_at, updated_at) VALUES
+                         ('task-a', '開発', '#ff0000', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP);
+                         INSERT INTO time_entries (id, task_id, started_at, ended_at, created_at, updated_at) VALUES
+                         ('e1', 'task-a', '2024-12-01 15:30:00+00', '2024-12-01 16:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('e2', 'task-a', '2024-12-02 00:00:00+00', '2024-12-02 01:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('e3', NULL, '2024-12-02 02:30:00+00', NULL, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('e4', 'task-a', '2024-12-01 09:00:00+00', '2024-12-01 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP);",
+                    )?;
+                    // JSTでは2024-12-02 12:00。e1 は12/2 0:30 JST開始で今日、e4 は前日（12/1 18:00 JST）
+                    fetch_today_report(conn, 540, now)
+                })
+                .unwrap();
+
+            assert_eq!(report.date, "2024-12-02");
+            assert_eq!(report.entry_count, 3);
+            // e1 30分 + e2 60分 + 計測中の e3 30分
+            assert_eq!(report.total_seconds, 7200);
+            let untasked = report
+                .task_summaries
+                .iter()
+                .find(|s| s.task_name == "未分類")
+                .unwrap();
+            assert_eq!(untasked.total_seconds, 1800);
+        }
+    }
+
+    mod task_streak_tests {
+        use super::*;
+
+        fn day(value: &str) -> NaiveDate {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+        }
+
+        #[test]
+        fn 現在の連続日数と最長の連続日数を求める() {
+            let days = [
+                day("2024-11-01"),
+                day("2024-11-02"),
+                day("2024-11-03"),
+                day("2024-11-10"),
+                day("2024-11-29"),
+                day("2024-11-30"),
+            ];
+
+            let streak = compute_streaks(&days, day("2024-12-01"));
+            assert_eq!(streak.current_days, 2);
+            assert_eq!(streak.current_start.as_deref(), Some("2024-11-29"));
+            assert_eq!(streak.current_end.as_deref(), Some("2024-11-30"));
+            assert_eq!(streak.longest_days, 3);
+            assert_eq!(streak.longest_start.as_deref(), Some("2024-11-01"));
+
+            // 2日以上空くと現在の連続は途切れる
+            let broken = compute_streaks(&days, day("2024-12-02"));
+            assert_eq!(broken.current_days, 0);
+            assert_eq!(broken.current_start, None);
+            assert_eq!(broken.longest_days, 3);
+        }
+
+        #[test]
+        fn 計測中のエントリしかない日は数えない() {
+            let db = create_test_db();
+
+            let days = db
+                .with_connection(|conn| {
+                    conn.execute_batch(
+                        "INSERT INTO tasks (id, name, color, created_at, updated_at) VALUES
+                         ('00000000-0000-0000-0000-000000000001', '習慣', '#ff0000', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP);
+                         INSERT INTO time_entries (id, task_id, started_at, ended_at, created_at, updated_at) VALUES
+                         ('e1', '00000000-0000-0000-0000-000000000001', '2024-12-01 09:00:00+00', '2024-12-01 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('e2', '00000000-0000-0000-0000-000000000001', '2024-12-01 20:00:00+00', '2024-12-01 21:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                         ('e3', '00000000-0000-0000-0000-000000000001', '2024-12-03 09:00:00+00', NULL, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP);",
+                    )?;
+                    fetch_task_active_days(conn, &Uuid::from_u128(1), 540)
+                })
+                .unwrap();
+
+            // JSTでは e2 は12/2になる。計測中の e3 は対象外
+            assert_eq!(days, vec![day("2024-12-01"), day("2024-12-02")]);
         }
     }
 }
@@ -0,0 +1,218 @@
+use chrono::Utc;
+use duckdb::Connection;
+
+use crate::db::Task;
+use crate::error::{AppError, AppResult};
+use crate::AppState;
+
+/// タスク作成時に色が指定されなかった場合のデフォルト色
+pub(crate) const DEFAULT_TASK_COLOR_KEY: &str = "default_task_color";
+/// フォルダ作成時に色が指定されなかった場合のデフォルト色
+pub(crate) const DEFAULT_FOLDER_COLOR_KEY: &str = "default_folder_color";
+/// メモテンプレートの設定キーの接頭辞（`memo_template.<名前>` に本文を保存する）
+pub(crate) const MEMO_TEMPLATE_KEY_PREFIX: &str = "memo_template.";
+/// 成果物の種別の許可リスト（JSON配列）
+pub(crate) const ARTIFACT_TYPES_KEY: &str = "artifact_types";
+/// 許可リストにない種別を拒否するか（"true" のときのみ有効）
+pub(crate) const ARTIFACT_TYPES_ENFORCED_KEY: &str = "artifact_types_enforced";
+/// 種別を比較前に正規化（trim・小文字化）するか（"true" のときのみ有効）
+pub(crate) const ARTIFACT_TYPES_NORMALIZE_KEY: &str = "artifact_types_normalize";
+/// 任意のSELECTクエリの実行を許可するか（"true" のときのみ有効、既定は無効）
+pub(crate) const READONLY_QUERY_ENABLED_KEY: &str = "enable_readonly_query";
+/// タスクを指定しメモなしで計測を開始したとき、タスク名をメモに入れるか（"true" のときのみ有効）
+pub(crate) const MEMO_FROM_TASK_NAME_KEY: &str = "memo_from_task_name";
+/// 最終ハートビートからこの秒数を超えて経過した計測を自動停止する（0または未設定で無効）
+pub(crate) const AUTO_STOP_IDLE_SECONDS_KEY: &str = "auto_stop_idle_seconds";
+
+/// 設定値を取得する（未設定の場合はNone）
+pub(crate) fn fetch_setting(conn: &Connection, key: &str) -> AppResult<Option<String>> {
+    let result = conn.query_row("SELECT value FROM settings WHERE key = ?", [key], |row| {
+        row.get(0)
+    });
+
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(duckdb::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(AppError::Database(e)),
+    }
+}
+
+/// 設定値を保存する（Noneの場合は削除してデフォルトに戻す）
+pub(crate) fn store_setting(conn: &Connection, key: &str, value: Option<&str>) -> AppResult<()> {
+    match value {
+        Some(value) => {
+            conn.execute(
+                "INSERT INTO settings (key, value, updated_at) VALUES (?, ?, ?)
+                 ON CONFLICT (key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
+                duckdb::params![key, value, Utc::now()],
+            )?;
+        }
+        None => {
+            conn.execute("DELETE FROM settings WHERE key = ?", [key])?;
+        }
+    }
+    Ok(())
+}
+
+/// 既知の設定キーについて値を検証する
+fn validate_setting(key: &str, value: &str) -> AppResult<()> {
+    if (key == DEFAULT_TASK_COLOR_KEY || key == DEFAULT_FOLDER_COLOR_KEY)
+        && !Task::is_valid_color(value)
+    {
+        return Err(AppError::InvalidInput(format!(
+            "Invalid color format: {}. Expected #RRGGBB",
+            value
+        )));
+    }
+    let is_flag = [
+        READONLY_QUERY_ENABLED_KEY,
+        MEMO_FROM_TASK_NAME_KEY,
+        ARTIFACT_TYPES_ENFORCED_KEY,
+        ARTIFACT_TYPES_NORMALIZE_KEY,
+    ]
+    .contains(&key);
+    if is_flag
+        && value != "true"
+        && value != "false"
+    {
+        return Err(AppError::InvalidInput(format!(
+            "Invalid value for {}: {}. Expected true or false",
+            key, value
+        )));
+    }
+    if key == AUTO_STOP_IDLE_SECONDS_KEY && !value.parse::<i64>().is_ok_and(|seconds| seconds >= 0) {
+        return Err(AppError::InvalidInput(format!(
+            "Invalid value for {}: {}. Expected a non-negative number of seconds",
+            key, value
+        )));
+    }
+    if key == ARTIFACT_TYPES_KEY {
+        // 読み込み時にJSONの文字列配列として解釈するため、保存前に形式を確かめる
+        let types: Vec<String> = serde_json::from_str(value).map_err(|_| {
+            AppError::InvalidInput(format!(
+                "Invalid value for {}: {}. Expected a JSON array of strings",
+                key, value
+            ))
+        })?;
+        if types.iter().any(|artifact_type| artifact_type.trim().is_empty()) {
+            return Err(AppError::InvalidInput(
+                "Artifact type cannot be empty".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 設定値を取得する
+#[tauri::command]
+pub fn get_setting(state: tauri::State<AppState>, key: String) -> AppResult<Option<String>> {
+    state.db.with_connection(|conn| fetch_setting(conn, &key))
+}
+
+/// 設定値を保存する（valueを省略すると設定を削除する）
+#[tauri::command]
+pub fn set_setting(
+    state: tauri::State<AppState>,
+    key: String,
+    value: Option<String>,
+) -> AppResult<()> {
+    if key.trim().is_empty() {
+        return Err(AppError::InvalidInput(
+            "Setting key cannot be empty".to_string(),
+        ));
+    }
+    if let Some(ref value) = value {
+        validate_setting(&key, value)?;
+    }
+
+    state
+        .db
+        .with_connection(|conn| store_setting(conn, &key, value.as_deref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+
+    fn create_test_db() -> Database {
+        Database::new_in_memory().unwrap()
+    }
+
+    mod setting_tests {
+        use super::*;
+
+        #[test]
+        fn 未設定のキーはNoneを返す() {
+            let db = create_test_db();
+
+            let value = db
+                .with_connection(|conn| fetch_setting(conn, DEFAULT_TASK_COLOR_KEY))
+                .unwrap();
+
+            assert!(value.is_none());
+        }
+
+        #[test]
+        fn 設定値を保存して上書きと削除ができる() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                store_setting(conn, DEFAULT_TASK_COLOR_KEY, Some("#111111"))?;
+                store_setting(conn, DEFAULT_TASK_COLOR_KEY, Some("#222222"))?;
+                assert_eq!(
+                    fetch_setting(conn, DEFAULT_TASK_COLOR_KEY)?,
+                    Some("#222222".to_string())
+                );
+
+                store_setting(conn, DEFAULT_TASK_COLOR_KEY, None)?;
+                assert!(fetch_setting(conn, DEFAULT_TASK_COLOR_KEY)?.is_none());
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 色の設定は形式が検証される() {
+            assert!(validate_setting(DEFAULT_FOLDER_COLOR_KEY, "red").is_err());
+            assert!(validate_setting(DEFAULT_FOLDER_COLOR_KEY, "#ff0000").is_ok());
+            assert!(validate_setting("other_key", "anything").is_ok());
+        }
+
+        #[test]
+        fn クエリ実行の許可設定は真偽値のみ受け付ける() {
+            assert!(validate_setting(READONLY_QUERY_ENABLED_KEY, "yes").is_err());
+            assert!(validate_setting(READONLY_QUERY_ENABLED_KEY, "true").is_ok());
+            assert!(validate_setting(READONLY_QUERY_ENABLED_KEY, "false").is_ok());
+        }
+
+        #[test]
+        fn 自動停止の設定は0以上の秒数のみ受け付ける() {
+            assert!(validate_setting(AUTO_STOP_IDLE_SECONDS_KEY, "-1").is_err());
+            assert!(validate_setting(AUTO_STOP_IDLE_SECONDS_KEY, "10m").is_err());
+            assert!(validate_setting(AUTO_STOP_IDLE_SECONDS_KEY, "0").is_ok());
+            assert!(validate_setting(AUTO_STOP_IDLE_SECONDS_KEY, "600").is_ok());
+        }
+
+        #[test]
+        fn タスク名をメモに入れる設定は真偽値のみ受け付ける() {
+            assert!(validate_setting(MEMO_FROM_TASK_NAME_KEY, "1").is_err());
+            assert!(validate_setting(MEMO_FROM_TASK_NAME_KEY, "true").is_ok());
+        }
+
+        #[test]
+        fn 成果物の種別の設定は形式が検証される() {
+            assert!(validate_setting(ARTIFACT_TYPES_KEY, "document").is_err());
+            assert!(validate_setting(ARTIFACT_TYPES_KEY, r#"{"type":"document"}"#).is_err());
+            assert!(validate_setting(ARTIFACT_TYPES_KEY, r#"["document", " "]"#).is_err());
+            assert!(validate_setting(ARTIFACT_TYPES_KEY, r#"["document","url"]"#).is_ok());
+            assert!(validate_setting(ARTIFACT_TYPES_KEY, "[]").is_ok());
+
+            for key in [ARTIFACT_TYPES_ENFORCED_KEY, ARTIFACT_TYPES_NORMALIZE_KEY] {
+                assert!(validate_setting(key, "yes").is_err());
+                assert!(validate_setting(key, "true").is_ok());
+                assert!(validate_setting(key, "false").is_ok());
+            }
+        }
+    }
+}
@@ -2,15 +2,26 @@ use chrono::{DateTime, Utc};
 use duckdb::Connection;
 use uuid::Uuid;
 
-use crate::db::{CreateTask, Task, UpdateTask};
+use crate::commands::audit::record_audit;
+use crate::commands::settings::{fetch_setting, DEFAULT_TASK_COLOR_KEY};
+use crate::commands::{contains_pattern, ensure_max_length, MAX_NAME_LENGTH};
+use crate::db::{ArchiveTaskResult, CreateTask, Task, TaskWithStats, TaskWithWarnings, UpdateTask};
 use crate::error::{AppError, AppResult};
 use crate::AppState;
 
+/// 最近使用したタスクの取得件数の既定値
+const DEFAULT_RECENT_TASKS_LIMIT: i64 = 5;
+
+/// これ以上明るい色は白に近く背景と見分けにくいとみなす相対輝度
+const NEAR_WHITE_LUMINANCE: f64 = 0.85;
+/// これ以下の暗い色は黒に近く文字と見分けにくいとみなす相対輝度
+const NEAR_BLACK_LUMINANCE: f64 = 0.02;
+
 /// タスクをDBに保存する
 fn insert_task(conn: &Connection, task: &Task) -> AppResult<()> {
     conn.execute(
-        "INSERT INTO tasks (id, folder_id, name, description, color, archived, created_at, updated_at)
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        "INSERT INTO tasks (id, folder_id, name, description, color, archived, created_at, updated_at, hourly_rate, currency)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
         duckdb::params![
             task.id.to_string(),
             task.folder_id.map(|id| id.to_string()),
@@ -20,21 +31,50 @@ fn insert_task(conn: &Connection, task: &Task) -> AppResult<()> {
             task.archived,
             task.created_at,
             task.updated_at,
+            task.hourly_rate,
+            &task.currency,
         ],
     )?;
     Ok(())
 }
 
-/// DBからタスクを取得する
-fn fetch_tasks(conn: &Connection, include_archived: bool) -> AppResult<Vec<Task>> {
-    let sql = if include_archived {
-        "SELECT id, folder_id, name, description, color, archived, created_at, updated_at FROM tasks ORDER BY created_at DESC"
+/// タスク一覧の絞り込み条件（WHERE句とパラメータ）を組み立てる
+///
+/// queryは名前と説明の部分一致（大文字小文字を区別しない）で、空白のみの場合は無視する。
+fn task_filter_clause(alias: &str, include_archived: bool, query: Option<&str>) -> (String, Vec<String>) {
+    let mut conditions = Vec::new();
+    let mut params = Vec::new();
+
+    if !include_archived {
+        conditions.push(format!("{alias}.archived = false"));
+    }
+    if let Some(query) = query.map(str::trim).filter(|q| !q.is_empty()) {
+        conditions.push(format!(
+            "({alias}.name ILIKE ? ESCAPE '\\' OR {alias}.description ILIKE ? ESCAPE '\\')"
+        ));
+        let pattern = contains_pattern(query);
+        params.push(pattern.clone());
+        params.push(pattern);
+    }
+
+    if conditions.is_empty() {
+        (String::new(), params)
     } else {
-        "SELECT id, folder_id, name, description, color, archived, created_at, updated_at FROM tasks WHERE archived = false ORDER BY created_at DESC"
-    };
+        (format!("WHERE {}", conditions.join(" AND ")), params)
+    }
+}
 
-    let mut stmt = conn.prepare(sql)?;
-    let rows = stmt.query_map([], |row| {
+/// DBからタスクを取得する
+pub(crate) fn fetch_tasks(conn: &Connection, include_archived: bool, query: Option<&str>) -> AppResult<Vec<Task>> {
+    let (where_clause, params) = task_filter_clause("t", include_archived, query);
+    let sql = format!(
+        "SELECT t.id, t.folder_id, t.name, t.description, t.color, t.archived, t.created_at, t.updated_at, t.hourly_rate, t.currency
+         FROM tasks t {} ORDER BY t.created_at DESC, t.id",
+        where_clause
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(duckdb::params_from_iter(params), |row| {
         let id_str: String = row.get(0)?;
         let folder_id_str: Option<String> = row.get(1)?;
         let created_at: DateTime<Utc> = row.get(6)?;
@@ -47,6 +87,8 @@ fn fetch_tasks(conn: &Connection, include_archived: bool) -> AppResult<Vec<Task>
             description: row.get(3)?,
             color: row.get(4)?,
             archived: row.get(5)?,
+            hourly_rate: row.get(8)?,
+            currency: row.get(9)?,
             created_at,
             updated_at,
         })
@@ -59,10 +101,132 @@ fn fetch_tasks(conn: &Connection, include_archived: bool) -> AppResult<Vec<Task>
     Ok(tasks)
 }
 
+/// DBから集計情報付きでタスクを取得する（要求されていない集計はNoneになる）
+fn fetch_tasks_with_stats(
+    conn: &Connection,
+    include_archived: bool,
+    with_entry_counts: bool,
+    with_totals: bool,
+    query: Option<&str>,
+) -> AppResult<Vec<TaskWithStats>> {
+    let (where_clause, params) = task_filter_clause("t", include_archived, query);
+    let sql = format!(
+        "SELECT t.id, t.folder_id, t.name, t.description, t.color, t.archived, t.created_at, t.updated_at,
+                t.hourly_rate, t.currency,
+                COALESCE(c.entry_count, 0)::BIGINT as entry_count,
+                COALESCE(c.total_seconds, 0)::BIGINT as total_seconds
+         FROM tasks t
+         LEFT JOIN (
+             SELECT task_id,
+                    COUNT(*) as entry_count,
+                    SUM(
+                        CASE
+                            WHEN ended_at IS NOT NULL
+                            THEN COALESCE(
+                                duration_seconds,
+                                EPOCH(ended_at::TIMESTAMP) - EPOCH(started_at::TIMESTAMP)
+                            )
+                            ELSE 0
+                        END
+                    ) as total_seconds
+             FROM time_entries GROUP BY task_id
+         ) c ON c.task_id = t.id
+         {}
+         ORDER BY t.created_at DESC, t.id",
+        where_clause
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(duckdb::params_from_iter(params), |row| {
+        let id_str: String = row.get(0)?;
+        let folder_id_str: Option<String> = row.get(1)?;
+        let created_at: DateTime<Utc> = row.get(6)?;
+        let updated_at: DateTime<Utc> = row.get(7)?;
+        let entry_count: i64 = row.get(10)?;
+        let total_seconds: i64 = row.get(11)?;
+
+        Ok(TaskWithStats {
+            task: Task {
+                id: Uuid::parse_str(&id_str).unwrap(),
+                folder_id: folder_id_str.and_then(|s| Uuid::parse_str(&s).ok()),
+                name: row.get(2)?,
+                description: row.get(3)?,
+                color: row.get(4)?,
+                archived: row.get(5)?,
+                hourly_rate: row.get(8)?,
+                currency: row.get(9)?,
+                created_at,
+                updated_at,
+            },
+            entry_count: with_entry_counts.then_some(entry_count),
+            total_seconds: with_totals.then_some(total_seconds),
+        })
+    })?;
+
+    let mut tasks = Vec::new();
+    for row in rows {
+        tasks.push(row?);
+    }
+    Ok(tasks)
+}
+
+/// 最近使用したタスクを最後の開始日時が新しい順に取得する（未使用・アーカイブ済みは除く）
+fn fetch_recent_tasks(conn: &Connection, limit: i64) -> AppResult<Vec<Task>> {
+    let mut stmt = conn.prepare(
+        "SELECT t.id, t.folder_id, t.name, t.description, t.color, t.archived, t.created_at, t.updated_at,
+                t.hourly_rate, t.currency
+         FROM tasks t
+         JOIN (
+             SELECT task_id, MAX(started_at) as last_started_at
+             FROM time_entries
+             WHERE task_id IS NOT NULL
+             GROUP BY task_id
+         ) r ON r.task_id = t.id
+         WHERE t.archived = false
+         ORDER BY r.last_started_at DESC
+         LIMIT ?",
+    )?;
+    let rows = stmt.query_map([limit], |row| {
+        let id_str: String = row.get(0)?;
+        let folder_id_str: Option<String> = row.get(1)?;
+        let created_at: DateTime<Utc> = row.get(6)?;
+        let updated_at: DateTime<Utc> = row.get(7)?;
+
+        Ok(Task {
+            id: Uuid::parse_str(&id_str).unwrap(),
+            folder_id: folder_id_str.and_then(|s| Uuid::parse_str(&s).ok()),
+            name: row.get(2)?,
+            description: row.get(3)?,
+            color: row.get(4)?,
+            archived: row.get(5)?,
+            hourly_rate: row.get(8)?,
+            currency: row.get(9)?,
+            created_at,
+            updated_at,
+        })
+    })?;
+
+    let mut tasks = Vec::new();
+    for row in rows {
+        tasks.push(row?);
+    }
+    Ok(tasks)
+}
+
+/// タスクを参照している時間記録の件数を取得する
+fn count_entries_for_task(conn: &Connection, id: &Uuid) -> AppResult<i64> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM time_entries WHERE task_id = ?",
+        [id.to_string()],
+        |row| row.get(0),
+    )?;
+    Ok(count)
+}
+
 /// IDでタスクを取得する
-fn fetch_task_by_id(conn: &Connection, id: &Uuid) -> AppResult<Task> {
+pub(crate) fn fetch_task_by_id(conn: &Connection, id: &Uuid) -> AppResult<Task> {
     let mut stmt = conn.prepare(
-        "SELECT id, folder_id, name, description, color, archived, created_at, updated_at FROM tasks WHERE id = ?",
+        "SELECT id, folder_id, name, description, color, archived, created_at, updated_at, hourly_rate, currency FROM tasks WHERE id = ?",
     )?;
 
     let task = stmt
@@ -79,6 +243,8 @@ fn fetch_task_by_id(conn: &Connection, id: &Uuid) -> AppResult<Task> {
                 description: row.get(3)?,
                 color: row.get(4)?,
                 archived: row.get(5)?,
+                hourly_rate: row.get(8)?,
+                currency: row.get(9)?,
                 created_at,
                 updated_at,
             })
@@ -88,18 +254,92 @@ fn fetch_task_by_id(conn: &Connection, id: &Uuid) -> AppResult<Task> {
     Ok(task)
 }
 
+/// 時間単価と通貨を検証する
+fn validate_billing(hourly_rate: Option<f64>, currency: Option<&str>) -> AppResult<()> {
+    if let Some(rate) = hourly_rate {
+        if !rate.is_finite() || rate < 0.0 {
+            return Err(AppError::InvalidInput(format!(
+                "Invalid hourly rate: {}. Expected a non-negative number",
+                rate
+            )));
+        }
+    }
+    if let Some(currency) = currency {
+        if currency.trim().is_empty() {
+            return Err(AppError::InvalidInput("Currency cannot be empty".to_string()));
+        }
+    }
+    Ok(())
+}
+
 /// タスク一覧を取得する
 #[tauri::command]
-pub fn list_tasks(state: tauri::State<AppState>, include_archived: bool) -> AppResult<Vec<Task>> {
-    state.db.with_connection(|conn| fetch_tasks(conn, include_archived))
+pub fn list_tasks(
+    state: tauri::State<AppState>,
+    include_archived: bool,
+    with_entry_counts: Option<bool>,
+    with_totals: Option<bool>,
+    query: Option<String>,
+) -> AppResult<Vec<TaskWithStats>> {
+    let with_entry_counts = with_entry_counts.unwrap_or(false);
+    let with_totals = with_totals.unwrap_or(false);
+
+    state.db.with_connection(|conn| {
+        if with_entry_counts || with_totals {
+            fetch_tasks_with_stats(
+                conn,
+                include_archived,
+                with_entry_counts,
+                with_totals,
+                query.as_deref(),
+            )
+        } else {
+            Ok(fetch_tasks(conn, include_archived, query.as_deref())?
+                .into_iter()
+                .map(|task| TaskWithStats {
+                    task,
+                    entry_count: None,
+                    total_seconds: None,
+                })
+                .collect())
+        }
+    })
 }
 
-/// タスクを作成する
+/// 最近使用したタスクを取得する
 #[tauri::command]
-pub fn create_task(state: tauri::State<AppState>, task: CreateTask) -> AppResult<Task> {
+pub fn list_recent_tasks(state: tauri::State<AppState>, limit: Option<i64>) -> AppResult<Vec<Task>> {
+    let limit = limit.unwrap_or(DEFAULT_RECENT_TASKS_LIMIT);
+    if limit <= 0 {
+        return Err(AppError::InvalidInput(
+            "Limit must be greater than 0".to_string(),
+        ));
+    }
+
+    state.db.with_connection(|conn| fetch_recent_tasks(conn, limit))
+}
+
+/// 白や黒に近く読みにくい色であれば警告メッセージを返す
+fn color_contrast_warnings(color: &str) -> Vec<String> {
+    match Task::perceived_luminance(color) {
+        Some(luminance) if luminance >= NEAR_WHITE_LUMINANCE => vec![format!(
+            "Color {} is close to white and may be hard to read",
+            color
+        )],
+        Some(luminance) if luminance <= NEAR_BLACK_LUMINANCE => vec![format!(
+            "Color {} is close to black and may be hard to read",
+            color
+        )],
+        _ => Vec::new(),
+    }
+}
+
+/// タスク作成の入力を検証する
+fn validate_create_task(task: &CreateTask) -> AppResult<()> {
     if task.name.trim().is_empty() {
         return Err(AppError::InvalidInput("Task name cannot be empty".to_string()));
     }
+    ensure_max_length(&task.name, "Task name", MAX_NAME_LENGTH)?;
 
     if let Some(ref color) = task.color {
         if !Task::is_valid_color(color) {
@@ -110,12 +350,125 @@ pub fn create_task(state: tauri::State<AppState>, task: CreateTask) -> AppResult
         }
     }
 
-    let new_task = Task::new(task.name, task.description, task.color, task.folder_id);
+    validate_billing(task.hourly_rate, task.currency.as_deref())
+}
 
-    state.db.with_connection(|conn| {
-        insert_task(conn, &new_task)?;
-        Ok(new_task)
-    })
+/// 検証済みの入力からタスクを組み立てて保存する
+fn insert_new_task(conn: &Connection, task: CreateTask) -> AppResult<Task> {
+    // 色が指定されなければ設定のデフォルト色を使う
+    let color = match task.color {
+        Some(color) => Some(color),
+        None => fetch_setting(conn, DEFAULT_TASK_COLOR_KEY)?,
+    };
+
+    let mut new_task = Task::new(task.name, task.description, color, task.folder_id);
+    new_task.hourly_rate = task.hourly_rate;
+    new_task.currency = task.currency.map(|c| c.trim().to_string());
+
+    insert_task(conn, &new_task)?;
+    Ok(new_task)
+}
+
+/// 複数のタスクをまとめて作成する
+///
+/// 1件でも検証に失敗した場合は何も保存せずにエラーを返す。
+fn insert_tasks(conn: &Connection, tasks: Vec<CreateTask>) -> AppResult<Vec<Task>> {
+    for (index, task) in tasks.iter().enumerate() {
+        validate_create_task(task).map_err(|e| match e {
+            AppError::InvalidInput(message) => {
+                AppError::InvalidInput(format!("Task #{}: {}", index + 1, message))
+            }
+            other => other,
+        })?;
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    let created = tasks
+        .into_iter()
+        .map(|task| insert_new_task(&tx, task))
+        .collect::<AppResult<Vec<Task>>>()?;
+    tx.commit()?;
+    Ok(created)
+}
+
+/// タスクを作成する（check_contrastがfalseでなければ読みにくい色を警告する）
+#[tauri::command]
+pub fn create_task(
+    state: tauri::State<AppState>,
+    task: CreateTask,
+    check_contrast: Option<bool>,
+) -> AppResult<TaskWithWarnings> {
+    validate_create_task(&task)?;
+
+    let task = state.db.with_connection(|conn| insert_new_task(conn, task))?;
+    let warnings = if check_contrast.unwrap_or(true) {
+        color_contrast_warnings(&task.color)
+    } else {
+        Vec::new()
+    };
+    Ok(TaskWithWarnings { task, warnings })
+}
+
+/// 複数のタスクを1つのトランザクションで作成する
+#[tauri::command]
+pub fn create_tasks(state: tauri::State<AppState>, tasks: Vec<CreateTask>) -> AppResult<Vec<Task>> {
+    state.db.with_connection(|conn| insert_tasks(conn, tasks))
+}
+
+/// タスクに更新内容を適用し、監査ログと同じトランザクションで保存する
+fn apply_task_update(conn: &Connection, task_id: &Uuid, update: UpdateTask) -> AppResult<Task> {
+    let tx = conn.unchecked_transaction()?;
+    let before = fetch_task_by_id(&tx, task_id)?;
+    let mut task = before.clone();
+
+    if let Some(name) = update.name {
+        task.name = name;
+    }
+    if let Some(description) = update.description {
+        task.description = Some(description);
+    }
+    if let Some(color) = update.color {
+        task.color = color;
+    }
+    if let Some(folder_id) = update.folder_id {
+        task.folder_id = folder_id;
+    }
+    if let Some(hourly_rate) = update.hourly_rate {
+        task.hourly_rate = hourly_rate;
+    }
+    if let Some(currency) = update.currency {
+        task.currency = currency;
+    }
+    task.updated_at = Utc::now();
+
+    tx.execute(
+        "UPDATE tasks SET name = ?, description = ?, color = ?, folder_id = ?, hourly_rate = ?, currency = ?, updated_at = ? WHERE id = ?",
+        duckdb::params![
+            &task.name,
+            &task.description,
+            &task.color,
+            task.folder_id.map(|id| id.to_string()),
+            task.hourly_rate,
+            &task.currency,
+            task.updated_at,
+            task.id.to_string(),
+        ],
+    )?;
+
+    if task.name != before.name {
+        record_audit(&tx, "task", task_id, "rename", Some(&before.name), Some(&task.name))?;
+    }
+    record_audit(
+        &tx,
+        "task",
+        task_id,
+        "update",
+        Some(&serde_json::to_string(&before)?),
+        Some(&serde_json::to_string(&task)?),
+    )?;
+    tx.commit()?;
+
+    Ok(task)
 }
 
 /// タスクを更新する
@@ -124,7 +477,8 @@ pub fn update_task(
     state: tauri::State<AppState>,
     id: String,
     update: UpdateTask,
-) -> AppResult<Task> {
+    check_contrast: Option<bool>,
+) -> AppResult<TaskWithWarnings> {
     let task_id = Uuid::parse_str(&id)
         .map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", id)))?;
 
@@ -132,6 +486,7 @@ pub fn update_task(
         if name.trim().is_empty() {
             return Err(AppError::InvalidInput("Task name cannot be empty".to_string()));
         }
+        ensure_max_length(name, "Task name", MAX_NAME_LENGTH)?;
     }
 
     if let Some(ref color) = update.color {
@@ -143,58 +498,211 @@ pub fn update_task(
         }
     }
 
-    state.db.with_connection(|conn| {
-        let mut task = fetch_task_by_id(conn, &task_id)?;
+    validate_billing(
+        update.hourly_rate.flatten(),
+        update.currency.as_ref().and_then(|c| c.as_deref()),
+    )?;
 
-        if let Some(name) = update.name {
-            task.name = name;
-        }
-        if let Some(description) = update.description {
-            task.description = Some(description);
-        }
-        if let Some(color) = update.color {
-            task.color = color;
-        }
-        if let Some(folder_id) = update.folder_id {
-            task.folder_id = folder_id;
-        }
-        task.updated_at = Utc::now();
+    // 色を変更した場合だけ読みやすさを確認する
+    let check_contrast = check_contrast.unwrap_or(true) && update.color.is_some();
+    let task = state
+        .db
+        .with_connection(|conn| apply_task_update(conn, &task_id, update))?;
+    // タスク名・色・フォルダはレポートに含まれるため、すべての月のキャッシュを破棄する
+    state.report_cache.clear();
 
-        conn.execute(
-            "UPDATE tasks SET name = ?, description = ?, color = ?, folder_id = ?, updated_at = ? WHERE id = ?",
-            duckdb::params![
-                &task.name,
-                &task.description,
-                &task.color,
-                task.folder_id.map(|id| id.to_string()),
-                task.updated_at,
-                task.id.to_string(),
-            ],
+    let warnings = if check_contrast {
+        color_contrast_warnings(&task.color)
+    } else {
+        Vec::new()
+    };
+    Ok(TaskWithWarnings { task, warnings })
+}
+
+/// タスクのアーカイブ状態を変更し、監査ログと同じトランザクションで保存する
+fn set_task_archived(conn: &Connection, task_id: &Uuid, archived: bool) -> AppResult<()> {
+    let tx = conn.unchecked_transaction()?;
+    // タスクが存在するか確認
+    let before = fetch_task_by_id(&tx, task_id)?;
+
+    tx.execute(
+        "UPDATE tasks SET archived = ?, updated_at = ? WHERE id = ?",
+        duckdb::params![archived, Utc::now(), task_id.to_string()],
+    )?;
+
+    if before.archived != archived {
+        record_audit(
+            &tx,
+            "task",
+            task_id,
+            if archived { "archive" } else { "unarchive" },
+            Some(&before.archived.to_string()),
+            Some(&archived.to_string()),
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// タスクの所属フォルダを設定する（Noneならフォルダから外す）
+fn assign_task_folder(conn: &Connection, task_id: &Uuid, folder_id: Option<Uuid>) -> AppResult<Task> {
+    if let Some(folder_id) = folder_id {
+        let exists: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM folders WHERE id = ?",
+            [folder_id.to_string()],
+            |row| row.get(0),
         )?;
+        if !exists {
+            return Err(AppError::NotFound(format!(
+                "Folder with id {} not found",
+                folder_id
+            )));
+        }
+    }
 
-        Ok(task)
-    })
+    apply_task_update(
+        conn,
+        task_id,
+        UpdateTask {
+            folder_id: Some(folder_id),
+            ..Default::default()
+        },
+    )
+}
+
+/// タスクの所属フォルダを設定・解除する
+#[tauri::command]
+pub fn set_task_folder(
+    state: tauri::State<AppState>,
+    task_id: String,
+    folder_id: Option<String>,
+) -> AppResult<Task> {
+    let task_uuid = Uuid::parse_str(&task_id)
+        .map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", task_id)))?;
+    let folder_uuid = folder_id
+        .map(|id| {
+            Uuid::parse_str(&id).map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", id)))
+        })
+        .transpose()?;
+
+    let task = state
+        .db
+        .with_connection(|conn| assign_task_folder(conn, &task_uuid, folder_uuid))?;
+    state.report_cache.clear();
+    Ok(task)
 }
 
 /// タスクをアーカイブ/復元する
 #[tauri::command]
-pub fn archive_task(state: tauri::State<AppState>, id: String, archived: bool) -> AppResult<()> {
+pub fn archive_task(
+    state: tauri::State<AppState>,
+    id: String,
+    archived: bool,
+) -> AppResult<ArchiveTaskResult> {
     let task_id = Uuid::parse_str(&id)
         .map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", id)))?;
 
     state.db.with_connection(|conn| {
-        // タスクが存在するか確認
-        let _ = fetch_task_by_id(conn, &task_id)?;
-
-        conn.execute(
-            "UPDATE tasks SET archived = ?, updated_at = ? WHERE id = ?",
-            duckdb::params![archived, Utc::now(), task_id.to_string()],
-        )?;
+        set_task_archived(conn, &task_id, archived)?;
 
-        Ok(())
+        Ok(ArchiveTaskResult {
+            id: task_id,
+            archived,
+            entry_count: count_entries_for_task(conn, &task_id)?,
+        })
     })
 }
 
+/// タスクを削除し、残っている時間記録を別タスクへ付け替えるか未分類にする
+///
+/// 時間記録が残っているのにどちらも指定されていない場合は削除しない。
+/// 影響を受けた時間記録の件数を返す。
+fn delete_task_by_id(
+    conn: &Connection,
+    task_id: &Uuid,
+    reassign_to: Option<&Uuid>,
+    detach_entries: bool,
+) -> AppResult<usize> {
+    if reassign_to.is_some() && detach_entries {
+        return Err(AppError::InvalidInput(
+            "Specify either reassign_to or detach_entries, not both".to_string(),
+        ));
+    }
+    if reassign_to == Some(task_id) {
+        return Err(AppError::InvalidInput(
+            "Cannot reassign entries to the task being deleted".to_string(),
+        ));
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    let before = fetch_task_by_id(&tx, task_id)?;
+
+    let affected = if let Some(target_id) = reassign_to {
+        // 付け替え先のタスクが存在するか確認
+        let _ = fetch_task_by_id(&tx, target_id)?;
+        tx.execute(
+            "UPDATE time_entries SET task_id = ?, updated_at = ? WHERE task_id = ?",
+            duckdb::params![target_id.to_string(), Utc::now(), task_id.to_string()],
+        )?
+    } else if detach_entries {
+        tx.execute(
+            "UPDATE time_entries SET task_id = NULL, updated_at = ? WHERE task_id = ?",
+            duckdb::params![Utc::now(), task_id.to_string()],
+        )?
+    } else {
+        let count = count_entries_for_task(&tx, task_id)?;
+        if count > 0 {
+            return Err(AppError::OperationFailed(format!(
+                "Task has {} entries; choose a task to reassign them to or detach them",
+                count
+            )));
+        }
+        0
+    };
+
+    tx.execute("DELETE FROM tasks WHERE id = ?", [task_id.to_string()])?;
+    record_audit(
+        &tx,
+        "task",
+        task_id,
+        "delete",
+        Some(&serde_json::to_string(&before)?),
+        None,
+    )?;
+    tx.commit()?;
+
+    Ok(affected)
+}
+
+/// タスクを削除する（影響を受けた時間記録の件数を返す）
+#[tauri::command]
+pub fn delete_task(
+    state: tauri::State<AppState>,
+    id: String,
+    reassign_to: Option<String>,
+    detach_entries: Option<bool>,
+) -> AppResult<usize> {
+    let task_id = Uuid::parse_str(&id)
+        .map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", id)))?;
+    let reassign_uuid = reassign_to
+        .map(|target| {
+            Uuid::parse_str(&target)
+                .map_err(|_| AppError::InvalidInput(format!("Invalid UUID: {}", target)))
+        })
+        .transpose()?;
+
+    let affected = state.db.with_connection(|conn| {
+        delete_task_by_id(
+            conn,
+            &task_id,
+            reassign_uuid.as_ref(),
+            detach_entries.unwrap_or(false),
+        )
+    })?;
+    state.report_cache.clear();
+    Ok(affected)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -204,6 +712,15 @@ mod tests {
         Database::new_in_memory().unwrap()
     }
 
+    fn insert_entry_for_task(conn: &Connection, task_id: &Uuid) -> AppResult<()> {
+        conn.execute(
+            "INSERT INTO time_entries (id, task_id, started_at, ended_at, created_at, updated_at)
+             VALUES (uuid(), ?, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+            [task_id.to_string()],
+        )?;
+        Ok(())
+    }
+
     mod list_tasks_tests {
         use super::*;
 
@@ -212,7 +729,7 @@ mod tests {
             let db = create_test_db();
 
             let tasks = db
-                .with_connection(|conn| fetch_tasks(conn, false))
+                .with_connection(|conn| fetch_tasks(conn, false, None))
                 .unwrap();
 
             assert!(tasks.is_empty());
@@ -225,7 +742,7 @@ mod tests {
 
             db.with_connection(|conn| {
                 insert_task(conn, &task)?;
-                let tasks = fetch_tasks(conn, false)?;
+                let tasks = fetch_tasks(conn, false, None)?;
 
                 assert_eq!(tasks.len(), 1);
                 assert_eq!(tasks[0].name, "テスト作業");
@@ -234,6 +751,33 @@ mod tests {
             .unwrap();
         }
 
+        #[test]
+        fn 作成日時が同じタスクは毎回同じ順序で返る() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let created_at = Utc::now();
+                let mut expected = Vec::new();
+                for i in 0..5 {
+                    let mut task = Task::new(format!("作業{}", i), None, None, None);
+                    task.created_at = created_at;
+                    insert_task(conn, &task)?;
+                    expected.push(task.id.to_string());
+                }
+                expected.sort();
+
+                for _ in 0..3 {
+                    let ids: Vec<String> = fetch_tasks(conn, false, None)?
+                        .iter()
+                        .map(|t| t.id.to_string())
+                        .collect();
+                    assert_eq!(ids, expected);
+                }
+                Ok(())
+            })
+            .unwrap();
+        }
+
         #[test]
         fn include_archivedがfalseの場合アーカイブ済みタスクは含まれない() {
             let db = create_test_db();
@@ -242,7 +786,7 @@ mod tests {
 
             db.with_connection(|conn| {
                 insert_task(conn, &task)?;
-                let tasks = fetch_tasks(conn, false)?;
+                let tasks = fetch_tasks(conn, false, None)?;
 
                 assert!(tasks.is_empty());
                 Ok(())
@@ -258,7 +802,7 @@ mod tests {
 
             db.with_connection(|conn| {
                 insert_task(conn, &task)?;
-                let tasks = fetch_tasks(conn, true)?;
+                let tasks = fetch_tasks(conn, true, None)?;
 
                 assert_eq!(tasks.len(), 1);
                 Ok(())
@@ -279,7 +823,7 @@ mod tests {
                 let task2 = Task::new("タスク2".to_string(), None, None, None);
                 insert_task(conn, &task2)?;
 
-                let tasks = fetch_tasks(conn, false)?;
+                let tasks = fetch_tasks(conn, false, None)?;
 
                 assert_eq!(tasks.len(), 2);
                 assert_eq!(tasks[0].name, "タスク2"); // 新しい方が先
@@ -290,6 +834,134 @@ mod tests {
         }
     }
 
+    mod task_query_tests {
+        use super::*;
+
+        fn names(tasks: &[Task]) -> Vec<&str> {
+            tasks.iter().map(|t| t.name.as_str()).collect()
+        }
+
+        #[test]
+        fn 名前と説明を大文字小文字を区別せずに検索できる() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                insert_task(conn, &Task::new("API設計".to_string(), None, None, None))?;
+                insert_task(
+                    conn,
+                    &Task::new("定例".to_string(), Some("api仕様の確認".to_string()), None, None),
+                )?;
+                insert_task(conn, &Task::new("雑務".to_string(), None, None, None))?;
+
+                let tasks = fetch_tasks(conn, false, Some("Api"))?;
+                let mut found = names(&tasks);
+                found.sort();
+                assert_eq!(found, vec!["API設計", "定例"]);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 空白のみの検索語は絞り込みなしとして扱われる() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                insert_task(conn, &Task::new("作業A".to_string(), None, None, None))?;
+                insert_task(conn, &Task::new("作業B".to_string(), None, None, None))?;
+
+                assert_eq!(fetch_tasks(conn, false, Some("   "))?.len(), 2);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 検索はアーカイブの条件と組み合わせられる() {
+            let db = create_test_db();
+            let mut archived = Task::new("古いレビュー".to_string(), None, None, None);
+            archived.archived = true;
+
+            db.with_connection(|conn| {
+                insert_task(conn, &archived)?;
+                insert_task(conn, &Task::new("レビュー".to_string(), None, None, None))?;
+
+                assert_eq!(names(&fetch_tasks(conn, false, Some("レビュー"))?), vec!["レビュー"]);
+                assert_eq!(fetch_tasks(conn, true, Some("レビュー"))?.len(), 2);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 検索語のワイルドカード文字はそのまま一致させる() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                insert_task(conn, &Task::new("進捗100%".to_string(), None, None, None))?;
+                insert_task(conn, &Task::new("進捗100件".to_string(), None, None, None))?;
+
+                assert_eq!(names(&fetch_tasks(conn, false, Some("100%"))?), vec!["進捗100%"]);
+                Ok(())
+            })
+            .unwrap();
+        }
+    }
+
+    mod list_recent_tasks_tests {
+        use super::*;
+
+        fn insert_entry_at(conn: &Connection, task_id: &Uuid, started_at: &str) -> AppResult<()> {
+            conn.execute(
+                "INSERT INTO time_entries (id, task_id, started_at, ended_at, created_at, updated_at)
+                 VALUES (uuid(), ?, ?::TIMESTAMPTZ, ?::TIMESTAMPTZ, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                [task_id.to_string(), started_at.to_string(), started_at.to_string()],
+            )?;
+            Ok(())
+        }
+
+        #[test]
+        fn 最後に使用した日時が新しい順に取得される() {
+            let db = create_test_db();
+            let old = Task::new("古い".to_string(), None, None, None);
+            let recent = Task::new("最近".to_string(), None, None, None);
+
+            db.with_connection(|conn| {
+                insert_task(conn, &old)?;
+                insert_task(conn, &recent)?;
+                insert_entry_at(conn, &old.id, "2024-01-10 09:00:00+00")?;
+                insert_entry_at(conn, &old.id, "2024-01-12 09:00:00+00")?;
+                insert_entry_at(conn, &recent.id, "2024-01-15 09:00:00+00")?;
+
+                let tasks = fetch_recent_tasks(conn, 10)?;
+                let names: Vec<&str> = tasks.iter().map(|t| t.name.as_str()).collect();
+                assert_eq!(names, vec!["最近", "古い"]);
+
+                assert_eq!(fetch_recent_tasks(conn, 1)?.len(), 1);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 未使用のタスクとアーカイブ済みのタスクは含まれない() {
+            let db = create_test_db();
+            let unused = Task::new("未使用".to_string(), None, None, None);
+            let mut archived = Task::new("アーカイブ済み".to_string(), None, None, None);
+            archived.archived = true;
+
+            db.with_connection(|conn| {
+                insert_task(conn, &unused)?;
+                insert_task(conn, &archived)?;
+                insert_entry_at(conn, &archived.id, "2024-01-15 09:00:00+00")?;
+
+                assert!(fetch_recent_tasks(conn, 10)?.is_empty());
+                Ok(())
+            })
+            .unwrap();
+        }
+    }
+
     mod create_task_tests {
         use super::*;
 
@@ -316,6 +988,8 @@ mod tests {
                 description: None,
                 color: None,
                 folder_id: None,
+                hourly_rate: None,
+                currency: None,
             };
 
             if create_task.name.trim().is_empty() {
@@ -331,11 +1005,169 @@ mod tests {
 
             assert!(!Task::is_valid_color(invalid_color));
         }
+
+        #[test]
+        fn 白や黒に近い色は警告され通常の色は警告されない() {
+            assert_eq!(color_contrast_warnings("#FFFFFF").len(), 1);
+            assert_eq!(color_contrast_warnings("#F5F5F5").len(), 1);
+            assert_eq!(color_contrast_warnings("#000000").len(), 1);
+            assert!(color_contrast_warnings("#3b82f6").is_empty());
+            assert!(color_contrast_warnings("#808080").is_empty());
+            assert!(color_contrast_warnings("invalid").is_empty());
+        }
+
+        fn create_input(name: &str, color: Option<&str>) -> CreateTask {
+            CreateTask {
+                name: name.to_string(),
+                description: None,
+                color: color.map(|c| c.to_string()),
+                folder_id: None,
+                hourly_rate: None,
+                currency: None,
+            }
+        }
+
+        #[test]
+        fn 複数のタスクをまとめて作成できる() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let created = insert_tasks(
+                    conn,
+                    vec![
+                        create_input("設計", Some("#112233")),
+                        create_input("実装", None),
+                        create_input("テスト", None),
+                    ],
+                )?;
+
+                assert_eq!(created.len(), 3);
+                assert_eq!(created[0].color, "#112233");
+                assert_eq!(fetch_tasks(conn, false, None)?.len(), 3);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 一括作成で1件でも不正な入力があれば何も作成されない() {
+            let db = create_test_db();
+
+            db.with_connection(|conn| {
+                let result = insert_tasks(
+                    conn,
+                    vec![
+                        create_input("設計", None),
+                        create_input("実装", Some("invalid")),
+                    ],
+                );
+
+                match result {
+                    Err(AppError::InvalidInput(message)) => assert!(message.contains("#2")),
+                    other => panic!("Expected InvalidInput, got {:?}", other),
+                }
+                assert!(fetch_tasks(conn, true, None)?.is_empty());
+                Ok(())
+            })
+            .unwrap();
+        }
     }
 
     mod update_task_tests {
         use super::*;
 
+        #[test]
+        fn フォルダを設定して解除できる() {
+            let db = create_test_db();
+            let task = Task::new("整理対象".to_string(), None, None, None);
+            let folder_id = Uuid::new_v4();
+
+            db.with_connection(|conn| {
+                insert_task(conn, &task)?;
+                conn.execute(
+                    "INSERT INTO folders (id, name, color, sort_order, created_at, updated_at)
+                     VALUES (?, '開発', '#000000', 1, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                    [folder_id.to_string()],
+                )?;
+
+                let moved = assign_task_folder(conn, &task.id, Some(folder_id))?;
+                assert_eq!(moved.folder_id, Some(folder_id));
+
+                let cleared = assign_task_folder(conn, &task.id, None)?;
+                assert_eq!(cleared.folder_id, None);
+                assert_eq!(fetch_task_by_id(conn, &task.id)?.folder_id, None);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 存在しないフォルダには移動できない() {
+            let db = create_test_db();
+            let task = Task::new("整理対象".to_string(), None, None, None);
+
+            db.with_connection(|conn| {
+                insert_task(conn, &task)?;
+                let result = assign_task_folder(conn, &task.id, Some(Uuid::new_v4()));
+                assert!(matches!(result, Err(AppError::NotFound(_))));
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 名前を変更すると監査ログに変更前後の名前が記録される() {
+            let db = create_test_db();
+            let task = Task::new("旧名".to_string(), None, None, None);
+
+            db.with_connection(|conn| {
+                insert_task(conn, &task)?;
+                let update = UpdateTask {
+                    name: Some("新名".to_string()),
+                    ..Default::default()
+                };
+                let updated = apply_task_update(conn, &task.id, update)?;
+                assert_eq!(updated.name, "新名");
+
+                let (old_value, new_value): (Option<String>, Option<String>) = conn.query_row(
+                    "SELECT old_value, new_value FROM audit_log
+                     WHERE entity_id = ? AND action = 'rename'",
+                    [task.id.to_string()],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )?;
+                assert_eq!(old_value.as_deref(), Some("旧名"));
+                assert_eq!(new_value.as_deref(), Some("新名"));
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 時間単価と通貨を保存して取得できる() {
+            let db = create_test_db();
+            let mut task = Task::new("請求対象".to_string(), None, None, None);
+            task.hourly_rate = Some(5000.0);
+            task.currency = Some("JPY".to_string());
+
+            db.with_connection(|conn| {
+                insert_task(conn, &task)?;
+                let fetched = fetch_task_by_id(conn, &task.id)?;
+
+                assert_eq!(fetched.hourly_rate, Some(5000.0));
+                assert_eq!(fetched.currency, Some("JPY".to_string()));
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 負の時間単価はエラーになる() {
+            assert!(validate_billing(Some(-1.0), None).is_err());
+            assert!(validate_billing(Some(f64::NAN), None).is_err());
+            assert!(validate_billing(None, Some("  ")).is_err());
+            assert!(validate_billing(Some(0.0), Some("USD")).is_ok());
+        }
+
         #[test]
         fn タスク名を更新できる() {
             let db = create_test_db();
@@ -410,6 +1242,120 @@ mod tests {
     mod archive_task_tests {
         use super::*;
 
+        #[test]
+        fn アーカイブ状態を変更すると監査ログに記録される() {
+            let db = create_test_db();
+            let task = Task::new("タスク".to_string(), None, None, None);
+
+            db.with_connection(|conn| {
+                insert_task(conn, &task)?;
+                set_task_archived(conn, &task.id, true)?;
+                // 状態が変わらない場合は記録しない
+                set_task_archived(conn, &task.id, true)?;
+
+                let actions: Vec<(String, Option<String>)> = conn
+                    .prepare("SELECT action, new_value FROM audit_log WHERE entity_id = ?")?
+                    .query_map([task.id.to_string()], |row| Ok((row.get(0)?, row.get(1)?)))?
+                    .collect::<Result<_, _>>()?;
+                assert_eq!(actions, vec![("archive".to_string(), Some("true".to_string()))]);
+                assert!(fetch_task_by_id(conn, &task.id)?.archived);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn タスクを参照するエントリ件数を取得できる() {
+            let db = create_test_db();
+            let task = Task::new("タスク".to_string(), None, None, None);
+
+            db.with_connection(|conn| {
+                insert_task(conn, &task)?;
+                assert_eq!(count_entries_for_task(conn, &task.id)?, 0);
+
+                insert_entry_for_task(conn, &task.id)?;
+                insert_entry_for_task(conn, &task.id)?;
+                assert_eq!(count_entries_for_task(conn, &task.id)?, 2);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn エントリ件数付きのタスク一覧を取得できる() {
+            let db = create_test_db();
+            let used = Task::new("使用中".to_string(), None, None, None);
+            let unused = Task::new("未使用".to_string(), None, None, None);
+
+            db.with_connection(|conn| {
+                insert_task(conn, &used)?;
+                insert_task(conn, &unused)?;
+                insert_entry_for_task(conn, &used.id)?;
+                insert_entry_for_task(conn, &used.id)?;
+                insert_entry_for_task(conn, &used.id)?;
+
+                let tasks = fetch_tasks_with_stats(conn, false, true, false, None)?;
+                let count_of = |id: &Uuid| {
+                    tasks.iter().find(|t| &t.task.id == id).unwrap().entry_count
+                };
+                assert_eq!(tasks.len(), 2);
+                assert_eq!(count_of(&used.id), Some(3));
+                assert_eq!(count_of(&unused.id), Some(0));
+                assert!(tasks.iter().all(|t| t.total_seconds.is_none()));
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 合計作業時間付きのタスク一覧を取得できる() {
+            let db = create_test_db();
+            let used = Task::new("使用中".to_string(), None, None, None);
+            let unused = Task::new("未使用".to_string(), None, None, None);
+
+            db.with_connection(|conn| {
+                insert_task(conn, &used)?;
+                insert_task(conn, &unused)?;
+                conn.execute(
+                    "INSERT INTO time_entries (id, task_id, started_at, ended_at, created_at, updated_at) VALUES
+                     (uuid(), ?, '2024-12-01 09:00:00+00', '2024-12-01 10:00:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP),
+                     (uuid(), ?, '2024-12-02 09:00:00+00', '2024-12-02 09:30:00+00', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                    [used.id.to_string(), used.id.to_string()],
+                )?;
+                // 計測中のエントリは合計に含めない
+                conn.execute(
+                    "INSERT INTO time_entries (id, task_id, started_at, created_at, updated_at)
+                     VALUES (uuid(), ?, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                    [used.id.to_string()],
+                )?;
+
+                let tasks = fetch_tasks_with_stats(conn, false, false, true, None)?;
+                let total_of = |id: &Uuid| {
+                    tasks.iter().find(|t| &t.task.id == id).unwrap().total_seconds
+                };
+                assert_eq!(total_of(&used.id), Some(5400));
+                assert_eq!(total_of(&unused.id), Some(0));
+                assert!(tasks.iter().all(|t| t.entry_count.is_none()));
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn エントリ件数を要求しない場合はタスクと同じ形でシリアライズされる() {
+            let task = Task::new("タスク".to_string(), None, None, None);
+            let with_stats = TaskWithStats {
+                task: task.clone(),
+                entry_count: None,
+                total_seconds: None,
+            };
+
+            assert_eq!(
+                serde_json::to_value(&with_stats).unwrap(),
+                serde_json::to_value(&task).unwrap()
+            );
+        }
+
         #[test]
         fn タスクをアーカイブできる() {
             let db = create_test_db();
@@ -461,4 +1407,101 @@ mod tests {
             assert!(result.is_err());
         }
     }
+
+    mod delete_task_tests {
+        use super::*;
+
+        #[test]
+        fn 時間記録のないタスクは削除できる() {
+            let db = create_test_db();
+            let task = Task::new("テスト".to_string(), None, None, None);
+
+            db.with_connection(|conn| {
+                insert_task(conn, &task)?;
+
+                assert_eq!(delete_task_by_id(conn, &task.id, None, false)?, 0);
+                assert!(matches!(
+                    fetch_task_by_id(conn, &task.id),
+                    Err(AppError::NotFound(_))
+                ));
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 時間記録が残っている場合は扱いを指定しないと削除できない() {
+            let db = create_test_db();
+            let task = Task::new("テスト".to_string(), None, None, None);
+
+            db.with_connection(|conn| {
+                insert_task(conn, &task)?;
+                insert_entry_for_task(conn, &task.id)?;
+
+                let result = delete_task_by_id(conn, &task.id, None, false);
+                assert!(matches!(result, Err(AppError::OperationFailed(_))));
+                assert!(fetch_task_by_id(conn, &task.id).is_ok());
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 時間記録を別のタスクに付け替えて削除できる() {
+            let db = create_test_db();
+            let task = Task::new("削除対象".to_string(), None, None, None);
+            let target = Task::new("付け替え先".to_string(), None, None, None);
+
+            db.with_connection(|conn| {
+                insert_task(conn, &task)?;
+                insert_task(conn, &target)?;
+                insert_entry_for_task(conn, &task.id)?;
+                insert_entry_for_task(conn, &task.id)?;
+
+                assert_eq!(delete_task_by_id(conn, &task.id, Some(&target.id), false)?, 2);
+                assert_eq!(count_entries_for_task(conn, &target.id)?, 2);
+                assert!(fetch_task_by_id(conn, &task.id).is_err());
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 時間記録を未分類にして削除できる() {
+            let db = create_test_db();
+            let task = Task::new("削除対象".to_string(), None, None, None);
+
+            db.with_connection(|conn| {
+                insert_task(conn, &task)?;
+                insert_entry_for_task(conn, &task.id)?;
+
+                assert_eq!(delete_task_by_id(conn, &task.id, None, true)?, 1);
+                let unassigned: i64 = conn.query_row(
+                    "SELECT COUNT(*) FROM time_entries WHERE task_id IS NULL",
+                    [],
+                    |row| row.get(0),
+                )?;
+                assert_eq!(unassigned, 1);
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        #[test]
+        fn 存在しない付け替え先を指定すると何も変更されない() {
+            let db = create_test_db();
+            let task = Task::new("削除対象".to_string(), None, None, None);
+
+            db.with_connection(|conn| {
+                insert_task(conn, &task)?;
+                insert_entry_for_task(conn, &task.id)?;
+
+                let result = delete_task_by_id(conn, &task.id, Some(&Uuid::new_v4()), false);
+                assert!(matches!(result, Err(AppError::NotFound(_))));
+                assert_eq!(count_entries_for_task(conn, &task.id)?, 1);
+                Ok(())
+            })
+            .unwrap();
+        }
+    }
 }
@@ -1,15 +1,17 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
 use duckdb::Connection;
 
-use crate::error::{AppError, AppResult};
+use crate::error::AppResult;
 
 use super::migrations::run_migrations;
 
 /// データベース管理構造体
 pub struct Database {
     conn: Mutex<Connection>,
+    /// ファイルベースの場合のデータベースファイルのパス
+    path: Option<PathBuf>,
 }
 
 impl Database {
@@ -19,27 +21,38 @@ impl Database {
         run_migrations(&conn)?;
         Ok(Self {
             conn: Mutex::new(conn),
+            path: None,
         })
     }
 
     /// ファイルベースのデータベースを開く/作成する
     pub fn open<P: AsRef<Path>>(path: P) -> AppResult<Self> {
-        let conn = Connection::open(path)?;
+        let conn = Connection::open(&path)?;
         run_migrations(&conn)?;
         Ok(Self {
             conn: Mutex::new(conn),
+            path: Some(path.as_ref().to_path_buf()),
         })
     }
 
+    /// データベースファイルのパスを取得する（メモリ上の場合はNone）
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
     /// データベース接続を取得してクロージャを実行する
+    ///
+    /// クロージャ内のパニックでロックが壊れても接続自体は使えるため、
+    /// 壊れたロックから接続を取り戻して処理を続ける。
     pub fn with_connection<F, T>(&self, f: F) -> AppResult<T>
     where
         F: FnOnce(&Connection) -> AppResult<T>,
     {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|e| AppError::OperationFailed(format!("Failed to acquire lock: {}", e)))?;
+        let conn = self.conn.lock().unwrap_or_else(|e| {
+            eprintln!("[with_connection] recovering from poisoned lock: {}", e);
+            self.conn.clear_poison();
+            e.into_inner()
+        });
         f(&conn)
     }
 }
@@ -82,6 +95,23 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn クロージャ内でパニックしても次のwith_connectionは成功する() {
+        let db = Database::new_in_memory().unwrap();
+
+        let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _ = db.with_connection(|_conn| -> AppResult<()> { panic!("テスト用のパニック") });
+        }));
+        assert!(panicked.is_err());
+
+        let result = db.with_connection(|conn| {
+            let value: i64 = conn.query_row("SELECT 1", [], |row| row.get(0))?;
+            Ok(value)
+        });
+
+        assert_eq!(result.unwrap(), 1);
+    }
+
     #[test]
     fn ファイルベースのデータベースを作成できる() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -4,43 +4,234 @@ use crate::error::AppResult;
 
 const MIGRATION_SQL: &str = include_str!("../../migrations/001_initial.sql");
 
+/// 現在のスキーマバージョン（UPGRADESの最後のバージョンと一致させる）
+pub const SCHEMA_VERSION: i32 = 7;
+
+/// 基本マイグレーションで作成するテーブル
+const BASE_TABLES: &[&str] = &[
+    "schema_meta",
+    "folders",
+    "tasks",
+    "artifacts",
+    "time_entries",
+    "entry_artifacts",
+    "heartbeats",
+    "entry_notes",
+    "settings",
+    "audit_log",
+    "auto_rules",
+];
+
+/// アップグレード処理
+type Upgrade = fn(&Connection) -> AppResult<()>;
+
+/// スキーマアップグレード（バージョン順に並べる。説明は適用前の確認表示に使う）
+///
+/// バージョン管理導入前に作成されたデータベースはバージョン0として扱われ、
+/// すべてのアップグレードが再適用されるため、各アップグレードは冪等にする。
+const UPGRADES: &[(i32, &str, Upgrade)] = &[
+    (1, "tasksにfolder_idを追加", upgrade_v1_task_folder_id),
+    (2, "foldersにiconを追加", upgrade_v2_folder_icon),
+    (3, "tasksに単価と通貨を追加", upgrade_v3_task_billing),
+    (4, "time_entriesに作業秒数を追加して既存の記録から埋める", upgrade_v4_entry_duration),
+    (5, "計測中のエントリを1件に制限する", upgrade_v5_single_running_entry),
+    (6, "artifactsに更新日時を追加して作成日時で埋める", upgrade_v6_artifact_updated_at),
+    (7, "foldersにメモを追加", upgrade_v7_folder_description),
+];
+
 /// マイグレーションを実行する
 pub fn run_migrations(conn: &Connection) -> AppResult<()> {
     // Run base migrations (creates tables if they don't exist)
     conn.execute_batch(MIGRATION_SQL)?;
 
-    // Schema upgrade: Add folder_id column to tasks if it doesn't exist
-    // (for databases created before folder feature was added)
-    let has_folder_id: bool = conn
-        .query_row(
-            "SELECT COUNT(*) > 0 FROM information_schema.columns
-             WHERE table_name = 'tasks' AND column_name = 'folder_id'",
-            [],
-            |row| row.get(0),
-        )
-        .unwrap_or(false);
+    let current = get_schema_version(conn)?;
+    for (version, _, upgrade) in UPGRADES {
+        if *version > current {
+            upgrade(conn)?;
+            set_schema_version(conn, *version)?;
+        }
+    }
 
-    if !has_folder_id {
-        conn.execute_batch("ALTER TABLE tasks ADD COLUMN folder_id VARCHAR")?;
+    Ok(())
+}
+
+/// スキーマバージョンを取得する（未記録の場合は0）
+pub fn get_schema_version(conn: &Connection) -> AppResult<i32> {
+    let version: Option<i32> = conn.query_row("SELECT MAX(version) FROM schema_meta", [], |row| {
+        row.get(0)
+    })?;
+    Ok(version.unwrap_or(0))
+}
+
+/// 適用されていないマイグレーションの内容を、実際には適用せずに返す
+pub fn check_migrations(conn: &Connection) -> AppResult<Vec<String>> {
+    let mut steps = Vec::new();
+
+    let mut missing_tables = Vec::new();
+    for table in BASE_TABLES {
+        if !table_exists(conn, table)? {
+            missing_tables.push(*table);
+        }
+    }
+    if !missing_tables.is_empty() {
+        steps.push(format!("テーブルを作成: {}", missing_tables.join(", ")));
     }
 
-    // Schema upgrade: Add icon column to folders if it doesn't exist
-    let has_icon: bool = conn
+    let current = if table_exists(conn, "schema_meta")? {
+        get_schema_version(conn)?
+    } else {
+        0
+    };
+    if current > SCHEMA_VERSION {
+        steps.push(format!(
+            "スキーマバージョン{}はこのアプリの対応バージョン{}より新しい",
+            current, SCHEMA_VERSION
+        ));
+        return Ok(steps);
+    }
+
+    for (version, description, _) in UPGRADES {
+        if *version > current {
+            steps.push(format!("v{}: {}", version, description));
+        }
+    }
+    if current < SCHEMA_VERSION {
+        steps.push(format!(
+            "スキーマバージョンを{}から{}に更新",
+            current, SCHEMA_VERSION
+        ));
+    }
+
+    Ok(steps)
+}
+
+/// テーブルが存在するか確認する
+fn table_exists(conn: &Connection, table: &str) -> AppResult<bool> {
+    let exists: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM information_schema.tables WHERE table_name = ?",
+        [table],
+        |row| row.get(0),
+    )?;
+    Ok(exists)
+}
+
+/// スキーマバージョンを記録する（schema_metaは常に1行のみ）
+fn set_schema_version(conn: &Connection, version: i32) -> AppResult<()> {
+    conn.execute("DELETE FROM schema_meta", [])?;
+    conn.execute("INSERT INTO schema_meta (version) VALUES (?)", [version])?;
+    Ok(())
+}
+
+/// カラムが存在しない場合のみ追加する
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> AppResult<()> {
+    let exists: bool = conn
         .query_row(
             "SELECT COUNT(*) > 0 FROM information_schema.columns
-             WHERE table_name = 'folders' AND column_name = 'icon'",
-            [],
+             WHERE table_name = ? AND column_name = ?",
+            [table, column],
             |row| row.get(0),
         )
         .unwrap_or(false);
 
-    if !has_icon {
-        conn.execute_batch("ALTER TABLE folders ADD COLUMN icon VARCHAR(50)")?;
+    if !exists {
+        conn.execute_batch(&format!(
+            "ALTER TABLE {} ADD COLUMN {} {}",
+            table, column, definition
+        ))?;
     }
+    Ok(())
+}
+
+/// v1: tasksにfolder_idを追加（フォルダ機能追加前のデータベース向け）
+fn upgrade_v1_task_folder_id(conn: &Connection) -> AppResult<()> {
+    add_column_if_missing(conn, "tasks", "folder_id", "VARCHAR")
+}
+
+/// v2: foldersにiconを追加
+fn upgrade_v2_folder_icon(conn: &Connection) -> AppResult<()> {
+    add_column_if_missing(conn, "folders", "icon", "VARCHAR(50)")
+}
 
+/// v3: tasksに請求用の単価と通貨を追加
+fn upgrade_v3_task_billing(conn: &Connection) -> AppResult<()> {
+    add_column_if_missing(conn, "tasks", "hourly_rate", "DOUBLE")?;
+    add_column_if_missing(conn, "tasks", "currency", "VARCHAR(10)")
+}
+
+/// v4: time_entriesに作業秒数を追加し、既存の記録を埋める
+fn upgrade_v4_entry_duration(conn: &Connection) -> AppResult<()> {
+    add_column_if_missing(conn, "time_entries", "duration_seconds", "BIGINT")?;
+    recompute_durations(conn)?;
+    Ok(())
+}
+
+/// v5: 計測中（ended_at IS NULL）のエントリを最大1件に制限する
+///
+/// DuckDBは部分インデックスに対応していないため、計測中のみ値を持つ式に
+/// UNIQUEインデックスを作成する（NULL同士は重複とみなされない）。
+/// 既に複数の計測中エントリがある場合は、最新以外を最新の開始時刻で終了させる。
+fn upgrade_v5_single_running_entry(conn: &Connection) -> AppResult<()> {
+    conn.execute_batch(
+        "UPDATE time_entries
+         SET ended_at = latest.started_at,
+             duration_seconds = TRUNC(
+                 EPOCH(latest.started_at::TIMESTAMP) - EPOCH(time_entries.started_at::TIMESTAMP)
+             )::BIGINT
+         FROM (
+             SELECT id, started_at FROM time_entries
+             WHERE ended_at IS NULL
+             ORDER BY started_at DESC, id DESC
+             LIMIT 1
+         ) latest
+         WHERE time_entries.ended_at IS NULL AND time_entries.id <> latest.id;
+         CREATE UNIQUE INDEX IF NOT EXISTS idx_time_entries_single_running
+             ON time_entries ((CASE WHEN ended_at IS NULL THEN TRUE END));",
+    )?;
     Ok(())
 }
 
+/// v6: artifactsに更新日時を追加し、既存の成果物は作成日時で埋める
+///
+/// 新規作成したスキーマと揃うよう、埋めたあとでNOT NULLと既定値を設定する。
+fn upgrade_v6_artifact_updated_at(conn: &Connection) -> AppResult<()> {
+    add_column_if_missing(conn, "artifacts", "updated_at", "TIMESTAMPTZ")?;
+    conn.execute_batch(
+        "UPDATE artifacts SET updated_at = created_at WHERE updated_at IS NULL;
+         ALTER TABLE artifacts ALTER COLUMN updated_at SET DEFAULT CURRENT_TIMESTAMP;
+         ALTER TABLE artifacts ALTER COLUMN updated_at SET NOT NULL;",
+    )?;
+    Ok(())
+}
+
+/// v7: foldersにメモを追加
+fn upgrade_v7_folder_description(conn: &Connection) -> AppResult<()> {
+    add_column_if_missing(conn, "folders", "description", "TEXT")
+}
+
+/// 保存済みの作業秒数を開始・終了日時から再計算する（計測中はNULL）
+///
+/// 値が変わった記録の件数を返す。
+pub fn recompute_durations(conn: &Connection) -> AppResult<usize> {
+    let updated = conn.execute(
+        "UPDATE time_entries
+         SET duration_seconds = CASE
+             WHEN ended_at IS NULL THEN NULL
+             ELSE TRUNC(EPOCH(ended_at::TIMESTAMP) - EPOCH(started_at::TIMESTAMP))::BIGINT
+         END
+         WHERE duration_seconds IS DISTINCT FROM CASE
+             WHEN ended_at IS NULL THEN NULL
+             ELSE TRUNC(EPOCH(ended_at::TIMESTAMP) - EPOCH(started_at::TIMESTAMP))::BIGINT
+         END",
+        [],
+    )?;
+    Ok(updated)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -111,6 +302,242 @@ mod tests {
         assert_eq!(count, 1);
     }
 
+    #[test]
+    fn 単価カラムがない古いtasksテーブルにカラムが追加される() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE tasks (
+                id VARCHAR PRIMARY KEY,
+                folder_id VARCHAR,
+                name VARCHAR NOT NULL,
+                description TEXT,
+                color VARCHAR(7) NOT NULL DEFAULT '#3b82f6',
+                archived BOOLEAN NOT NULL DEFAULT FALSE,
+                created_at TIMESTAMPTZ NOT NULL,
+                updated_at TIMESTAMPTZ NOT NULL
+            )",
+        )
+        .unwrap();
+
+        run_migrations(&conn).unwrap();
+
+        let count: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM information_schema.columns
+                 WHERE table_name = 'tasks' AND column_name IN ('hourly_rate', 'currency')",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn 作業秒数カラムがない古いtime_entriesは既存の記録から埋められる() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE time_entries (
+                id VARCHAR PRIMARY KEY,
+                task_id VARCHAR,
+                started_at TIMESTAMPTZ NOT NULL,
+                ended_at TIMESTAMPTZ,
+                memo TEXT,
+                created_at TIMESTAMPTZ NOT NULL,
+                updated_at TIMESTAMPTZ NOT NULL
+            );
+            INSERT INTO time_entries VALUES
+                ('done', NULL, '2024-01-15 09:00:00+00', '2024-01-15 10:30:00+00', NULL,
+                 '2024-01-15 09:00:00+00', '2024-01-15 10:30:00+00'),
+                ('running', NULL, '2024-01-15 11:00:00+00', NULL, NULL,
+                 '2024-01-15 11:00:00+00', '2024-01-15 11:00:00+00');",
+        )
+        .unwrap();
+
+        run_migrations(&conn).unwrap();
+
+        let done: Option<i64> = conn
+            .query_row(
+                "SELECT duration_seconds FROM time_entries WHERE id = 'done'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        let running: Option<i64> = conn
+            .query_row(
+                "SELECT duration_seconds FROM time_entries WHERE id = 'running'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(done, Some(5400));
+        assert_eq!(running, None);
+    }
+
+    #[test]
+    fn 更新日時カラムがない古いartifactsは作成日時で埋められる() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE artifacts (
+                id VARCHAR PRIMARY KEY,
+                name VARCHAR NOT NULL,
+                artifact_type VARCHAR(50) NOT NULL,
+                reference TEXT,
+                metadata JSON,
+                created_at TIMESTAMPTZ NOT NULL
+            );
+            INSERT INTO artifacts VALUES
+                ('old', '設計書', 'document', NULL, NULL, '2024-01-15 09:00:00+00');",
+        )
+        .unwrap();
+
+        run_migrations(&conn).unwrap();
+
+        let same: bool = conn
+            .query_row(
+                "SELECT updated_at = created_at FROM artifacts WHERE id = 'old'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(same);
+
+        // 新規作成したスキーマと同じくNOT NULLで、省略時は現在時刻が入る
+        let is_nullable: String = conn
+            .query_row(
+                "SELECT is_nullable FROM information_schema.columns
+                 WHERE table_name = 'artifacts' AND column_name = 'updated_at'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(is_nullable, "NO");
+        conn.execute(
+            "INSERT INTO artifacts (id, name, artifact_type, created_at)
+             VALUES ('new', '議事録', 'document', CURRENT_TIMESTAMP)",
+            [],
+        )
+        .unwrap();
+        let filled: bool = conn
+            .query_row(
+                "SELECT updated_at IS NOT NULL FROM artifacts WHERE id = 'new'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(filled);
+    }
+
+    #[test]
+    fn 作業秒数の再計算は変化した記録のみ更新する() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO time_entries (id, started_at, ended_at, duration_seconds, created_at, updated_at)
+             VALUES
+                ('ok', '2024-01-15 09:00:00+00', '2024-01-15 10:00:00+00', 3600,
+                 '2024-01-15 09:00:00+00', '2024-01-15 10:00:00+00'),
+                ('stale', '2024-01-15 11:00:00+00', '2024-01-15 11:30:00+00', 60,
+                 '2024-01-15 11:00:00+00', '2024-01-15 11:30:00+00');",
+        )
+        .unwrap();
+
+        assert_eq!(recompute_durations(&conn).unwrap(), 1);
+        assert_eq!(recompute_durations(&conn).unwrap(), 0);
+
+        let stale: i64 = conn
+            .query_row(
+                "SELECT duration_seconds FROM time_entries WHERE id = 'stale'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(stale, 1800);
+    }
+
+    #[test]
+    fn 計測中のエントリは2件以上保存できない() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+
+        let insert_running = |id: &str| {
+            conn.execute(
+                "INSERT INTO time_entries (id, started_at, created_at, updated_at)
+                 VALUES (?, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)",
+                [id],
+            )
+        };
+        assert!(insert_running("first").is_ok());
+        assert!(insert_running("second").is_err());
+
+        // 終了済みにすれば次の計測中エントリを保存できる
+        conn.execute(
+            "UPDATE time_entries SET ended_at = CURRENT_TIMESTAMP WHERE id = 'first'",
+            [],
+        )
+        .unwrap();
+        assert!(insert_running("second").is_ok());
+    }
+
+    #[test]
+    fn 既存の複数の計測中エントリは最新以外が終了される() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE time_entries (
+                id VARCHAR PRIMARY KEY,
+                task_id VARCHAR,
+                started_at TIMESTAMPTZ NOT NULL,
+                ended_at TIMESTAMPTZ,
+                duration_seconds BIGINT,
+                memo TEXT,
+                created_at TIMESTAMPTZ NOT NULL,
+                updated_at TIMESTAMPTZ NOT NULL
+            );
+            INSERT INTO time_entries (id, started_at, created_at, updated_at) VALUES
+                ('older', '2024-01-15 09:00:00+00', '2024-01-15 09:00:00+00', '2024-01-15 09:00:00+00'),
+                ('latest', '2024-01-15 10:00:00+00', '2024-01-15 10:00:00+00', '2024-01-15 10:00:00+00');",
+        )
+        .unwrap();
+
+        run_migrations(&conn).unwrap();
+
+        let running: Vec<String> = conn
+            .prepare("SELECT id FROM time_entries WHERE ended_at IS NULL")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(running, vec!["latest".to_string()]);
+
+        let older_duration: i64 = conn
+            .query_row(
+                "SELECT duration_seconds FROM time_entries WHERE id = 'older'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(older_duration, 3600);
+    }
+
+    #[test]
+    fn マイグレーション後のスキーマバージョンは最新になる() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+
+        assert_eq!(get_schema_version(&conn).unwrap(), SCHEMA_VERSION);
+        let rows: i64 = conn
+            .query_row("SELECT COUNT(*) FROM schema_meta", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(rows, 1);
+    }
+
+    #[test]
+    fn 最新のアップグレード定義とスキーマバージョン定数が一致する() {
+        assert_eq!(UPGRADES.last().map(|(v, _, _)| *v), Some(SCHEMA_VERSION));
+        assert!(UPGRADES.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
     #[test]
     fn マイグレーションは冪等性がある_複数回実行しても問題ない() {
         let conn = Connection::open_in_memory().unwrap();
@@ -121,4 +548,40 @@ mod tests {
         assert!(result1.is_ok());
         assert!(result2.is_ok());
     }
+
+    #[test]
+    fn 最新のデータベースでは適用待ちのマイグレーションはない() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+
+        assert!(check_migrations(&conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn 古いデータベースでは適用待ちのマイグレーションを適用せずに列挙する() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE folders (id VARCHAR PRIMARY KEY, name VARCHAR NOT NULL);
+             CREATE TABLE tasks (id VARCHAR PRIMARY KEY, name VARCHAR NOT NULL);",
+        )
+        .unwrap();
+
+        let steps = check_migrations(&conn).unwrap();
+        assert!(steps[0].contains("schema_meta"));
+        assert!(steps.iter().any(|s| s.contains("folder_id")));
+        assert!(steps.iter().any(|s| s.contains("icon")));
+        assert!(steps.last().unwrap().contains(&SCHEMA_VERSION.to_string()));
+
+        // 確認のみでスキーマは変更されない
+        let has_folder_id: bool = conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM information_schema.columns
+                 WHERE table_name = 'tasks' AND column_name = 'folder_id'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(!has_folder_id);
+        assert!(!table_exists(&conn, "schema_meta").unwrap());
+    }
 }
@@ -1,7 +1,16 @@
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use uuid::Uuid;
 
+/// 指定されたフィールドはnullでもSome(None)として読み込む（未指定のみNoneになる）
+fn deserialize_present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
 /// タスク（作業内容）
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Task {
@@ -13,6 +22,11 @@ pub struct Task {
     pub archived: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// 時間単価（未設定の場合は請求対象外）
+    #[serde(default)]
+    pub hourly_rate: Option<f64>,
+    #[serde(default)]
+    pub currency: Option<String>,
 }
 
 /// タスク作成用DTO
@@ -22,6 +36,10 @@ pub struct CreateTask {
     pub description: Option<String>,
     pub color: Option<String>,
     pub folder_id: Option<Uuid>,
+    #[serde(default)]
+    pub hourly_rate: Option<f64>,
+    #[serde(default)]
+    pub currency: Option<String>,
 }
 
 /// タスク更新用DTO
@@ -31,6 +49,40 @@ pub struct UpdateTask {
     pub description: Option<String>,
     pub color: Option<String>,
     pub folder_id: Option<Option<Uuid>>,
+    #[serde(default)]
+    pub hourly_rate: Option<Option<f64>>,
+    #[serde(default)]
+    pub currency: Option<Option<String>>,
+}
+
+/// 集計情報付きタスク（集計を要求しない場合は Task と同じ形でシリアライズされる）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TaskWithStats {
+    #[serde(flatten)]
+    pub task: Task,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entry_count: Option<i64>,
+    /// 完了済みエントリの合計秒数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_seconds: Option<i64>,
+}
+
+/// 保存は成功したが確認を促す警告付きのタスク（警告がなければ Task と同じ形でシリアライズされる）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TaskWithWarnings {
+    #[serde(flatten)]
+    pub task: Task,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// アーカイブ操作の結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveTaskResult {
+    pub id: Uuid,
+    pub archived: bool,
+    /// タスクを参照している時間記録の件数
+    pub entry_count: i64,
 }
 
 /// 成果物
@@ -42,6 +94,26 @@ pub struct Artifact {
     pub reference: Option<String>,
     pub metadata: Option<serde_json::Value>,
     pub created_at: DateTime<Utc>,
+    /// 更新日時（この項目がない古いエクスポートファイルでは既定値になる）
+    #[serde(default)]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 参照件数付きの成果物
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ArtifactWithUsage {
+    #[serde(flatten)]
+    pub artifact: Artifact,
+    /// この成果物を紐付けている時間記録の件数
+    pub usage_count: i64,
+}
+
+/// 成果物の作成日時の範囲と件数
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ArtifactDateRange {
+    pub earliest: DateTime<Utc>,
+    pub latest: DateTime<Utc>,
+    pub count: i64,
 }
 
 /// 成果物作成用DTO
@@ -53,6 +125,16 @@ pub struct CreateArtifact {
     pub metadata: Option<serde_json::Value>,
 }
 
+/// 成果物の種別の許可リストと運用方針
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct ArtifactTypePolicy {
+    pub types: Vec<String>,
+    /// 許可リストにない種別での成果物作成を拒否するか（既定は無効）
+    pub enforce: bool,
+    /// 比較前に前後の空白を除去し小文字に揃えるか
+    pub normalize: bool,
+}
+
 /// 時間記録
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct TimeEntry {
@@ -76,8 +158,70 @@ pub struct TimeEntryWithRelations {
     pub duration_seconds: Option<i64>,
     pub memo: Option<String>,
     pub artifacts: Vec<Artifact>,
+    /// 時刻付きのメモ（作成日時順）
+    #[serde(default)]
+    pub notes: Vec<EntryNote>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// 計測中（ended_atが未設定）かどうか
+    #[serde(default)]
+    pub is_running: bool,
+    /// 計測中のエントリの現在までの経過秒数（計測中のエントリを含めて一覧を取得した場合のみ）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elapsed_seconds: Option<i64>,
+    /// 操作は成功したが確認を促す警告（停止時の作業時間が短すぎる場合など）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+}
+
+/// 日別にまとめた時間記録
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayEntries {
+    /// ローカル日付（YYYY-MM-DD）
+    pub date: String,
+    pub total_seconds: i64,
+    pub entries: Vec<TimeEntryWithRelations>,
+}
+
+/// 計測中エントリの状態（高頻度のポーリング用の軽量な情報）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RunningEntryStatus {
+    pub id: Uuid,
+    pub started_at: DateTime<Utc>,
+    pub elapsed_seconds: i64,
+}
+
+/// 再起動後に終了時刻の確認が必要な計測中エントリ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoveryCandidate {
+    pub entry: TimeEntry,
+    /// 最後にハートビートを受け取った時刻（記録がなければNone）
+    pub last_heartbeat: Option<DateTime<Utc>>,
+}
+
+/// 自動停止の判定結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoStopResult {
+    pub stopped: bool,
+    /// 停止した場合の終了日時（最終ハートビート。開始日時より前にはしない）
+    pub ended_at: Option<DateTime<Utc>>,
+}
+
+/// 確認が必要な時間記録（reasonは "zero_duration" / "negative_duration" / "too_long"）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuspiciousEntry {
+    pub entry: TimeEntry,
+    pub duration_seconds: i64,
+    pub reason: String,
+}
+
+/// 時間が重なり合う時間記録のまとまり（計測中の記録を含む場合はended_atがNone）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverlapCluster {
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    /// 開始日時順
+    pub entries: Vec<TimeEntry>,
 }
 
 /// 時間記録更新用DTO
@@ -86,7 +230,35 @@ pub struct UpdateEntry {
     pub task_id: Option<Option<Uuid>>,
     pub started_at: Option<DateTime<Utc>>,
     pub ended_at: Option<Option<DateTime<Utc>>>,
-    pub memo: Option<String>,
+    /// Noneは変更なし、Some(None)（JSONではnull）はメモを消す
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub memo: Option<Option<String>>,
+}
+
+/// 時間記録の更新内容の確認用情報（タスクの付け替え先がアーカイブ済みか・どのフォルダか）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct UpdateEntryInfo {
+    pub task_changed: bool,
+    /// 更新後のタスクがアーカイブ済みか（タスク未設定の場合はfalse）
+    pub task_archived: bool,
+    /// 更新後のタスクが属するフォルダ名（タスクやフォルダが未設定の場合はNone）
+    pub folder_name: Option<String>,
+}
+
+/// 更新後の時間記録と確認用情報（TimeEntry と同じ形に info を加えてシリアライズされる）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdatedEntry {
+    #[serde(flatten)]
+    pub entry: TimeEntry,
+    pub info: UpdateEntryInfo,
+}
+
+/// 計測の切り替え結果（停止した記録の終了日時と開始した記録の開始日時は同じ）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntrySwitch {
+    /// 停止した記録（計測中の記録がなければNone）
+    pub stopped: Option<TimeEntry>,
+    pub started: TimeEntry,
 }
 
 /// エントリ検索条件
@@ -96,6 +268,42 @@ pub struct EntryFilter {
     pub to: Option<DateTime<Utc>>,
     pub task_id: Option<Uuid>,
     pub limit: Option<i64>,
+    /// メモの部分一致（大文字小文字を区別しない）
+    #[serde(default)]
+    pub memo_query: Option<String>,
+    /// 作業時間の下限（秒）。指定時は計測中の記録を含めない
+    #[serde(default)]
+    pub min_duration_seconds: Option<i64>,
+    /// 作業時間の上限（秒）。指定時は計測中の記録を含めない
+    #[serde(default)]
+    pub max_duration_seconds: Option<i64>,
+    /// 成果物の有無（trueは成果物が1つ以上ある記録、falseは成果物のない記録）
+    #[serde(default)]
+    pub has_artifacts: Option<bool>,
+}
+
+impl EntryFilter {
+    /// 作業時間の範囲指定があるか
+    pub fn has_duration_bounds(&self) -> bool {
+        self.min_duration_seconds.is_some() || self.max_duration_seconds.is_some()
+    }
+}
+
+/// 検索にヒットした記録の月別件数
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SearchFacet {
+    pub year: i32,
+    pub month: u32,
+    pub match_count: i64,
+}
+
+/// 時間記録の検索結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntrySearchResult {
+    pub entries: Vec<TimeEntryWithRelations>,
+    /// 月別の件数（with_facets指定時のみ。limitに関わらず全件を数える）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub facets: Option<Vec<SearchFacet>>,
 }
 
 /// エクスポートデータ
@@ -107,6 +315,22 @@ pub struct ExportData {
     pub artifacts: Vec<Artifact>,
     pub time_entries: Vec<ExportTimeEntry>,
     pub entry_artifacts: Vec<EntryArtifact>,
+    /// 時刻付きメモ（追加前にエクスポートしたファイルにはない）
+    #[serde(default)]
+    pub entry_notes: Vec<EntryNote>,
+}
+
+/// エクスポートバンドル（zip）に含めるマニフェスト
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportBundleManifest {
+    pub version: String,
+    pub exported_at: DateTime<Utc>,
+    pub tasks: usize,
+    pub artifacts: usize,
+    pub time_entries: usize,
+    pub entry_artifacts: usize,
+    #[serde(default)]
+    pub entry_notes: usize,
 }
 
 /// エクスポート用の時間記録（duration_seconds付き）
@@ -129,14 +353,99 @@ pub struct EntryArtifact {
     pub artifact_id: Uuid,
 }
 
+/// エクスポートの進捗（テーブル単位）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExportProgress {
+    pub step: usize,
+    pub total: usize,
+    pub table: String,
+}
+
+/// マージインポートで同じIDのデータが既に存在する場合の扱い
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportStrategy {
+    /// 既存データを残してスキップする
+    #[default]
+    SkipExisting,
+    /// インポートするデータで上書きする
+    Overwrite,
+    /// 更新日時が新しい方を残す
+    NewerWins,
+}
+
 /// インポート結果
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ImportResult {
     pub tasks_imported: usize,
     pub entries_imported: usize,
     pub artifacts_imported: usize,
+    #[serde(default)]
+    pub tasks_updated: usize,
+    #[serde(default)]
+    pub entries_updated: usize,
+    #[serde(default)]
+    pub artifacts_updated: usize,
+    /// 既存データを優先して取り込まなかった件数
+    #[serde(default)]
+    pub skipped: usize,
+    /// 取り込めなかった行などの警告
+    #[serde(default)]
+    pub warnings: Vec<String>,
 }
 
+/// CSVインポート時の列名の対応
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsvColumnMap {
+    pub started_at: String,
+    pub ended_at: String,
+    pub task_name: Option<String>,
+    pub memo: Option<String>,
+}
+
+/// 変更履歴（監査ログ）の1件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditLogEntry {
+    pub id: Uuid,
+    /// 対象の種類（"task" / "entry"）
+    pub entity_type: String,
+    pub entity_id: Uuid,
+    /// 操作（"update" / "rename" / "archive" / "unarchive" / "delete"）
+    pub action: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub at: DateTime<Utc>,
+}
+
+/// 時間記録に時刻付きで追加するメモ（編集できるメインのメモとは別に残る）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryNote {
+    pub id: Uuid,
+    pub entry_id: Uuid,
+    pub text: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// メモのキーワードからタスクを割り当てるルール
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoRule {
+    pub id: Uuid,
+    /// メモに含まれるキーワード（大文字小文字を区別しない）
+    pub pattern: String,
+    pub task_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 自動割り当ての結果
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AutoRuleResult {
+    pub matched: bool,
+    pub task_id: Option<Uuid>,
+}
+
+/// タスクの色の初期値
+pub const DEFAULT_TASK_COLOR: &str = "#3b82f6";
+
 impl Task {
     /// 新しいタスクを作成する
     pub fn new(name: String, description: Option<String>, color: Option<String>, folder_id: Option<Uuid>) -> Self {
@@ -146,10 +455,12 @@ impl Task {
             folder_id,
             name,
             description,
-            color: color.unwrap_or_else(|| "#3b82f6".to_string()),
+            color: color.unwrap_or_else(|| DEFAULT_TASK_COLOR.to_string()),
             archived: false,
             created_at: now,
             updated_at: now,
+            hourly_rate: None,
+            currency: None,
         }
     }
 
@@ -163,6 +474,23 @@ impl Task {
         }
         color[1..].chars().all(|c| c.is_ascii_hexdigit())
     }
+
+    /// カラーコードの相対輝度（0.0=黒〜1.0=白）を計算する。不正な形式の場合はNone
+    pub fn perceived_luminance(color: &str) -> Option<f64> {
+        if !Self::is_valid_color(color) {
+            return None;
+        }
+        let channel = |range: std::ops::Range<usize>| -> Option<f64> {
+            let value = u8::from_str_radix(&color[range], 16).ok()? as f64 / 255.0;
+            // sRGBのガンマを外して線形値にする
+            Some(if value <= 0.04045 {
+                value / 12.92
+            } else {
+                ((value + 0.055) / 1.055).powf(2.4)
+            })
+        };
+        Some(0.2126 * channel(1..3)? + 0.7152 * channel(3..5)? + 0.0722 * channel(5..7)?)
+    }
 }
 
 impl TimeEntry {
@@ -201,13 +529,15 @@ impl Artifact {
         reference: Option<String>,
         metadata: Option<serde_json::Value>,
     ) -> Self {
+        let now = Utc::now();
         Self {
             id: Uuid::new_v4(),
             name,
             artifact_type,
             reference,
             metadata,
-            created_at: Utc::now(),
+            created_at: now,
+            updated_at: now,
         }
     }
 }
@@ -293,6 +623,16 @@ mod tests {
             assert!(!Task::is_valid_color("red"));
         }
 
+        #[test]
+        fn カラーコードの相対輝度を計算できる() {
+            assert_eq!(Task::perceived_luminance("#000000"), Some(0.0));
+            assert!((Task::perceived_luminance("#FFFFFF").unwrap() - 1.0).abs() < 1e-9);
+            let blue = Task::perceived_luminance("#3b82f6").unwrap();
+            assert!(blue > 0.1 && blue < 0.5);
+            assert_eq!(Task::perceived_luminance("red"), None);
+            assert_eq!(Task::perceived_luminance("#gggggg"), None);
+        }
+
         #[test]
         fn タスクをJSONにシリアライズできる() {
             let task = Task::new("テスト".to_string(), None, None, None);
@@ -491,6 +831,7 @@ mod tests {
                 artifacts: vec![],
                 time_entries: vec![],
                 entry_artifacts: vec![],
+                entry_notes: vec![],
             };
             let json = serde_json::to_string(&export_data);
 
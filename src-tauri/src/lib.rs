@@ -4,12 +4,18 @@ pub mod error;
 
 use std::path::PathBuf;
 
+use commands::entries::TaskLock;
+use commands::reports::ReportCache;
 use db::Database;
 use tauri::Manager;
 
 /// アプリケーションの状態
 pub struct AppState {
     pub db: Database,
+    /// 月次レポートの計算結果のキャッシュ
+    pub report_cache: ReportCache,
+    /// 計測を開始できるタスクのロック（メモリ上のみ）
+    pub task_lock: TaskLock,
 }
 
 /// データベースパスを取得する
@@ -28,34 +34,112 @@ pub fn run() {
         .setup(|app| {
             let db_path = get_db_path(app);
             let db = Database::open(&db_path).expect("Failed to open database");
-            app.manage(AppState { db });
+            app.manage(AppState {
+                db,
+                report_cache: ReportCache::default(),
+                task_lock: TaskLock::default(),
+            });
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             commands::tasks::list_tasks,
+            commands::tasks::list_recent_tasks,
             commands::tasks::create_task,
+            commands::tasks::create_tasks,
             commands::tasks::update_task,
+            commands::tasks::set_task_folder,
             commands::tasks::archive_task,
+            commands::tasks::delete_task,
             commands::entries::start_entry,
+            commands::entries::start_entry_from_template,
+            commands::entries::set_active_task_lock,
+            commands::entries::get_active_task_lock,
             commands::entries::stop_entry,
+            commands::entries::switch_entry,
             commands::entries::get_running_entry,
+            commands::entries::get_running_entry_status,
+            commands::entries::get_recovery_candidate,
+            commands::entries::apply_recovery,
+            commands::entries::set_auto_stop_policy,
+            commands::entries::enforce_auto_stop,
+            commands::entries::list_suspicious_entries,
+            commands::entries::split_at_midnight,
+            commands::entries::record_heartbeat,
+            commands::entries::recover_stale_entries,
             commands::entries::list_entries,
+            commands::entries::list_entries_by_day,
+            commands::entries::search_entries,
             commands::entries::update_entry,
+            commands::entries::append_memo,
+            commands::entries::add_entry_note,
+            commands::entries::list_entry_notes,
+            commands::entries::delete_entry_note,
+            commands::entries::clone_entry,
             commands::entries::delete_entry,
+            commands::entries::get_import_conflicts,
             commands::artifacts::create_artifact,
+            commands::artifacts::import_file_artifact,
             commands::artifacts::list_artifacts,
+            commands::artifacts::get_artifact_date_range,
+            commands::artifacts::get_artifact_types,
+            commands::artifacts::set_artifact_types,
+            commands::artifacts::extract_links_from_memo,
+            commands::artifacts::find_artifacts_by_metadata,
             commands::artifacts::link_artifact,
             commands::artifacts::unlink_artifact,
+            commands::artifacts::update_artifact,
             commands::artifacts::delete_artifact,
             commands::export::export_data,
+            commands::export::export_data_to_file,
+            commands::export::export_data_filtered,
             commands::export::import_data,
+            commands::export::import_entries,
+            commands::export::import_csv,
             commands::export::export_parquet,
+            commands::export::export_bundle,
+            commands::export::import_parquet,
+            commands::export::export_task_entries_csv,
+            commands::export::export_ics,
             commands::reports::get_monthly_report,
+            commands::reports::get_combined_report,
+            commands::reports::compare_reports,
+            commands::reports::get_tasks_report,
+            commands::reports::clear_report_cache,
             commands::reports::get_available_months,
+            commands::reports::export_all_monthly_reports,
+            commands::reports::get_active_task_counts,
+            commands::reports::get_longest_work_block,
+            commands::reports::get_billing_report,
+            commands::reports::get_duration_histogram,
+            commands::reports::get_cumulative_timeline,
+            commands::reports::get_records,
+            commands::reports::get_color_report,
+            commands::reports::get_gaps_report,
+            commands::reports::get_activity_distribution,
+            commands::reports::get_today_report,
+            commands::reports::get_weekly_digest_text,
+            commands::reports::get_task_streak,
             commands::folders::list_folders,
+            commands::folders::get_folder_tree,
+            commands::folders::get_folder_counts,
             commands::folders::create_folder,
             commands::folders::update_folder,
             commands::folders::delete_folder,
+            commands::settings::get_setting,
+            commands::settings::set_setting,
+            commands::database::get_schema_version,
+            commands::database::preview_migrations,
+            commands::database::compact_database,
+            commands::database::recompute_durations,
+            commands::database::get_database_stats,
+            commands::database::check_integrity,
+            commands::database::repair_integrity,
+            commands::database::run_readonly_query,
+            commands::audit::get_audit_log,
+            commands::auto_rules::list_auto_rules,
+            commands::auto_rules::create_auto_rule,
+            commands::auto_rules::delete_auto_rule,
+            commands::auto_rules::apply_auto_rules,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
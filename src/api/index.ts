@@ -3,18 +3,49 @@ import type {
   Folder,
   CreateFolder,
   UpdateFolder,
+  FolderTreeNode,
+  FolderCounts,
   Task,
+  TaskWithStats,
+  TaskWithWarnings,
+  ArchiveTaskResult,
   CreateTask,
   UpdateTask,
   TimeEntry,
   TimeEntryWithRelations,
+  RunningEntryStatus,
+  SuspiciousEntry,
+  RecoveryCandidate,
+  AutoStopResult,
+  DayEntries,
+  EntrySearchResult,
   UpdateEntry,
+  UpdatedEntry,
+  EntrySwitch,
+  EntryNote,
+  OverlapCluster,
   Artifact,
+  ArtifactWithUsage,
+  ArtifactDateRange,
+  ArtifactTypePolicy,
   CreateArtifact,
   ExportData,
+  ExportTimeEntry,
   ImportResult,
+  ImportStrategy,
   ListEntriesFilter,
   MonthlyReport,
+  Rounding,
+  TasksReport,
+  ReportComparison,
+  ActivityDistribution,
+  TimelinePoint,
+  ReportRecords,
+  ColorSummary,
+  TodayReport,
+  TaskStreak,
+  AutoRule,
+  AutoRuleResult,
 } from '../types';
 
 // Folders API
@@ -23,6 +54,14 @@ export const foldersApi = {
     return invoke('list_folders');
   },
 
+  getTree: (includeArchived?: boolean): Promise<FolderTreeNode[]> => {
+    return invoke('get_folder_tree', { includeArchived });
+  },
+
+  getCounts: (countArchivedEntries?: boolean): Promise<FolderCounts[]> => {
+    return invoke('get_folder_counts', { countArchivedEntries });
+  },
+
   create: (folder: CreateFolder): Promise<Folder> => {
     return invoke('create_folder', { folder });
   },
@@ -38,47 +77,153 @@ export const foldersApi = {
 
 // Tasks API
 export const tasksApi = {
-  list: (includeArchived: boolean = false): Promise<Task[]> => {
-    return invoke('list_tasks', { includeArchived });
+  list: (
+    includeArchived: boolean = false,
+    withEntryCounts?: boolean,
+    withTotals?: boolean,
+    query?: string,
+  ): Promise<TaskWithStats[]> => {
+    return invoke('list_tasks', { includeArchived, withEntryCounts, withTotals, query });
+  },
+
+  listRecent: (limit?: number): Promise<Task[]> => {
+    return invoke('list_recent_tasks', { limit });
+  },
+
+  create: (task: CreateTask, checkContrast?: boolean): Promise<TaskWithWarnings> => {
+    return invoke('create_task', { task, checkContrast });
+  },
+
+  createMany: (tasks: CreateTask[]): Promise<Task[]> => {
+    return invoke('create_tasks', { tasks });
   },
 
-  create: (task: CreateTask): Promise<Task> => {
-    return invoke('create_task', { task });
+  update: (id: string, update: UpdateTask, checkContrast?: boolean): Promise<TaskWithWarnings> => {
+    return invoke('update_task', { id, update, checkContrast });
   },
 
-  update: (id: string, update: UpdateTask): Promise<Task> => {
-    return invoke('update_task', { id, update });
+  setFolder: (taskId: string, folderId: string | null): Promise<Task> => {
+    return invoke('set_task_folder', { taskId, folderId });
   },
 
-  archive: (id: string, archived: boolean): Promise<void> => {
+  archive: (id: string, archived: boolean): Promise<ArchiveTaskResult> => {
     return invoke('archive_task', { id, archived });
   },
+
+  delete: (id: string, reassignTo?: string, detachEntries?: boolean): Promise<number> => {
+    return invoke('delete_task', { id, reassignTo, detachEntries });
+  },
 };
 
 // Entries API
 export const entriesApi = {
-  list: (filter: ListEntriesFilter = {}): Promise<TimeEntryWithRelations[]> => {
+  // includeRunningを指定すると計測中のエントリを期間外でも経過秒数付きで含める
+  list: (
+    filter: ListEntriesFilter = {},
+    includeRunning?: boolean
+  ): Promise<TimeEntryWithRelations[]> => {
     return invoke('list_entries', {
       from: filter.from,
       to: filter.to,
       taskId: filter.task_id,
       limit: filter.limit,
+      includeRunning,
+      minDurationSeconds: filter.min_duration_seconds,
+      maxDurationSeconds: filter.max_duration_seconds,
+      hasArtifacts: filter.has_artifacts,
     });
   },
 
+  listByDay: (filter: ListEntriesFilter = {}, tzOffsetMinutes?: number): Promise<DayEntries[]> => {
+    return invoke('list_entries_by_day', { filter, tzOffsetMinutes });
+  },
+
   getRunning: (): Promise<TimeEntryWithRelations | null> => {
     return invoke('get_running_entry');
   },
 
-  start: (taskId?: string, memo?: string): Promise<TimeEntry> => {
-    return invoke('start_entry', { taskId, memo });
+  getRunningStatus: (): Promise<RunningEntryStatus | null> => {
+    return invoke('get_running_entry_status');
+  },
+
+  getRecoveryCandidate: (): Promise<RecoveryCandidate | null> => {
+    return invoke('get_recovery_candidate');
+  },
+
+  applyRecovery: (entryId: string, endedAt: string): Promise<TimeEntry> => {
+    return invoke('apply_recovery', { entryId, endedAt });
+  },
+
+  // 0で自動停止を無効にする
+  setAutoStopPolicy: (idleSeconds: number): Promise<void> => {
+    return invoke('set_auto_stop_policy', { idleSeconds });
+  },
+
+  enforceAutoStop: (): Promise<AutoStopResult> => {
+    return invoke('enforce_auto_stop');
+  },
+
+  listSuspicious: (maxSeconds?: number): Promise<SuspiciousEntry[]> => {
+    return invoke('list_suspicious_entries', { maxSeconds });
+  },
+
+  start: (taskId?: string, memo?: string, startedAt?: string): Promise<TimeEntry> => {
+    return invoke('start_entry', { taskId, memo, startedAt });
+  },
+
+  // テンプレート本文は設定キー `memo_template.<名前>` に保存する
+  startFromTemplate: (
+    templateName: string,
+    vars: Record<string, string>,
+    taskId?: string,
+    strict?: boolean
+  ): Promise<TimeEntry> => {
+    return invoke('start_entry_from_template', { taskId, templateName, vars, strict });
+  },
+
+  // 集中作業中に他のタスクで計測を始めないようにする（nullで解除、再起動で解除される）
+  setTaskLock: (taskId: string | null): Promise<string | null> => {
+    return invoke('set_active_task_lock', { taskId });
+  },
+
+  getTaskLock: (): Promise<string | null> => {
+    return invoke('get_active_task_lock');
+  },
+
+  stop: (
+    id: string,
+    memo?: string,
+    artifacts?: CreateArtifact[],
+    endedAt?: string,
+    minDurationSeconds?: number
+  ): Promise<TimeEntryWithRelations> => {
+    return invoke('stop_entry', {
+      id,
+      memo,
+      artifacts,
+      endedAt,
+      minDurationSeconds,
+    });
+  },
+
+  getImportConflicts: (): Promise<OverlapCluster[]> => {
+    return invoke('get_import_conflicts');
   },
 
-  stop: (id: string, memo?: string): Promise<TimeEntry> => {
-    return invoke('stop_entry', { id, memo });
+  switch: (taskId?: string, memo?: string): Promise<EntrySwitch> => {
+    return invoke('switch_entry', { taskId, memo });
   },
 
-  update: (id: string, update: UpdateEntry): Promise<TimeEntry> => {
+  search: (
+    query: string,
+    limit?: number,
+    withFacets?: boolean,
+    tzOffsetMinutes?: number
+  ): Promise<EntrySearchResult> => {
+    return invoke('search_entries', { query, limit, withFacets, tzOffsetMinutes });
+  },
+
+  update: (id: string, update: UpdateEntry): Promise<UpdatedEntry> => {
     return invoke('update_entry', {
       id,
       update: {
@@ -90,19 +235,70 @@ export const entriesApi = {
     });
   },
 
+  clone: (id: string, newStartedAt: string): Promise<TimeEntryWithRelations> => {
+    return invoke('clone_entry', { id, newStartedAt });
+  },
+
   delete: (id: string): Promise<void> => {
     return invoke('delete_entry', { id });
   },
+
+  addNote: (entryId: string, text: string): Promise<EntryNote> => {
+    return invoke('add_entry_note', { entryId, text });
+  },
+
+  listNotes: (entryId: string): Promise<EntryNote[]> => {
+    return invoke('list_entry_notes', { entryId });
+  },
+
+  deleteNote: (id: string): Promise<void> => {
+    return invoke('delete_entry_note', { id });
+  },
 };
 
 // Artifacts API
 export const artifactsApi = {
-  list: (limit?: number): Promise<Artifact[]> => {
-    return invoke('list_artifacts', { limit });
+  list: (limit?: number, unlinkedOnly?: boolean): Promise<ArtifactWithUsage[]> => {
+    return invoke('list_artifacts', { limit, unlinkedOnly });
+  },
+
+  create: (artifact: CreateArtifact, entryId?: string, dedupe?: boolean): Promise<Artifact> => {
+    return invoke('create_artifact', { artifact, entryId, dedupe });
+  },
+
+  // 空文字のreferenceを渡すと参照先を消す
+  update: (
+    id: string,
+    name?: string,
+    reference?: string,
+    metadata?: Record<string, unknown>
+  ): Promise<Artifact> => {
+    return invoke('update_artifact', { id, name, reference, metadata });
+  },
+
+  getDateRange: (artifactType?: string): Promise<ArtifactDateRange | null> => {
+    return invoke('get_artifact_date_range', { artifactType });
   },
 
-  create: (artifact: CreateArtifact, entryId?: string): Promise<Artifact> => {
-    return invoke('create_artifact', { artifact, entryId });
+  getTypes: (): Promise<ArtifactTypePolicy> => {
+    return invoke('get_artifact_types');
+  },
+
+  setTypes: (
+    types: string[],
+    enforce?: boolean,
+    normalize?: boolean
+  ): Promise<ArtifactTypePolicy> => {
+    return invoke('set_artifact_types', { types, enforce, normalize });
+  },
+
+  // ファイルをアプリのデータディレクトリにコピーして紐付ける
+  importFile: (entryId: string, sourcePath: string): Promise<Artifact> => {
+    return invoke('import_file_artifact', { entryId, sourcePath });
+  },
+
+  extractLinksFromMemo: (entryId: string): Promise<Artifact[]> => {
+    return invoke('extract_links_from_memo', { entryId });
   },
 
   link: (entryId: string, artifactId: string): Promise<void> => {
@@ -113,7 +309,7 @@ export const artifactsApi = {
     return invoke('unlink_artifact', { entryId, artifactId });
   },
 
-  delete: (id: string): Promise<void> => {
+  delete: (id: string): Promise<number> => {
     return invoke('delete_artifact', { id });
   },
 };
@@ -124,23 +320,161 @@ export const exportApi = {
     return invoke('export_data');
   },
 
-  importData: (data: ExportData, merge: boolean): Promise<ImportResult> => {
-    return invoke('import_data', { data, merge });
+  exportDataToFile: (path: string, pretty: boolean): Promise<number> => {
+    return invoke('export_data_to_file', { path, pretty });
   },
 
-  exportParquet: (outputDir: string): Promise<string[]> => {
-    return invoke('export_parquet', { outputDir });
+  exportDataFiltered: (taskIds: string[]): Promise<ExportData> => {
+    return invoke('export_data_filtered', { taskIds });
+  },
+
+  importData: (
+    data: ExportData,
+    merge: boolean,
+    strategy?: ImportStrategy,
+    dedupeByContent?: boolean
+  ): Promise<ImportResult> => {
+    return invoke('import_data', { data, merge, strategy, dedupeByContent });
+  },
+
+  importEntries: (entries: ExportTimeEntry[], merge: boolean): Promise<ImportResult> => {
+    return invoke('import_entries', { entries, merge });
+  },
+
+  // batchRowsを指定するとtime_entriesを time_entries_0001.parquet, ... に分割する
+  exportParquet: (outputDir: string, batchRows?: number): Promise<string[]> => {
+    return invoke('export_parquet', { outputDir, batchRows });
+  },
+
+  exportBundle: (outputPath: string): Promise<string> => {
+    return invoke('export_bundle', { outputPath });
+  },
+
+  exportIcs: (outputPath: string, from?: string, to?: string): Promise<string> => {
+    return invoke('export_ics', { from, to, outputPath });
+  },
+
+  importParquet: (
+    inputDir: string,
+    merge: boolean,
+    strategy?: ImportStrategy
+  ): Promise<ImportResult> => {
+    return invoke('import_parquet', { inputDir, merge, strategy });
   },
 };
 
 // Reports API
 export const reportsApi = {
-  getMonthlyReport: (year: number, month: number, folderId?: string): Promise<MonthlyReport> => {
-    return invoke('get_monthly_report', { year, month, folderId });
+  getMonthlyReport: (
+    year: number,
+    month: number,
+    folderId?: string,
+    tzOffsetMinutes?: number,
+    excludeTaskIds?: string[],
+    dailyTargetSeconds?: number,
+    rounding?: Rounding
+  ): Promise<MonthlyReport> => {
+    return invoke('get_monthly_report', {
+      year,
+      month,
+      folderId,
+      tzOffsetMinutes,
+      excludeTaskIds,
+      dailyTargetSeconds,
+      rounding,
+    });
+  },
+
+  getCombinedReport: (
+    otherDbPaths: string[],
+    year: number,
+    month: number,
+    tzOffsetMinutes?: number
+  ): Promise<MonthlyReport> => {
+    return invoke('get_combined_report', { otherDbPaths, year, month, tzOffsetMinutes });
+  },
+
+  compareReports: (
+    periodA: [number, number],
+    periodB: [number, number],
+    tzOffsetMinutes?: number
+  ): Promise<ReportComparison> => {
+    return invoke('compare_reports', { periodA, periodB, tzOffsetMinutes });
+  },
+
+  getTasksReport: (taskIds: string[], from?: string, to?: string): Promise<TasksReport> => {
+    return invoke('get_tasks_report', { taskIds, from, to });
+  },
+
+  getTaskStreak: (taskId: string, tzOffsetMinutes?: number): Promise<TaskStreak> => {
+    return invoke('get_task_streak', { taskId, tzOffsetMinutes });
+  },
+
+  getTodayReport: (tzOffsetMinutes?: number): Promise<TodayReport> => {
+    return invoke('get_today_report', { tzOffsetMinutes });
+  },
+
+  // weekはISO週番号（月曜始まり）
+  getWeeklyDigestText: (
+    year: number,
+    week: number,
+    tzOffsetMinutes?: number
+  ): Promise<string> => {
+    return invoke('get_weekly_digest_text', { year, week, tzOffsetMinutes });
+  },
+
+  getActivityDistribution: (
+    from?: string,
+    to?: string,
+    tzOffsetMinutes?: number
+  ): Promise<ActivityDistribution> => {
+    return invoke('get_activity_distribution', { from, to, tzOffsetMinutes });
+  },
+
+  getCumulativeTimeline: (from?: string, to?: string): Promise<TimelinePoint[]> => {
+    return invoke('get_cumulative_timeline', { from, to });
+  },
+
+  getColorReport: (from?: string, to?: string): Promise<ColorSummary[]> => {
+    return invoke('get_color_report', { from, to });
+  },
+
+  getRecords: (tzOffsetMinutes?: number): Promise<ReportRecords> => {
+    return invoke('get_records', { tzOffsetMinutes });
+  },
+
+  clearCache: (): Promise<void> => {
+    return invoke('clear_report_cache');
+  },
+
+  getAvailableMonths: (tzOffsetMinutes?: number): Promise<[number, number][]> => {
+    return invoke('get_available_months', { tzOffsetMinutes });
+  },
+
+  exportAllMonthly: (
+    outputDir: string,
+    tzOffsetMinutes?: number
+  ): Promise<string[]> => {
+    return invoke('export_all_monthly_reports', { outputDir, tzOffsetMinutes });
+  },
+};
+
+// Auto rules API
+export const autoRulesApi = {
+  list: (): Promise<AutoRule[]> => {
+    return invoke('list_auto_rules');
+  },
+
+  create: (pattern: string, taskId: string): Promise<AutoRule> => {
+    return invoke('create_auto_rule', { pattern, taskId });
+  },
+
+  delete: (id: string): Promise<void> => {
+    return invoke('delete_auto_rule', { id });
   },
 
-  getAvailableMonths: (): Promise<[number, number][]> => {
-    return invoke('get_available_months');
+  apply: (entryId: string): Promise<AutoRuleResult> => {
+    return invoke('apply_auto_rules', { entryId });
   },
 };
 
@@ -152,6 +486,7 @@ export const api = {
   artifacts: artifactsApi,
   export: exportApi,
   reports: reportsApi,
+  autoRules: autoRulesApi,
 };
 
 export default api;
@@ -4,6 +4,7 @@ export interface Folder {
   name: string;
   color: string;
   icon: string | null;
+  description: string | null;
   sort_order: number;
   created_at: string;
   updated_at: string;
@@ -13,15 +14,28 @@ export interface CreateFolder {
   name: string;
   color?: string;
   icon?: string;
+  description?: string;
 }
 
 export interface UpdateFolder {
   name?: string;
   color?: string;
   icon?: string;
+  description?: string;
   sort_order?: number;
 }
 
+export interface FolderTreeNode {
+  folder: Folder | null;
+  tasks: Task[];
+}
+
+export interface FolderCounts {
+  folder_id: string | null;
+  task_count: number;
+  entry_count: number;
+}
+
 // Task types
 export interface Task {
   id: string;
@@ -32,6 +46,24 @@ export interface Task {
   archived: boolean;
   created_at: string;
   updated_at: string;
+  hourly_rate: number | null;
+  currency: string | null;
+}
+
+export interface TaskWithStats extends Task {
+  entry_count?: number;
+  total_seconds?: number;
+}
+
+// 読みにくい色などの警告（保存は成功している）
+export interface TaskWithWarnings extends Task {
+  warnings?: string[];
+}
+
+export interface ArchiveTaskResult {
+  id: string;
+  archived: boolean;
+  entry_count: number;
 }
 
 export interface CreateTask {
@@ -39,6 +71,8 @@ export interface CreateTask {
   description?: string;
   color?: string;
   folder_id?: string;
+  hourly_rate?: number;
+  currency?: string;
 }
 
 export interface UpdateTask {
@@ -46,6 +80,8 @@ export interface UpdateTask {
   description?: string;
   color?: string;
   folder_id?: string | null;
+  hourly_rate?: number | null;
+  currency?: string | null;
 }
 
 // TimeEntry types
@@ -59,10 +95,39 @@ export interface TimeEntry {
   updated_at: string;
 }
 
+// 時間記録に時刻付きで追加するメモ（メインのメモとは別）
+export interface EntryNote {
+  id: string;
+  entry_id: string;
+  text: string;
+  created_at: string;
+}
+
 export interface TimeEntryWithRelations extends TimeEntry {
   task: Task | null;
   artifacts: Artifact[];
+  notes: EntryNote[];
   duration_seconds: number | null;
+  is_running: boolean;
+  elapsed_seconds?: number;
+  warning?: string;
+}
+
+export interface SearchFacet {
+  year: number;
+  month: number;
+  match_count: number;
+}
+
+export interface EntrySearchResult {
+  entries: TimeEntryWithRelations[];
+  facets?: SearchFacet[];
+}
+
+export interface DayEntries {
+  date: string;
+  total_seconds: number;
+  entries: TimeEntryWithRelations[];
 }
 
 export interface StartEntry {
@@ -70,6 +135,29 @@ export interface StartEntry {
   memo?: string;
 }
 
+export interface RunningEntryStatus {
+  id: string;
+  started_at: string;
+  elapsed_seconds: number;
+}
+
+export interface RecoveryCandidate {
+  entry: TimeEntry;
+  last_heartbeat: string | null;
+}
+
+export interface AutoStopResult {
+  stopped: boolean;
+  ended_at: string | null;
+}
+
+export interface SuspiciousEntry {
+  entry: TimeEntry;
+  duration_seconds: number;
+  reason: 'zero_duration' | 'negative_duration' | 'too_long';
+}
+
+// memo は null を指定するとメモを消す（省略した項目は変更しない）
 export interface UpdateEntry {
   task_id?: string | null;
   started_at?: string;
@@ -77,6 +165,27 @@ export interface UpdateEntry {
   memo?: string | null;
 }
 
+export interface UpdateEntryInfo {
+  task_changed: boolean;
+  task_archived: boolean;
+  folder_name: string | null;
+}
+
+export interface UpdatedEntry extends TimeEntry {
+  info: UpdateEntryInfo;
+}
+
+export interface OverlapCluster {
+  started_at: string;
+  ended_at: string | null;
+  entries: TimeEntry[];
+}
+
+export interface EntrySwitch {
+  stopped: TimeEntry | null;
+  started: TimeEntry;
+}
+
 // Artifact types
 export interface Artifact {
   id: string;
@@ -85,6 +194,11 @@ export interface Artifact {
   reference: string | null;
   metadata: Record<string, unknown> | null;
   created_at: string;
+  updated_at: string;
+}
+
+export interface ArtifactWithUsage extends Artifact {
+  usage_count: number;
 }
 
 export interface CreateArtifact {
@@ -94,6 +208,31 @@ export interface CreateArtifact {
   metadata?: Record<string, unknown>;
 }
 
+export interface ArtifactDateRange {
+  earliest: string;
+  latest: string;
+  count: number;
+}
+
+export interface ArtifactTypePolicy {
+  types: string[];
+  enforce: boolean;
+  normalize: boolean;
+}
+
+// Auto rule types
+export interface AutoRule {
+  id: string;
+  pattern: string;
+  task_id: string;
+  created_at: string;
+}
+
+export interface AutoRuleResult {
+  matched: boolean;
+  task_id: string | null;
+}
+
 // Export/Import types
 export interface ExportTimeEntry {
   id: string;
@@ -118,12 +257,26 @@ export interface ExportData {
   artifacts: Artifact[];
   time_entries: ExportTimeEntry[];
   entry_artifacts: EntryArtifact[];
+  entry_notes: EntryNote[];
+}
+
+export interface ExportProgress {
+  step: number;
+  total: number;
+  table: string;
 }
 
+export type ImportStrategy = 'skip_existing' | 'overwrite' | 'newer_wins';
+
 export interface ImportResult {
   tasks_imported: number;
   entries_imported: number;
   artifacts_imported: number;
+  tasks_updated: number;
+  entries_updated: number;
+  artifacts_updated: number;
+  skipped: number;
+  warnings: string[];
 }
 
 // Query filters
@@ -132,6 +285,9 @@ export interface ListEntriesFilter {
   to?: string;
   task_id?: string;
   limit?: number;
+  min_duration_seconds?: number;
+  max_duration_seconds?: number;
+  has_artifacts?: boolean;
 }
 
 // Report types
@@ -141,12 +297,23 @@ export interface TaskSummary {
   task_color: string;
   total_seconds: number;
   entry_count: number;
+  percentage: number;
 }
 
 export interface DailySummary {
   date: string;
   total_seconds: number;
   entry_count: number;
+  met_target?: boolean;
+  delta_seconds?: number;
+}
+
+export type RoundMode = 'up' | 'nearest' | 'down';
+
+// 記録ごとの作業時間の丸め設定
+export interface Rounding {
+  increment_minutes: number;
+  mode: RoundMode;
 }
 
 export interface MonthlyReport {
@@ -160,6 +327,101 @@ export interface MonthlyReport {
   daily_summaries: DailySummary[];
 }
 
+// 期間Aから期間Bへの変化（B - A）
+export interface TaskDelta {
+  task_id: string | null;
+  task_name: string;
+  task_color: string;
+  seconds_a: number;
+  seconds_b: number;
+  delta_seconds: number;
+  entries_a: number;
+  entries_b: number;
+  delta_entries: number;
+}
+
+export interface ReportDiff {
+  delta_total_seconds: number;
+  delta_entries: number;
+  task_deltas: TaskDelta[];
+}
+
+export interface ReportComparison {
+  period_a: MonthlyReport;
+  period_b: MonthlyReport;
+  diff: ReportDiff;
+}
+
+export interface TasksReport {
+  total_seconds: number;
+  entry_count: number;
+  task_summaries: TaskSummary[];
+}
+
+// タスクの連続記録日数（日付はYYYY-MM-DD）
+export interface TaskStreak {
+  current_days: number;
+  current_start: string | null;
+  current_end: string | null;
+  longest_days: number;
+  longest_start: string | null;
+  longest_end: string | null;
+}
+
+// 今日のここまでの集計（計測中のエントリは現在までの経過秒数を含む）
+export interface TodayReport {
+  date: string;
+  total_seconds: number;
+  entry_count: number;
+  task_summaries: TaskSummary[];
+}
+
+// 開始時刻の曜日（0=日曜）・時間帯ごとの合計秒数
+export interface ActivityDistribution {
+  by_day_of_week: number[];
+  by_hour: number[];
+}
+
+// 累積作業時間のタイムライン（完了済みエントリ1件ごとの点）
+export interface TimelinePoint {
+  entry_id: string;
+  started_at: string;
+  ended_at: string;
+  duration_seconds: number;
+  cumulative_seconds: number;
+  task_id: string | null;
+  task_color: string;
+}
+
+// タスクの色別の作業時間（colorがnullはタスクなしの未分類）
+export interface ColorSummary {
+  color: string | null;
+  total_seconds: number;
+  entry_count: number;
+}
+
+// 統計ページ用の最高記録（データがなければ各項目はnull）
+export interface LongestSession {
+  entry_id: string;
+  task_id: string | null;
+  task_name: string;
+  started_at: string;
+  ended_at: string;
+  duration_seconds: number;
+}
+
+export interface DayRecord {
+  date: string;
+  total_seconds: number;
+  entry_count: number;
+}
+
+export interface ReportRecords {
+  longest_session: LongestSession | null;
+  busiest_day: DayRecord | null;
+  most_entries_day: DayRecord | null;
+}
+
 // App state types
 export interface AppState {
   folders: Folder[];